@@ -64,6 +64,13 @@ typedef struct TxKit_Method TxKit_Method;
  */
 typedef struct TxKit_Registry TxKit_Registry;
 
+/**
+ * List of shapes of a 2D signed distance field
+ *
+ * Shapes are combined in order, starting from the empty set.
+ */
+typedef struct TxKit_Sdf2dShapes TxKit_Sdf2dShapes;
+
 typedef struct {
     uintptr_t width;
     uintptr_t height;
@@ -74,8 +81,42 @@ typedef struct {
 typedef TxKit_ImageDimensions_usize TxKit_ImageDim;
 
 typedef struct {
-    float alpha_value;
-} TxKit_DebugParams;
+    /**
+     * height of a unit of the height texture, in pixels
+     */
+    float strength;
+    /**
+     * distance to search for occluders, in pixels
+     */
+    float radius;
+    /**
+     * number of directions to search for occluders
+     */
+    int32_t directions;
+    /**
+     * number of samples along each direction
+     */
+    int32_t steps;
+    /**
+     * texture inputs, the height is bound to unit 0
+     */
+    TxKit_ImageIo *io;
+} TxKit_AmbientOcclusionParams;
+
+typedef struct {
+    /**
+     * pseudo-random seed of the initial binary pattern
+     */
+    uint32_t global_seed;
+    /**
+     * standard deviation of the gaussian energy filter, in pixels
+     */
+    float sigma;
+    /**
+     * fraction of the pixels set in the initial binary pattern
+     */
+    float initial_density;
+} TxKit_BlueNoiseParams;
 
 /**
  * A 2-dimensional vector.
@@ -93,6 +134,223 @@ typedef struct {
     float y;
 } TxKit_Vector2_f32;
 
+typedef struct {
+    /**
+     * pseudo-random seed of the brick IDs
+     */
+    uint32_t global_seed;
+    /**
+     * number of bricks across the image along each axis
+     */
+    TxKit_Vector2_f32 scale;
+    /**
+     * horizontal shift of each row relative to the previous one, in bricks
+     */
+    float row_offset;
+    /**
+     * width of the mortar joints, in brick heights
+     */
+    float mortar_width;
+    /**
+     * width of the anti-aliasing filter, in pixels (0: no anti-aliasing)
+     */
+    float antialiasing;
+} TxKit_BrickPatternParams;
+
+typedef struct {
+    /**
+     * pseudo-random seed of the tile IDs
+     */
+    uint32_t global_seed;
+    /**
+     * number of squares across the image
+     */
+    float scale;
+    /**
+     * width of the anti-aliasing filter, in pixels (0: no anti-aliasing)
+     */
+    float antialiasing;
+} TxKit_CheckerPatternParams;
+
+typedef struct {
+    /**
+     * pseudo-random seed, 3D potential components are seeded with global_seed + i
+     */
+    uint32_t global_seed;
+    /**
+     * lattice scale (size in pixels)
+     */
+    float scale;
+    /**
+     * potential noise
+     */
+    int32_t basis;
+    /**
+     * multiplier applied to the vector field before remapping it to [0, 1]
+     */
+    float amplitude;
+} TxKit_CurlNoiseParams;
+
+typedef struct {
+    /**
+     * height of a unit of the height texture, in pixels
+     */
+    float strength;
+    /**
+     * radius of curvature mapped to 0 (concave) or 1 (convex), in pixels
+     */
+    float radius;
+    /**
+     * texture inputs, the height is bound to unit 0
+     */
+    TxKit_ImageIo *io;
+} TxKit_CurvatureParams;
+
+typedef struct {
+    float alpha_value;
+} TxKit_DebugParams;
+
+typedef struct {
+    /**
+     * pixels of the mask are those with a red channel greater than this value
+     */
+    float threshold;
+    /**
+     * texture inputs, the mask is bound to unit 0
+     */
+    TxKit_ImageIo *io;
+} TxKit_DistanceTransformParams;
+
+typedef struct {
+    /**
+     * displacement scale, in texture coordinates
+     */
+    float strength;
+    /**
+     * number of warping iterations, 0 = no warping
+     */
+    int32_t iterations;
+    /**
+     * interpretation of the displacement field
+     */
+    int32_t displacement_mode;
+    /**
+     * computed output
+     */
+    int32_t output_mode;
+    /**
+     * texture inputs
+     */
+    TxKit_ImageIo *io;
+} TxKit_DomainWarpParams;
+
+typedef struct {
+    /**
+     * pseudo-random seed of the droplet positions
+     */
+    uint32_t global_seed;
+    /**
+     * number of simulation steps
+     */
+    int32_t iterations;
+    /**
+     * height of a unit of the height texture, in pixels
+     */
+    float strength;
+    /**
+     * fraction of the material above the talus slope moved at every step (0: no thermal erosion)
+     */
+    float thermal_rate;
+    /**
+     * steepest stable slope for thermal erosion, in pixels per pixel
+     */
+    float talus_slope;
+    /**
+     * number of water droplets per step (0: no hydraulic erosion)
+     */
+    int32_t droplets;
+    /**
+     * maximum number of moves of a droplet
+     */
+    int32_t droplet_lifetime;
+    /**
+     * fraction of the previous direction kept by droplets at every move
+     */
+    float inertia;
+    /**
+     * sediment carried by droplets per unit of water, speed and descent
+     */
+    float sediment_capacity;
+    /**
+     * fraction of the free sediment capacity eroded at every move
+     */
+    float erosion_rate;
+    /**
+     * fraction of the excess sediment deposited at every move
+     */
+    float deposition_rate;
+    /**
+     * fraction of the droplet water evaporated at every move
+     */
+    float evaporation_rate;
+    /**
+     * texture inputs, the height is bound to unit 0
+     */
+    TxKit_ImageIo *io;
+} TxKit_ErosionParams;
+
+typedef struct {
+    /**
+     * pseudo-random seed, octave i is seeded with global_seed + i
+     */
+    uint32_t global_seed;
+    /**
+     * lattice scale of the first octave (size in pixels)
+     */
+    float scale;
+    /**
+     * stats mode (0: normal, 1: process, 2: lookat)
+     */
+    int32_t stats_mode;
+    /**
+     * look-at parameter (if stats_mode == lookat) in [0, 1]^2
+     */
+    TxKit_Vector2_f32 stats_look_at;
+    /**
+     * basis noise
+     */
+    int32_t basis;
+    /**
+     * octave combination
+     */
+    int32_t variant;
+    /**
+     * number of octaves
+     */
+    int32_t octaves;
+    /**
+     * scale multiplier between successive octaves
+     */
+    float lacunarity;
+    /**
+     * amplitude multiplier between successive octaves
+     */
+    float gain;
+    /**
+     * noise dimensions of the value, gradient and simplex octaves
+     */
+    int32_t dimensions;
+    /**
+     * position along the time axis of 4D noise, in lattice cells of the first octave
+     */
+    float time;
+    /**
+     * period of 4D noise along the time axis, in lattice cells of the first octave, 0 for no
+     * looping
+     */
+    float period;
+} TxKit_FractalNoiseParams;
+
 typedef struct {
     /**
      * pseudo-random seed
@@ -110,8 +368,98 @@ typedef struct {
      * look-at parameter (if stats_mode == lookat) in [0, 1]^2
      */
     TxKit_Vector2_f32 stats_look_at;
+    /**
+     * kernel orientation mode
+     */
+    int32_t mode;
+    /**
+     * kernel count per cell
+     */
+    int32_t kernel_count;
+    /**
+     * point distribution
+     */
+    int32_t noise_point_distribution;
+    /**
+     * jittering amount, 0 = no random, 1 = full subcell random
+     */
+    float jitter_amount;
+    /**
+     * max jittering subcells, 0 = no limit
+     */
+    int32_t jitter_max;
+    /**
+     * width of the gaussian envelope in the frequency domain (in 1 / noise cell)
+     */
+    float bandwidth;
+    /**
+     * kernel frequency (in oscillations / noise cell)
+     */
+    float frequency;
+    /**
+     * main kernel orientation (in radians)
+     */
+    float orientation;
+    /**
+     * width of the interval of random orientations in hybrid mode (in radians)
+     */
+    float orientation_spread;
+    /**
+     * texture inputs
+     */
+    TxKit_ImageIo *io;
+} TxKit_GaborNoiseParams;
+
+typedef struct {
+    /**
+     * pseudo-random seed
+     */
+    uint32_t global_seed;
+    /**
+     * lattice scale (size in pixels)
+     */
+    float scale;
+    /**
+     * stats mode (0: normal, 1: process, 2: lookat)
+     */
+    int32_t stats_mode;
+    /**
+     * look-at parameter (if stats_mode == lookat) in [0, 1]^2
+     */
+    TxKit_Vector2_f32 stats_look_at;
+    /**
+     * output channels
+     */
+    int32_t output_mode;
+    /**
+     * noise dimensions
+     */
+    int32_t dimensions;
+    /**
+     * position along the time axis of 4D noise, in lattice cells
+     */
+    float time;
+    /**
+     * period of 4D noise along the time axis, in lattice cells, 0 for no looping
+     */
+    float period;
 } TxKit_GradientNoiseParams;
 
+typedef struct {
+    /**
+     * height of a unit of the height texture, in pixels
+     */
+    float strength;
+    /**
+     * gradient estimation kernel (0: central differences, 1: Sobel)
+     */
+    int32_t kernel;
+    /**
+     * texture inputs, the height is bound to unit 0
+     */
+    TxKit_ImageIo *io;
+} TxKit_NormalMapParams;
+
 typedef struct {
     /**
      * pseudo-random seed
@@ -142,34 +490,300 @@ typedef struct {
      */
     int32_t noise_profile;
     /**
-     * noise weights
+     * noise weights
+     */
+    int32_t noise_weights;
+    /**
+     * point distribution
+     */
+    int32_t noise_point_distribution;
+    /**
+     * noise frequency (in oscillations / noise cell)
+     */
+    float noise_frequency;
+    /**
+     * noise angle (in radians)
+     */
+    float noise_angle;
+    /**
+     * animation time, in the same unit as `period`
+     */
+    float time;
+    /**
+     * animation period, 0 for no animation
+     */
+    float period;
+    /**
+     * jittering amount, 0 = no random, 1 = full subcell random
+     */
+    float jitter_amount;
+    /**
+     * max jittering subcells, 0 = no limit
+     */
+    int32_t jitter_max;
+    /**
+     * texture inputs
+     */
+    TxKit_ImageIo *io;
+} TxKit_PhasorNoiseParams;
+
+typedef struct {
+    /**
+     * pseudo-random seed of the tile IDs
+     */
+    uint32_t global_seed;
+    /**
+     * number of tiles across the image width
+     */
+    float scale;
+    /**
+     * tile shape (0: hexagons, 1: triangles)
+     */
+    int32_t shape;
+    /**
+     * width of the mortar joints, in tile widths
+     */
+    float mortar_width;
+    /**
+     * width of the anti-aliasing filter, in pixels (0: no anti-aliasing)
+     */
+    float antialiasing;
+} TxKit_PolygonPatternParams;
+
+/**
+ * Callback receiving the number of completed steps, the total number of steps, and the user
+ * data pointer given to txkit_method_set_progress
+ */
+typedef void (*TxKit_ProgressCallback)(uintptr_t completed, uintptr_t total, void *user_data);
+
+typedef struct {
+    /**
+     * output values (0: signed distance, 1: coverage)
+     */
+    int32_t output_mode;
+    /**
+     * width of the anti-aliasing filter for the coverage, in pixels (0: no anti-aliasing)
+     */
+    float antialiasing;
+    /**
+     * shapes of the distance field
+     */
+    TxKit_Sdf2dShapes *shapes;
+    /**
+     * texture inputs, the shape data is bound to unit 0 before computing
+     */
+    TxKit_ImageIo *io;
+} TxKit_Sdf2dParams;
+
+typedef struct {
+    /**
+     * pseudo-random seed
+     */
+    uint32_t global_seed;
+    /**
+     * lattice scale (size in pixels)
+     */
+    float scale;
+    /**
+     * stats mode (0: normal, 1: process, 2: lookat)
+     */
+    int32_t stats_mode;
+    /**
+     * look-at parameter (if stats_mode == lookat) in [0, 1]^2
+     */
+    TxKit_Vector2_f32 stats_look_at;
+    /**
+     * output channels
+     */
+    int32_t output_mode;
+    /**
+     * noise dimensions
+     */
+    int32_t dimensions;
+    /**
+     * position along the time axis of 4D noise, in lattice cells
+     */
+    float time;
+    /**
+     * period of 4D noise along the time axis, in lattice cells, 0 for no looping
+     */
+    float period;
+} TxKit_SimplexNoiseParams;
+
+typedef struct {
+    /**
+     * pseudo-random seed
+     */
+    uint32_t global_seed;
+    /**
+     * lattice scale (size in pixels)
+     */
+    float scale;
+    /**
+     * stats mode (0: normal, 1: process, 2: lookat)
+     */
+    int32_t stats_mode;
+    /**
+     * look-at parameter (if stats_mode == lookat) in [0, 1]^2
+     */
+    TxKit_Vector2_f32 stats_look_at;
+    /**
+     * kernel profile
+     */
+    int32_t kernel;
+    /**
+     * kernel count per cell
+     */
+    int32_t kernel_count;
+    /**
+     * point distribution
+     */
+    int32_t noise_point_distribution;
+    /**
+     * jittering amount, 0 = no random, 1 = full subcell random
+     */
+    float jitter_amount;
+    /**
+     * max jittering subcells, 0 = no limit
+     */
+    int32_t jitter_max;
+    /**
+     * kernel support radius (in noise cells)
+     */
+    float kernel_radius;
+} TxKit_SparseConvolutionNoiseParams;
+
+typedef struct {
+    /**
+     * pseudo-random seed of the stripe IDs
+     */
+    uint32_t global_seed;
+    /**
+     * number of stripe periods across the image
+     */
+    float scale;
+    /**
+     * direction across the stripes (in radians, 0: vertical stripes)
+     */
+    float orientation;
+    /**
+     * fraction of the period covered by the stripe
+     */
+    float duty;
+    /**
+     * width of the anti-aliasing filter, in pixels (0: no anti-aliasing)
+     */
+    float antialiasing;
+} TxKit_StripePatternParams;
+
+typedef struct {
+    /**
+     * pseudo-random seed of the tile offsets
+     */
+    uint32_t global_seed;
+    /**
+     * number of exemplar widths across the output image
+     */
+    float repeat;
+    /**
+     * size of the triangular tiles, in exemplar widths
+     */
+    float tile_size;
+    /**
+     * texture inputs, the exemplar is bound to unit 0
+     */
+    TxKit_ImageIo *io;
+} TxKit_TilingAndBlendingParams;
+
+typedef struct {
+    /**
+     * pseudo-random seed of the tile rotations
+     */
+    uint32_t global_seed;
+    /**
+     * number of tiles across the image
+     */
+    float scale;
+    /**
+     * tile motif (0: arcs, 1: triangles)
+     */
+    int32_t tile;
+    /**
+     * width of the arcs, in tile widths
+     */
+    float line_width;
+    /**
+     * width of the anti-aliasing filter, in pixels (0: no anti-aliasing)
+     */
+    float antialiasing;
+} TxKit_TruchetPatternParams;
+
+typedef struct {
+    /**
+     * pseudo-random seed
+     */
+    uint32_t global_seed;
+    /**
+     * lattice scale (size in pixels)
+     */
+    float scale;
+    /**
+     * stats mode (0: normal, 1: process, 2: lookat)
+     */
+    int32_t stats_mode;
+    /**
+     * look-at parameter (if stats_mode == lookat) in [0, 1]^2
+     */
+    TxKit_Vector2_f32 stats_look_at;
+    /**
+     * noise dimensions
+     */
+    int32_t dimensions;
+    /**
+     * position along the time axis of 4D noise, in lattice cells
+     */
+    float time;
+    /**
+     * period of 4D noise along the time axis, in lattice cells, 0 for no looping
+     */
+    float period;
+} TxKit_ValueNoiseParams;
+
+typedef struct {
+    /**
+     * pseudo-random seed
+     */
+    uint32_t global_seed;
+    /**
+     * distribution of the samples (0: uniform, 1: gaussian, 2: exponential, 3: bernoulli, 4:
+     * discrete)
      */
-    int32_t noise_weights;
+    int32_t distribution;
     /**
-     * point distribution
+     * channel sampling (0: independent, 1: shared)
      */
-    int32_t noise_point_distribution;
+    int32_t channels;
     /**
-     * noise frequency (in oscillations / noise cell)
+     * mean of the gaussian distribution
      */
-    float noise_frequency;
+    float mean;
     /**
-     * noise angle (in radians)
+     * standard deviation of the gaussian distribution
      */
-    float noise_angle;
+    float std_dev;
     /**
-     * jittering amount, 0 = no random, 1 = full subcell random
+     * rate of the exponential distribution
      */
-    float jitter_amount;
+    float rate;
     /**
-     * max jittering subcells, 0 = no limit
+     * probability of 1 for the bernoulli distribution
      */
-    int32_t jitter_max;
+    float probability;
     /**
-     * texture inputs
+     * number of levels of the discrete distribution
      */
-    TxKit_ImageIo *io;
-} TxKit_PhasorNoiseParams;
+    uint32_t levels;
+} TxKit_WhiteNoiseParams;
 
 typedef struct {
     /**
@@ -188,33 +802,35 @@ typedef struct {
      * look-at parameter (if stats_mode == lookat) in [0, 1]^2
      */
     TxKit_Vector2_f32 stats_look_at;
-} TxKit_SimplexNoiseParams;
-
-typedef struct {
     /**
-     * pseudo-random seed
+     * cell lookahead: number of cells to check for feature points
      */
-    uint32_t global_seed;
+    int32_t lookahead;
     /**
-     * lattice scale (size in pixels)
+     * feature point count per cell
      */
-    float scale;
+    int32_t point_count;
     /**
-     * stats mode (0: normal, 1: process, 2: lookat)
+     * point distribution
      */
-    int32_t stats_mode;
+    int32_t point_distribution;
     /**
-     * look-at parameter (if stats_mode == lookat) in [0, 1]^2
+     * jittering amount, 0 = no random, 1 = full subcell random
      */
-    TxKit_Vector2_f32 stats_look_at;
-} TxKit_ValueNoiseParams;
-
-typedef struct {
+    float jitter_amount;
     /**
-     * pseudo-random seed
+     * max jittering subcells, 0 = no limit
      */
-    uint32_t global_seed;
-} TxKit_WhiteNoiseParams;
+    int32_t jitter_max;
+    /**
+     * distance metric
+     */
+    int32_t metric;
+    /**
+     * output value
+     */
+    int32_t output_mode;
+} TxKit_WorleyNoiseParams;
 
 #ifdef __cplusplus
 extern "C" {
@@ -229,6 +845,17 @@ extern "C" {
  */
 TXKIT_API void txkit_context_destroy(TxKit_Context *ctx);
 
+/**
+ * Create a new context, preferring the GPU
+ *
+ * A CPU context is created if a GPU context can't be created.
+ *
+ * # Returns
+ *
+ * Pointer to the created context, or null if the creation failed.
+ */
+TXKIT_API TxKit_Context *txkit_context_new_auto(void);
+
 /**
  * Create a new CPU context
  *
@@ -512,7 +1139,8 @@ TXKIT_API int32_t txkit_image_upload(TxKit_Image *image);
  * * `ctx`: context to use for computing the image
  * * `method`: texturing method
  * * `tgt`: target image to be computed
- * * `params`: pointer to the parameter structure for this method
+ * * `params`: pointer to the parameter structure for this method, or null to use the parameters
+ *   set with the txkit_method_set_param_* functions
  * * `params_size`: size of the parameter structure
  *
  * # Returns
@@ -535,6 +1163,96 @@ int32_t txkit_method_compute(TxKit_Context *ctx,
  */
 TXKIT_API void txkit_method_destroy(TxKit_Method *method);
 
+/**
+ * Get a float parameter of a method by name
+ *
+ * # Parameters
+ *
+ * * `method`: method to query
+ * * `name`: name of the parameter
+ * * `value`: pointer to the value to write
+ *
+ * # Returns
+ *
+ * TxKit_SUCCESS if no error occurred, else a non-zero code.
+ *
+ * # Safety
+ *
+ * `value` must be a valid pointer to write the value to.
+ */
+TXKIT_API
+int32_t txkit_method_get_param_f32(const TxKit_Method *method,
+                                   const char *name,
+                                   float *value);
+
+/**
+ * Get a signed integer parameter of a method by name
+ *
+ * # Parameters
+ *
+ * * `method`: method to query
+ * * `name`: name of the parameter
+ * * `value`: pointer to the value to write
+ *
+ * # Returns
+ *
+ * TxKit_SUCCESS if no error occurred, else a non-zero code.
+ *
+ * # Safety
+ *
+ * `value` must be a valid pointer to write the value to.
+ */
+TXKIT_API
+int32_t txkit_method_get_param_i32(const TxKit_Method *method,
+                                   const char *name,
+                                   int32_t *value);
+
+/**
+ * Get an unsigned integer parameter of a method by name
+ *
+ * # Parameters
+ *
+ * * `method`: method to query
+ * * `name`: name of the parameter
+ * * `value`: pointer to the value to write
+ *
+ * # Returns
+ *
+ * TxKit_SUCCESS if no error occurred, else a non-zero code.
+ *
+ * # Safety
+ *
+ * `value` must be a valid pointer to write the value to.
+ */
+TXKIT_API
+int32_t txkit_method_get_param_u32(const TxKit_Method *method,
+                                   const char *name,
+                                   uint32_t *value);
+
+/**
+ * Get a 2D vector parameter of a method by name
+ *
+ * # Parameters
+ *
+ * * `method`: method to query
+ * * `name`: name of the parameter
+ * * `x`: pointer to the first component of the value to write
+ * * `y`: pointer to the second component of the value to write
+ *
+ * # Returns
+ *
+ * TxKit_SUCCESS if no error occurred, else a non-zero code.
+ *
+ * # Safety
+ *
+ * `x` and `y` must be valid pointers to floats.
+ */
+TXKIT_API
+int32_t txkit_method_get_param_vec2(const TxKit_Method *method,
+                                    const char *name,
+                                    float *x,
+                                    float *y);
+
 /**
  * Create a new method by name
  *
@@ -550,6 +1268,206 @@ TXKIT_API void txkit_method_destroy(TxKit_Method *method);
  */
 TXKIT_API TxKit_Method *txkit_method_new(const TxKit_Registry *registry, const char *method_name);
 
+/**
+ * Set the method computing an input of a method by name
+ *
+ * The inputs of a method are listed in its metadata, e.g. `source` and `displacement` for
+ * `domain_warp`. Inner methods are computed with the parameters set on them.
+ *
+ * # Parameters
+ *
+ * * `method`: method to update
+ * * `name`: name of the input
+ * * `input`: inner method, or NULL to use the texture binding of the input instead
+ *
+ * # Returns
+ *
+ * TxKit_SUCCESS if no error occurred, else a non-zero code.
+ *
+ * # Safety
+ *
+ * `input` must be NULL or a method returned by txkit_method_new. It is consumed by this call,
+ * even if it fails, and must not be used or destroyed afterwards. */
+TXKIT_API
+int32_t txkit_method_set_input(TxKit_Method *method,
+                               const char *name,
+                               TxKit_Method *input);
+
+/**
+ * Set a float parameter of a method by name
+ *
+ * The parameters set on a method are used when calling txkit_method_compute without a parameter
+ * structure.
+ *
+ * # Parameters
+ *
+ * * `method`: method to update
+ * * `name`: name of the parameter
+ * * `value`: new value of the parameter
+ *
+ * # Returns
+ *
+ * TxKit_SUCCESS if no error occurred, else a non-zero code.
+ */
+TXKIT_API int32_t txkit_method_set_param_f32(TxKit_Method *method, const char *name, float value);
+
+/**
+ * Set a signed integer parameter of a method by name
+ *
+ * The parameters set on a method are used when calling txkit_method_compute without a parameter
+ * structure.
+ *
+ * # Parameters
+ *
+ * * `method`: method to update
+ * * `name`: name of the parameter
+ * * `value`: new value of the parameter
+ *
+ * # Returns
+ *
+ * TxKit_SUCCESS if no error occurred, else a non-zero code.
+ */
+TXKIT_API int32_t txkit_method_set_param_i32(TxKit_Method *method, const char *name, int32_t value);
+
+/**
+ * Set an unsigned integer parameter of a method by name
+ *
+ * The parameters set on a method are used when calling txkit_method_compute without a parameter
+ * structure.
+ *
+ * # Parameters
+ *
+ * * `method`: method to update
+ * * `name`: name of the parameter
+ * * `value`: new value of the parameter
+ *
+ * # Returns
+ *
+ * TxKit_SUCCESS if no error occurred, else a non-zero code.
+ */
+TXKIT_API
+int32_t txkit_method_set_param_u32(TxKit_Method *method,
+                                   const char *name,
+                                   uint32_t value);
+
+/**
+ * Set a 2D vector parameter of a method by name
+ *
+ * The parameters set on a method are used when calling txkit_method_compute without a parameter
+ * structure.
+ *
+ * # Parameters
+ *
+ * * `method`: method to update
+ * * `name`: name of the parameter
+ * * `x`: new value of the first component of the parameter
+ * * `y`: new value of the second component of the parameter
+ *
+ * # Returns
+ *
+ * TxKit_SUCCESS if no error occurred, else a non-zero code.
+ */
+TXKIT_API
+int32_t txkit_method_set_param_vec2(TxKit_Method *method,
+                                    const char *name,
+                                    float x,
+                                    float y);
+
+/**
+ * Set the callback notified of the progress of long computations of a method, e.g. `erosion`
+ *
+ * The callback is called on the thread calling txkit_method_compute. Methods which complete
+ * quickly don't report progress, and ignore the callback.
+ *
+ * # Parameters
+ *
+ * * `method`: method to update
+ * * `callback`: progress callback, or NULL to disable progress reports
+ * * `user_data`: pointer passed to every call of the callback
+ *
+ * # Safety
+ *
+ * `user_data` must remain valid until the callback is replaced or the method is destroyed.
+ */
+TXKIT_API
+void txkit_method_set_progress(TxKit_Method *method,
+                               TxKit_ProgressCallback callback,
+                               void *user_data);
+
+/**
+ * Set a shape list parameter of a method by name, e.g. `shapes` for `sdf_2d`
+ *
+ * The shapes are copied, so the shape list can be changed or destroyed afterwards. The
+ * parameters set on a method are used when calling txkit_method_compute without a parameter
+ * structure.
+ *
+ * # Parameters
+ *
+ * * `method`: method to update
+ * * `name`: name of the parameter
+ * * `shapes`: shape list to copy
+ *
+ * # Returns
+ *
+ * TxKit_SUCCESS if no error occurred, else a non-zero code.
+ */
+TXKIT_API
+int32_t txkit_method_set_sdf_2d_shapes(TxKit_Method *method,
+                                       const char *name,
+                                       const TxKit_Sdf2dShapes *shapes);
+
+/**
+ * Bind an image to a texture unit of a method by name
+ *
+ * The bindings set on a method are used when calling txkit_method_compute without a parameter
+ * structure. The image must outlive the binding.
+ *
+ * # Parameters
+ *
+ * * `method`: method to update
+ * * `name`: name of the texture unit, e.g. `height`
+ * * `image`: image to bind, or NULL to clear the binding
+ *
+ * # Returns
+ *
+ * TxKit_SUCCESS if no error occurred, else a non-zero code.
+ */
+TXKIT_API
+int32_t txkit_method_set_texture_binding(TxKit_Method *method,
+                                         const char *name,
+                                         TxKit_Image *image);
+
+/**
+ * Check if a method can compute into an image in a context
+ *
+ * # Parameters
+ *
+ * * `ctx`: context to compute in
+ * * `method`: texturing method
+ * * `tgt`: target image
+ *
+ * # Returns
+ *
+ * true if the method supports this context and image, false otherwise.
+ */
+TXKIT_API
+bool txkit_method_supports(const TxKit_Context *ctx,
+                           const TxKit_Method *method,
+                           const TxKit_Image *tgt);
+
+/**
+ * Get the number of methods in a registry
+ *
+ * # Parameters
+ *
+ * * `registry`: registry to query
+ *
+ * # Returns
+ *
+ * Number of methods in the registry.
+ */
+TXKIT_API uintptr_t txkit_registry_count(const TxKit_Registry *registry);
+
 /**
  * Destroy a registry
  *
@@ -559,6 +1477,23 @@ TXKIT_API TxKit_Method *txkit_method_new(const TxKit_Registry *registry, const c
  */
 TXKIT_API void txkit_registry_destroy(TxKit_Registry *registry);
 
+/**
+ * Get the name of a method in a registry
+ *
+ * Methods are sorted by name.
+ *
+ * # Parameters
+ *
+ * * `registry`: registry to query
+ * * `index`: index of the method, less than `txkit_registry_count(registry)`
+ *
+ * # Returns
+ *
+ * Null pointer if the index is out of range, otherwise pointer to the name of the method. The
+ * name is owned by the registry.
+ */
+TXKIT_API const char *txkit_registry_name_at(const TxKit_Registry *registry, uintptr_t index);
+
 /**
  * Create a new registry with txkit built-in methods registered
  *
@@ -568,6 +1503,61 @@ TXKIT_API void txkit_registry_destroy(TxKit_Registry *registry);
  */
 TXKIT_API TxKit_Registry *txkit_registry_new_builtin(void);
 
+/**
+ * Add a shape at the end of a shape list
+ *
+ * # Parameters
+ *
+ * * `shapes`: shape list to change
+ * * `kind`: kind of primitive, see `SDF_2D_SHAPE_*`
+ * * `op`: operator combining the shape with the previous shapes, see `SDF_2D_OP_*`
+ * * `smoothness`: smoothing radius of the operator, 0 for a sharp boolean operator
+ * * `data`: pointer to the primitive data, see `SDF_2D_SHAPE_*` for the layout
+ * * `len`: number of elements in `data`
+ *
+ * # Returns
+ *
+ * TxKit_SUCCESS on success, non-zero on error
+ *
+ * # Safety
+ *
+ * `data` must point to at least `len` floats.
+ */
+TXKIT_API
+int32_t txkit_sdf_2d_shapes_add(TxKit_Sdf2dShapes *shapes,
+                                int32_t kind,
+                                int32_t op,
+                                float smoothness,
+                                const float *data,
+                                uintptr_t len);
+
+/**
+ * Remove all the shapes of a shape list
+ *
+ * # Parameters
+ *
+ * * `shapes`: shape list to clear
+ */
+TXKIT_API void txkit_sdf_2d_shapes_clear(TxKit_Sdf2dShapes *shapes);
+
+/**
+ * Destroy a shape list
+ *
+ * # Parameters
+ *
+ * * `shapes`: shape list to destroy
+ *
+ * # Safety
+ *
+ * `shapes` must have been returned by txkit_sdf_2d_shapes_new, and not destroyed yet.
+ */
+TXKIT_API void txkit_sdf_2d_shapes_destroy(TxKit_Sdf2dShapes *shapes);
+
+/**
+ * Create a new empty shape list for the Sdf2d method
+ */
+TXKIT_API TxKit_Sdf2dShapes *txkit_sdf_2d_shapes_new(void);
+
 #ifdef __cplusplus
 } // extern "C"
 #endif // __cplusplus
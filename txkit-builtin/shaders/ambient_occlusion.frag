@@ -0,0 +1,49 @@
+#version 460 core
+
+/**
+ * @file ambient_occlusion.frag
+ * @brief Horizon-based ambient occlusion of a heightfield
+ * @author Alixinne <alixinne@pm.me>
+ * @see https://doi.org/10.1145/1401032.1401061
+ *
+ * Finds the elevation of the horizon in a set of directions around the
+ * current pixel by marching along the heightfield, and averages the sine of
+ * the horizon elevation angles into the occlusion.
+ */
+
+layout(location = 0) in vec3 pixelUv;
+layout(location = 0) out vec4 o_FragColor;
+
+#include "heightfield.glsl"
+
+layout(location = 22) uniform float radius;
+layout(location = 23) uniform int directions;
+layout(location = 24) uniform int steps;
+
+void main() {
+    vec3 uv = domainUv(pixelUv);
+
+    vec2 p = uv.xy;
+    float c = heightAt(p, vec2(0.));
+    float occlusion = 0.;
+
+    for (int i = 0; i < directions; ++i) {
+        float a = M_2PI * float(i) / float(directions);
+        vec2 dir = vec2(cos(a), sin(a));
+
+        // Sine of the horizon elevation angle
+        float s = 0.;
+        for (int j = 1; j <= steps; ++j) {
+            float t = radius * float(j) / float(steps);
+            float dh = heightAt(p, t * dir) - c;
+            s = max(s, dh / sqrt(dh * dh + t * t));
+        }
+
+        occlusion += s;
+    }
+
+    float v = 1. - occlusion / float(max(directions, 1));
+    o_FragColor = vec4(v, v, v, 1.);
+}
+
+// vim: ft=glsl.doxygen
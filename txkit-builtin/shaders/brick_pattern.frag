@@ -0,0 +1,44 @@
+#version 460 core
+
+/**
+ * @file brick_pattern.frag
+ * @brief Brick pattern
+ * @author Alixinne <alixinne@pm.me>
+ *
+ * Rows of bricks separated by mortar joints, every row being shifted by
+ * `row_offset` bricks relative to the previous one. Distances are measured in
+ * brick heights, so the mortar joints have the same width in both directions.
+ */
+
+layout(location = 0) in vec3 pixelUv;
+layout(location = 0) out vec4 o_FragColor;
+
+#include "patterns.glsl"
+
+layout(location = 20) uniform vec2 scale;
+layout(location = 21) uniform float row_offset;
+layout(location = 22) uniform float mortar_width;
+layout(location = 23) uniform float antialiasing;
+
+void main() {
+    vec3 uv = domainUv(pixelUv);
+
+    vec2 p = uv.xy * scale;
+    float row = floor(p.y);
+    p.x += row_offset * row;
+
+    ivec2 cell = ivec2(floor(p.x), row);
+
+    // Position in the brick, in brick heights
+    float aspect = float(iResolution.x) * scale.y / (float(iResolution.y) * scale.x);
+    vec2 f = fract(p) * vec2(aspect, 1.);
+
+    // Distance to the closest edge of the brick
+    float d = min(min(f.x, aspect - f.x), min(f.y, 1. - f.y));
+    float w = antialiasing * scale.y / float(iResolution.y);
+
+    o_FragColor =
+        vec4(filteredEdge(d - .5 * mortar_width, w), tileId(cell), cell);
+}
+
+// vim: ft=glsl.doxygen
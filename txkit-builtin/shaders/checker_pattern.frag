@@ -0,0 +1,34 @@
+#version 460 core
+
+/**
+ * @file checker_pattern.frag
+ * @brief Checkerboard pattern
+ * @author Alixinne <alixinne@pm.me>
+ *
+ * Checkerboard of `scale` squares across the image, anti-aliased with a box
+ * filter. The checkerboard is the XOR of two pulse trains, so it can be
+ * filtered exactly from the filtered pulse trains.
+ */
+
+layout(location = 0) in vec3 pixelUv;
+layout(location = 0) out vec4 o_FragColor;
+
+#include "patterns.glsl"
+
+layout(location = 20) uniform float scale;
+layout(location = 21) uniform float antialiasing;
+
+void main() {
+    vec3 uv = domainUv(pixelUv);
+
+    vec2 p = uv.xy * scale;
+    vec2 w = antialiasing * scale / vec2(iResolution.xy);
+
+    float a = filteredPulse(p.x, .5, w.x);
+    float b = filteredPulse(p.y, .5, w.y);
+    ivec2 cell = ivec2(floor(p));
+
+    o_FragColor = vec4(a * b + (1. - a) * (1. - b), tileId(cell), cell);
+}
+
+// vim: ft=glsl.doxygen
@@ -0,0 +1,109 @@
+#version 460 core
+
+/**
+ * @file curl_noise.frag
+ * @brief Curl noise fragment shader
+ * @author Alixinne <alixinne@pm.me>
+ * @see https://doi.org/10.1145/1276377.1276435
+ *
+ * Divergence-free vector field, computed as the curl of a gradient or simplex
+ * noise potential. 2D images use a scalar potential, 3D images (more than one
+ * layer) use a vector potential made of three noises.
+ */
+
+layout(location = 0) in vec3 pixelUv;
+layout(location = 0) out vec4 o_FragColor;
+
+/// Base pseudo-random seed
+layout(location = 10) uniform uint globalSeed;
+
+/// Seed of the current potential component
+uint potentialSeed;
+
+// Lattice noises are evaluated with the seed of the current potential component
+#define PARAM_GLOBAL_SEED potentialSeed
+
+// Required built-ins
+#include "noise.glsl"
+#include "shared.glsl"
+
+// Noise statistics helpers
+#include "noise_stats.glsl"
+
+// Potential noises
+#include "gradient_noise.glsl"
+#include "simplex_noise.glsl"
+
+layout(location = 20) uniform int basis;
+layout(location = 21) uniform float amplitude;
+
+#define CURL_BASIS_GRADIENT 0
+#define CURL_BASIS_SIMPLEX 1
+
+/**
+ * @brief Compute the gradient of a 2D potential component
+ * @param position Input position in the viewport
+ * @param seed Seed of the potential component
+ * @return Gradient, in lattice units
+ */
+vec2 potentialGradient(vec2 position, uint seed) {
+    potentialSeed = seed;
+
+    if (basis == CURL_BASIS_GRADIENT) {
+        return gradientNoiseDerivatives(
+                   latticeSample(position, LATTICE_MODE_RECT_2D))
+            .yz;
+    } else {
+        return simplexNoiseDerivatives(
+                   latticeSample(position, LATTICE_MODE_SIMPLEX_2D))
+            .yz;
+    }
+}
+
+/**
+ * @brief Compute the gradient of a 3D potential component
+ * @param position Input position in the viewport
+ * @param seed Seed of the potential component
+ * @return Gradient, in lattice units
+ */
+vec3 potentialGradient(vec3 position, uint seed) {
+    potentialSeed = seed;
+
+    if (basis == CURL_BASIS_GRADIENT) {
+        return gradientNoise3DDerivatives(
+                   latticeSample3D(position, LATTICE_MODE_RECT_3D))
+            .yzw;
+    } else {
+        return simplexNoise3DDerivatives(
+                   latticeSample3D(position, LATTICE_MODE_SIMPLEX_3D))
+            .yzw;
+    }
+}
+
+void main() {
+    vec3 uv = domainUv(pixelUv);
+
+    if (basis != CURL_BASIS_GRADIENT && basis != CURL_BASIS_SIMPLEX) {
+        // Invalid enum value
+        o_FragColor = vec4(1., 0., 1., 1.);
+        return;
+    }
+
+    if (iResolution.z > 1) {
+        // Curl of the vector potential (psi1, psi2, psi3)
+        vec3 d1 = potentialGradient(uv, globalSeed);
+        vec3 d2 = potentialGradient(uv, globalSeed + 1);
+        vec3 d3 = potentialGradient(uv, globalSeed + 2);
+
+        vec3 curl = vec3(d3.y - d2.z, d1.z - d3.x, d2.x - d1.y);
+        o_FragColor = vec4(to01(amplitude * curl), 1.);
+    } else {
+        // Curl of the scalar potential psi
+        vec2 d = potentialGradient(uv.xy, globalSeed);
+
+        vec2 curl = vec2(d.y, -d.x);
+        o_FragColor = vec4(to01(amplitude * curl), 0., 1.);
+    }
+}
+
+// vim: ft=glsl.doxygen
@@ -0,0 +1,49 @@
+#version 460 core
+
+/**
+ * @file curvature.frag
+ * @brief Curvature map of a heightfield
+ * @author Alixinne <alixinne@pm.me>
+ *
+ * Mean curvature of the heightfield surface, from central differences of the
+ * adjacent pixels. Convex areas are brighter than .5, concave areas darker.
+ */
+
+layout(location = 0) in vec3 pixelUv;
+layout(location = 0) out vec4 o_FragColor;
+
+#include "heightfield.glsl"
+
+layout(location = 22) uniform float radius;
+
+void main() {
+    vec3 uv = domainUv(pixelUv);
+
+    vec2 p = uv.xy;
+    float c = heightAt(p, vec2(0.));
+    float l = heightAt(p, vec2(-1., 0.));
+    float r = heightAt(p, vec2(1., 0.));
+    float t = heightAt(p, vec2(0., -1.));
+    float b = heightAt(p, vec2(0., 1.));
+    float tl = heightAt(p, vec2(-1., -1.));
+    float tr = heightAt(p, vec2(1., -1.));
+    float bl = heightAt(p, vec2(-1., 1.));
+    float br = heightAt(p, vec2(1., 1.));
+
+    // First and second derivatives of the height
+    vec2 d = .5 * vec2(r - l, b - t);
+    float dxx = r - 2. * c + l;
+    float dyy = b - 2. * c + t;
+    float dxy = .25 * (br - bl - tr + tl);
+
+    // Mean curvature, in inverse pixels, positive on convex areas
+    float w = 1. + dot(d, d);
+    float k = -((1. + d.y * d.y) * dxx - 2. * d.x * d.y * dxy +
+                (1. + d.x * d.x) * dyy) /
+              (2. * w * sqrt(w));
+
+    float v = .5 + .5 * clamp(k * radius, -1., 1.);
+    o_FragColor = vec4(v, v, v, 1.);
+}
+
+// vim: ft=glsl.doxygen
@@ -0,0 +1,57 @@
+#version 460 core
+
+/**
+ * @file distance_transform.frag
+ * @brief Jump flooding Euclidean distance transform
+ * @author Alixinne <alixinne@pm.me>
+ *
+ * Last pass of the jump flooding distance transform. The seed pass, in
+ * distance_transform_seed.frag, and the propagation passes, in
+ * distance_transform_jump.frag, find for every pixel an approximation of its
+ * closest mask and background pixels. This pass outputs the signed distance to
+ * the one on the other side of the boundary.
+ */
+
+layout(location = 0) in vec3 uv;
+layout(location = 0) out vec4 o_FragColor;
+
+#include "shared.glsl"
+
+layout(location = 20) uniform float threshold;
+
+layout(location = 21, binding = 0) uniform sampler2D mask;
+layout(location = 22, binding = 1) uniform sampler2D seeds;
+
+/// Distance to the empty set
+#define DISTANCE_TRANSFORM_FAR 1e9
+
+/**
+ * @brief Check if a pixel of the output is part of the mask
+ * @param p Integer pixel coordinates
+ */
+bool inMask(ivec2 p) {
+    return texture(mask, (vec2(p) + .5) / vec2(iResolution.xy)).r > threshold;
+}
+
+void main() {
+    ivec2 p = ivec2(gl_FragCoord.xy);
+
+    // Red, green: closest mask pixel, blue, alpha: closest background pixel
+    vec4 s = texelFetch(seeds, p, 0);
+
+    bool inside = inMask(p);
+    vec2 seed = inside ? s.zw : s.xy;
+    float d = seed.x < 0. ? DISTANCE_TRANSFORM_FAR : length(seed - vec2(p));
+
+    if (d >= DISTANCE_TRANSFORM_FAR) {
+        d = DISTANCE_TRANSFORM_FAR;
+    } else {
+        // The boundary lies halfway between pixel centers
+        d = (d - .5) / float(iResolution.x);
+    }
+
+    d = inside ? -d : d;
+    o_FragColor = vec4(d, d, d, 1.);
+}
+
+// vim: ft=glsl.doxygen
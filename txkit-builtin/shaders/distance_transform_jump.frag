@@ -0,0 +1,59 @@
+#version 460 core
+
+/**
+ * @file distance_transform_jump.frag
+ * @brief Propagation pass of the jump flooding distance transform
+ * @author Alixinne <alixinne@pm.me>
+ *
+ * Every pixel looks at the seeds found by its 8 neighbours at a distance of
+ * `jump` pixels, and keeps the closest mask and background pixels. See Rong and
+ * Tan, Jump Flooding in GPU with Applications to Voronoi Diagram and Distance
+ * Transform, 2006.
+ */
+
+layout(location = 0) in vec3 uv;
+layout(location = 0) out vec4 o_FragColor;
+
+#include "shared.glsl"
+
+layout(location = 20) uniform int jump;
+
+layout(location = 21, binding = 0) uniform sampler2D seeds;
+
+/**
+ * @brief Pick the closest of two seeds to a pixel
+ * @param p Pixel coordinates
+ * @param a Current seed, with negative coordinates if not found yet
+ * @param b Candidate seed, with negative coordinates if not found yet
+ */
+vec2 closestSeed(vec2 p, vec2 a, vec2 b) {
+    if (b.x < 0.)
+        return a;
+    if (a.x < 0.)
+        return b;
+
+    vec2 da = a - p, db = b - p;
+    return dot(db, db) < dot(da, da) ? b : a;
+}
+
+void main() {
+    ivec2 p = ivec2(gl_FragCoord.xy);
+    ivec2 size = ivec2(iResolution.xy);
+    vec4 s = texelFetch(seeds, p, 0);
+
+    for (int dy = -1; dy <= 1; ++dy) {
+        for (int dx = -1; dx <= 1; ++dx) {
+            ivec2 q = p + jump * ivec2(dx, dy);
+            if (any(lessThan(q, ivec2(0))) || any(greaterThanEqual(q, size)))
+                continue;
+
+            vec4 t = texelFetch(seeds, q, 0);
+            s.xy = closestSeed(vec2(p), s.xy, t.xy);
+            s.zw = closestSeed(vec2(p), s.zw, t.zw);
+        }
+    }
+
+    o_FragColor = s;
+}
+
+// vim: ft=glsl.doxygen
@@ -0,0 +1,34 @@
+#version 460 core
+
+/**
+ * @file distance_transform_seed.frag
+ * @brief Seeds of the jump flooding distance transform
+ * @author Alixinne <alixinne@pm.me>
+ *
+ * First pass of the jump flooding distance transform, see
+ * distance_transform.frag. Every pixel of the mask is its own closest mask
+ * pixel, and every other pixel its own closest background pixel.
+ */
+
+layout(location = 0) in vec3 uv;
+layout(location = 0) out vec4 o_FragColor;
+
+#include "shared.glsl"
+
+layout(location = 20) uniform float threshold;
+
+layout(location = 21, binding = 0) uniform sampler2D mask;
+
+/// Coordinates of a seed that was not found yet
+#define DISTANCE_TRANSFORM_NO_SEED -1.
+
+void main() {
+    vec2 p = floor(gl_FragCoord.xy);
+    bool inside = texture(mask, (p + .5) / vec2(iResolution.xy)).r > threshold;
+    vec2 none = vec2(DISTANCE_TRANSFORM_NO_SEED);
+
+    // Red, green: closest mask pixel, blue, alpha: closest background pixel
+    o_FragColor = inside ? vec4(p, none) : vec4(none, p);
+}
+
+// vim: ft=glsl.doxygen
@@ -0,0 +1,75 @@
+#version 460 core
+
+/**
+ * @file domain_warp.frag
+ * @brief Domain warping fragment shader
+ * @author Alixinne <alixinne@pm.me>
+ * @see https://iquilezles.org/articles/warp/
+ *
+ * Resamples a source texture at coordinates displaced by a displacement field, or outputs
+ * these coordinates.
+ */
+
+layout(location = 0) in vec3 pixelUv;
+layout(location = 0) out vec4 o_FragColor;
+
+// Required built-ins
+#include "shared.glsl"
+
+layout(location = 20) uniform float strength;
+layout(location = 21) uniform int iterations;
+layout(location = 22) uniform int displacement_mode;
+layout(location = 23) uniform int output_mode;
+
+layout(location = 24, binding = 0) uniform sampler2D source_texture;
+layout(location = 25, binding = 1) uniform sampler2D displacement_field;
+
+#define DOMAIN_WARP_DISPLACEMENT_VECTOR 0
+#define DOMAIN_WARP_DISPLACEMENT_ANGLE 1
+
+#define DOMAIN_WARP_OUTPUT_WARPED 0
+#define DOMAIN_WARP_OUTPUT_COORDINATES 1
+
+/**
+ * @brief Sample the displacement field
+ * @param p Texture coordinates
+ * @return Displacement vector, of length at most 1
+ */
+vec2 displacement(vec2 p) {
+    vec4 d = texture(displacement_field, p);
+
+    if (displacement_mode == DOMAIN_WARP_DISPLACEMENT_ANGLE) {
+        float a = M_2PI * d.x;
+        return vec2(cos(a), sin(a));
+    }
+
+    return 2. * d.xy - 1.;
+}
+
+void main() {
+    vec3 uv = domainUv(pixelUv);
+
+    if ((displacement_mode != DOMAIN_WARP_DISPLACEMENT_VECTOR &&
+         displacement_mode != DOMAIN_WARP_DISPLACEMENT_ANGLE) ||
+        (output_mode != DOMAIN_WARP_OUTPUT_WARPED &&
+         output_mode != DOMAIN_WARP_OUTPUT_COORDINATES)) {
+        // Invalid enum value
+        o_FragColor = vec4(1., 0., 1., 1.);
+        return;
+    }
+
+    // Iterate p_{n+1} = p_0 + strength * displacement(p_n)
+    vec2 p = uv.xy;
+    for (int i = 0; i < iterations; ++i) {
+        p = uv.xy + strength * displacement(p);
+    }
+
+    if (output_mode == DOMAIN_WARP_OUTPUT_COORDINATES) {
+        o_FragColor = vec4(p, 1., 1.);
+        return;
+    }
+
+    o_FragColor = texture(source_texture, p);
+}
+
+// vim: ft=glsl.doxygen
@@ -0,0 +1,199 @@
+#version 460 core
+
+/**
+ * @file fractal_noise.frag
+ * @brief Fractal noise fragment shader
+ * @author Alixinne <alixinne@pm.me>
+ * @see https://doi.org/10.1145/325165.325247
+ *
+ * Sum of octaves of a lattice noise: fBm, turbulence and ridged variants.
+ * Value, gradient and simplex octaves may be 3D or 4D, each octave scaling
+ * the time axis like the spatial axes.
+ */
+
+layout(location = 0) in vec3 pixelUv;
+layout(location = 0) out vec4 o_FragColor;
+
+/// Base pseudo-random seed
+layout(location = 10) uniform uint globalSeed;
+/// Scale of the first octave
+layout(location = 52) uniform float scale;
+/// Time period of the first octave
+layout(location = 53) uniform float period;
+
+/// Seed of the current octave
+uint octaveSeed;
+/// Scale of the current octave
+float octaveScale;
+/// Time period of the current octave
+float octavePeriod;
+
+// Lattice noises are evaluated with the seed, scale and period of the current
+// octave
+#define PARAM_GLOBAL_SEED octaveSeed
+#define PARAM_SCALE octaveScale
+#define PARAM_PERIOD octavePeriod
+
+// Required built-ins
+#include "noise.glsl"
+#include "shared.glsl"
+
+// Noise statistics helpers
+#include "noise_stats.glsl"
+
+// Basis noises
+#include "gradient_noise.glsl"
+#include "simplex_noise.glsl"
+#include "value_noise.glsl"
+#include "worley_noise.glsl"
+
+layout(location = 20) uniform int basis;
+layout(location = 21) uniform int variant;
+layout(location = 22) uniform int octaves;
+layout(location = 23) uniform float lacunarity;
+layout(location = 24) uniform float gain;
+layout(location = 25) uniform int dimensions;
+layout(location = 26) uniform float time;
+
+#define FRACTAL_BASIS_VALUE 0
+#define FRACTAL_BASIS_GRADIENT 1
+#define FRACTAL_BASIS_SIMPLEX 2
+#define FRACTAL_BASIS_WORLEY 3
+
+#define FRACTAL_VARIANT_FBM 0
+#define FRACTAL_VARIANT_TURBULENCE 1
+#define FRACTAL_VARIANT_RIDGED 2
+
+/**
+ * @brief Evaluate value noise for the current octave
+ * @param position Input position in the viewport, followed by the time of the
+ * current octave
+ * @return Noise value, in [0, 1]
+ */
+float valueBasis(vec4 position) {
+    if (dimensions == LATTICE_DIMENSIONS_3D) {
+        return valueNoise3D(latticeSample3D(position.xyz, LATTICE_MODE_RECT_3D));
+    } else if (dimensions == LATTICE_DIMENSIONS_4D) {
+        return valueNoise4D(latticeSample4D(position, LATTICE_MODE_RECT_4D));
+    }
+
+    return valueNoise(latticeSample(position.xy, LATTICE_MODE_RECT_2D));
+}
+
+/**
+ * @brief Evaluate gradient noise for the current octave
+ * @param position Input position in the viewport, followed by the time of the
+ * current octave
+ * @return Noise value, in [-1, 1]
+ */
+float gradientBasis(vec4 position) {
+    if (dimensions == LATTICE_DIMENSIONS_3D) {
+        return gradientNoise3D(
+            latticeSample3D(position.xyz, LATTICE_MODE_RECT_3D));
+    } else if (dimensions == LATTICE_DIMENSIONS_4D) {
+        return gradientNoise4D(latticeSample4D(position, LATTICE_MODE_RECT_4D));
+    }
+
+    return gradientNoise(latticeSample(position.xy, LATTICE_MODE_RECT_2D));
+}
+
+/**
+ * @brief Evaluate simplex noise for the current octave
+ * @param position Input position in the viewport, followed by the time of the
+ * current octave
+ * @return Noise value, in [-1, 1]
+ */
+float simplexBasis(vec4 position) {
+    if (dimensions == LATTICE_DIMENSIONS_3D) {
+        return simplexNoise3D(
+            latticeSample3D(position.xyz, LATTICE_MODE_SIMPLEX_3D));
+    } else if (dimensions == LATTICE_DIMENSIONS_4D) {
+        return simplexNoise4D(
+            latticeSample4D(position, LATTICE_MODE_SIMPLEX_4D));
+    }
+
+    return simplexNoise(latticeSample(position.xy, LATTICE_MODE_SIMPLEX_2D));
+}
+
+/**
+ * @brief Evaluate the basis noise for the current octave
+ * @param position Input position in the viewport, followed by the time of the
+ * current octave
+ * @return Noise value, in [-1, 1]
+ *
+ * Worley octaves are always 2D.
+ */
+float basisNoise(vec4 position) {
+    if (basis == FRACTAL_BASIS_VALUE) {
+        return 2. * valueBasis(position) - 1.;
+    } else if (basis == FRACTAL_BASIS_GRADIENT) {
+        return gradientBasis(position);
+    } else if (basis == FRACTAL_BASIS_SIMPLEX) {
+        return simplexBasis(position);
+    } else if (basis == FRACTAL_BASIS_WORLEY) {
+        float f1, f2;
+        uint id;
+        worleyNoise(latticeSample(position.xy, LATTICE_MODE_RECT_2D), 1, 1,
+                    POINTS_RECT_JITTERED, 1., 0, WORLEY_METRIC_EUCLIDEAN, f1,
+                    f2, id);
+        return 2. * f1 - 1.;
+    }
+
+    return 0.;
+}
+
+vec3 noise(vec3 position) {
+    float value = 0., amplitude = 1., total = 0.;
+
+    // Time and period are scaled with the octaves, so all octaves loop together
+    float frequency = 1.;
+    octaveScale = scale;
+
+    for (int i = 0; i < octaves; ++i) {
+        octaveSeed = globalSeed + uint(i);
+        octavePeriod = period * frequency;
+
+        float n = basisNoise(vec4(position, time * frequency));
+
+        if (variant == FRACTAL_VARIANT_TURBULENCE) {
+            n = abs(n);
+        } else if (variant == FRACTAL_VARIANT_RIDGED) {
+            n = 1. - abs(n);
+            n *= n;
+        }
+
+        value += amplitude * n;
+        total += amplitude;
+
+        amplitude *= gain;
+        octaveScale *= lacunarity;
+        frequency *= lacunarity;
+    }
+
+    if (total != 0.) {
+        value /= total;
+    }
+
+    if (basis < FRACTAL_BASIS_VALUE || basis > FRACTAL_BASIS_WORLEY ||
+        dimensions < LATTICE_DIMENSIONS_2D ||
+        dimensions > LATTICE_DIMENSIONS_4D) {
+        // Invalid enum value
+        return vec3(1., 0., 1.);
+    } else if (variant == FRACTAL_VARIANT_FBM) {
+        return vec3(to01(value));
+    } else if (variant == FRACTAL_VARIANT_TURBULENCE ||
+               variant == FRACTAL_VARIANT_RIDGED) {
+        return vec3(value);
+    }
+
+    // Invalid enum value
+    return vec3(1., 0., 1.);
+}
+
+void main() {
+    vec3 uv = domainUv(pixelUv);
+
+    o_FragColor = vec4(noise(uv), 1.);
+}
+
+// vim: ft=glsl.doxygen
@@ -0,0 +1,136 @@
+#version 460 core
+
+/**
+ * @file gabor_noise.frag
+ * @brief Gabor noise fragment shader
+ * @author Alixinne <alixinne@pm.me>
+ * @see https://doi.org/10.1145/1531326.1531360
+ *
+ * Sparse convolution of random impulses with Gabor kernels. The orientation of
+ * the kernels is fixed (anisotropic), uniformly random (isotropic), or random
+ * within an interval around the main orientation (hybrid).
+ */
+
+layout(location = 0) in vec3 pixelUv;
+layout(location = 0) out vec4 o_FragColor;
+
+// Required built-ins
+#include "noise.glsl"
+#include "shared.glsl"
+
+// Noise statistics helpers
+#include "noise_stats.glsl"
+
+// Fast rng
+#include "lcg.glsl"
+
+// Point distributions
+#include "points.glsl"
+
+layout(location = 20) uniform int mode;
+layout(location = 21) uniform int kernel_count;
+layout(location = 22) uniform int noise_point_distribution;
+layout(location = 23) uniform float jitter_amount;
+layout(location = 24) uniform int jitter_max;
+
+layout(location = 25) uniform float bandwidth;
+layout(location = 26) uniform float frequency;
+layout(location = 27) uniform float orientation;
+layout(location = 28) uniform float orientation_spread;
+
+layout(location = 29, binding = 0) uniform sampler2D frequency_orientation_field;
+
+#define GABOR_MODE_ANISOTROPIC 0
+#define GABOR_MODE_ISOTROPIC 1
+#define GABOR_MODE_HYBRID 2
+
+/// Radius of the kernels relative to 1 / bandwidth, where the gaussian envelope
+/// reaches 5% of its peak
+#define GABOR_RADIUS 0.9765
+
+/**
+ * @brief Evaluate a Gabor kernel
+ * @param x Position relative to the kernel center, in cells
+ * @param f Frequency vector, in oscillations per cell
+ */
+float gabor(vec2 x, vec2 f) {
+    return exp(-M_PI * bandwidth * bandwidth * dot(x, x)) *
+           cos(M_2PI * dot(x, f));
+}
+
+float noiseCell(vec2 pos, ivec2 cell, uint seed) {
+    float res = 0.;
+    float radius = GABOR_RADIUS / bandwidth;
+
+    // Seed the random number generator
+    LCG rng = lcgSeed(hash(cell, seed));
+
+    // Compute impulse count
+    PointSet ps = pointSetInit(rng, noise_point_distribution, kernel_count,
+                               jitter_max, jitter_amount);
+
+    for (int i = 0; i < ps.count; ++i) {
+        vec2 kpos = pointSetNext(rng, ps, i);
+
+        // Always step the generator so all modes use the same impulses
+        float weight = lcgNext11(rng);
+        float angle = lcgNext01(rng);
+
+        vec2 x = pos - kpos;
+        if (length(x) > radius) {
+            continue;
+        }
+
+        // Kernel parameters at the impulse location
+        vec4 fo = texture(frequency_orientation_field, (vec2(cell) + kpos) / scale);
+
+        if (mode == GABOR_MODE_ISOTROPIC) {
+            angle *= M_2PI;
+        } else {
+            angle = orientation + fo.g + (mode == GABOR_MODE_HYBRID ? orientation_spread * (angle - .5) : 0.);
+        }
+
+        res += weight * gabor(x, frequency * (1. + fo.r) * vec2(cos(angle), sin(angle)));
+    }
+
+    return res;
+}
+
+float noise(LatticeNoiseSample s) {
+    float res = 0.;
+    int lookahead = int(ceil(GABOR_RADIUS / bandwidth));
+
+    ivec2 cell = s.cell;
+    for (cell.x = s.cell.x - lookahead; cell.x <= s.cell.x + lookahead; ++cell.x) {
+        for (cell.y = s.cell.y - lookahead; cell.y <= s.cell.y + lookahead; ++cell.y) {
+            // Make sure the noise tiles correctly
+            ivec2 looped_cell = latticeLoop(cell);
+
+            // Compute the position of the current point relative to the target
+            // cell
+            vec2 position = s.position - vec2(cell - s.cell);
+
+            // Add contribution of target noise cell
+            res += noiseCell(position, looped_cell, s.seed);
+        }
+    }
+
+    // Normalize by the standard deviation of the noise, with uniform weights in
+    // [-1, 1] and a kernel energy of 1 / (4 * bandwidth^2)
+    float sigma = sqrt(float(kernel_count) / 12.) / bandwidth;
+    return res / (3. * sigma);
+}
+
+void main() {
+    vec3 uv = domainUv(pixelUv);
+
+    if (mode < GABOR_MODE_ANISOTROPIC || mode > GABOR_MODE_HYBRID) {
+        // Invalid enum value
+        o_FragColor = vec4(1., 0., 1., 1.);
+        return;
+    }
+
+    o_FragColor = vec4(vec3(to01(noise(latticeSample(uv.xy, LATTICE_MODE_RECT_2D)))), 1.);
+}
+
+// vim: ft=glsl.doxygen
@@ -2,15 +2,15 @@
 
 /**
  * @file gradient_noise.frag
- * @brief 2D gradient noise fragment shader
+ * @brief 2D, 3D and 4D gradient noise fragment shader
  * @author inigo quilez
  * @author Alixinne <alixinne@pm.me>
  * @see https://www.shadertoy.com/view/XdXGW8
  *
- * 2D Gradient noise, adapted for use in txkit.
+ * 2D Gradient noise, adapted for use in txkit, and extended to 3D and 4D.
  */
 
-layout(location = 0) in vec3 uv;
+layout(location = 0) in vec3 pixelUv;
 layout(location = 0) out vec4 o_FragColor;
 
 // Required built-ins
@@ -20,34 +20,61 @@ layout(location = 0) out vec4 o_FragColor;
 // Noise statistics helpers
 #include "noise_stats.glsl"
 
-vec2 noisehash(uvec2 p, uint seed) {
-    float x = 2. * M_PI * tofloat11(hash(p, seed));
-    return vec2(cos(x), sin(x));
-}
+// Gradient noise
+#include "gradient_noise.glsl"
 
-float noise(LatticeNoiseSample s) {
-    uvec2 i = uvec2(s.cell);
-    vec2 f = s.position;
-
-    vec2 u = f * f * (3. - 2. * f);
-
-    return mix(mix(dot(noisehash(latticeLoop(i + uvec2(0, 0)), s.seed),
-                       f - uvec2(0, 0)),
-                   dot(noisehash(latticeLoop(i + uvec2(1, 0)), s.seed),
-                       f - uvec2(1, 0)),
-                   u.x),
-               mix(dot(noisehash(latticeLoop(i + uvec2(0, 1)), s.seed),
-                       f - uvec2(0, 1)),
-                   dot(noisehash(latticeLoop(i + uvec2(1, 1)), s.seed),
-                       f - uvec2(1, 1)),
-                   u.x),
-               u.y) *
-           1.49315244;
-}
+layout(location = 20) uniform int output_mode;
+layout(location = 21) uniform int dimensions;
+layout(location = 22) uniform float time;
+
+#define GRADIENT_OUTPUT_VALUE 0
+#define GRADIENT_OUTPUT_DERIVATIVES 1
 
 void main() {
-    o_FragColor = vec4(
-        vec3(to01(noise(latticeSample(uv.xy, LATTICE_MODE_RECT_2D)))), 1.0);
+    vec3 uv = domainUv(pixelUv);
+
+    // Noise value, followed by its partial derivatives
+    vec4 res;
+
+    if (dimensions == LATTICE_DIMENSIONS_2D) {
+        LatticeNoiseSample s = latticeSample(uv.xy, LATTICE_MODE_RECT_2D);
+
+        if (output_mode == GRADIENT_OUTPUT_DERIVATIVES) {
+            res = vec4(gradientNoiseDerivatives(s), 0.);
+        } else {
+            res = vec4(gradientNoise(s));
+        }
+    } else if (dimensions == LATTICE_DIMENSIONS_3D) {
+        LatticeNoiseSample3D s = latticeSample3D(uv, LATTICE_MODE_RECT_3D);
+
+        if (output_mode == GRADIENT_OUTPUT_DERIVATIVES) {
+            res = gradientNoise3DDerivatives(s);
+        } else {
+            res = vec4(gradientNoise3D(s));
+        }
+    } else if (dimensions == LATTICE_DIMENSIONS_4D) {
+        LatticeNoiseSample4D s =
+            latticeSample4D(vec4(uv, time), LATTICE_MODE_RECT_4D);
+
+        if (output_mode == GRADIENT_OUTPUT_DERIVATIVES) {
+            res = gradientNoise4DDerivatives(s);
+        } else {
+            res = vec4(gradientNoise4D(s));
+        }
+    } else {
+        // Invalid enum value
+        o_FragColor = vec4(1., 0., 1., 1.);
+        return;
+    }
+
+    if (output_mode == GRADIENT_OUTPUT_VALUE) {
+        o_FragColor = vec4(vec3(to01(res.x)), 1.0);
+    } else if (output_mode == GRADIENT_OUTPUT_DERIVATIVES) {
+        o_FragColor = to01(res);
+    } else {
+        // Invalid enum value
+        o_FragColor = vec4(1., 0., 1., 1.);
+    }
 }
 
 // vim: ft=glsl.doxygen
@@ -0,0 +1,196 @@
+/**
+ * @file gradient_noise.glsl
+ * @brief 2D, 3D and 4D gradient noise
+ * @author inigo quilez
+ * @author Alixinne <alixinne@pm.me>
+ * @see https://www.shadertoy.com/view/XdXGW8
+ *
+ * 2D, 3D and 4D gradient noise, shared by the gradient, fractal and curl
+ * noise shaders.
+ */
+
+#ifndef _GRADIENT_NOISE_GLSL_
+#define _GRADIENT_NOISE_GLSL_
+
+#include "noise_stats.glsl"
+
+vec2 gradientNoiseHash(uvec2 p, uint seed) {
+    float x = 2. * M_PI * tofloat11(hash(p, seed));
+    return vec2(cos(x), sin(x));
+}
+
+/**
+ * @brief Evaluate gradient noise
+ * @param s Sample in a rectangular lattice
+ * @return Noise value, in [-1, 1]
+ */
+float gradientNoise(LatticeNoiseSample s) {
+    uvec2 i = uvec2(s.cell);
+    vec2 f = s.position;
+
+    vec2 u = f * f * (3. - 2. * f);
+
+    return mix(mix(dot(gradientNoiseHash(latticeLoop(i + uvec2(0, 0)), s.seed),
+                       f - uvec2(0, 0)),
+                   dot(gradientNoiseHash(latticeLoop(i + uvec2(1, 0)), s.seed),
+                       f - uvec2(1, 0)),
+                   u.x),
+               mix(dot(gradientNoiseHash(latticeLoop(i + uvec2(0, 1)), s.seed),
+                       f - uvec2(0, 1)),
+                   dot(gradientNoiseHash(latticeLoop(i + uvec2(1, 1)), s.seed),
+                       f - uvec2(1, 1)),
+                   u.x),
+               u.y) *
+           1.49315244;
+}
+
+/**
+ * @brief Evaluate gradient noise and its derivatives
+ * @param s Sample in a rectangular lattice
+ * @return Noise value, in [-1, 1], followed by its partial derivatives with
+ * respect to the lattice coordinates
+ */
+vec3 gradientNoiseDerivatives(LatticeNoiseSample s) {
+    uvec2 i = uvec2(s.cell);
+    vec2 f = s.position;
+
+    vec2 u = f * f * (3. - 2. * f);
+    vec2 du = 6. * f * (1. - f);
+
+    vec2 ga = gradientNoiseHash(latticeLoop(i + uvec2(0, 0)), s.seed);
+    vec2 gb = gradientNoiseHash(latticeLoop(i + uvec2(1, 0)), s.seed);
+    vec2 gc = gradientNoiseHash(latticeLoop(i + uvec2(0, 1)), s.seed);
+    vec2 gd = gradientNoiseHash(latticeLoop(i + uvec2(1, 1)), s.seed);
+
+    float va = dot(ga, f - uvec2(0, 0));
+    float vb = dot(gb, f - uvec2(1, 0));
+    float vc = dot(gc, f - uvec2(0, 1));
+    float vd = dot(gd, f - uvec2(1, 1));
+
+    float k = va - vb - vc + vd;
+    vec2 d = ga + u.x * (gb - ga) + u.y * (gc - ga) +
+             u.x * u.y * (ga - gb - gc + gd) +
+             du * (u.yx * k + vec2(vb, vc) - va);
+
+    return vec3(mix(mix(va, vb, u.x), mix(vc, vd, u.x), u.y), d) * 1.49315244;
+}
+
+vec3 gradientNoiseHash3D(uvec3 p, uint seed) {
+    return tofloat11(hash3(p, seed));
+}
+
+/**
+ * @brief Evaluate 3D gradient noise
+ * @param s Sample in a rectangular 3D lattice
+ * @return Noise value, in [-1, 1]
+ */
+float gradientNoise3D(LatticeNoiseSample3D s) {
+    uvec3 i = uvec3(s.cell);
+    vec3 f = s.position;
+
+    vec3 u = f * f * (3. - 2. * f);
+
+    // Gradient contributions of the cell corners
+    float g000 = dot(gradientNoiseHash3D(latticeLoop(i + uvec3(0, 0, 0)), s.seed),
+                     f - vec3(0, 0, 0));
+    float g100 = dot(gradientNoiseHash3D(latticeLoop(i + uvec3(1, 0, 0)), s.seed),
+                     f - vec3(1, 0, 0));
+    float g010 = dot(gradientNoiseHash3D(latticeLoop(i + uvec3(0, 1, 0)), s.seed),
+                     f - vec3(0, 1, 0));
+    float g110 = dot(gradientNoiseHash3D(latticeLoop(i + uvec3(1, 1, 0)), s.seed),
+                     f - vec3(1, 1, 0));
+    float g001 = dot(gradientNoiseHash3D(latticeLoop(i + uvec3(0, 0, 1)), s.seed),
+                     f - vec3(0, 0, 1));
+    float g101 = dot(gradientNoiseHash3D(latticeLoop(i + uvec3(1, 0, 1)), s.seed),
+                     f - vec3(1, 0, 1));
+    float g011 = dot(gradientNoiseHash3D(latticeLoop(i + uvec3(0, 1, 1)), s.seed),
+                     f - vec3(0, 1, 1));
+    float g111 = dot(gradientNoiseHash3D(latticeLoop(i + uvec3(1, 1, 1)), s.seed),
+                     f - vec3(1, 1, 1));
+
+    return mix(mix(mix(g000, g100, u.x), mix(g010, g110, u.x), u.y),
+               mix(mix(g001, g101, u.x), mix(g011, g111, u.x), u.y), u.z) *
+           1.3;
+}
+
+/**
+ * @brief Evaluate 3D gradient noise and its derivatives
+ * @param s Sample in a rectangular 3D lattice
+ * @return Noise value, in [-1, 1], followed by its partial derivatives with
+ * respect to the lattice coordinates
+ */
+vec4 gradientNoise3DDerivatives(LatticeNoiseSample3D s) {
+    uvec3 i = uvec3(s.cell);
+    vec3 f = s.position;
+
+    vec3 u = f * f * (3. - 2. * f);
+    vec3 du = 6. * f * (1. - f);
+
+    // Sum the contributions of the 8 cell corners
+    vec4 res = vec4(0.);
+    for (uint c = 0; c < 8; ++c) {
+        uvec3 o = (uvec3(c) >> uvec3(0, 1, 2)) & 1u;
+        vec3 g = gradientNoiseHash3D(latticeLoop(i + o), s.seed);
+        float v = dot(g, f - vec3(o));
+
+        // Interpolation weights along each axis, and their derivatives
+        vec3 w = mix(1. - u, u, vec3(o));
+        vec3 dw = mix(-du, du, vec3(o));
+
+        float wp = w.x * w.y * w.z;
+        res += vec4(wp * v, wp * g + v * vec3(dw.x * w.y * w.z,
+                                              w.x * dw.y * w.z,
+                                              w.x * w.y * dw.z));
+    }
+
+    return res * 1.3;
+}
+
+vec4 gradientNoiseHash4D(ivec4 p, uint seed) {
+    return tofloat11(hash4(uvec4(p), seed));
+}
+
+/**
+ * @brief Evaluate 4D gradient noise and its spatial derivatives
+ * @param s Sample in a rectangular 4D lattice
+ * @return Noise value, in [-1, 1], followed by its partial derivatives with
+ * respect to the spatial lattice coordinates
+ */
+vec4 gradientNoise4DDerivatives(LatticeNoiseSample4D s) {
+    vec4 f = s.position;
+
+    vec4 u = f * f * (3. - 2. * f);
+    vec4 du = 6. * f * (1. - f);
+
+    // Sum the contributions of the 16 cell corners
+    vec4 res = vec4(0.);
+    for (int c = 0; c < 16; ++c) {
+        ivec4 o = (ivec4(c) >> ivec4(0, 1, 2, 3)) & 1;
+        vec4 g = gradientNoiseHash4D(latticeLoop(s.cell + o), s.seed);
+        float v = dot(g, f - vec4(o));
+
+        // Interpolation weights along each axis, and their derivatives
+        vec4 w = mix(1. - u, u, vec4(o));
+        vec4 dw = mix(-du, du, vec4(o));
+
+        float wp = w.x * w.y * w.z * w.w;
+        res += vec4(wp * v, wp * g.xyz + v * w.w * vec3(dw.x * w.y * w.z,
+                                                        w.x * dw.y * w.z,
+                                                        w.x * w.y * dw.z));
+    }
+
+    return res * 1.35;
+}
+
+/**
+ * @brief Evaluate 4D gradient noise
+ * @param s Sample in a rectangular 4D lattice
+ * @return Noise value, in [-1, 1]
+ */
+float gradientNoise4D(LatticeNoiseSample4D s) {
+    return gradientNoise4DDerivatives(s).x;
+}
+
+#endif /* _GRADIENT_NOISE_GLSL_ */
+
+// vim: ft=glsl.doxygen
@@ -0,0 +1,34 @@
+/**
+ * @file heightfield.glsl
+ * @brief Shared heightfield definitions
+ * @author Alixinne <alixinne@pm.me>
+ *
+ * Heightfield shaders read the height in the red channel of the texture bound
+ * to unit 0. The height texture is sampled at the pixels of the output, with
+ * bilinear filtering and repeat wrapping, so tileable heights give tileable
+ * results.
+ */
+
+#ifndef _HEIGHTFIELD_GLSL_
+#define _HEIGHTFIELD_GLSL_
+
+#include "shared.glsl"
+
+/// Height of a unit of the height texture, in pixels
+layout(location = 20) uniform float strength;
+
+layout(location = 21, binding = 0) uniform sampler2D height;
+
+/**
+ * @brief Sample the scaled height
+ * @param p Texture coordinates of the current pixel
+ * @param offset Offset from the current pixel, in pixels
+ * @return Height at the given offset, in pixels
+ */
+float heightAt(vec2 p, vec2 offset) {
+    return strength * texture(height, p + offset / vec2(iResolution.xy)).r;
+}
+
+#endif /* _HEIGHTFIELD_GLSL_ */
+
+// vim: ft=glsl.doxygen
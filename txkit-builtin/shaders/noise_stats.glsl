@@ -27,6 +27,26 @@
 #define LATTICE_MODE_RECT_2D 0
 /// Sample a simplex lattice
 #define LATTICE_MODE_SIMPLEX_2D 1
+/// Sample a rectangular 3D lattice
+#define LATTICE_MODE_RECT_3D 2
+/// Sample a simplex 3D lattice
+#define LATTICE_MODE_SIMPLEX_3D 3
+/// Sample a rectangular 4D lattice
+#define LATTICE_MODE_RECT_4D 4
+/// Sample a simplex 4D lattice
+#define LATTICE_MODE_SIMPLEX_4D 5
+
+/**
+ * @enum LatticeDimensions
+ * @brief Dimensions of the lattice noises
+ */
+
+/// Noise over (x, y), repeated on every layer
+#define LATTICE_DIMENSIONS_2D 2
+/// Noise over (x, y, layer)
+#define LATTICE_DIMENSIONS_3D 3
+/// Noise over (x, y, layer, time)
+#define LATTICE_DIMENSIONS_4D 4
 
 #ifndef PARAM_SCALE
 /// Name of the scale uniform
@@ -36,6 +56,14 @@
 layout(location = 52) uniform float scale;
 #endif /* PARAM_SCALE */
 
+#ifndef PARAM_PERIOD
+/// Name of the period uniform
+#define PARAM_PERIOD period
+/// Period of the noise along the time axis, in lattice cells, 0 for no
+/// looping
+layout(location = 53) uniform float period;
+#endif /* PARAM_PERIOD */
+
 /// Current statistics computation mode
 layout(location = 50) uniform int statsMode;
 /// Location for local sampling
@@ -51,10 +79,197 @@ struct LatticeNoiseSample {
     uint seed;
 };
 
+/// Sample in a 3D lattice
+struct LatticeNoiseSample3D {
+    /// Position in the current cell (in [0, 1])
+    vec3 position;
+    /// Cell number
+    ivec3 cell;
+    /// Seed for the current sample
+    uint seed;
+};
+
+/// Sample in a 4D lattice
+struct LatticeNoiseSample4D {
+    /// Position in the current cell (in [0, 1])
+    vec4 position;
+    /// Cell number
+    ivec4 cell;
+    /// Seed for the current sample
+    uint seed;
+};
+
 /// Simplex constant for (sqrt(3)-1)/2
 const float SIMPLEX_K1 = 0.366025404;
 /// Simplex constant for (3-sqrt(3))/6
 const float SIMPLEX_K2 = 0.211324865;
+/// 3D simplex skewing constant (1/3)
+const float SIMPLEX3D_K1 = 0.333333333;
+/// 3D simplex unskewing constant (1/6)
+const float SIMPLEX3D_K2 = 0.166666667;
+/// 4D simplex skewing constant ((sqrt(5)-1)/4)
+const float SIMPLEX4D_K1 = 0.309016994;
+/// 4D simplex unskewing constant ((5-sqrt(5))/20)
+const float SIMPLEX4D_K2 = 0.138196601;
+
+/**
+ * @brief Compute the period of a looping simplex lattice
+ * @return Skewed coordinates (m, n) of the lattice vector closest to (scale, 0)
+ *
+ * A simplex lattice cannot tile a square texture exactly. Instead, the texture
+ * periods (scale, 0) and (0, scale) are mapped onto the closest lattice
+ * vectors, which are (m, n) and (n, m) in skewed coordinates. This slightly
+ * distorts the lattice for small scales.
+ */
+ivec2 simplexLatticePeriod() {
+    return ivec2(max(1, int(floor(PARAM_SCALE * (1. + SIMPLEX_K1) + .5))),
+                 int(floor(PARAM_SCALE * SIMPLEX_K1 + .5)));
+}
+
+/**
+ * @brief Unskew a simplex lattice vector
+ * @param p Skewed coordinates of the vector
+ * @return Position of the vector in the viewport
+ */
+vec2 simplexLatticeVector(ivec2 p) { return vec2(p) - (p.x + p.y) * SIMPLEX_K2; }
+
+/**
+ * @brief Scale of the lattice coordinates of simplex gradients
+ *
+ * Lattice coordinates are the viewport position times the scale. A simplex
+ * lattice spans at least one lattice vector per texture period, so for
+ * non-positive scales, gradients are taken with respect to the viewport
+ * position instead.
+ */
+float simplexLatticeScale() { return PARAM_SCALE > 0. ? PARAM_SCALE : 1.; }
+
+/**
+ * @brief Convert a gradient in a simplex lattice to lattice coordinates
+ * @param g Gradient with respect to the simplex lattice positions returned by
+ * #latticeSample
+ * @return Gradient with respect to the lattice coordinates
+ *
+ * In normal mode, the simplex lattice is slightly distorted so it tiles, see
+ * #simplexLatticePeriod.
+ */
+vec2 simplexLatticeGradient(vec2 g) {
+    if (statsMode == STATS_MODE_NORMAL) {
+        // The mapping is symmetric, so it is its own transpose
+        vec2 a = simplexLatticeVector(simplexLatticePeriod()) /
+                 simplexLatticeScale();
+        return g.x * a + g.y * a.yx;
+    }
+
+    return g;
+}
+
+/**
+ * @brief Compute the period of a looping 3D simplex lattice
+ * @return Skewed coordinates (m, n, n) of the lattice vector closest to
+ * (scale, 0, 0)
+ *
+ * See #simplexLatticePeriod. The other periods are (n, m, n) and (n, n, m).
+ */
+ivec2 simplexLatticePeriod3D() {
+    return ivec2(max(1, int(floor(PARAM_SCALE * (1. + SIMPLEX3D_K1) + .5))),
+                 int(floor(PARAM_SCALE * SIMPLEX3D_K1 + .5)));
+}
+
+/**
+ * @brief Unskew a 3D simplex lattice vector
+ * @param p Skewed coordinates of the vector
+ * @return Position of the vector in the viewport
+ */
+vec3 simplexLatticeVector3D(ivec3 p) {
+    return vec3(p) - (p.x + p.y + p.z) * SIMPLEX3D_K2;
+}
+
+/**
+ * @brief Convert a gradient in a 3D simplex lattice to lattice coordinates
+ * @param g Gradient with respect to the simplex lattice positions returned by
+ * #latticeSample3D
+ * @return Gradient with respect to the lattice coordinates
+ */
+vec3 simplexLatticeGradient3D(vec3 g) {
+    if (statsMode == STATS_MODE_NORMAL) {
+        ivec2 t = simplexLatticePeriod3D();
+        return vec3(dot(simplexLatticeVector3D(t.xyy), g),
+                    dot(simplexLatticeVector3D(t.yxy), g),
+                    dot(simplexLatticeVector3D(t.yyx), g)) /
+               simplexLatticeScale();
+    }
+
+    return g;
+}
+
+/**
+ * @brief Compute the periods of a looping 4D simplex lattice
+ * @return Skewed coordinates (m, n, m', n') of the lattice vectors (m, n, n, n)
+ * closest to (scale, 0, 0, 0) and (n', n', n', m') closest to (0, 0, 0,
+ * period)
+ *
+ * See #simplexLatticePeriod. Without a time period, (n', n', n', m') is (0, 0,
+ * 0, 1), which only serves to reduce cells modulo the spatial periods.
+ */
+ivec4 simplexLatticePeriod4D() {
+    ivec2 s = ivec2(max(1, int(floor(PARAM_SCALE * (1. + SIMPLEX4D_K1) + .5))),
+                    int(floor(PARAM_SCALE * SIMPLEX4D_K1 + .5)));
+
+    if (PARAM_PERIOD <= 0.) {
+        return ivec4(s, 1, 0);
+    }
+
+    return ivec4(s,
+                 max(1, int(floor(PARAM_PERIOD * (1. + SIMPLEX4D_K1) + .5))),
+                 int(floor(PARAM_PERIOD * SIMPLEX4D_K1 + .5)));
+}
+
+/**
+ * @brief Compute the period of a looping rectangular 4D lattice along the
+ * time axis
+ * @return Period in whole lattice cells, 0 for no looping
+ *
+ * The period is rounded to the closest non-zero number of cells, and
+ * #latticeSample4D stretches time accordingly so the noise loops exactly over
+ * PARAM_PERIOD.
+ */
+int latticeTimePeriod() {
+    return PARAM_PERIOD > 0. ? max(1, int(floor(PARAM_PERIOD + .5))) : 0;
+}
+
+/**
+ * @brief Unskew a 4D simplex lattice vector
+ * @param p Skewed coordinates of the vector
+ * @return Position of the vector in the viewport
+ */
+vec4 simplexLatticeVector4D(ivec4 p) {
+    return vec4(p) - (p.x + p.y + p.z + p.w) * SIMPLEX4D_K2;
+}
+
+/**
+ * @brief Convert a gradient in a 4D simplex lattice to lattice coordinates
+ * @param g Gradient with respect to the simplex lattice positions returned by
+ * #latticeSample4D
+ * @return Gradient with respect to the spatial lattice coordinates
+ */
+vec3 simplexLatticeGradient4D(vec4 g) {
+    if (statsMode == STATS_MODE_NORMAL) {
+        ivec4 t = simplexLatticePeriod4D();
+        return vec3(dot(simplexLatticeVector4D(t.xyyy), g),
+                    dot(simplexLatticeVector4D(t.yxyy), g),
+                    dot(simplexLatticeVector4D(t.yyxy), g)) /
+               simplexLatticeScale();
+    }
+
+    return g.xyz;
+}
+
+/**
+ * @brief Integer division rounding towards negative infinity
+ * @param a Dividend
+ * @param b Divisor, must be positive
+ */
+int floorDiv(int a, int b) { return (a >= 0 ? a : a - b + 1) / b; }
 
 /**
  * @brief Sample a 2D lattice
@@ -64,6 +279,7 @@ const float SIMPLEX_K2 = 0.211324865;
  */
 LatticeNoiseSample latticeSample(vec2 position, int mode) {
     LatticeNoiseSample res;
+    vec2 uv = position;
 
     // Regular sampling mode inside the lattice
     res.seed = PARAM_GLOBAL_SEED;
@@ -93,6 +309,14 @@ LatticeNoiseSample latticeSample(vec2 position, int mode) {
         res.position = fract(position);
         res.cell = ivec2(position);
     } else if (mode == LATTICE_MODE_SIMPLEX_2D) {
+        if (statsMode == STATS_MODE_NORMAL) {
+            // Map the texture periods onto lattice vectors so the noise tiles.
+            // The mapping is applied to the viewport position, so it holds for
+            // a zero scale.
+            vec2 a = simplexLatticeVector(simplexLatticePeriod());
+            position = uv.x * a + uv.y * a.yx;
+        }
+
         res.cell = ivec2(position + (position.x + position.y) * SIMPLEX_K1);
         res.position =
             position - vec2(res.cell) + (res.cell.x + res.cell.y) * SIMPLEX_K2;
@@ -101,10 +325,146 @@ LatticeNoiseSample latticeSample(vec2 position, int mode) {
     return res;
 }
 
+/**
+ * @brief Sample a 3D lattice
+ * @param position Input position in the viewport, the z coordinate spanning
+ * the layers of the target image
+ * @param mode Sampling mode, see #SamplingMode
+ * @return Sampled position structure
+ *
+ * The lattice has the same scale along the three axes.
+ */
+LatticeNoiseSample3D latticeSample3D(vec3 position, int mode) {
+    LatticeNoiseSample3D res;
+    vec3 uvw = position;
+
+    // Regular sampling mode inside the lattice
+    res.seed = PARAM_GLOBAL_SEED;
+
+    if (statsMode == STATS_MODE_NORMAL) {
+        // Regular sampling mode is the default
+        position *= PARAM_SCALE;
+    } else {
+        // For process and look-at mode: each voxel being evaluated is seeded
+        // differently
+        uvec3 px = uvec3(position * iResolution);
+
+        // Generate one seed per voxel, mix it with the base seed, then hash it
+        res.seed = hash(morton(morton(px.x, px.y, px.z), res.seed));
+
+        if (statsMode == STATS_MODE_PROCESS) {
+            // In process mode, evaluate at random positions for each voxel
+            position = tofloat(hash3(px, res.seed * 32165431u)) * PARAM_SCALE;
+        } else if (statsMode == STATS_MODE_LOOKAT) {
+            // Look-at mode: all voxels describe the same position in a cell,
+            // but are seeded differently
+            position = vec3(statsLookAt, 0.);
+        }
+    }
+
+    if (mode == LATTICE_MODE_RECT_3D) {
+        res.position = fract(position);
+        res.cell = ivec3(position);
+    } else if (mode == LATTICE_MODE_SIMPLEX_3D) {
+        if (statsMode == STATS_MODE_NORMAL) {
+            // Map the texture periods onto lattice vectors so the noise tiles
+            ivec2 t = simplexLatticePeriod3D();
+            position = uvw.x * simplexLatticeVector3D(t.xyy) +
+                       uvw.y * simplexLatticeVector3D(t.yxy) +
+                       uvw.z * simplexLatticeVector3D(t.yyx);
+        }
+
+        res.cell = ivec3(position +
+                         (position.x + position.y + position.z) * SIMPLEX3D_K1);
+        res.position = position - vec3(res.cell) +
+                       (res.cell.x + res.cell.y + res.cell.z) * SIMPLEX3D_K2;
+    }
+
+    return res;
+}
+
+/**
+ * @brief Sample a 4D lattice
+ * @param position Input position in the viewport, the z coordinate spanning
+ * the layers of the target image, and the w coordinate being the time in
+ * lattice cells
+ * @param mode Sampling mode, see #SamplingMode
+ * @return Sampled position structure
+ *
+ * The lattice has the same scale along the three spatial axes. Statistics
+ * modes only apply to the spatial axes.
+ */
+LatticeNoiseSample4D latticeSample4D(vec4 position, int mode) {
+    LatticeNoiseSample4D res;
+    vec3 uvw = position.xyz;
+
+    // Regular sampling mode inside the lattice
+    res.seed = PARAM_GLOBAL_SEED;
+
+    if (statsMode == STATS_MODE_NORMAL) {
+        // Regular sampling mode is the default
+        position.xyz *= PARAM_SCALE;
+    } else {
+        // For process and look-at mode: each voxel being evaluated is seeded
+        // differently
+        uvec3 px = uvec3(position.xyz * iResolution);
+
+        // Generate one seed per voxel, mix it with the base seed, then hash it
+        res.seed = hash(morton(morton(px.x, px.y, px.z), res.seed));
+
+        if (statsMode == STATS_MODE_PROCESS) {
+            // In process mode, evaluate at random positions for each voxel
+            position.xyz =
+                tofloat(hash3(px, res.seed * 32165431u)) * PARAM_SCALE;
+        } else if (statsMode == STATS_MODE_LOOKAT) {
+            // Look-at mode: all voxels describe the same position in a cell,
+            // but are seeded differently
+            position.xyz = vec3(statsLookAt, 0.);
+        }
+    }
+
+    if (mode == LATTICE_MODE_RECT_4D) {
+        int period = latticeTimePeriod();
+        if (period > 0) {
+            // Stretch time so the period is a whole number of cells
+            position.w *= float(period) / PARAM_PERIOD;
+        }
+
+        // Time may be negative, round cells towards negative infinity
+        res.position = fract(position);
+        res.cell = ivec4(floor(position));
+    } else if (mode == LATTICE_MODE_SIMPLEX_4D) {
+        if (statsMode == STATS_MODE_NORMAL) {
+            // Map the texture and time periods onto lattice vectors so the
+            // noise tiles and loops
+            ivec4 t = simplexLatticePeriod4D();
+            vec4 p = uvw.x * simplexLatticeVector4D(t.xyyy) +
+                     uvw.y * simplexLatticeVector4D(t.yxyy) +
+                     uvw.z * simplexLatticeVector4D(t.yyxy);
+
+            if (PARAM_PERIOD > 0.) {
+                p += position.w * simplexLatticeVector4D(t.wwwz) / PARAM_PERIOD;
+            } else {
+                p.w += position.w;
+            }
+
+            position = p;
+        }
+
+        res.cell = ivec4(
+            floor(position + (position.x + position.y + position.z + position.w) *
+                                 SIMPLEX4D_K1));
+        res.position =
+            position - vec4(res.cell) +
+            (res.cell.x + res.cell.y + res.cell.z + res.cell.w) * SIMPLEX4D_K2;
+    }
+
+    return res;
+}
+
 /**
  * @brief Ensure 2D lattice cells loop around the texture borders
  * @param p Cell coordinates to loop
- * @todo Support looping simplex grids
  */
 ivec2 latticeLoop(ivec2 p) {
     const int S = int(PARAM_SCALE);
@@ -120,7 +480,6 @@ ivec2 latticeLoop(ivec2 p) {
 /**
  * @brief Ensure 2D lattice cells loop around the texture borders
  * @param p Cell coordinates to loop
- * @todo Support looping simplex grids
  */
 uvec2 latticeLoop(uvec2 p) {
     if (statsMode == STATS_MODE_NORMAL) {
@@ -130,6 +489,121 @@ uvec2 latticeLoop(uvec2 p) {
     return p;
 }
 
+/**
+ * @brief Ensure 2D simplex lattice cells loop around the texture borders
+ * @param p Skewed cell coordinates to loop
+ *
+ * Cells are reduced modulo the lattice generated by the periods returned by
+ * #simplexLatticePeriod.
+ */
+ivec2 simplexLatticeLoop(ivec2 p) {
+    if (statsMode == STATS_MODE_NORMAL) {
+        ivec2 t = simplexLatticePeriod();
+        int det = t.x * t.x - t.y * t.y;
+
+        int alpha = floorDiv(p.x * t.x - p.y * t.y, det);
+        int beta = floorDiv(p.y * t.x - p.x * t.y, det);
+
+        return p - alpha * t - beta * t.yx;
+    }
+
+    return p;
+}
+
+/**
+ * @brief Ensure 3D lattice cells loop around the texture borders
+ * @param p Cell coordinates to loop
+ */
+uvec3 latticeLoop(uvec3 p) {
+    if (statsMode == STATS_MODE_NORMAL) {
+        return p % int(PARAM_SCALE);
+    }
+
+    return p;
+}
+
+/**
+ * @brief Ensure 3D simplex lattice cells loop around the texture borders
+ * @param p Skewed cell coordinates to loop
+ *
+ * Cells are reduced modulo the lattice generated by the periods returned by
+ * #simplexLatticePeriod3D.
+ */
+ivec3 simplexLatticeLoop3D(ivec3 p) {
+    if (statsMode == STATS_MODE_NORMAL) {
+        ivec2 t = simplexLatticePeriod3D();
+        // The period matrix is (m - n) I + n J, with J the all-ones matrix
+        int k = t.x + 2 * t.y;
+        int det = (t.x - t.y) * k;
+        int sum = p.x + p.y + p.z;
+
+        ivec3 alpha = ivec3(floorDiv(k * p.x - t.y * sum, det),
+                            floorDiv(k * p.y - t.y * sum, det),
+                            floorDiv(k * p.z - t.y * sum, det));
+
+        return p - (t.x - t.y) * alpha - t.y * (alpha.x + alpha.y + alpha.z);
+    }
+
+    return p;
+}
+
+/**
+ * @brief Ensure 4D lattice cells loop around the texture borders and the time
+ * period
+ * @param p Cell coordinates to loop
+ */
+ivec4 latticeLoop(ivec4 p) {
+    if (statsMode == STATS_MODE_NORMAL) {
+        const int S = max(1, int(PARAM_SCALE));
+        p.xyz -= S * ivec3(floorDiv(p.x, S), floorDiv(p.y, S), floorDiv(p.z, S));
+
+        const int P = latticeTimePeriod();
+        if (P > 0) {
+            p.w -= P * floorDiv(p.w, P);
+        }
+    }
+
+    return p;
+}
+
+/**
+ * @brief Ensure 4D simplex lattice cells loop around the texture borders and
+ * the time period
+ * @param p Skewed cell coordinates to loop
+ *
+ * Cells are reduced modulo the lattice generated by the periods returned by
+ * #simplexLatticePeriod4D. Products are computed in 32-bit arithmetic, which
+ * doesn't overflow for the scales and periods of at most
+ * SIMPLEX_LATTICE_4D_MAX_PERIOD cells accepted by the methods.
+ */
+ivec4 simplexLatticeLoop4D(ivec4 p) {
+    if (statsMode == STATS_MODE_NORMAL) {
+        // The period matrix T has columns (m, n, n, n), (n, m, n, n),
+        // (n, n, m, n) and (n', n', n', m'). Solve T alpha = p by first
+        // solving for alpha.w and the sum of the spatial coefficients.
+        ivec4 t = simplexLatticePeriod4D();
+        int a = t.x - t.y;
+        int b = t.x + 2 * t.y;
+        int det = b * t.z - 3 * t.y * t.w;
+        int sum = p.x + p.y + p.z;
+
+        int sumNum = t.z * sum - 3 * t.w * p.w;
+        int wNum = b * p.w - t.y * sum;
+
+        ivec4 alpha;
+        alpha.w = floorDiv(wNum, det);
+        alpha.x = floorDiv(det * p.x - t.y * sumNum - t.w * wNum, a * det);
+        alpha.y = floorDiv(det * p.y - t.y * sumNum - t.w * wNum, a * det);
+        alpha.z = floorDiv(det * p.z - t.y * sumNum - t.w * wNum, a * det);
+
+        int offset = t.y * (alpha.x + alpha.y + alpha.z);
+        return p - ivec4(a * alpha.xyz + offset + t.w * alpha.w,
+                         offset + t.z * alpha.w);
+    }
+
+    return p;
+}
+
 #endif /* _NOISE_STATS_GLSL_ */
 
 // vim: ft=glsl.doxygen
@@ -0,0 +1,59 @@
+#version 460 core
+
+/**
+ * @file normal_map.frag
+ * @brief Tangent-space normal map of a heightfield
+ * @author Alixinne <alixinne@pm.me>
+ *
+ * Normal of the heightfield surface, with the height gradient estimated by
+ * central differences or a Sobel filter. The x and y axes of the normal follow
+ * the columns and rows of the image, and the normal is remapped from [-1, 1]
+ * to [0, 1].
+ */
+
+layout(location = 0) in vec3 pixelUv;
+layout(location = 0) out vec4 o_FragColor;
+
+#include "heightfield.glsl"
+
+layout(location = 22) uniform int kernel;
+
+#define NORMAL_MAP_KERNEL_CENTRAL_DIFFERENCE 0
+#define NORMAL_MAP_KERNEL_SOBEL 1
+
+void main() {
+    vec3 uv = domainUv(pixelUv);
+
+    if (kernel < NORMAL_MAP_KERNEL_CENTRAL_DIFFERENCE ||
+        kernel > NORMAL_MAP_KERNEL_SOBEL) {
+        // Invalid enum value
+        o_FragColor = vec4(1., 0., 1., 1.);
+        return;
+    }
+
+    vec2 p = uv.xy;
+    float l = heightAt(p, vec2(-1., 0.));
+    float r = heightAt(p, vec2(1., 0.));
+    float t = heightAt(p, vec2(0., -1.));
+    float b = heightAt(p, vec2(0., 1.));
+
+    // Height gradient, in pixels per pixel
+    vec2 g;
+    if (kernel == NORMAL_MAP_KERNEL_CENTRAL_DIFFERENCE) {
+        g = .5 * vec2(r - l, b - t);
+    } else {
+        float tl = heightAt(p, vec2(-1., -1.));
+        float tr = heightAt(p, vec2(1., -1.));
+        float bl = heightAt(p, vec2(-1., 1.));
+        float br = heightAt(p, vec2(1., 1.));
+
+        g = vec2(tr + 2. * r + br - tl - 2. * l - bl,
+                 bl + 2. * b + br - tl - 2. * t - tr) /
+            8.;
+    }
+
+    vec3 n = normalize(vec3(-g, 1.));
+    o_FragColor = vec4(.5 * n + .5, 1.);
+}
+
+// vim: ft=glsl.doxygen
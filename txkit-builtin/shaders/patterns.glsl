@@ -0,0 +1,61 @@
+/**
+ * @file patterns.glsl
+ * @brief Shared pattern definitions
+ * @author Alixinne <alixinne@pm.me>
+ *
+ * Pattern shaders output the pattern value in the red channel, the random ID
+ * of the current tile in the green channel, and the integer coordinates of
+ * the current tile in the blue and alpha channels.
+ */
+
+#ifndef _PATTERNS_GLSL_
+#define _PATTERNS_GLSL_
+
+#include "noise.glsl"
+#include "shared.glsl"
+
+/// The square root of 3
+#define M_SQRT3 1.73205080756887729353
+
+/**
+ * @brief Compute the random ID of a tile
+ * @param cell Integer coordinates of the tile
+ * @return Random value in [0, 1]
+ */
+float tileId(ivec2 cell) { return tofloat(hash2(uvec2(cell), globalSeed).x); }
+
+/**
+ * @brief Box-filtered pulse train
+ * @param x Coordinate along the pulse train, in periods
+ * @param duty Fraction of the period where the pulse is 1
+ * @param w Width of the box filter, in periods
+ * @return Average of the pulse train over [x - w / 2, x + w / 2]
+ */
+float filteredPulse(float x, float duty, float w) {
+    if (w <= 0.) {
+        return fract(x) < duty ? 1. : 0.;
+    }
+
+    // Integral of the pulse train
+    vec2 t = vec2(x - .5 * w, x + .5 * w);
+    vec2 i = floor(t) * duty + min(fract(t), duty);
+    return (i.y - i.x) / w;
+}
+
+/**
+ * @brief Box-filtered edge
+ * @param d Signed distance to the edge, positive inside
+ * @param w Width of the box filter, in distance units
+ * @return Coverage of the inside of the edge
+ */
+float filteredEdge(float d, float w) {
+    if (w <= 0.) {
+        return d >= 0. ? 1. : 0.;
+    }
+
+    return clamp(d / w + .5, 0., 1.);
+}
+
+#endif /* _PATTERNS_GLSL_ */
+
+// vim: ft=glsl.doxygen
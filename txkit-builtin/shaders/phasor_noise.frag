@@ -8,10 +8,11 @@
  * @see https://hal.inria.fr/hal-02524371/
  *
  * Phasor noise implementation without kernel optimization. Also
- * implements Gabor noise by using the relevant profile function.
+ * implements Gabor noise by using the relevant profile function. The kernel
+ * phases are shifted by time / period turns for looping animations.
  */
 
-layout(location = 0) in vec3 uv;
+layout(location = 0) in vec3 pixelUv;
 layout(location = 0) out vec4 o_FragColor;
 
 // Required built-ins
@@ -24,6 +25,9 @@ layout(location = 0) out vec4 o_FragColor;
 // Fast rng
 #include "lcg.glsl"
 
+// Point distributions
+#include "points.glsl"
+
 layout(location = 20) uniform int noise_lookahead;
 layout(location = 21) uniform int kernel_count;
 layout(location = 22) uniform int noise_profile;
@@ -38,10 +42,10 @@ layout(location = 28) uniform int jitter_max;
 
 layout(location = 29, binding = 0) uniform sampler2D frequency_orientation_field;
 
-#define PHASOR_PROFILE_IMPULSES 5
+/// Animation time, in the same unit as the period uniform
+layout(location = 30) uniform float time;
 
-#define PHASOR_POINTS_RECT_JITTERED 2
-#define PHASOR_POINTS_HEX_JITTERED 3
+#define PHASOR_PROFILE_IMPULSES 5
 
 struct Kernel {
     vec2 pos;
@@ -64,6 +68,14 @@ vec2 phasor(vec2 x, Kernel k) {
     return k.weight * gaus * vec2(cos(osc), sin(osc));
 }
 
+/**
+ * @brief Compute the phase shift of the kernels at the current time
+ * @return Phase shift, in radians
+ */
+float phasorPhase() {
+    return period > 0. ? M_2PI * fract(time / period) : 0.;
+}
+
 vec2 noiseCell(vec2 pos, ivec2 cell, uint seed) {
     vec2 res = vec2(0.);
 
@@ -71,47 +83,17 @@ vec2 noiseCell(vec2 pos, ivec2 cell, uint seed) {
     LCG rng = lcgSeed(hash(cell, seed));
 
     // Compute impulse count
-    int ic = kernel_count, tc;
-    ivec2 bc;
-
-    if (noise_point_distribution == 1 /* PHASOR_POINTS_POISSON */) {
-        ic = lcgPoisson(rng, ic);
-    } else if (noise_point_distribution == PHASOR_POINTS_RECT_JITTERED ||
-            noise_point_distribution == PHASOR_POINTS_HEX_JITTERED) {
-        bc.x = int(sqrt(ic));
-        bc.x = jitter_max == 0 ? bc.x : min(jitter_max, bc.x);
-        bc.y = jitter_max == 0 ? ic / bc.x : min(jitter_max, ic / bc.x);
-        tc = bc.x * bc.y;
-        ic = (ic / tc) * tc;
-    }
+    PointSet ps = pointSetInit(rng, noise_point_distribution, kernel_count,
+                               jitter_max, jitter_amount);
 
     // Fixed number of impulses per cell
-    for (int i = 0; i < ic; ++i) {
+    for (int i = 0; i < ps.count; ++i) {
         // Sample kernel parameters
         vec4 fo = texture(frequency_orientation_field, (pos + vec2(cell)) / scale);
 
         // Generate a kernel
         Kernel k;
-        k.pos = .5 + (vec2(lcgNext01(rng), lcgNext01(rng)) - .5) * jitter_amount;
-
-        if (noise_point_distribution == PHASOR_POINTS_RECT_JITTERED ||
-                noise_point_distribution == PHASOR_POINTS_HEX_JITTERED)
-        {
-            int col = (i % tc) % bc.x;
-            int row = (i % tc) / bc.x;
-
-            if (noise_point_distribution == PHASOR_POINTS_HEX_JITTERED)
-            {
-                // Triangle transform
-                k.pos = vec2(.25 * (k.pos.x - k.pos.y), .5 * abs(k.pos.x + k.pos.y));
-
-                // Offset one every two triangles
-                if ((ic + row) % 2 == 0)
-                    k.pos = vec2(k.pos.x + .5, 1. - k.pos.y);
-            }
-
-            k.pos = (k.pos + vec2(col, row)) / vec2(bc);
-        }
+        k.pos = pointSetNext(rng, ps, i);
 
         // Compute weighting: always step the generator so we can get the same
         // image with and without weights
@@ -124,7 +106,7 @@ vec2 noiseCell(vec2 pos, ivec2 cell, uint seed) {
         }
 
         k.angle = noise_frequency / scale * (1. + fo.r) * vec2(cos(noise_angle + fo.g), sin(noise_angle + fo.g));
-        k.phase = 0.;
+        k.phase = phasorPhase();
 
         // Compute contribution
         res += phasor(scale * (pos - k.pos), k);
@@ -179,6 +161,8 @@ vec3 noise(LatticeNoiseSample s) {
 }
 
 void main() {
+    vec3 uv = domainUv(pixelUv);
+
     o_FragColor = vec4(noise(latticeSample(uv.xy, LATTICE_MODE_RECT_2D)), 1.);
 }
 
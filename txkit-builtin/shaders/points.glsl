@@ -0,0 +1,105 @@
+/**
+ * @file points.glsl
+ * @brief Point distributions in lattice cells
+ * @author Alixinne <alixinne@pm.me>
+ *
+ * Random point distributions shared by sparse convolution and cellular noises.
+ */
+
+#ifndef _POINTS_GLSL_
+#define _POINTS_GLSL_
+
+#include "lcg.glsl"
+
+/**
+ * @enum PointDistribution
+ * @brief Distribution of points in a lattice cell
+ */
+
+/// Constant number of points per cell
+#define POINTS_STRAT_POISSON 0
+/// Poisson-distributed number of points per cell
+#define POINTS_POISSON 1
+/// Rectangular jittered grid
+#define POINTS_RECT_JITTERED 2
+/// Hexagonal jittered grid
+#define POINTS_HEX_JITTERED 3
+
+/// Set of points in a lattice cell
+struct PointSet {
+    /// Distribution of the points, see #PointDistribution
+    int distribution;
+    /// Number of points in the cell
+    int count;
+    /// Number of subcells in jittered grids
+    ivec2 grid;
+    /// Jittering amount, 0 = no random, 1 = full subcell random
+    float jitterAmount;
+};
+
+/**
+ * @brief Initialize the set of points in a lattice cell
+ * @param rng Random number generator seeded for the current cell
+ * @param distribution Distribution of the points, see #PointDistribution
+ * @param count Requested number of points
+ * @param jitterMax Max jittering subcells, 0 = no limit
+ * @param jitterAmount Jittering amount
+ */
+PointSet pointSetInit(inout LCG rng, int distribution, int count,
+                      int jitterMax, float jitterAmount) {
+    PointSet ps;
+    ps.distribution = distribution;
+    ps.count = count;
+    ps.grid = ivec2(1);
+    ps.jitterAmount = jitterAmount;
+
+    if (distribution == POINTS_POISSON) {
+        ps.count = lcgPoisson(rng, count);
+    } else if (distribution == POINTS_RECT_JITTERED ||
+               distribution == POINTS_HEX_JITTERED) {
+        ps.grid.x = int(sqrt(count));
+        ps.grid.x = max(1, jitterMax == 0 ? ps.grid.x : min(jitterMax, ps.grid.x));
+        ps.grid.y = jitterMax == 0 ? count / ps.grid.x
+                                   : min(jitterMax, count / ps.grid.x);
+
+        int tc = ps.grid.x * ps.grid.y;
+        ps.count = tc > 0 ? (count / tc) * tc : 0;
+    }
+
+    return ps;
+}
+
+/**
+ * @brief Generate the next point of a set
+ * @param rng Random number generator used to initialize the set
+ * @param ps Set of points
+ * @param i Index of the point, in [0, ps.count)
+ * @return Position of the point in the cell, in [0, 1]^2
+ */
+vec2 pointSetNext(inout LCG rng, PointSet ps, int i) {
+    vec2 pos = .5 + (vec2(lcgNext01(rng), lcgNext01(rng)) - .5) * ps.jitterAmount;
+
+    if (ps.distribution == POINTS_RECT_JITTERED ||
+        ps.distribution == POINTS_HEX_JITTERED) {
+        int tc = ps.grid.x * ps.grid.y;
+        int col = (i % tc) % ps.grid.x;
+        int row = (i % tc) / ps.grid.x;
+
+        if (ps.distribution == POINTS_HEX_JITTERED) {
+            // Triangle transform
+            pos = vec2(.25 * (pos.x - pos.y), .5 * abs(pos.x + pos.y));
+
+            // Offset one every two triangles
+            if ((ps.count + row) % 2 == 0)
+                pos = vec2(pos.x + .5, 1. - pos.y);
+        }
+
+        pos = (pos + vec2(col, row)) / vec2(ps.grid);
+    }
+
+    return pos;
+}
+
+#endif /* _POINTS_GLSL_ */
+
+// vim: ft=glsl.doxygen
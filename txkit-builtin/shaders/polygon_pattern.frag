@@ -0,0 +1,102 @@
+#version 460 core
+
+/**
+ * @file polygon_pattern.frag
+ * @brief Hexagonal and triangular tilings
+ * @author Alixinne <alixinne@pm.me>
+ *
+ * Tilings of regular polygons separated by mortar joints. Both tilings are
+ * computed on the same skewed grid: hexagons are centered on the grid
+ * vertices, and every grid cell is split into two triangles.
+ */
+
+layout(location = 0) in vec3 pixelUv;
+layout(location = 0) out vec4 o_FragColor;
+
+#include "patterns.glsl"
+
+layout(location = 20) uniform float scale;
+layout(location = 21) uniform int shape;
+layout(location = 22) uniform float mortar_width;
+layout(location = 23) uniform float antialiasing;
+
+#define POLYGON_PATTERN_HEXAGONS 0
+#define POLYGON_PATTERN_TRIANGLES 1
+
+/**
+ * @brief Find the hexagon containing a point
+ * @param p Position, in hexagon widths
+ * @param cell Axial coordinates of the hexagon
+ * @return Distance to the closest edge of the hexagon
+ */
+float hexagon(vec2 p, out ivec2 cell) {
+    // Cube coordinates of the point, rounded to the closest hexagon center
+    vec3 c = vec3(p.x - p.y / M_SQRT3, 0., 2. * p.y / M_SQRT3);
+    c.y = -c.x - c.z;
+
+    vec3 r = round(c);
+    vec3 d = abs(r - c);
+
+    if (d.x > d.y && d.x > d.z) {
+        r.x = -r.y - r.z;
+    } else if (d.y <= d.z) {
+        r.z = -r.x - r.y;
+    }
+
+    cell = ivec2(r.xz);
+
+    // Position relative to the hexagon center
+    vec2 l = p - vec2(r.x + .5 * r.z, .5 * M_SQRT3 * r.z);
+    vec2 n = vec2(.5, .5 * M_SQRT3);
+    return .5 - max(abs(l.x), max(abs(dot(l, n)), abs(dot(l, n * vec2(-1., 1.)))));
+}
+
+/**
+ * @brief Find the triangle containing a point
+ * @param p Position, in triangle sides
+ * @param cell Coordinates of the triangle
+ * @return Distance to the closest edge of the triangle
+ */
+float triangle(vec2 p, out ivec2 cell) {
+    vec2 s = vec2(p.x - p.y / M_SQRT3, 2. * p.y / M_SQRT3);
+    ivec2 base = ivec2(floor(s));
+    vec2 f = fract(s);
+
+    // Barycentric coordinates of the point in its triangle
+    vec3 b;
+    if (f.x + f.y > 1.) {
+        cell = ivec2(2 * base.x + 1, base.y);
+        b = vec3(f.x + f.y - 1., 1. - f.x, 1. - f.y);
+    } else {
+        cell = ivec2(2 * base.x, base.y);
+        b = vec3(1. - f.x - f.y, f.x, f.y);
+    }
+
+    // Scale by the triangle height
+    return min(b.x, min(b.y, b.z)) * .5 * M_SQRT3;
+}
+
+void main() {
+    vec3 uv = domainUv(pixelUv);
+
+    vec2 p = uv.xy * scale * vec2(1., float(iResolution.y) / float(iResolution.x));
+    float w = antialiasing * scale / float(iResolution.x);
+
+    ivec2 cell;
+    float d;
+
+    if (shape == POLYGON_PATTERN_HEXAGONS) {
+        d = hexagon(p, cell);
+    } else if (shape == POLYGON_PATTERN_TRIANGLES) {
+        d = triangle(p, cell);
+    } else {
+        // Invalid enum value
+        o_FragColor = vec4(1., 0., 1., 1.);
+        return;
+    }
+
+    o_FragColor =
+        vec4(filteredEdge(d - .5 * mortar_width, w), tileId(cell), cell);
+}
+
+// vim: ft=glsl.doxygen
@@ -0,0 +1,178 @@
+#version 460 core
+
+/**
+ * @file sdf_2d.frag
+ * @brief 2D signed distance fields
+ * @author Alixinne <alixinne@pm.me>
+ *
+ * Signed distance field of a list of shapes combined with boolean operators.
+ * The shapes are read from the shape data texture, see the Sdf2d method for
+ * its layout. Positions and distances are measured in image widths.
+ */
+
+layout(location = 0) in vec3 pixelUv;
+layout(location = 0) out vec4 o_FragColor;
+
+#include "shared.glsl"
+
+layout(location = 20) uniform int output_mode;
+layout(location = 21) uniform float antialiasing;
+
+layout(location = 22, binding = 0) uniform sampler2D shape_data;
+
+#define SDF_2D_OUTPUT_DISTANCE 0
+#define SDF_2D_OUTPUT_COVERAGE 1
+
+#define SDF_2D_SHAPE_CIRCLE 0
+#define SDF_2D_SHAPE_BOX 1
+#define SDF_2D_SHAPE_ROUNDED_BOX 2
+#define SDF_2D_SHAPE_SEGMENT 3
+#define SDF_2D_SHAPE_POLYGON 4
+
+#define SDF_2D_OP_UNION 0
+#define SDF_2D_OP_INTERSECTION 1
+#define SDF_2D_OP_SUBTRACTION 2
+
+/// Distance to the empty set
+#define SDF_2D_EMPTY 1e9
+
+vec4 shapeData(int i) { return texelFetch(shape_data, ivec2(i, 0), 0); }
+
+/**
+ * @brief Distance to a box
+ * @param p Position relative to the box center
+ * @param h Half size of the box
+ */
+float sdBox(vec2 p, vec2 h) {
+    vec2 q = abs(p) - h;
+    return length(max(q, 0.)) + min(max(q.x, q.y), 0.);
+}
+
+/**
+ * @brief Distance to a line segment
+ * @param p Position
+ * @param a First end of the segment
+ * @param b Second end of the segment
+ */
+float sdSegment(vec2 p, vec2 a, vec2 b) {
+    vec2 pa = p - a, ba = b - a;
+    float l = dot(ba, ba);
+    float h = l > 0. ? clamp(dot(pa, ba) / l, 0., 1.) : 0.;
+    return length(pa - ba * h);
+}
+
+/**
+ * @brief Distance to a polygon
+ * @param p Position
+ * @param base Index of the first vertex texel
+ * @param n Number of vertices
+ * @see https://iquilezles.org/articles/distfunctions2d/
+ */
+float sdPolygon(vec2 p, int base, int n) {
+    if (n == 0) {
+        return SDF_2D_EMPTY;
+    }
+
+    float d = SDF_2D_EMPTY;
+    float s = 1.;
+
+    for (int i = 0, j = n - 1; i < n; j = i, ++i) {
+        vec2 vi = (i % 2 == 0) ? shapeData(base + i / 2).xy
+                               : shapeData(base + i / 2).zw;
+        vec2 vj = (j % 2 == 0) ? shapeData(base + j / 2).xy
+                               : shapeData(base + j / 2).zw;
+
+        d = min(d, sdSegment(p, vi, vj));
+
+        // Winding number test
+        vec2 e = vj - vi, w = p - vi;
+        bvec3 c = bvec3(p.y >= vi.y, p.y < vj.y, e.x * w.y > e.y * w.x);
+        if (all(c) || all(not(c))) {
+            s = -s;
+        }
+    }
+
+    return s * d;
+}
+
+/**
+ * @brief Polynomial smooth minimum
+ * @param a First distance
+ * @param b Second distance
+ * @param k Smoothing radius, 0 for the regular minimum
+ */
+float smin(float a, float b, float k) {
+    if (k <= 0.) {
+        return min(a, b);
+    }
+
+    float h = clamp(.5 + .5 * (b - a) / k, 0., 1.);
+    return mix(b, a, h) - k * h * (1. - h);
+}
+
+void main() {
+    vec3 uv = domainUv(pixelUv);
+
+    if (output_mode < SDF_2D_OUTPUT_DISTANCE ||
+        output_mode > SDF_2D_OUTPUT_COVERAGE) {
+        // Invalid enum value
+        o_FragColor = vec4(1., 0., 1., 1.);
+        return;
+    }
+
+    vec2 p = uv.xy * vec2(1., float(iResolution.y) / float(iResolution.x));
+    float d = SDF_2D_EMPTY;
+
+    for (int i = 0;;) {
+        vec4 header = shapeData(i++);
+        int kind = int(header.x);
+        int op = int(header.y);
+        float k = header.z;
+        int n = int(header.w);
+
+        float s;
+        if (kind == SDF_2D_SHAPE_CIRCLE) {
+            vec4 c = shapeData(i++);
+            s = length(p - c.xy) - c.z;
+        } else if (kind == SDF_2D_SHAPE_BOX) {
+            vec4 b = shapeData(i++);
+            s = sdBox(p - b.xy, b.zw);
+        } else if (kind == SDF_2D_SHAPE_ROUNDED_BOX) {
+            vec4 b = shapeData(i++);
+            float r = shapeData(i++).x;
+            s = sdBox(p - b.xy, max(b.zw - r, 0.)) - r;
+        } else if (kind == SDF_2D_SHAPE_SEGMENT) {
+            vec4 l = shapeData(i++);
+            float r = shapeData(i++).x;
+            s = sdSegment(p, l.xy, l.zw) - r;
+        } else if (kind == SDF_2D_SHAPE_POLYGON) {
+            s = sdPolygon(p, i, n);
+            i += (n + 1) / 2;
+        } else {
+            // End of the shape list
+            break;
+        }
+
+        if (op == SDF_2D_OP_UNION) {
+            d = smin(d, s, k);
+        } else if (op == SDF_2D_OP_INTERSECTION) {
+            d = -smin(-d, -s, k);
+        } else if (op == SDF_2D_OP_SUBTRACTION) {
+            d = -smin(-d, s, k);
+        } else {
+            // Invalid enum value
+            o_FragColor = vec4(1., 0., 1., 1.);
+            return;
+        }
+    }
+
+    if (output_mode == SDF_2D_OUTPUT_DISTANCE) {
+        o_FragColor = vec4(d, d, d, 1.);
+    } else {
+        float w = antialiasing / float(iResolution.x);
+        float v = w > 0. ? clamp(.5 - d / w, 0., 1.) : (d <= 0. ? 1. : 0.);
+        o_FragColor = vec4(v, v, v, 1.);
+    }
+}
+
+// vim: ft=glsl.doxygen
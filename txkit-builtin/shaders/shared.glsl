@@ -14,6 +14,21 @@
 /// Output image resolution
 layout(location = 0) uniform uvec3 iResolution;
 
+/// Texture coordinates of the pixels: red and green are the coordinates, blue
+/// is their weight against the pixel center. Unbound without a domain, so the
+/// weight is 0.
+layout(location = 2, binding = 15) uniform sampler2D iDomain;
+
+/**
+ * @brief Get the texture coordinates a pixel is evaluated at
+ * @param uv Coordinates of the pixel center, as produced by quad.vert
+ * @return Coordinates from the domain, or the pixel center without a domain
+ */
+vec3 domainUv(vec3 uv) {
+    vec4 d = texture(iDomain, uv.xy);
+    return vec3(mix(uv.xy, d.xy, d.z), uv.z);
+}
+
 /**
  * @brief Low-bias 32 bit hash function
  * @param x Value to hash
@@ -177,6 +192,19 @@ uint mortonPart1By2(uint x) {
     return x;
 }
 
+/**
+ * @brief Insert three 0 bits after each of the 8 low bits of x
+ * @param x Value to part
+ * @see https://fgiesen.wordpress.com/2009/12/13/decoding-morton-codes/
+ */
+uint mortonPart1By3(uint x) {
+    x &= 0x000000ffu;
+    x = (x ^ (x << 12)) & 0x000f000fu;
+    x = (x ^ (x << 6)) & 0x03030303u;
+    x = (x ^ (x << 3)) & 0x11111111u;
+    return x;
+}
+
 /**
  * @brief Encode two coordinates in Morton order
  * @param x First parameter
@@ -199,6 +227,19 @@ uint morton(uint x, uint y, uint z) {
            mortonPart1By2(x);
 }
 
+/**
+ * @brief Encode four coordinates in Morton order
+ * @param x First parameter
+ * @param y Second parameter
+ * @param z Third parameter
+ * @param w Fourth parameter
+ * @return Encoded Morton value
+ */
+uint morton(uint x, uint y, uint z, uint w) {
+    return (mortonPart1By3(w) << 3) | (mortonPart1By3(z) << 2) |
+           (mortonPart1By3(y) << 1) | mortonPart1By3(x);
+}
+
 /**
  * @brief Hash a (coordinates, seed) pair
  * @param x Coordinates
@@ -242,6 +283,61 @@ uvec2 hash2(uvec2 x, uint seed) {
     return uvec2(hash(2 * base), hash(2 * base + 1));
 }
 
+/**
+ * @brief Hash a (coordinates, seed) pair
+ * @param x Coordinates
+ * @param seed Random seed
+ * @return Hashed value
+ *
+ * Note that the coordinates are enumerated in Morton order, then mixed with
+ * the seed. Thus, cell coordinates are limited to their low 10 bits.
+ */
+uint hash(uvec3 x, uint seed) { return hash(seed + morton(x.x, x.y, x.z)); }
+
+/**
+ * @brief Hash a (coordinates, seed) pair, return three values
+ * @param x Coordinates
+ * @param seed Random seed
+ * @return Hashed value
+ *
+ * See uvec3 version of hash for limitations.
+ */
+uvec3 hash3(uvec3 x, uint seed) {
+    // Mix all coordinates into one seed value
+    uint base = hash(x, seed);
+    // Hash all coordinates
+    return uvec3(hash(3 * base), hash(3 * base + 1), hash(3 * base + 2));
+}
+
+/**
+ * @brief Hash a (coordinates, seed) pair
+ * @param x Coordinates
+ * @param seed Random seed
+ * @return Hashed value
+ *
+ * Note that the coordinates are enumerated in Morton order, then mixed with
+ * the seed. Thus, cell coordinates are limited to their low 8 bits.
+ */
+uint hash(uvec4 x, uint seed) {
+    return hash(seed + morton(x.x, x.y, x.z, x.w));
+}
+
+/**
+ * @brief Hash a (coordinates, seed) pair, return four values
+ * @param x Coordinates
+ * @param seed Random seed
+ * @return Hashed value
+ *
+ * See uvec4 version of hash for limitations.
+ */
+uvec4 hash4(uvec4 x, uint seed) {
+    // Mix all coordinates into one seed value
+    uint base = hash(x, seed);
+    // Hash all coordinates
+    return uvec4(hash(4 * base), hash(4 * base + 1), hash(4 * base + 2),
+                 hash(4 * base + 3));
+}
+
 /**
  * @brief Convert an unsigned int to a float in [0, 1]
  * @param u Unsigned int to convert
@@ -263,6 +359,22 @@ float tofloat(uint u) {
  */
 vec2 tofloat(uvec2 u) { return vec2(tofloat(u.x), tofloat(u.y)); }
 
+/**
+ * @brief Convert a vector of unsigned ints to floats
+ * @param u Vector to convert
+ * @return vec in [0, 1]
+ */
+vec3 tofloat(uvec3 u) { return vec3(tofloat(u.x), tofloat(u.y), tofloat(u.z)); }
+
+/**
+ * @brief Convert a vector of unsigned ints to floats
+ * @param u Vector to convert
+ * @return vec in [0, 1]
+ */
+vec4 tofloat(uvec4 u) {
+    return vec4(tofloat(u.x), tofloat(u.y), tofloat(u.z), tofloat(u.w));
+}
+
 
 /**
  * @brief Convert an unsigned int to a float in [-1, 1]
@@ -278,6 +390,20 @@ float tofloat11(uint u) { return 2. * tofloat(u) - 1.; }
  */
 vec2 tofloat11(uvec2 u) { return 2. * tofloat(u) - 1.; }
 
+/**
+ * @brief Convert a vector of unsigned ints to floats
+ * @param u Vector to convert
+ * @return vec in [-1, 1]
+ */
+vec3 tofloat11(uvec3 u) { return 2. * tofloat(u) - 1.; }
+
+/**
+ * @brief Convert a vector of unsigned ints to floats
+ * @param u Vector to convert
+ * @return vec in [-1, 1]
+ */
+vec4 tofloat11(uvec4 u) { return 2. * tofloat(u) - 1.; }
+
 /**
  * @brief Convert a float value from [-1, 1] to [0, 1]
  * @param x Value to convert
@@ -292,6 +418,20 @@ float to01(float x) { return .5 * x + .5; }
  */
 vec2 to01(vec2 x) { return .5 * x + .5; }
 
+/**
+ * @brief Convert a float value from [-1, 1] to [0, 1]
+ * @param x Value to convert
+ * @return Value in [0, 1]
+ */
+vec3 to01(vec3 x) { return .5 * x + .5; }
+
+/**
+ * @brief Convert a float value from [-1, 1] to [0, 1]
+ * @param x Value to convert
+ * @return Value in [0, 1]
+ */
+vec4 to01(vec4 x) { return .5 * x + .5; }
+
 #endif /* _SHARED_GLSL_ */
 
 // vim: ft=glsl.doxygen
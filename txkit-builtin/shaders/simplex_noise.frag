@@ -2,16 +2,15 @@
 
 /**
  * @file simplex_noise.frag
- * @brief 2D simplex noise fragment shader
+ * @brief 2D, 3D and 4D simplex noise fragment shader
  * @author inigo quilez
  * @author Alixinne <alixinne@pm.me>
  * @see https://www.shadertoy.com/view/Msf3WH
- * @todo Support cyclic coordinates
  *
- * 2D Simplex noise, adapted for use in txkit.
+ * 2D Simplex noise, adapted for use in txkit, and extended to 3D and 4D.
  */
 
-layout(location = 0) in vec3 uv;
+layout(location = 0) in vec3 pixelUv;
 layout(location = 0) out vec4 o_FragColor;
 
 // Required built-ins
@@ -21,25 +20,61 @@ layout(location = 0) out vec4 o_FragColor;
 // Noise statistics helpers
 #include "noise_stats.glsl"
 
-vec2 noisehash(uvec2 p, uint seed) { return tofloat11(hash2(p, seed)); }
-
-float noise(LatticeNoiseSample s) {
-    vec2 a = s.position;
-    float m = step(a.y, a.x);
-    vec2 o = vec2(m, 1.0 - m);
-    vec2 b = a - o + SIMPLEX_K2;
-    vec2 c = a - 1.0 + 2.0 * SIMPLEX_K2;
-    vec3 h = max(0.5 - vec3(dot(a, a), dot(b, b), dot(c, c)), 0.0);
-    vec3 n = h * h * h * h *
-             vec3(dot(a, noisehash(s.cell + 0, s.seed)),
-                  dot(b, noisehash(s.cell + uvec2(o), s.seed)),
-                  dot(c, noisehash(s.cell + 1, s.seed)));
-    return dot(n, vec3(71.2825901));
-}
+// Simplex noise
+#include "simplex_noise.glsl"
+
+layout(location = 20) uniform int output_mode;
+layout(location = 21) uniform int dimensions;
+layout(location = 22) uniform float time;
+
+#define SIMPLEX_OUTPUT_VALUE 0
+#define SIMPLEX_OUTPUT_DERIVATIVES 1
 
 void main() {
-    o_FragColor = vec4(
-        vec3(to01(noise(latticeSample(uv.xy, LATTICE_MODE_SIMPLEX_2D)))), 1.0);
+    vec3 uv = domainUv(pixelUv);
+
+    // Noise value, followed by its partial derivatives
+    vec4 res;
+
+    if (dimensions == LATTICE_DIMENSIONS_2D) {
+        LatticeNoiseSample s = latticeSample(uv.xy, LATTICE_MODE_SIMPLEX_2D);
+
+        if (output_mode == SIMPLEX_OUTPUT_DERIVATIVES) {
+            res = vec4(simplexNoiseDerivatives(s), 0.);
+        } else {
+            res = vec4(simplexNoise(s));
+        }
+    } else if (dimensions == LATTICE_DIMENSIONS_3D) {
+        LatticeNoiseSample3D s = latticeSample3D(uv, LATTICE_MODE_SIMPLEX_3D);
+
+        if (output_mode == SIMPLEX_OUTPUT_DERIVATIVES) {
+            res = simplexNoise3DDerivatives(s);
+        } else {
+            res = vec4(simplexNoise3D(s));
+        }
+    } else if (dimensions == LATTICE_DIMENSIONS_4D) {
+        LatticeNoiseSample4D s =
+            latticeSample4D(vec4(uv, time), LATTICE_MODE_SIMPLEX_4D);
+
+        if (output_mode == SIMPLEX_OUTPUT_DERIVATIVES) {
+            res = simplexNoise4DDerivatives(s);
+        } else {
+            res = vec4(simplexNoise4D(s));
+        }
+    } else {
+        // Invalid enum value
+        o_FragColor = vec4(1., 0., 1., 1.);
+        return;
+    }
+
+    if (output_mode == SIMPLEX_OUTPUT_VALUE) {
+        o_FragColor = vec4(vec3(to01(res.x)), 1.0);
+    } else if (output_mode == SIMPLEX_OUTPUT_DERIVATIVES) {
+        o_FragColor = to01(res);
+    } else {
+        // Invalid enum value
+        o_FragColor = vec4(1., 0., 1., 1.);
+    }
 }
 
 // vim: ft=glsl.doxygen
@@ -0,0 +1,197 @@
+/**
+ * @file simplex_noise.glsl
+ * @brief 2D, 3D and 4D simplex noise
+ * @author inigo quilez
+ * @author Alixinne <alixinne@pm.me>
+ * @see https://www.shadertoy.com/view/Msf3WH
+ *
+ * 2D, 3D and 4D simplex noise, shared by the simplex, fractal and curl noise
+ * shaders.
+ */
+
+#ifndef _SIMPLEX_NOISE_GLSL_
+#define _SIMPLEX_NOISE_GLSL_
+
+#include "noise_stats.glsl"
+
+vec2 simplexNoiseHash(uvec2 p, uint seed) { return tofloat11(hash2(p, seed)); }
+
+/**
+ * @brief Evaluate simplex noise
+ * @param s Sample in a simplex lattice
+ * @return Noise value, in [-1, 1]
+ */
+float simplexNoise(LatticeNoiseSample s) {
+    vec2 a = s.position;
+    float m = step(a.y, a.x);
+    vec2 o = vec2(m, 1.0 - m);
+    vec2 b = a - o + SIMPLEX_K2;
+    vec2 c = a - 1.0 + 2.0 * SIMPLEX_K2;
+    vec3 h = max(0.5 - vec3(dot(a, a), dot(b, b), dot(c, c)), 0.0);
+    vec3 n = h * h * h * h *
+             vec3(dot(a, simplexNoiseHash(simplexLatticeLoop(s.cell), s.seed)),
+                  dot(b, simplexNoiseHash(simplexLatticeLoop(s.cell + ivec2(o)), s.seed)),
+                  dot(c, simplexNoiseHash(simplexLatticeLoop(s.cell + 1), s.seed)));
+    return dot(n, vec3(71.2825901));
+}
+
+/**
+ * @brief Evaluate simplex noise and its derivatives
+ * @param s Sample in a simplex lattice
+ * @return Noise value, in [-1, 1], followed by its partial derivatives with
+ * respect to the lattice coordinates
+ */
+vec3 simplexNoiseDerivatives(LatticeNoiseSample s) {
+    vec2 a = s.position;
+    float m = step(a.y, a.x);
+    vec2 o = vec2(m, 1.0 - m);
+    vec2 b = a - o + SIMPLEX_K2;
+    vec2 c = a - 1.0 + 2.0 * SIMPLEX_K2;
+    vec3 h = max(0.5 - vec3(dot(a, a), dot(b, b), dot(c, c)), 0.0);
+
+    vec2 ga = simplexNoiseHash(simplexLatticeLoop(s.cell), s.seed);
+    vec2 gb = simplexNoiseHash(simplexLatticeLoop(s.cell + ivec2(o)), s.seed);
+    vec2 gc = simplexNoiseHash(simplexLatticeLoop(s.cell + 1), s.seed);
+
+    vec3 v = vec3(dot(a, ga), dot(b, gb), dot(c, gc));
+    vec3 h4 = h * h * h * h;
+
+    // d(h^4 v) = h^4 g - 8 h^3 v x, with h = 0.5 - |x|^2
+    vec3 t = -8. * h * h * h * v;
+    vec2 d = t.x * a + t.y * b + t.z * c + h4.x * ga + h4.y * gb + h4.z * gc;
+
+    return vec3(dot(h4 * v, vec3(71.2825901)),
+                simplexLatticeGradient(d * 71.2825901));
+}
+
+vec3 simplexNoiseHash3D(ivec3 p, uint seed) {
+    return tofloat11(hash3(uvec3(p), seed));
+}
+
+/**
+ * @brief Evaluate 3D simplex noise
+ * @param s Sample in a 3D simplex lattice
+ * @return Noise value, in [-1, 1]
+ */
+float simplexNoise3D(LatticeNoiseSample3D s) {
+    vec3 a = s.position;
+
+    // Offsets of the second and third corners of the current simplex. Ties are
+    // broken in x, y, z order, the z >= x test is strict to keep the ordering
+    // consistent when all coordinates are equal.
+    vec3 g = vec3(step(a.yz, a.xy), 1. - step(a.z, a.x));
+    vec3 l = 1. - g;
+    vec3 o1 = min(g, l.zxy);
+    vec3 o2 = max(g, l.zxy);
+
+    vec3 b = a - o1 + SIMPLEX3D_K2;
+    vec3 c = a - o2 + 2. * SIMPLEX3D_K2;
+    vec3 d = a - 1. + 3. * SIMPLEX3D_K2;
+
+    vec4 h = max(0.5 - vec4(dot(a, a), dot(b, b), dot(c, c), dot(d, d)), 0.);
+    vec4 n = h * h * h * h *
+             vec4(dot(a, simplexNoiseHash3D(simplexLatticeLoop3D(s.cell), s.seed)),
+                  dot(b, simplexNoiseHash3D(simplexLatticeLoop3D(s.cell + ivec3(o1)), s.seed)),
+                  dot(c, simplexNoiseHash3D(simplexLatticeLoop3D(s.cell + ivec3(o2)), s.seed)),
+                  dot(d, simplexNoiseHash3D(simplexLatticeLoop3D(s.cell + 1), s.seed)));
+    return dot(n, vec4(66.));
+}
+
+/**
+ * @brief Evaluate 3D simplex noise and its derivatives
+ * @param s Sample in a 3D simplex lattice
+ * @return Noise value, in [-1, 1], followed by its partial derivatives with
+ * respect to the lattice coordinates
+ */
+vec4 simplexNoise3DDerivatives(LatticeNoiseSample3D s) {
+    vec3 a = s.position;
+
+    // Offsets of the second and third corners of the current simplex. Ties are
+    // broken in x, y, z order, the z >= x test is strict to keep the ordering
+    // consistent when all coordinates are equal.
+    vec3 g = vec3(step(a.yz, a.xy), 1. - step(a.z, a.x));
+    vec3 l = 1. - g;
+    vec3 o1 = min(g, l.zxy);
+    vec3 o2 = max(g, l.zxy);
+
+    // Positions relative to the corners, and corner offsets
+    vec3 x[4] = vec3[4](a, a - o1 + SIMPLEX3D_K2, a - o2 + 2. * SIMPLEX3D_K2,
+                        a - 1. + 3. * SIMPLEX3D_K2);
+    ivec3 o[4] = ivec3[4](ivec3(0), ivec3(o1), ivec3(o2), ivec3(1));
+
+    vec4 res = vec4(0.);
+    for (int c = 0; c < 4; ++c) {
+        float h = max(0.5 - dot(x[c], x[c]), 0.);
+        vec3 gc = simplexNoiseHash3D(simplexLatticeLoop3D(s.cell + o[c]), s.seed);
+        float v = dot(x[c], gc);
+
+        // d(h^4 v) = h^4 g - 8 h^3 v x, with h = 0.5 - |x|^2
+        res += vec4(h * h * h * h * v,
+                    h * h * h * h * gc - 8. * h * h * h * v * x[c]);
+    }
+
+    return vec4(res.x, simplexLatticeGradient3D(res.yzw)) * 66.;
+}
+
+vec4 simplexNoiseHash4D(ivec4 p, uint seed) {
+    return tofloat11(hash4(uvec4(p), seed));
+}
+
+/**
+ * @brief Evaluate 4D simplex noise and its spatial derivatives
+ * @param s Sample in a 4D simplex lattice
+ * @return Noise value, in [-1, 1], followed by its partial derivatives with
+ * respect to the spatial lattice coordinates
+ */
+vec4 simplexNoise4DDerivatives(LatticeNoiseSample4D s) {
+    vec4 a = s.position;
+
+    // Rank of each coordinate, to find the corners of the current simplex
+    vec4 rank = vec4(0.);
+    vec3 isX = step(a.yzw, a.xxx);
+    rank.x = isX.x + isX.y + isX.z;
+    rank.yzw = 1. - isX;
+    vec2 isY = step(a.zw, a.yy);
+    rank.y += isY.x + isY.y;
+    rank.zw += 1. - isY;
+    float isZ = step(a.w, a.z);
+    rank.z += isZ;
+    rank.w += 1. - isZ;
+
+    vec4 o1 = clamp(rank - 2., 0., 1.);
+    vec4 o2 = clamp(rank - 1., 0., 1.);
+    vec4 o3 = clamp(rank, 0., 1.);
+
+    // Positions relative to the corners, and corner offsets
+    vec4 x[5] = vec4[5](a, a - o1 + SIMPLEX4D_K2, a - o2 + 2. * SIMPLEX4D_K2,
+                        a - o3 + 3. * SIMPLEX4D_K2, a - 1. + 4. * SIMPLEX4D_K2);
+    ivec4 o[5] =
+        ivec4[5](ivec4(0), ivec4(o1), ivec4(o2), ivec4(o3), ivec4(1));
+
+    float value = 0.;
+    vec4 d = vec4(0.);
+    for (int c = 0; c < 5; ++c) {
+        float h = max(0.5 - dot(x[c], x[c]), 0.);
+        vec4 gc = simplexNoiseHash4D(simplexLatticeLoop4D(s.cell + o[c]), s.seed);
+        float v = dot(x[c], gc);
+
+        // d(h^4 v) = h^4 g - 8 h^3 v x, with h = 0.5 - |x|^2
+        value += h * h * h * h * v;
+        d += h * h * h * h * gc - 8. * h * h * h * v * x[c];
+    }
+
+    return vec4(value, simplexLatticeGradient4D(d)) * 62.;
+}
+
+/**
+ * @brief Evaluate 4D simplex noise
+ * @param s Sample in a 4D simplex lattice
+ * @return Noise value, in [-1, 1]
+ */
+float simplexNoise4D(LatticeNoiseSample4D s) {
+    return simplexNoise4DDerivatives(s).x;
+}
+
+#endif /* _SIMPLEX_NOISE_GLSL_ */
+
+// vim: ft=glsl.doxygen
@@ -0,0 +1,130 @@
+#version 460 core
+
+/**
+ * @file sparse_convolution_noise.frag
+ * @brief Sparse convolution noise fragment shader
+ * @author Alixinne <alixinne@pm.me>
+ * @see https://doi.org/10.1145/15886.15897
+ *
+ * Sparse convolution of random impulses with a radial kernel: truncated
+ * gaussian, raised cosine or cubic B-spline.
+ */
+
+layout(location = 0) in vec3 pixelUv;
+layout(location = 0) out vec4 o_FragColor;
+
+// Required built-ins
+#include "noise.glsl"
+#include "shared.glsl"
+
+// Noise statistics helpers
+#include "noise_stats.glsl"
+
+// Fast rng
+#include "lcg.glsl"
+
+// Point distributions
+#include "points.glsl"
+
+layout(location = 20) uniform int kernel;
+layout(location = 21) uniform int kernel_count;
+layout(location = 22) uniform int noise_point_distribution;
+layout(location = 23) uniform float jitter_amount;
+layout(location = 24) uniform int jitter_max;
+layout(location = 25) uniform float kernel_radius;
+
+#define SPARSE_CONVOLUTION_KERNEL_GAUSSIAN 0
+#define SPARSE_CONVOLUTION_KERNEL_COSINE 1
+#define SPARSE_CONVOLUTION_KERNEL_BSPLINE 2
+
+/**
+ * @brief Evaluate the radial kernel
+ * @param s Distance to the kernel center, relative to the kernel radius
+ */
+float kernelProfile(float s) {
+    if (kernel == SPARSE_CONVOLUTION_KERNEL_GAUSSIAN) {
+        return exp(-4. * M_PI * s * s);
+    } else if (kernel == SPARSE_CONVOLUTION_KERNEL_COSINE) {
+        return .5 * (1. + cos(M_PI * s));
+    } else {
+        float t = 2. * s;
+        return t < 1. ? (4. - 6. * t * t + 3. * t * t * t) / 4.
+                      : (2. - t) * (2. - t) * (2. - t) / 4.;
+    }
+}
+
+/**
+ * @brief Integral of the squared kernel over the plane, for a unit radius
+ */
+float kernelEnergy() {
+    if (kernel == SPARSE_CONVOLUTION_KERNEL_GAUSSIAN) {
+        return .125;
+    } else if (kernel == SPARSE_CONVOLUTION_KERNEL_COSINE) {
+        return .54148;
+    } else {
+        return .28891;
+    }
+}
+
+float noiseCell(vec2 pos, ivec2 cell, uint seed) {
+    float res = 0.;
+
+    // Seed the random number generator
+    LCG rng = lcgSeed(hash(cell, seed));
+
+    // Compute impulse count
+    PointSet ps = pointSetInit(rng, noise_point_distribution, kernel_count,
+                               jitter_max, jitter_amount);
+
+    for (int i = 0; i < ps.count; ++i) {
+        vec2 kpos = pointSetNext(rng, ps, i);
+        float weight = lcgNext11(rng);
+
+        float s = length(pos - kpos) / kernel_radius;
+        if (s < 1.) {
+            res += weight * kernelProfile(s);
+        }
+    }
+
+    return res;
+}
+
+float noise(LatticeNoiseSample s) {
+    float res = 0.;
+    int lookahead = int(ceil(kernel_radius));
+
+    ivec2 cell = s.cell;
+    for (cell.x = s.cell.x - lookahead; cell.x <= s.cell.x + lookahead; ++cell.x) {
+        for (cell.y = s.cell.y - lookahead; cell.y <= s.cell.y + lookahead; ++cell.y) {
+            // Make sure the noise tiles correctly
+            ivec2 looped_cell = latticeLoop(cell);
+
+            // Compute the position of the current point relative to the target
+            // cell
+            vec2 position = s.position - vec2(cell - s.cell);
+
+            // Add contribution of target noise cell
+            res += noiseCell(position, looped_cell, s.seed);
+        }
+    }
+
+    // Normalize by the standard deviation of the noise, with uniform weights in
+    // [-1, 1]
+    float sigma = kernel_radius * sqrt(float(kernel_count) * kernelEnergy() / 3.);
+    return res / (3. * sigma);
+}
+
+void main() {
+    vec3 uv = domainUv(pixelUv);
+
+    if (kernel < SPARSE_CONVOLUTION_KERNEL_GAUSSIAN ||
+        kernel > SPARSE_CONVOLUTION_KERNEL_BSPLINE) {
+        // Invalid enum value
+        o_FragColor = vec4(1., 0., 1., 1.);
+        return;
+    }
+
+    o_FragColor = vec4(vec3(to01(noise(latticeSample(uv.xy, LATTICE_MODE_RECT_2D)))), 1.);
+}
+
+// vim: ft=glsl.doxygen
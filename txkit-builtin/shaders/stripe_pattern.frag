@@ -0,0 +1,35 @@
+#version 460 core
+
+/**
+ * @file stripe_pattern.frag
+ * @brief Stripe pattern
+ * @author Alixinne <alixinne@pm.me>
+ *
+ * Stripes with `scale` periods across the image along the direction given by
+ * `orientation`, anti-aliased with a box filter.
+ */
+
+layout(location = 0) in vec3 pixelUv;
+layout(location = 0) out vec4 o_FragColor;
+
+#include "patterns.glsl"
+
+layout(location = 20) uniform float scale;
+layout(location = 21) uniform float orientation;
+layout(location = 22) uniform float duty;
+layout(location = 23) uniform float antialiasing;
+
+void main() {
+    vec3 uv = domainUv(pixelUv);
+
+    vec2 d = vec2(cos(orientation), sin(orientation));
+    float t = dot(uv.xy * scale, d);
+
+    // Footprint of a pixel along the stripe direction
+    float w = antialiasing * scale * dot(abs(d), 1. / vec2(iResolution.xy));
+
+    ivec2 cell = ivec2(floor(t), 0);
+    o_FragColor = vec4(filteredPulse(t, duty, w), tileId(cell), cell);
+}
+
+// vim: ft=glsl.doxygen
@@ -0,0 +1,99 @@
+#version 460 core
+
+/**
+ * @file tiling_and_blending.frag
+ * @brief Histogram-preserving tiling and blending fragment shader
+ * @author Alixinne <alixinne@pm.me>
+ * @see https://doi.org/10.1145/3233304
+ *
+ * By-example texture synthesis: the output is a blend of three randomly
+ * offset copies of the gaussianized exemplar on a triangle grid. The blend
+ * preserves the gaussian histogram, which is then mapped back to the
+ * exemplar histogram through the inverse histogram lookup table.
+ */
+
+layout(location = 0) in vec3 pixelUv;
+layout(location = 0) out vec4 o_FragColor;
+
+#include "noise.glsl"
+#include "shared.glsl"
+
+layout(location = 20) uniform float repeat;
+layout(location = 21) uniform float tile_size;
+
+layout(location = 22, binding = 1) uniform sampler2D gaussian_exemplar;
+layout(location = 23, binding = 2) uniform sampler2D inverse_histogram;
+
+/**
+ * @brief Find the vertices of the triangle grid cell containing a point
+ * @param p Position in grid units
+ * @param w Barycentric weights of the vertices
+ * @param v1 First vertex
+ * @param v2 Second vertex
+ * @param v3 Third vertex
+ */
+void triangleGrid(vec2 p, out vec3 w, out ivec2 v1, out ivec2 v2,
+                  out ivec2 v3) {
+    // Skew the grid so equilateral triangles become right triangles
+    vec2 skewed = vec2(p.x - .57735027 * p.y, 1.15470054 * p.y);
+    ivec2 base = ivec2(floor(skewed));
+    vec3 t = vec3(fract(skewed), 0.);
+    t.z = 1. - t.x - t.y;
+
+    if (t.z > 0.) {
+        w = vec3(t.z, t.y, t.x);
+        v1 = base;
+        v2 = base + ivec2(0, 1);
+        v3 = base + ivec2(1, 0);
+    } else {
+        w = vec3(-t.z, 1. - t.y, 1. - t.x);
+        v1 = base + ivec2(1, 1);
+        v2 = base + ivec2(1, 0);
+        v3 = base + ivec2(0, 1);
+    }
+}
+
+/**
+ * @brief Sample the gaussianized exemplar with the random offset of a vertex
+ * @param p Position in the exemplar, in texture coordinates
+ * @param v Triangle grid vertex
+ */
+vec4 vertexSample(vec2 p, ivec2 v) {
+    return texture(gaussian_exemplar, p + tofloat(hash2(uvec2(v), globalSeed)));
+}
+
+/**
+ * @brief Map a gaussianized value back to the exemplar histogram
+ * @param g Gaussianized value
+ * @param c Channel index
+ */
+float inverseHistogram(float g, int c) {
+    // Clamp to texel centers to avoid wrapping around the table
+    float n = float(textureSize(inverse_histogram, 0).x);
+    return texture(inverse_histogram, vec2(clamp(g, .5 / n, 1. - .5 / n), .5))[c];
+}
+
+void main() {
+    vec3 uv = domainUv(pixelUv);
+
+    vec2 exemplarSize = vec2(textureSize(gaussian_exemplar, 0));
+
+    // Position in exemplar pixels
+    vec2 p = uv.xy * vec2(iResolution.xy) * repeat * exemplarSize.x /
+             float(iResolution.x);
+
+    vec3 w;
+    ivec2 v1, v2, v3;
+    triangleGrid(p / (tile_size * exemplarSize.x), w, v1, v2, v3);
+
+    // Variance-preserving blend of the gaussianized samples
+    vec2 t = p / exemplarSize;
+    vec4 g = w.x * vertexSample(t, v1) + w.y * vertexSample(t, v2) +
+             w.z * vertexSample(t, v3);
+    g = (g - .5) * inversesqrt(dot(w, w)) + .5;
+
+    o_FragColor = vec4(inverseHistogram(g.r, 0), inverseHistogram(g.g, 1),
+                       inverseHistogram(g.b, 2), inverseHistogram(g.a, 3));
+}
+
+// vim: ft=glsl.doxygen
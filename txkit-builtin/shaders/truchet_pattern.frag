@@ -0,0 +1,59 @@
+#version 460 core
+
+/**
+ * @file truchet_pattern.frag
+ * @brief Truchet tiles
+ * @author Alixinne <alixinne@pm.me>
+ *
+ * Square tiles with a random rotation given by the tile ID. Arc tiles join
+ * the midpoints of adjacent edges with two quarter circles, triangle tiles
+ * are split along a diagonal.
+ */
+
+layout(location = 0) in vec3 pixelUv;
+layout(location = 0) out vec4 o_FragColor;
+
+#include "patterns.glsl"
+
+layout(location = 20) uniform float scale;
+layout(location = 21) uniform int tile;
+layout(location = 22) uniform float line_width;
+layout(location = 23) uniform float antialiasing;
+
+#define TRUCHET_PATTERN_ARCS 0
+#define TRUCHET_PATTERN_TRIANGLES 1
+
+void main() {
+    vec3 uv = domainUv(pixelUv);
+
+    if (tile < TRUCHET_PATTERN_ARCS || tile > TRUCHET_PATTERN_TRIANGLES) {
+        // Invalid enum value
+        o_FragColor = vec4(1., 0., 1., 1.);
+        return;
+    }
+
+    vec2 p = uv.xy * scale;
+    ivec2 cell = ivec2(floor(p));
+    vec2 f = fract(p);
+    float id = tileId(cell);
+
+    // Rotate the tile by a random multiple of 90 degrees
+    int rotation = int(id * 4.) & 3;
+    for (int i = 0; i < rotation; ++i) {
+        f = vec2(1. - f.y, f.x);
+    }
+
+    float w = antialiasing * scale / float(min(iResolution.x, iResolution.y));
+    float value;
+
+    if (tile == TRUCHET_PATTERN_ARCS) {
+        float d = min(abs(length(f) - .5), abs(length(f - 1.) - .5));
+        value = filteredEdge(.5 * line_width - d, w);
+    } else {
+        value = filteredEdge((f.x - f.y) / sqrt(2.), w);
+    }
+
+    o_FragColor = vec4(value, id, cell);
+}
+
+// vim: ft=glsl.doxygen
@@ -2,15 +2,16 @@
 
 /**
  * @file value_noise.frag
- * @brief 2D value noise fragment shader
+ * @brief 2D, 3D and 4D value noise fragment shader
  * @author inigo quilez
  * @author Alixinne <alixinne@pm.me>
  * @see https://www.shadertoy.com/view/lsf3WH
  *
- * 2D value noise, adapted for use in txkit.
+ * 2D value noise, adapted for use in txkit, and extended to 3D and 4D. 4D
+ * noise uses the layer as third coordinate and the time uniform as fourth.
  */
 
-layout(location = 0) in vec3 uv;
+layout(location = 0) in vec3 pixelUv;
 layout(location = 0) out vec4 o_FragColor;
 
 // Required built-ins
@@ -20,25 +21,31 @@ layout(location = 0) out vec4 o_FragColor;
 // Noise statistics helpers
 #include "noise_stats.glsl"
 
-float noisehash(uvec2 p, uint seed) { return tofloat(hash(p, seed)); }
+// Value noise
+#include "value_noise.glsl"
 
-float noise(LatticeNoiseSample s) {
-    uvec2 i = uvec2(s.cell);
-    vec2 f = s.position;
-
-    vec2 u = f * f * (3. - 2. * f);
-
-    return mix(mix(noisehash(latticeLoop(i + uvec2(0, 0)), s.seed),
-                   noisehash(latticeLoop(i + uvec2(1, 0)), s.seed), u.x),
-               mix(noisehash(latticeLoop(i + uvec2(0, 1)), s.seed),
-                   noisehash(latticeLoop(i + uvec2(1, 1)), s.seed), u.x),
-               u.y) *
-           0.73582062;
-}
+layout(location = 20) uniform int dimensions;
+layout(location = 21) uniform float time;
 
 void main() {
-    o_FragColor =
-        vec4(vec3(noise(latticeSample(uv.xy, LATTICE_MODE_RECT_2D))), 1.0);
+    vec3 uv = domainUv(pixelUv);
+
+    float value;
+
+    if (dimensions == LATTICE_DIMENSIONS_2D) {
+        value = valueNoise(latticeSample(uv.xy, LATTICE_MODE_RECT_2D));
+    } else if (dimensions == LATTICE_DIMENSIONS_3D) {
+        value = valueNoise3D(latticeSample3D(uv, LATTICE_MODE_RECT_3D));
+    } else if (dimensions == LATTICE_DIMENSIONS_4D) {
+        value =
+            valueNoise4D(latticeSample4D(vec4(uv, time), LATTICE_MODE_RECT_4D));
+    } else {
+        // Invalid enum value
+        o_FragColor = vec4(1., 0., 1., 1.);
+        return;
+    }
+
+    o_FragColor = vec4(vec3(value * 0.73582062), 1.0);
 }
 
 // vim: ft=glsl.doxygen
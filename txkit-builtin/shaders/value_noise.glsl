@@ -0,0 +1,91 @@
+/**
+ * @file value_noise.glsl
+ * @brief 2D, 3D and 4D value noise
+ * @author inigo quilez
+ * @author Alixinne <alixinne@pm.me>
+ * @see https://www.shadertoy.com/view/lsf3WH
+ *
+ * 2D, 3D and 4D value noise, shared by the value and fractal noise shaders.
+ */
+
+#ifndef _VALUE_NOISE_GLSL_
+#define _VALUE_NOISE_GLSL_
+
+#include "noise_stats.glsl"
+
+float valueNoiseHash(uvec2 p, uint seed) { return tofloat(hash(p, seed)); }
+
+/**
+ * @brief Evaluate value noise
+ * @param s Sample in a rectangular lattice
+ * @return Interpolated lattice value, in [0, 1]
+ */
+float valueNoise(LatticeNoiseSample s) {
+    uvec2 i = uvec2(s.cell);
+    vec2 f = s.position;
+
+    vec2 u = f * f * (3. - 2. * f);
+
+    return mix(mix(valueNoiseHash(latticeLoop(i + uvec2(0, 0)), s.seed),
+                   valueNoiseHash(latticeLoop(i + uvec2(1, 0)), s.seed), u.x),
+               mix(valueNoiseHash(latticeLoop(i + uvec2(0, 1)), s.seed),
+                   valueNoiseHash(latticeLoop(i + uvec2(1, 1)), s.seed), u.x),
+               u.y);
+}
+
+float valueNoiseHash3D(uvec3 p, uint seed) { return tofloat(hash(p, seed)); }
+
+/**
+ * @brief Evaluate 3D value noise
+ * @param s Sample in a rectangular 3D lattice
+ * @return Interpolated lattice value, in [0, 1]
+ */
+float valueNoise3D(LatticeNoiseSample3D s) {
+    uvec3 i = uvec3(s.cell);
+    vec3 f = s.position;
+
+    vec3 u = f * f * (3. - 2. * f);
+
+    return mix(
+        mix(mix(valueNoiseHash3D(latticeLoop(i + uvec3(0, 0, 0)), s.seed),
+                valueNoiseHash3D(latticeLoop(i + uvec3(1, 0, 0)), s.seed), u.x),
+            mix(valueNoiseHash3D(latticeLoop(i + uvec3(0, 1, 0)), s.seed),
+                valueNoiseHash3D(latticeLoop(i + uvec3(1, 1, 0)), s.seed), u.x),
+            u.y),
+        mix(mix(valueNoiseHash3D(latticeLoop(i + uvec3(0, 0, 1)), s.seed),
+                valueNoiseHash3D(latticeLoop(i + uvec3(1, 0, 1)), s.seed), u.x),
+            mix(valueNoiseHash3D(latticeLoop(i + uvec3(0, 1, 1)), s.seed),
+                valueNoiseHash3D(latticeLoop(i + uvec3(1, 1, 1)), s.seed), u.x),
+            u.y),
+        u.z);
+}
+
+float valueNoiseHash4D(ivec4 p, uint seed) {
+    return tofloat(hash(uvec4(p), seed));
+}
+
+/**
+ * @brief Evaluate 4D value noise
+ * @param s Sample in a rectangular 4D lattice
+ * @return Interpolated lattice value, in [0, 1]
+ */
+float valueNoise4D(LatticeNoiseSample4D s) {
+    vec4 f = s.position;
+    vec4 u = f * f * (3. - 2. * f);
+
+    // Sum the contributions of the 16 cell corners
+    float res = 0.;
+    for (int c = 0; c < 16; ++c) {
+        ivec4 o = (ivec4(c) >> ivec4(0, 1, 2, 3)) & 1;
+        vec4 w = mix(1. - u, u, vec4(o));
+
+        res += w.x * w.y * w.z * w.w *
+               valueNoiseHash4D(latticeLoop(s.cell + o), s.seed);
+    }
+
+    return res;
+}
+
+#endif /* _VALUE_NOISE_GLSL_ */
+
+// vim: ft=glsl.doxygen
@@ -2,10 +2,13 @@
 
 /**
  * @file white_noise.frag
- * @brief 3D uniform white noise
+ * @brief 3D white noise
  * @author Alixinne <alixinne@pm.me>
  *
- * 3D uniform white noise
+ * 3D white noise, with uniform, gaussian, exponential, bernoulli or discrete
+ * distributions. Non-uniform distributions are sampled from the uniform
+ * samples by inverse transform, or with the Box-Muller transform for the
+ * gaussian distribution.
  */
 
 layout(location = 0) in vec3 uv;
@@ -14,7 +17,52 @@ layout(location = 0) out vec4 o_FragColor;
 #include "noise.glsl"
 #include "shared.glsl"
 
+layout(location = 20) uniform int distribution;
+layout(location = 21) uniform int channels;
+layout(location = 22) uniform float mean;
+layout(location = 23) uniform float std_dev;
+layout(location = 24) uniform float rate;
+layout(location = 25) uniform float probability;
+layout(location = 26) uniform uint levels;
+
+#define WHITE_NOISE_DISTRIBUTION_UNIFORM 0
+#define WHITE_NOISE_DISTRIBUTION_GAUSSIAN 1
+#define WHITE_NOISE_DISTRIBUTION_EXPONENTIAL 2
+#define WHITE_NOISE_DISTRIBUTION_BERNOULLI 3
+#define WHITE_NOISE_DISTRIBUTION_DISCRETE 4
+
+#define WHITE_NOISE_CHANNELS_SHARED 1
+
+/**
+ * @brief Sample the noise distribution
+ * @param h Pair of independent hashes
+ * @return Sample value
+ */
+float sampleDistribution(uvec2 h) {
+    float u = tofloat(h.x);
+
+    if (distribution == WHITE_NOISE_DISTRIBUTION_UNIFORM) {
+        return u;
+    } else if (distribution == WHITE_NOISE_DISTRIBUTION_GAUSSIAN) {
+        return mean + std_dev * sqrt(-2. * log(1. - u)) * cos(M_2PI * tofloat(h.y));
+    } else if (distribution == WHITE_NOISE_DISTRIBUTION_EXPONENTIAL) {
+        return -log(1. - u) / rate;
+    } else if (distribution == WHITE_NOISE_DISTRIBUTION_BERNOULLI) {
+        return u < probability ? 1. : 0.;
+    } else {
+        float l = float(max(levels, 2u) - 1u);
+        return min(floor(u * (l + 1.)), l) / l;
+    }
+}
+
 void main() {
+    if (distribution < WHITE_NOISE_DISTRIBUTION_UNIFORM ||
+        distribution > WHITE_NOISE_DISTRIBUTION_DISCRETE) {
+        // Invalid enum value
+        o_FragColor = vec4(1., 0., 1., 1.);
+        return;
+    }
+
     ivec3 px = ivec3(uv * vec3(iResolution));
 
     uvec2 idx = shl64(
@@ -23,10 +71,14 @@ void main() {
             .zw,
         2);
 
-    o_FragColor = vec4(tofloat(hash64(idx | uvec2(0, 0)).x),
-                       tofloat(hash64(idx | uvec2(0, 1)).x),
-                       tofloat(hash64(idx | uvec2(0, 2)).x),
-                       tofloat(hash64(idx | uvec2(0, 3)).x));
+    if (channels == WHITE_NOISE_CHANNELS_SHARED) {
+        o_FragColor = vec4(sampleDistribution(hash64(idx)));
+    } else {
+        o_FragColor = vec4(sampleDistribution(hash64(idx | uvec2(0, 0))),
+                           sampleDistribution(hash64(idx | uvec2(0, 1))),
+                           sampleDistribution(hash64(idx | uvec2(0, 2))),
+                           sampleDistribution(hash64(idx | uvec2(0, 3))));
+    }
 }
 
 // vim: ft=glsl.doxygen
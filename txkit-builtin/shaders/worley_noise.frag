@@ -0,0 +1,65 @@
+#version 460 core
+
+/**
+ * @file worley_noise.frag
+ * @brief Worley (cellular) noise fragment shader
+ * @author Alixinne <alixinne@pm.me>
+ * @see https://doi.org/10.1145/237170.237267
+ *
+ * Cellular noise: distances to the closest feature points, using the same
+ * point distributions as phasor noise.
+ */
+
+layout(location = 0) in vec3 pixelUv;
+layout(location = 0) out vec4 o_FragColor;
+
+// Required built-ins
+#include "noise.glsl"
+#include "shared.glsl"
+
+// Noise statistics helpers
+#include "noise_stats.glsl"
+
+// Worley noise
+#include "worley_noise.glsl"
+
+layout(location = 20) uniform int lookahead;
+layout(location = 21) uniform int point_count;
+layout(location = 22) uniform int point_distribution;
+layout(location = 23) uniform float jitter_amount;
+layout(location = 24) uniform int jitter_max;
+layout(location = 25) uniform int metric;
+layout(location = 26) uniform int output_mode;
+
+#define WORLEY_OUTPUT_F1 0
+#define WORLEY_OUTPUT_F2 1
+#define WORLEY_OUTPUT_F2_MINUS_F1 2
+#define WORLEY_OUTPUT_CELL_ID 3
+
+vec3 noise(LatticeNoiseSample s) {
+    float f1, f2;
+    uint id;
+    worleyNoise(s, lookahead, point_count, point_distribution, jitter_amount,
+                jitter_max, metric, f1, f2, id);
+
+    if (output_mode == WORLEY_OUTPUT_F1) {
+        return vec3(f1);
+    } else if (output_mode == WORLEY_OUTPUT_F2) {
+        return vec3(f2);
+    } else if (output_mode == WORLEY_OUTPUT_F2_MINUS_F1) {
+        return vec3(f2 - f1);
+    } else if (output_mode == WORLEY_OUTPUT_CELL_ID) {
+        return vec3(tofloat(id));
+    }
+
+    // Invalid enum value
+    return vec3(1., 0., 1.);
+}
+
+void main() {
+    vec3 uv = domainUv(pixelUv);
+
+    o_FragColor = vec4(noise(latticeSample(uv.xy, LATTICE_MODE_RECT_2D)), 1.);
+}
+
+// vim: ft=glsl.doxygen
@@ -0,0 +1,91 @@
+/**
+ * @file worley_noise.glsl
+ * @brief Worley (cellular) noise
+ * @author Alixinne <alixinne@pm.me>
+ * @see https://doi.org/10.1145/237170.237267
+ *
+ * Cellular noise distances, shared by the Worley and fractal noise shaders.
+ */
+
+#ifndef _WORLEY_NOISE_GLSL_
+#define _WORLEY_NOISE_GLSL_
+
+#include "noise_stats.glsl"
+#include "points.glsl"
+
+#define WORLEY_METRIC_EUCLIDEAN 0
+#define WORLEY_METRIC_MANHATTAN 1
+#define WORLEY_METRIC_CHEBYSHEV 2
+
+/// Distance returned when no feature point is within the lookahead
+#define WORLEY_MAX_DISTANCE 1e9
+
+float worleyDistance(vec2 d, int metric) {
+    if (metric == WORLEY_METRIC_MANHATTAN) {
+        return abs(d.x) + abs(d.y);
+    } else if (metric == WORLEY_METRIC_CHEBYSHEV) {
+        return max(abs(d.x), abs(d.y));
+    }
+
+    return length(d);
+}
+
+/**
+ * @brief Find the two closest feature points to a lattice sample
+ * @param s Sample in a rectangular lattice
+ * @param lookahead Number of cells to check for feature points
+ * @param pointCount Feature point count per cell
+ * @param pointDistribution Distribution of the points, see #PointDistribution
+ * @param jitterAmount Jittering amount
+ * @param jitterMax Max jittering subcells, 0 = no limit
+ * @param metric Distance metric
+ * @param f1 Distance to the closest feature point
+ * @param f2 Distance to the second closest feature point
+ * @param id Identifier of the closest feature point
+ */
+void worleyNoise(LatticeNoiseSample s, int lookahead, int pointCount,
+                 int pointDistribution, float jitterAmount, int jitterMax,
+                 int metric, out float f1, out float f2, out uint id) {
+    f1 = WORLEY_MAX_DISTANCE;
+    f2 = WORLEY_MAX_DISTANCE;
+    id = 0u;
+
+    ivec2 cell = s.cell;
+    for (cell.x = s.cell.x - lookahead; cell.x <= s.cell.x + lookahead;
+         ++cell.x) {
+        for (cell.y = s.cell.y - lookahead; cell.y <= s.cell.y + lookahead;
+             ++cell.y) {
+            // Make sure the noise tiles correctly
+            ivec2 looped_cell = latticeLoop(cell);
+
+            // Compute the position of the current point relative to the target
+            // cell
+            vec2 position =
+                s.position - vec2(cell.x - s.cell.x, cell.y - s.cell.y);
+
+            // Seed the random number generator
+            uint cellSeed = hash(looped_cell, s.seed);
+            LCG rng = lcgSeed(cellSeed);
+
+            PointSet ps = pointSetInit(rng, pointDistribution, pointCount,
+                                       jitterMax, jitterAmount);
+
+            for (int i = 0; i < ps.count; ++i) {
+                float d =
+                    worleyDistance(pointSetNext(rng, ps, i) - position, metric);
+
+                if (d < f1) {
+                    f2 = f1;
+                    f1 = d;
+                    id = hash(cellSeed + uint(i));
+                } else if (d < f2) {
+                    f2 = d;
+                }
+            }
+        }
+    }
+}
+
+#endif /* _WORLEY_NOISE_GLSL_ */
+
+// vim: ft=glsl.doxygen
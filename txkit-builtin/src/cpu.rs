@@ -0,0 +1,119 @@
+//! Helpers for CPU methods which compute all the channels of a pixel at once
+
+use cgmath::Vector3;
+use ndarray::{par_azip, s, Array3, Axis};
+use txkit_core::context::CpuContext;
+use txkit_core::image::{Image, ImageDim, IntoElementType};
+use txkit_core::{Error, Result};
+
+use crate::shared::pixel_uv;
+
+/// Evaluate a function for every pixel of an image in parallel
+///
+/// Channels beyond the image's channel count are discarded.
+///
+/// # Parameters
+///
+/// * `ctx`: CPU context to perform computations in
+/// * `tgt`: image to fill
+/// * `f`: function computing the RGBA value of the pixel at `(layer, row, column)`
+pub fn compute_pixels<F>(ctx: &mut CpuContext, tgt: &mut Image, f: F) -> Result<()>
+where
+    F: Fn((usize, usize, usize), ImageDim) -> [f32; 4] + Sync,
+{
+    let dim = tgt.dim();
+    let mut data_mut = tgt.data_mut()?;
+
+    if let Some(mut data) = data_mut.as_u8_nd_array_mut() {
+        ctx.thread_pool.install(|| {
+            par_azip!((index idx, mut px in data.lanes_mut(Axis(3))) {
+                for (o, v) in px.iter_mut().zip(&f(idx, dim)) {
+                    *o = v.into_u8();
+                }
+            });
+        });
+
+        Ok(())
+    } else if let Some(mut data) = data_mut.as_f32_nd_array_mut() {
+        ctx.thread_pool.install(|| {
+            par_azip!((index idx, mut px in data.lanes_mut(Axis(3))) {
+                for (o, v) in px.iter_mut().zip(&f(idx, dim)) {
+                    *o = *v;
+                }
+            });
+        });
+
+        Ok(())
+    } else {
+        Err(Error::FormatNotSupported)
+    }
+}
+
+/// Texture coordinates at which a CPU method evaluates pixels
+///
+/// See [Context::set_domain](txkit_core::context::Context::set_domain).
+pub struct Domain {
+    /// Red, green and blue channels of the domain, indexed by `(row, column)`
+    rgb: Option<Array3<f32>>,
+}
+
+impl Domain {
+    /// Copy the domain of a CPU context for computing an image
+    ///
+    /// # Parameters
+    ///
+    /// * `ctx`: CPU context to read the domain of
+    /// * `dim`: dimensions of the image to compute
+    pub fn new(ctx: &CpuContext, dim: ImageDim) -> Result<Self> {
+        let domain = match &ctx.domain {
+            Some(domain) => domain.borrow(),
+            None => return Ok(Self { rgb: None }),
+        };
+
+        let domain_dim = domain.dim();
+        if domain_dim.width != dim.width || domain_dim.height != dim.height {
+            return Err(Error::InvalidDomain(format!(
+                "expected a {}x{} image, got {}x{}",
+                dim.width, dim.height, domain_dim.width, domain_dim.height
+            )));
+        }
+
+        if domain_dim.channels < 3 {
+            return Err(Error::InvalidDomain(
+                "at least 3 channels are required".to_owned(),
+            ));
+        }
+
+        let data = domain.data()?;
+        let data = data
+            .as_f32_nd_array()
+            .ok_or_else(|| Error::InvalidDomain("a Float32 image is required".to_owned()))?;
+
+        Ok(Self {
+            rgb: Some(data.slice(s![0, .., .., 0..3]).to_owned()),
+        })
+    }
+
+    /// Compute the `uv` coordinates at which a pixel is evaluated
+    ///
+    /// # Parameters
+    ///
+    /// * `idx`: pixel coordinates (layer, row, column)
+    /// * `dim`: dimensions of the target image
+    pub fn uv(&self, idx: (usize, usize, usize), dim: ImageDim) -> Vector3<f32> {
+        let uv = pixel_uv(idx, dim);
+
+        match &self.rgb {
+            Some(rgb) => {
+                let (_, j, i) = idx;
+                let w = rgb[(j, i, 2)];
+                Vector3::new(
+                    uv.x * (1. - w) + rgb[(j, i, 0)] * w,
+                    uv.y * (1. - w) + rgb[(j, i, 1)] * w,
+                    uv.z,
+                )
+            }
+            None => uv,
+        }
+    }
+}
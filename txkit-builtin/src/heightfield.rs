@@ -0,0 +1,45 @@
+//! CPU port of the shared heightfield definitions in `heightfield.glsl`
+
+use cgmath::{vec2, Vector2};
+use txkit_core::image::ImageDim;
+use txkit_core::io::cpu::{CpuImageIoExt, CpuTexture};
+use txkit_core::io::ImageIo;
+use txkit_core::Result;
+
+/// Texture unit of the height, see `heightfield.glsl`
+const HEIGHT_UNIT: usize = 0;
+
+/// Thread-safe copy of a height texture, for sampling the scaled height on the CPU
+pub struct HeightField {
+    height: CpuTexture,
+    strength: f32,
+    resolution: Vector2<f32>,
+}
+
+impl HeightField {
+    /// Copy the height bound to texture unit 0
+    ///
+    /// # Parameters
+    ///
+    /// * `io`: texture bindings of the method
+    /// * `strength`: height of a unit of the height texture, in pixels
+    /// * `dim`: dimensions of the target image
+    pub fn new(io: &ImageIo, strength: f32, dim: ImageDim) -> Result<Self> {
+        Ok(Self {
+            height: io.texture_binding_data(HEIGHT_UNIT)?,
+            strength,
+            resolution: vec2(dim.width as f32, dim.height as f32),
+        })
+    }
+
+    /// Sample the scaled height, in pixels
+    ///
+    /// # Parameters
+    ///
+    /// * `p`: texture coordinates of the current pixel
+    /// * `offset`: offset from the current pixel, in pixels
+    pub fn at(&self, p: Vector2<f32>, offset: Vector2<f32>) -> f32 {
+        let uv = p + vec2(offset.x / self.resolution.x, offset.y / self.resolution.y);
+        self.strength * self.height.sample_2d(uv.into())[0]
+    }
+}
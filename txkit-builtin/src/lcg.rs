@@ -0,0 +1,47 @@
+//! CPU port of the linear congruential generator in `lcg.glsl`
+
+/// Linear congruential pseudo-random number generator
+#[derive(Debug, Clone, Copy)]
+pub struct Lcg {
+    state: u32,
+}
+
+impl Lcg {
+    /// Create a new generator from the given seed
+    pub fn new(seed: u32) -> Self {
+        Self { state: seed }
+    }
+
+    /// Get the next value of the generator, in [0, 32767]
+    pub fn next_u32(&mut self) -> u32 {
+        let x = 1103515245u32.wrapping_mul(self.state).wrapping_add(12345) % (1 << 31);
+        self.state = x;
+        x >> 16
+    }
+
+    /// Get the next value of the generator as a float in [0, 1]
+    pub fn next_01(&mut self) -> f32 {
+        self.next_u32() as f32 / 32767.
+    }
+
+    /// Get the next value of the generator as a float in [-1, 1]
+    pub fn next_11(&mut self) -> f32 {
+        2. * self.next_01() - 1.
+    }
+
+    /// Sample a Poisson distribution with the given mean
+    ///
+    /// Only Knuth's algorithm is implemented, since it is the only branch `lcgPoisson` takes.
+    pub fn poisson(&mut self, mean: f32) -> i32 {
+        let mut em = 0;
+
+        let g = (-mean).exp();
+        let mut t = self.next_01();
+        while t > g {
+            em += 1;
+            t *= self.next_01();
+        }
+
+        em
+    }
+}
@@ -1 +1,14 @@
 pub mod methods;
+#[cfg(feature = "serde")]
+pub mod preset;
+
+#[cfg(feature = "cpu")]
+mod cpu;
+#[cfg(feature = "cpu")]
+mod heightfield;
+mod lcg;
+#[cfg(feature = "cpu")]
+mod patterns;
+#[cfg(feature = "cpu")]
+mod points;
+mod shared;
@@ -16,6 +16,63 @@ pub use simplex_noise::*;
 mod phasor_noise;
 pub use phasor_noise::*;
 
+mod worley_noise;
+pub use worley_noise::*;
+
+mod fractal_noise;
+pub use fractal_noise::*;
+
+mod domain_warp;
+pub use domain_warp::*;
+
+mod curl_noise;
+pub use curl_noise::*;
+
+mod blue_noise;
+pub use blue_noise::*;
+
+mod gabor_noise;
+pub use gabor_noise::*;
+
+mod sparse_convolution_noise;
+pub use sparse_convolution_noise::*;
+
+mod tiling_and_blending;
+pub use tiling_and_blending::*;
+
+mod checker_pattern;
+pub use checker_pattern::*;
+
+mod stripe_pattern;
+pub use stripe_pattern::*;
+
+mod brick_pattern;
+pub use brick_pattern::*;
+
+mod polygon_pattern;
+pub use polygon_pattern::*;
+
+mod truchet_pattern;
+pub use truchet_pattern::*;
+
+mod sdf_2d;
+pub use sdf_2d::*;
+
+mod distance_transform;
+pub use distance_transform::*;
+
+mod normal_map;
+pub use normal_map::*;
+
+mod curvature;
+pub use curvature::*;
+
+mod ambient_occlusion;
+pub use ambient_occlusion::*;
+
+mod erosion;
+pub use erosion::*;
+
 use txkit_core::method::MethodRegistry;
 pub fn new_registry() -> MethodRegistry {
     let mut registry = MethodRegistry::new();
@@ -28,5 +85,48 @@ pub fn new_registry() -> MethodRegistry {
     );
     registry.register("simplex_noise", Box::new(|| Box::new(SimplexNoise::new())));
     registry.register("phasor_noise", Box::new(|| Box::new(PhasorNoise::new())));
+    registry.register("worley_noise", Box::new(|| Box::new(WorleyNoise::new())));
+    registry.register("fractal_noise", Box::new(|| Box::new(FractalNoise::new())));
+    registry.register("domain_warp", Box::new(|| Box::new(DomainWarp::new())));
+    registry.register("curl_noise", Box::new(|| Box::new(CurlNoise::new())));
+    registry.register("blue_noise", Box::new(|| Box::new(BlueNoise::new())));
+    registry.register("gabor_noise", Box::new(|| Box::new(GaborNoise::new())));
+    registry.register(
+        "sparse_convolution_noise",
+        Box::new(|| Box::new(SparseConvolutionNoise::new())),
+    );
+    registry.register(
+        "tiling_and_blending",
+        Box::new(|| Box::new(TilingAndBlending::new())),
+    );
+    registry.register(
+        "checker_pattern",
+        Box::new(|| Box::new(CheckerPattern::new())),
+    );
+    registry.register(
+        "stripe_pattern",
+        Box::new(|| Box::new(StripePattern::new())),
+    );
+    registry.register("brick_pattern", Box::new(|| Box::new(BrickPattern::new())));
+    registry.register(
+        "polygon_pattern",
+        Box::new(|| Box::new(PolygonPattern::new())),
+    );
+    registry.register(
+        "truchet_pattern",
+        Box::new(|| Box::new(TruchetPattern::new())),
+    );
+    registry.register("sdf_2d", Box::new(|| Box::new(Sdf2d::new())));
+    registry.register(
+        "distance_transform",
+        Box::new(|| Box::new(DistanceTransform::new())),
+    );
+    registry.register("normal_map", Box::new(|| Box::new(NormalMap::new())));
+    registry.register("curvature", Box::new(|| Box::new(Curvature::new())));
+    registry.register(
+        "ambient_occlusion",
+        Box::new(|| Box::new(AmbientOcclusion::new())),
+    );
+    registry.register("erosion", Box::new(|| Box::new(Erosion::new())));
     registry
 }
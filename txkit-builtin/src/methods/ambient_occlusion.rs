@@ -0,0 +1,121 @@
+use txkit_core::io::ImageIo;
+use txkit_impl::{Method, ParamsFor};
+
+#[cfg(feature = "cpu")]
+use cgmath::vec2;
+#[cfg(feature = "cpu")]
+use txkit_core::{context::CpuContext, image::Image};
+
+#[cfg(feature = "cpu")]
+use crate::cpu::{compute_pixels, Domain};
+#[cfg(feature = "cpu")]
+use crate::heightfield::HeightField;
+
+#[derive(Clone, PartialEq, ParamsFor)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[repr(C)]
+#[txkit(program = "AmbientOcclusionProgram")]
+pub struct AmbientOcclusionParams {
+    /// height of a unit of the height texture, in pixels
+    pub strength: f32,
+    /// distance to search for occluders, in pixels
+    pub radius: f32,
+    /// number of directions to search for occluders
+    pub directions: i32,
+    /// number of samples along each direction
+    pub steps: i32,
+
+    /// texture inputs, the height is bound to unit 0
+    #[texture_io(height)]
+    pub io: Box<ImageIo>,
+}
+
+impl Default for AmbientOcclusionParams {
+    fn default() -> Self {
+        Self {
+            strength: 8.,
+            radius: 16.,
+            directions: 8,
+            steps: 8,
+            io: Box::default(),
+        }
+    }
+}
+
+/// Horizon-based ambient occlusion
+///
+/// Ambient occlusion of the surface described by the red channel of the height texture bound to
+/// unit 0, scaled by `strength`. The horizon is searched in `directions` evenly spaced directions
+/// around each pixel, with `steps` samples up to `radius` pixels away, and the output is one
+/// minus the average sine of the horizon elevation: 1 is unoccluded. The height is sampled with
+/// repeat wrapping, so tileable heights give tileable occlusion maps.
+///
+/// See Bavoil, Sainz and Dimitrov, Image-Space Horizon-Based Ambient Occlusion, 2008.
+#[derive(Default, Method)]
+#[txkit(
+    gpu(
+        name = "AmbientOcclusionGpu",
+        program("shaders/quad.vert", "shaders/ambient_occlusion.frag"),
+        method(run = "program", params = "AmbientOcclusionParams"),
+        dims(Image2D)
+    ),
+    cpu(
+        method(run = "Self::compute_cpu_occlusion", params = "AmbientOcclusionParams"),
+        dims(Image1D, Image2D)
+    ),
+    method()
+)]
+pub struct AmbientOcclusion {
+    /// parameters used when computing without explicit parameters
+    params: AmbientOcclusionParams,
+    #[cfg(feature = "gpu")]
+    gpu: Option<AmbientOcclusionGpu>,
+}
+
+impl AmbientOcclusion {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[cfg(feature = "cpu")]
+impl AmbientOcclusion {
+    fn compute_cpu_occlusion(
+        &mut self,
+        ctx: &mut CpuContext,
+        tgt: &mut Image,
+        params: &AmbientOcclusionParams,
+    ) -> txkit_core::Result<()> {
+        use std::f32::consts::PI;
+
+        let height = HeightField::new(&params.io, params.strength, tgt.dim())?;
+        let (radius, directions, steps) = (params.radius, params.directions, params.steps);
+
+        let domain = Domain::new(ctx, tgt.dim())?;
+
+        compute_pixels(ctx, tgt, |idx, dim| {
+            let p = domain.uv(idx, dim).truncate();
+            let c = height.at(p, vec2(0., 0.));
+            let mut occlusion = 0.;
+
+            for i in 0..directions {
+                let a = 2. * PI * i as f32 / directions as f32;
+                let dir = vec2(a.cos(), a.sin());
+
+                // Sine of the horizon elevation angle
+                let mut s = 0f32;
+                for j in 1..=steps {
+                    let t = radius * j as f32 / steps as f32;
+                    let dh = height.at(p, dir * t) - c;
+                    s = s.max(dh / (dh * dh + t * t).sqrt());
+                }
+
+                occlusion += s;
+            }
+
+            let v = 1. - occlusion / directions.max(1) as f32;
+            [v, v, v, 1.]
+        })
+    }
+}
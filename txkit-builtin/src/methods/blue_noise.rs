@@ -0,0 +1,340 @@
+use txkit_impl::{Method, ParamsFor};
+
+#[cfg(feature = "cpu")]
+use ndarray::{par_azip, Axis};
+#[cfg(feature = "cpu")]
+use rayon::prelude::*;
+#[cfg(feature = "cpu")]
+use txkit_core::{context::CpuContext, hash::hash, image::Image, Error};
+
+#[derive(Clone, Copy, PartialEq, ParamsFor)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[repr(C)]
+pub struct BlueNoiseParams {
+    /// pseudo-random seed of the initial binary pattern
+    pub global_seed: u32,
+    /// standard deviation of the gaussian energy filter, in pixels
+    pub sigma: f32,
+    /// fraction of the pixels set in the initial binary pattern
+    pub initial_density: f32,
+}
+
+impl Default for BlueNoiseParams {
+    fn default() -> Self {
+        Self {
+            global_seed: 0,
+            sigma: 1.5,
+            initial_density: 0.1,
+        }
+    }
+}
+
+/// Cache key of a ranked mask: size, seed, and bit patterns of the float parameters
+#[cfg(feature = "cpu")]
+type MaskKey = ([usize; 3], u32, u32, u32);
+
+/// Number of masks kept in the cache of a method
+#[cfg(feature = "cpu")]
+const MASK_CACHE_SIZE: usize = 4;
+
+/// Radius of the gaussian energy filter, in standard deviations
+#[cfg(feature = "cpu")]
+const FILTER_RADIUS: f32 = 3.;
+
+/// Blue noise dither mask
+///
+/// Ranked mask computed with the void-and-cluster algorithm on a torus the size of the target
+/// image, so the result tiles seamlessly. Images with a single row and layer get a 1D mask,
+/// images with a single layer a 2D mask, and other images a 3D mask.
+///
+/// Every pixel gets a distinct rank in `0..n`, written to all channels as `(rank + 0.5) / n` for
+/// Float32 images and `rank * 256 / n` for UInt8 images. Thresholding the mask at `t` thus sets
+/// a fraction `t` of the pixels.
+///
+/// Generation is quadratic in the number of pixels, so the most recently used masks are cached by
+/// size and parameters. `sigma` must be positive. Only the CPU context is supported.
+#[derive(Default, Method)]
+#[txkit(
+    cpu(method(run = "Self::compute_cpu_blue", params = "BlueNoiseParams")),
+    method()
+)]
+pub struct BlueNoise {
+    /// parameters used when computing without explicit parameters
+    params: BlueNoiseParams,
+    /// ranks of the most recently used masks, the last one being the most recent
+    #[cfg(feature = "cpu")]
+    cache: Vec<(MaskKey, Vec<u32>)>,
+}
+
+impl BlueNoise {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[cfg(feature = "cpu")]
+impl BlueNoise {
+    fn compute_cpu_blue(
+        &mut self,
+        ctx: &mut CpuContext,
+        tgt: &mut Image,
+        params: &BlueNoiseParams,
+    ) -> txkit_core::Result<()> {
+        let dim = tgt.dim();
+        let size = [dim.width, dim.height, dim.depth];
+        let n = size.iter().product::<usize>();
+
+        if params.sigma.is_nan() || params.sigma <= 0. {
+            return Err(Error::InvalidParameters);
+        }
+
+        if n == 0 {
+            return Ok(());
+        }
+
+        let key = (
+            size,
+            params.global_seed,
+            params.sigma.to_bits(),
+            params.initial_density.to_bits(),
+        );
+
+        // Move the mask to the back of the cache, computing it if needed
+        let entry = match self.cache.iter().position(|(k, _)| *k == key) {
+            Some(pos) => self.cache.remove(pos),
+            None => {
+                if self.cache.len() >= MASK_CACHE_SIZE {
+                    self.cache.remove(0);
+                }
+
+                let ranks = ctx
+                    .thread_pool
+                    .install(|| Self::void_and_cluster(size, params));
+                (key, ranks)
+            }
+        };
+
+        self.cache.push(entry);
+        let ranks = &self.cache.last().unwrap().1;
+
+        let mut data_mut = tgt.data_mut()?;
+        let index = |(k, j, i): (usize, usize, usize)| i + dim.width * (j + dim.height * k);
+
+        if let Some(mut data) = data_mut.as_u8_nd_array_mut() {
+            ctx.thread_pool.install(|| {
+                par_azip!((index idx, mut px in data.lanes_mut(Axis(3))) {
+                    px.fill((ranks[index(idx)] as u64 * 256 / n as u64) as u8);
+                });
+            });
+
+            Ok(())
+        } else if let Some(mut data) = data_mut.as_f32_nd_array_mut() {
+            ctx.thread_pool.install(|| {
+                par_azip!((index idx, mut px in data.lanes_mut(Axis(3))) {
+                    px.fill((ranks[index(idx)] as f32 + 0.5) / n as f32);
+                });
+            });
+
+            Ok(())
+        } else {
+            Err(Error::FormatNotSupported)
+        }
+    }
+
+    /// Compute the rank of every pixel of a non-empty toroidal mask
+    ///
+    /// Must be called from the thread pool the energy searches should run on.
+    fn void_and_cluster(size: [usize; 3], params: &BlueNoiseParams) -> Vec<u32> {
+        let n = size.iter().product::<usize>();
+        let mut mask = VoidAndCluster::new(size, params.sigma);
+
+        // Initial binary pattern: pixels with the lowest hashes
+        let seed = hash(params.global_seed);
+        let mut order: Vec<usize> = (0..n).collect();
+        order.sort_by_key(|&i| (hash(i as u32 ^ seed), i));
+
+        let initial_count = ((n as f32 * params.initial_density).round() as usize).clamp(1, n);
+        for &i in &order[..initial_count] {
+            mask.toggle(i, true);
+        }
+
+        // Spread the initial pattern by moving its tightest cluster into its largest void
+        for _ in 0..n {
+            let cluster = mask.tightest_cluster();
+            mask.toggle(cluster, false);
+            let void = mask.largest_void();
+            mask.toggle(void, true);
+
+            if cluster == void {
+                break;
+            }
+        }
+
+        let mut ranks = vec![0u32; n];
+
+        // Phase 1: rank the initial pattern by removing its tightest clusters
+        let mut removed = mask.clone();
+        for rank in (0..initial_count).rev() {
+            let cluster = removed.tightest_cluster();
+            removed.toggle(cluster, false);
+            ranks[cluster] = rank as u32;
+        }
+
+        // Phases 2 and 3: rank the other pixels by filling the largest voids. The tightest
+        // cluster of unset pixels is the largest void, so both phases are the same here.
+        for rank in initial_count..n {
+            let void = mask.largest_void();
+            mask.toggle(void, true);
+            ranks[void] = rank as u32;
+        }
+
+        ranks
+    }
+}
+
+/// Binary pattern on a torus, with the gaussian energy of its set pixels
+#[cfg(feature = "cpu")]
+#[derive(Clone)]
+struct VoidAndCluster {
+    size: [usize; 3],
+    /// Separable gaussian filter, indexed by the wrapped difference of coordinates on each axis
+    filter: [Vec<f32>; 3],
+    /// Wrapped differences of coordinates on each axis where the filter is not zero
+    window: [Vec<usize>; 3],
+    pattern: Vec<bool>,
+    energy: Vec<f32>,
+}
+
+#[cfg(feature = "cpu")]
+impl VoidAndCluster {
+    fn new(size: [usize; 3], sigma: f32) -> Self {
+        let n = size.iter().product::<usize>();
+        let radius = (FILTER_RADIUS * sigma).ceil() as usize;
+
+        // The filter is truncated beyond the radius, so toggling a pixel only updates the energy
+        // of the pixels in a window around it
+        let axis_filter = |len: usize| -> Vec<f32> {
+            (0..len)
+                .map(|d| {
+                    let d = d.min(len - d);
+                    if d > radius {
+                        0.
+                    } else {
+                        let d = d as f32;
+                        (-d * d / (2. * sigma * sigma)).exp()
+                    }
+                })
+                .collect()
+        };
+
+        let axis_window = |len: usize| -> Vec<usize> {
+            if radius >= len / 2 {
+                (0..len).collect()
+            } else {
+                (0..=radius).chain(len - radius..len).collect()
+            }
+        };
+
+        Self {
+            size,
+            filter: [
+                axis_filter(size[0]),
+                axis_filter(size[1]),
+                axis_filter(size[2]),
+            ],
+            window: [
+                axis_window(size[0]),
+                axis_window(size[1]),
+                axis_window(size[2]),
+            ],
+            pattern: vec![false; n],
+            energy: vec![0.; n],
+        }
+    }
+
+    fn coords(&self, i: usize) -> [usize; 3] {
+        [
+            i % self.size[0],
+            (i / self.size[0]) % self.size[1],
+            i / (self.size[0] * self.size[1]),
+        ]
+    }
+
+    /// Set or unset a pixel, and update the energy of the pixels in its window accordingly
+    fn toggle(&mut self, i: usize, value: bool) {
+        self.pattern[i] = value;
+
+        let p = self.coords(i);
+        let sign = if value { 1. } else { -1. };
+        let [sx, sy, sz] = self.size;
+
+        for &dz in &self.window[2] {
+            let z = (p[2] + dz) % sz;
+            let wz = sign * self.filter[2][dz];
+
+            for &dy in &self.window[1] {
+                let y = (p[1] + dy) % sy;
+                let wy = wz * self.filter[1][dy];
+
+                for &dx in &self.window[0] {
+                    let x = (p[0] + dx) % sx;
+                    self.energy[x + sx * (y + sy * z)] += wy * self.filter[0][dx];
+                }
+            }
+        }
+    }
+
+    /// Find the pixel with the extreme energy among the pixels of the given value
+    ///
+    /// Ties are broken by the lowest index, so the result does not depend on scheduling.
+    fn find(&self, value: bool, highest: bool) -> usize {
+        self.energy
+            .par_iter()
+            .enumerate()
+            .filter(|(i, _)| self.pattern[*i] == value)
+            .map(|(i, &e)| (if highest { -e } else { e }, i))
+            .reduce_with(|a, b| {
+                if b.0 < a.0 || (b.0 == a.0 && b.1 < a.1) {
+                    b
+                } else {
+                    a
+                }
+            })
+            .map(|(_, i)| i)
+            .expect("no pixel with the requested value")
+    }
+
+    /// Set pixel with the highest energy
+    fn tightest_cluster(&self) -> usize {
+        self.find(true, true)
+    }
+
+    /// Unset pixel with the lowest energy
+    fn largest_void(&self) -> usize {
+        self.find(false, false)
+    }
+}
+
+#[cfg(all(test, feature = "cpu"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ranks_are_a_permutation() {
+        for &size in &[[16, 1, 1], [8, 8, 1], [24, 20, 1], [4, 4, 3]] {
+            for &sigma in &[0.5, 1.5, 8.] {
+                let params = BlueNoiseParams {
+                    sigma,
+                    ..Default::default()
+                };
+
+                let mut ranks = BlueNoise::void_and_cluster(size, &params);
+                ranks.sort_unstable();
+
+                let n = size.iter().product::<usize>() as u32;
+                assert!(ranks.into_iter().eq(0..n), "{:?} {}", size, sigma);
+            }
+        }
+    }
+}
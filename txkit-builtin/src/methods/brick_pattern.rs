@@ -0,0 +1,114 @@
+use txkit_impl::{Method, ParamsFor};
+
+#[cfg(feature = "cpu")]
+use txkit_core::{context::CpuContext, image::Image};
+
+#[cfg(feature = "cpu")]
+use crate::cpu::{compute_pixels, Domain};
+#[cfg(feature = "cpu")]
+use crate::patterns::{filtered_edge, tile_id};
+
+#[derive(Clone, Copy, PartialEq, ParamsFor)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[repr(C)]
+#[txkit(program = "BrickPatternProgram")]
+pub struct BrickPatternParams {
+    /// pseudo-random seed of the brick IDs
+    pub global_seed: u32,
+    /// number of bricks across the image along each axis
+    pub scale: cgmath::Vector2<f32>,
+    /// horizontal shift of each row relative to the previous one, in bricks
+    pub row_offset: f32,
+    /// width of the mortar joints, in brick heights
+    pub mortar_width: f32,
+    /// width of the anti-aliasing filter, in pixels (0: no anti-aliasing)
+    pub antialiasing: f32,
+}
+
+impl Default for BrickPatternParams {
+    fn default() -> Self {
+        Self {
+            global_seed: 0,
+            scale: cgmath::vec2(4., 8.),
+            row_offset: 0.5,
+            mortar_width: 0.1,
+            antialiasing: 1.,
+        }
+    }
+}
+
+/// Brick pattern
+///
+/// Rows of bricks separated by mortar joints, where each row is shifted by `row_offset` bricks
+/// relative to the previous one. A `row_offset` of 0 gives a grid of rectangular tiles. The
+/// output is the coverage of the brick (0 on the mortar), the random ID of the brick in [0, 1],
+/// and the integer coordinates of the brick. The pattern is the same for all layers of 3D images.
+#[derive(Default, Method)]
+#[txkit(
+    gpu(
+        name = "BrickPatternGpu",
+        program("shaders/quad.vert", "shaders/brick_pattern.frag"),
+        method(run = "program", params = "BrickPatternParams"),
+        dims(Image2D)
+    ),
+    cpu(
+        method(run = "Self::compute_cpu_bricks", params = "BrickPatternParams"),
+        dims(Image1D, Image2D)
+    ),
+    method()
+)]
+pub struct BrickPattern {
+    /// parameters used when computing without explicit parameters
+    params: BrickPatternParams,
+    #[cfg(feature = "gpu")]
+    gpu: Option<BrickPatternGpu>,
+}
+
+impl BrickPattern {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[cfg(feature = "cpu")]
+impl BrickPattern {
+    fn compute_cpu_bricks(
+        &mut self,
+        ctx: &mut CpuContext,
+        tgt: &mut Image,
+        params: &BrickPatternParams,
+    ) -> txkit_core::Result<()> {
+        let dim = tgt.dim();
+        let scale = params.scale;
+
+        // Width of a brick and of the filter, in brick heights
+        let aspect = dim.width as f32 * scale.y / (dim.height as f32 * scale.x);
+        let w = params.antialiasing * scale.y / dim.height as f32;
+
+        let domain = Domain::new(ctx, tgt.dim())?;
+
+        compute_pixels(ctx, tgt, |idx, dim| {
+            let uv = domain.uv(idx, dim);
+            let row = (uv.y * scale.y).floor();
+            let x = uv.x * scale.x + params.row_offset * row;
+            let y = uv.y * scale.y;
+
+            let cell = [x.floor() as i32, row as i32];
+
+            // Position in the brick, in brick heights
+            let fx = (x - x.floor()) * aspect;
+            let fy = y - row;
+
+            // Distance to the closest edge of the brick
+            let d = fx.min(aspect - fx).min(fy.min(1. - fy));
+
+            [
+                filtered_edge(d - 0.5 * params.mortar_width, w),
+                tile_id(cell, params.global_seed),
+                cell[0] as f32,
+                cell[1] as f32,
+            ]
+        })
+    }
+}
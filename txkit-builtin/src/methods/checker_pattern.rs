@@ -0,0 +1,97 @@
+use txkit_impl::{Method, ParamsFor};
+
+#[cfg(feature = "cpu")]
+use txkit_core::{context::CpuContext, image::Image};
+
+#[cfg(feature = "cpu")]
+use crate::cpu::{compute_pixels, Domain};
+#[cfg(feature = "cpu")]
+use crate::patterns::{filtered_pulse, tile_id};
+
+#[derive(Clone, Copy, PartialEq, ParamsFor)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[repr(C)]
+#[txkit(program = "CheckerPatternProgram")]
+pub struct CheckerPatternParams {
+    /// pseudo-random seed of the tile IDs
+    pub global_seed: u32,
+    /// number of squares across the image
+    pub scale: f32,
+    /// width of the anti-aliasing filter, in pixels (0: no anti-aliasing)
+    pub antialiasing: f32,
+}
+
+impl Default for CheckerPatternParams {
+    fn default() -> Self {
+        Self {
+            global_seed: 0,
+            scale: 8.,
+            antialiasing: 1.,
+        }
+    }
+}
+
+/// Checkerboard pattern
+///
+/// Checkerboard of `scale` squares across the image, where squares with an even sum of
+/// coordinates are 1. Like all patterns, the output is the pattern value, the random ID of the
+/// square in [0, 1], and the integer coordinates of the square. The pattern is the same for all
+/// layers of 3D images.
+#[derive(Default, Method)]
+#[txkit(
+    gpu(
+        name = "CheckerPatternGpu",
+        program("shaders/quad.vert", "shaders/checker_pattern.frag"),
+        method(run = "program", params = "CheckerPatternParams"),
+        dims(Image2D)
+    ),
+    cpu(
+        method(run = "Self::compute_cpu_checker", params = "CheckerPatternParams"),
+        dims(Image1D, Image2D)
+    ),
+    method()
+)]
+pub struct CheckerPattern {
+    /// parameters used when computing without explicit parameters
+    params: CheckerPatternParams,
+    #[cfg(feature = "gpu")]
+    gpu: Option<CheckerPatternGpu>,
+}
+
+impl CheckerPattern {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[cfg(feature = "cpu")]
+impl CheckerPattern {
+    fn compute_cpu_checker(
+        &mut self,
+        ctx: &mut CpuContext,
+        tgt: &mut Image,
+        params: &CheckerPatternParams,
+    ) -> txkit_core::Result<()> {
+        let domain = Domain::new(ctx, tgt.dim())?;
+
+        compute_pixels(ctx, tgt, |idx, dim| {
+            let p = domain.uv(idx, dim).truncate() * params.scale;
+            let w = [
+                params.antialiasing * params.scale / dim.width as f32,
+                params.antialiasing * params.scale / dim.height as f32,
+            ];
+
+            let a = filtered_pulse(p.x, 0.5, w[0]);
+            let b = filtered_pulse(p.y, 0.5, w[1]);
+            let cell = [p.x.floor() as i32, p.y.floor() as i32];
+
+            [
+                a * b + (1. - a) * (1. - b),
+                tile_id(cell, params.global_seed),
+                cell[0] as f32,
+                cell[1] as f32,
+            ]
+        })
+    }
+}
@@ -0,0 +1,176 @@
+use txkit_impl::{Method, ParamsFor};
+
+#[cfg(feature = "cpu")]
+use cgmath::{vec2, vec3, Vector2, Vector3};
+#[cfg(feature = "cpu")]
+use txkit_core::{
+    context::CpuContext,
+    image::Image,
+    lattice::{Lattice, LatticeMode, LatticeMode3D, STATS_MODE_NORMAL},
+};
+
+#[cfg(feature = "cpu")]
+use super::{GradientNoise, SimplexNoise};
+#[cfg(feature = "cpu")]
+use crate::cpu::{compute_pixels, Domain};
+#[cfg(feature = "cpu")]
+use crate::shared::to01;
+
+/// Curl: gradient noise potential
+pub const CURL_BASIS_GRADIENT: i32 = 0;
+/// Curl: simplex noise potential
+pub const CURL_BASIS_SIMPLEX: i32 = 1;
+
+#[derive(Clone, Copy, PartialEq, ParamsFor)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[repr(C)]
+#[txkit(program = "CurlNoiseProgram")]
+pub struct CurlNoiseParams {
+    /// pseudo-random seed, 3D potential components are seeded with global_seed + i
+    pub global_seed: u32,
+    /// lattice scale (size in pixels)
+    pub scale: f32,
+    /// potential noise
+    #[txkit(values(CURL_BASIS_GRADIENT, CURL_BASIS_SIMPLEX))]
+    pub basis: i32,
+    /// multiplier applied to the vector field before remapping it to [0, 1]
+    pub amplitude: f32,
+}
+
+impl Default for CurlNoiseParams {
+    fn default() -> Self {
+        Self {
+            global_seed: 0,
+            scale: 8.,
+            basis: CURL_BASIS_GRADIENT,
+            amplitude: 0.25,
+        }
+    }
+}
+
+/// Curl noise
+///
+/// Divergence-free vector field, computed as the curl of a noise potential. The derivatives of
+/// the potential are the analytic derivatives of the underlying noise.
+///
+/// For 2D images, the potential is a scalar noise `psi` and the red and green channels are the
+/// components of `(d psi / dy, -d psi / dx)`. For 3D images (more than one layer), the potential
+/// is made of three noises over (x, y, layer) and the red, green and blue channels are the
+/// components of its curl. Components are multiplied by `amplitude` and remapped from [-1, 1] to
+/// [0, 1], so a zero vector is encoded as 0.5.
+///
+/// The CPU implementation is a port of `curl_noise.frag`.
+#[derive(Default, Method)]
+#[txkit(
+    gpu(
+        name = "CurlNoiseGpu",
+        program("shaders/quad.vert", "shaders/curl_noise.frag"),
+        method(run = "program", params = "CurlNoiseParams")
+    ),
+    cpu(method(run = "Self::compute_cpu_curl", params = "CurlNoiseParams")),
+    method()
+)]
+pub struct CurlNoise {
+    /// parameters used when computing without explicit parameters
+    params: CurlNoiseParams,
+    #[cfg(feature = "gpu")]
+    gpu: Option<CurlNoiseGpu>,
+}
+
+impl CurlNoise {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[cfg(feature = "cpu")]
+impl CurlNoise {
+    fn compute_cpu_curl(
+        &mut self,
+        ctx: &mut CpuContext,
+        tgt: &mut Image,
+        params: &CurlNoiseParams,
+    ) -> txkit_core::Result<()> {
+        if params.basis != CURL_BASIS_GRADIENT && params.basis != CURL_BASIS_SIMPLEX {
+            // Invalid enum value
+            return compute_pixels(ctx, tgt, |_, _| [1., 0., 1., 1.]);
+        }
+
+        let dim = tgt.dim();
+
+        // Lattices of the potential components
+        let lattices: Vec<_> = (0..3)
+            .map(|i| {
+                Lattice::new(
+                    params.scale,
+                    params.global_seed.wrapping_add(i),
+                    STATS_MODE_NORMAL,
+                    vec2(0., 0.),
+                    dim,
+                )
+            })
+            .collect();
+
+        let domain = Domain::new(ctx, tgt.dim())?;
+
+        compute_pixels(ctx, tgt, |idx, dim| {
+            let uv = domain.uv(idx, dim);
+
+            if dim.depth > 1 {
+                // Curl of the vector potential (psi1, psi2, psi3)
+                let d1 = Self::gradient_3d(uv, &lattices[0], params);
+                let d2 = Self::gradient_3d(uv, &lattices[1], params);
+                let d3 = Self::gradient_3d(uv, &lattices[2], params);
+
+                let curl = vec3(d3.y - d2.z, d1.z - d3.x, d2.x - d1.y) * params.amplitude;
+                [to01(curl.x), to01(curl.y), to01(curl.z), 1.]
+            } else {
+                // Curl of the scalar potential psi
+                let d = Self::gradient_2d(uv.truncate(), &lattices[0], params);
+
+                let curl = vec2(d.y, -d.x) * params.amplitude;
+                [to01(curl.x), to01(curl.y), 0., 1.]
+            }
+        })
+    }
+
+    /// Gradient of a 2D potential component, in lattice units
+    fn gradient_2d(
+        position: Vector2<f32>,
+        lattice: &Lattice,
+        params: &CurlNoiseParams,
+    ) -> Vector2<f32> {
+        let d = if params.basis == CURL_BASIS_GRADIENT {
+            GradientNoise::noise_derivatives(lattice.sample(position, LatticeMode::Rect2D), lattice)
+        } else {
+            SimplexNoise::noise_derivatives(
+                lattice.sample(position, LatticeMode::Simplex2D),
+                lattice,
+            )
+        };
+
+        vec2(d.y, d.z)
+    }
+
+    /// Gradient of a 3D potential component, in lattice units
+    fn gradient_3d(
+        position: Vector3<f32>,
+        lattice: &Lattice,
+        params: &CurlNoiseParams,
+    ) -> Vector3<f32> {
+        let d = if params.basis == CURL_BASIS_GRADIENT {
+            GradientNoise::noise_3d_derivatives(
+                lattice.sample_3d(position, LatticeMode3D::Rect3D),
+                lattice,
+            )
+        } else {
+            SimplexNoise::noise_3d_derivatives(
+                lattice.sample_3d(position, LatticeMode3D::Simplex3D),
+                lattice,
+            )
+        };
+
+        vec3(d.y, d.z, d.w)
+    }
+}
@@ -0,0 +1,109 @@
+use txkit_core::io::ImageIo;
+use txkit_impl::{Method, ParamsFor};
+
+#[cfg(feature = "cpu")]
+use cgmath::vec2;
+#[cfg(feature = "cpu")]
+use txkit_core::{context::CpuContext, image::Image};
+
+#[cfg(feature = "cpu")]
+use crate::cpu::{compute_pixels, Domain};
+#[cfg(feature = "cpu")]
+use crate::heightfield::HeightField;
+
+#[derive(Clone, PartialEq, ParamsFor)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[repr(C)]
+#[txkit(program = "CurvatureProgram")]
+pub struct CurvatureParams {
+    /// height of a unit of the height texture, in pixels
+    pub strength: f32,
+    /// radius of curvature mapped to 0 (concave) or 1 (convex), in pixels
+    pub radius: f32,
+
+    /// texture inputs, the height is bound to unit 0
+    #[texture_io(height)]
+    pub io: Box<ImageIo>,
+}
+
+impl Default for CurvatureParams {
+    fn default() -> Self {
+        Self {
+            strength: 8.,
+            radius: 8.,
+            io: Box::default(),
+        }
+    }
+}
+
+/// Curvature map
+///
+/// Mean curvature of the surface described by the red channel of the height texture bound to
+/// unit 0, scaled by `strength`. The curvature is mapped linearly to [0, 1], where flat areas are
+/// 0.5, and areas curved with a radius of `radius` pixels or less are 0 for concave areas and 1
+/// for convex areas. The height is sampled at the pixels of the output with repeat wrapping, so
+/// tileable heights give tileable curvature maps.
+#[derive(Default, Method)]
+#[txkit(
+    gpu(
+        name = "CurvatureGpu",
+        program("shaders/quad.vert", "shaders/curvature.frag"),
+        method(run = "program", params = "CurvatureParams"),
+        dims(Image2D)
+    ),
+    cpu(
+        method(run = "Self::compute_cpu_curvature", params = "CurvatureParams"),
+        dims(Image1D, Image2D)
+    ),
+    method()
+)]
+pub struct Curvature {
+    /// parameters used when computing without explicit parameters
+    params: CurvatureParams,
+    #[cfg(feature = "gpu")]
+    gpu: Option<CurvatureGpu>,
+}
+
+impl Curvature {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[cfg(feature = "cpu")]
+impl Curvature {
+    fn compute_cpu_curvature(
+        &mut self,
+        ctx: &mut CpuContext,
+        tgt: &mut Image,
+        params: &CurvatureParams,
+    ) -> txkit_core::Result<()> {
+        let height = HeightField::new(&params.io, params.strength, tgt.dim())?;
+        let radius = params.radius;
+
+        let domain = Domain::new(ctx, tgt.dim())?;
+
+        compute_pixels(ctx, tgt, |idx, dim| {
+            let p = domain.uv(idx, dim).truncate();
+            let h = |x, y| height.at(p, vec2(x, y));
+            let c = h(0., 0.);
+            let (l, r, t, b) = (h(-1., 0.), h(1., 0.), h(0., -1.), h(0., 1.));
+            let (tl, tr, bl, br) = (h(-1., -1.), h(1., -1.), h(-1., 1.), h(1., 1.));
+
+            // First and second derivatives of the height
+            let (dx, dy) = (0.5 * (r - l), 0.5 * (b - t));
+            let dxx = r - 2. * c + l;
+            let dyy = b - 2. * c + t;
+            let dxy = 0.25 * (br - bl - tr + tl);
+
+            // Mean curvature, in inverse pixels, positive on convex areas
+            let w = 1. + dx * dx + dy * dy;
+            let k = -((1. + dy * dy) * dxx - 2. * dx * dy * dxy + (1. + dx * dx) * dyy)
+                / (2. * w * w.sqrt());
+
+            let v = 0.5 + 0.5 * (k * radius).clamp(-1., 1.);
+            [v, v, v, 1.]
+        })
+    }
+}
@@ -2,9 +2,12 @@ use txkit_core::image::ImageDim;
 use txkit_impl::{Method, ParamsFor};
 
 #[derive(Clone, Copy, PartialEq, ParamsFor)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[repr(C)]
 #[txkit(program = "DebugProgram")]
 pub struct DebugParams {
+    /// value written to the alpha channel
     pub alpha_value: f32,
 }
 
@@ -14,6 +17,7 @@ impl Default for DebugParams {
     }
 }
 
+/// Debug method writing pixel coordinates to the color channels
 #[derive(Default, Method)]
 #[txkit(
     gpu(
@@ -25,6 +29,8 @@ impl Default for DebugParams {
     method()
 )]
 pub struct Debug {
+    /// parameters used when computing without explicit parameters
+    params: DebugParams,
     #[cfg(feature = "gpu")]
     gpu: Option<DebugGpu>,
 }
@@ -0,0 +1,453 @@
+#[cfg(feature = "gpu")]
+use std::cell::RefCell;
+use std::ops::ControlFlow;
+#[cfg(feature = "gpu")]
+use std::rc::Rc;
+
+use txkit_core::context::Context;
+use txkit_core::image::Image;
+use txkit_core::io::ImageIo;
+use txkit_core::Result;
+use txkit_impl::{Method, ParamsFor};
+
+#[cfg(feature = "cpu")]
+use rayon::prelude::*;
+#[cfg(feature = "cpu")]
+use txkit_core::{context::CpuContext, io::cpu::CpuImageIoExt};
+#[cfg(feature = "gpu")]
+use txkit_core::{
+    image::{ImageDataType, ImageDim},
+    io::ImageBinding,
+    Error,
+};
+
+#[cfg(feature = "cpu")]
+use crate::cpu::compute_pixels;
+#[cfg(feature = "cpu")]
+use crate::shared::pixel_uv;
+
+#[derive(Clone, PartialEq, ParamsFor)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[repr(C)]
+#[txkit(program = "DistanceTransformProgram")]
+pub struct DistanceTransformParams {
+    /// pixels of the mask are those with a red channel greater than this value
+    pub threshold: f32,
+
+    /// texture inputs, the mask is bound to unit 0
+    #[texture_io(mask, seeds(internal))]
+    pub io: Box<ImageIo>,
+}
+
+impl Default for DistanceTransformParams {
+    fn default() -> Self {
+        Self {
+            threshold: 0.5,
+            io: Box::default(),
+        }
+    }
+}
+
+/// Parameters of the seed pass of the GPU distance transform, see
+/// `distance_transform_seed.frag`
+#[cfg(feature = "gpu")]
+#[derive(Default, ParamsFor)]
+#[txkit(program = "DistanceTransformSeedProgram")]
+pub struct DistanceTransformSeedParams {
+    /// pixels of the mask are those with a red channel greater than this value
+    pub threshold: f32,
+
+    /// texture inputs, the mask is bound to unit 0
+    #[texture_io(mask)]
+    pub io: Box<ImageIo>,
+}
+
+/// Parameters of the propagation passes of the GPU distance transform, see
+/// `distance_transform_jump.frag`
+#[cfg(feature = "gpu")]
+#[derive(Default, ParamsFor)]
+#[txkit(program = "DistanceTransformJumpProgram")]
+pub struct DistanceTransformJumpParams {
+    /// distance to the neighbours to propagate seeds from, in pixels
+    pub jump: i32,
+
+    /// texture inputs, the seeds of the previous pass are bound to unit 0
+    #[texture_io(seeds)]
+    pub io: Box<ImageIo>,
+}
+
+/// Texture unit of the mask, see `distance_transform.frag`
+pub const DISTANCE_TRANSFORM_MASK_UNIT: usize = 0;
+/// Texture unit of the closest seeds, bound by the method, see `distance_transform.frag`
+#[cfg(feature = "gpu")]
+const SEEDS_UNIT: usize = 1;
+
+/// Distance to the empty set, see `distance_transform.frag`
+#[cfg(feature = "cpu")]
+const DISTANCE_TRANSFORM_FAR: f32 = 1e9;
+
+/// Euclidean distance transform
+///
+/// Signed distance from every pixel to the boundary of the mask bound to texture unit 0, in image
+/// widths. The mask is sampled at the center of every pixel of the output, and thresholded on its
+/// red channel. Distances are negative inside the mask, and the boundary lies halfway between
+/// the centers of pixels inside and outside the mask. Without any pixel inside (resp. outside)
+/// the mask, the output is `1e9` (resp. `-1e9`). The distance is the same for all layers of 3D
+/// images.
+///
+/// On CPU contexts, the exact distance is computed with the separable algorithm of Felzenszwalb
+/// and Huttenlocher, Distance Transforms of Sampled Functions, 2012. On GPU contexts, the
+/// distance is approximated by jump flooding in `log2` of the image size passes over intermediate
+/// `Float32` images, and may rarely exceed the exact distance by a fraction of a pixel.
+#[derive(Default, Method)]
+#[txkit(
+    gpu(
+        name = "DistanceTransformGpu",
+        program("shaders/quad.vert", "shaders/distance_transform.frag"),
+        seed(
+            name = "DistanceTransformSeed",
+            "shaders/quad.vert",
+            "shaders/distance_transform_seed.frag"
+        ),
+        jump(
+            name = "DistanceTransformJump",
+            "shaders/quad.vert",
+            "shaders/distance_transform_jump.frag"
+        ),
+        method(run = "program", params = "DistanceTransformParams"),
+        dims(Image2D)
+    ),
+    cpu(
+        method(run = "Self::compute_cpu_distance", params = "DistanceTransformParams"),
+        dims(Image1D, Image2D)
+    ),
+    method(prepare = "Self::bind_seeds")
+)]
+pub struct DistanceTransform {
+    /// parameters used when computing without explicit parameters
+    params: DistanceTransformParams,
+    #[cfg(feature = "gpu")]
+    gpu: Option<DistanceTransformGpu>,
+}
+
+impl DistanceTransform {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Compute the closest seeds on GPU contexts, and bind them for the last pass
+    #[cfg_attr(not(feature = "gpu"), allow(unused_variables))]
+    fn bind_seeds(
+        &mut self,
+        ctx: &mut Context,
+        tgt: &mut Image,
+        params: &mut DistanceTransformParams,
+    ) -> Result<ControlFlow<()>> {
+        #[cfg(feature = "gpu")]
+        if ctx.gpu().is_some() {
+            let seeds = self.compute_gpu_seeds(ctx, tgt, params)?;
+            params
+                .io
+                .set_texture_binding(SEEDS_UNIT, ImageBinding::ImageRef(seeds));
+        }
+
+        Ok(ControlFlow::Continue(()))
+    }
+}
+
+#[cfg(feature = "gpu")]
+impl DistanceTransform {
+    /// Find the closest mask and background pixels of every pixel by jump flooding
+    fn compute_gpu_seeds(
+        &mut self,
+        ctx: &mut Context,
+        tgt: &Image,
+        params: &DistanceTransformParams,
+    ) -> Result<Rc<RefCell<Image>>> {
+        use tinygl::wrappers::ProgramCommonExt;
+        use txkit_core::method::GpuMethodParams;
+
+        let dim = tgt.dim();
+        let new_seeds = || -> Result<_> {
+            Ok(Rc::new(RefCell::new(Image::new_gpu_2d(
+                ImageDim::new(dim.width, dim.height, 4),
+                ImageDataType::Float32,
+                ctx,
+            )?)))
+        };
+
+        // Passes ping-pong between these images
+        let mut seeds = new_seeds()?;
+        let mut next = new_seeds()?;
+
+        let gpu_context = ctx.gpu_mut().unwrap();
+
+        // Initialize GPU if needed
+        if self.gpu.is_none() {
+            self.gpu = Some(DistanceTransformGpu::new(gpu_context)?);
+        }
+
+        let gpu = self.gpu.as_ref().unwrap();
+        let seed = DistanceTransformSeedParams {
+            threshold: params.threshold,
+            io: params.io.clone(),
+        };
+
+        render_pass(gpu_context, &mut seeds.borrow_mut(), |gl, dim, layer| {
+            unsafe {
+                gpu.seed.use_program(gl);
+            }
+
+            gpu.seed.set_i_resolution(gl, dim);
+            gpu.seed.set_i_layer(gl, layer);
+            seed.apply(gl, &gpu.seed)
+        })?;
+
+        // Halve the jump from the largest power of two below the image size, and finish with an
+        // extra pass at a jump of 1 which fixes most of the remaining errors
+        let mut jumps = Vec::new();
+        let mut jump = dim.width.max(dim.height).next_power_of_two() / 2;
+        while jump > 0 {
+            jumps.push(jump as i32);
+            jump /= 2;
+        }
+        jumps.push(1);
+
+        for jump in jumps {
+            let mut pass = DistanceTransformJumpParams {
+                jump,
+                io: Box::default(),
+            };
+
+            pass.io
+                .set_texture_binding(0, ImageBinding::ImageRef(seeds.clone()));
+            render_pass(gpu_context, &mut next.borrow_mut(), |gl, dim, layer| {
+                unsafe {
+                    gpu.jump.use_program(gl);
+                }
+
+                gpu.jump.set_i_resolution(gl, dim);
+                gpu.jump.set_i_layer(gl, layer);
+                pass.apply(gl, &gpu.jump)
+            })?;
+
+            std::mem::swap(&mut seeds, &mut next);
+        }
+
+        Ok(seeds)
+    }
+}
+
+/// Render one pass of the GPU distance transform into `tgt`
+///
+/// `setup` selects the program of the pass and sets its uniforms, given the resolution and layer
+/// of the target.
+#[cfg(feature = "gpu")]
+fn render_pass(
+    gpu_context: &mut txkit_core::context::GpuContext,
+    tgt: &mut Image,
+    setup: impl Fn(&tinygl::Context, cgmath::Vector3<u32>, u32) -> Result<()>,
+) -> Result<()> {
+    use txkit_core::image::{ImageDataBase, ImageDimGpuExt};
+
+    let target = tgt.as_gpu_image_mut().ok_or(Error::FormatNotSupported)?;
+    let dim = target.dim().into_cgmath();
+
+    gpu_context.render_to_framebuffer(target, |gl, layer| {
+        setup(gl, dim, layer)?;
+
+        unsafe {
+            gl.draw_arrays(tinygl::gl::TRIANGLES, 0, 3);
+        }
+
+        Ok(())
+    })
+}
+
+#[cfg(feature = "cpu")]
+impl DistanceTransform {
+    fn compute_cpu_distance(
+        &mut self,
+        ctx: &mut CpuContext,
+        tgt: &mut Image,
+        params: &DistanceTransformParams,
+    ) -> txkit_core::Result<()> {
+        let mask = params
+            .io
+            .texture_binding_data(DISTANCE_TRANSFORM_MASK_UNIT)?;
+        let threshold = params.threshold;
+
+        let dim = tgt.dim();
+        let (width, height) = (dim.width, dim.height);
+
+        let distance: Vec<f32> = ctx.thread_pool.install(|| {
+            // Threshold the mask at the output resolution
+            let inside: Vec<bool> = (0..width * height)
+                .into_par_iter()
+                .map(|i| {
+                    let uv = pixel_uv((0, i / width, i % width), dim);
+                    mask.sample_2d([uv.x, uv.y])[0] > threshold
+                })
+                .collect();
+
+            let to_mask = squared_distance(&inside, true, width, height);
+            let to_background = squared_distance(&inside, false, width, height);
+
+            inside
+                .par_iter()
+                .zip(to_mask.par_iter().zip(to_background.par_iter()))
+                .map(|(&inside, (&to_mask, &to_background))| {
+                    let (d, sign) = if inside {
+                        (to_background, -1.)
+                    } else {
+                        (to_mask, 1.)
+                    };
+
+                    if d.is_infinite() {
+                        sign * DISTANCE_TRANSFORM_FAR
+                    } else {
+                        // The boundary lies halfway between pixel centers
+                        sign * (d.sqrt() as f32 - 0.5) / width as f32
+                    }
+                })
+                .collect()
+        });
+
+        compute_pixels(ctx, tgt, |(_, j, i), _| {
+            let d = distance[i + width * j];
+            [d, d, d, 1.]
+        })
+    }
+}
+
+/// Squared Euclidean distance from every pixel to the closest pixel where `seeds` is `value`, in
+/// pixels
+///
+/// Must be called from the thread pool the rows and columns should be processed on.
+#[cfg(feature = "cpu")]
+fn squared_distance(seeds: &[bool], value: bool, width: usize, height: usize) -> Vec<f64> {
+    // Vertical distances, stored by column
+    let mut columns = vec![0.; width * height];
+    columns
+        .par_chunks_mut(height)
+        .enumerate()
+        .for_each(|(i, column)| {
+            let f: Vec<f64> = (0..height)
+                .map(|j| {
+                    if seeds[i + width * j] == value {
+                        0.
+                    } else {
+                        f64::INFINITY
+                    }
+                })
+                .collect();
+
+            distance_1d(&f, column);
+        });
+
+    // Combine the vertical distances along rows
+    let mut res = vec![0.; width * height];
+    res.par_chunks_mut(width).enumerate().for_each(|(j, row)| {
+        let f: Vec<f64> = (0..width).map(|i| columns[j + height * i]).collect();
+        distance_1d(&f, row);
+    });
+
+    res
+}
+
+/// One-dimensional squared distance transform
+///
+/// Computes `d[q] = min_p (q - p)^2 + f[p]` from the lower envelope of the parabolas rooted at
+/// the finite samples of `f`. Without any finite sample, `d` is infinite.
+#[cfg(feature = "cpu")]
+fn distance_1d(f: &[f64], d: &mut [f64]) {
+    // Roots of the parabolas of the lower envelope, and the start of their interval
+    let mut v: Vec<usize> = Vec::with_capacity(f.len());
+    let mut z: Vec<f64> = Vec::with_capacity(f.len());
+
+    for (q, &fq) in f.iter().enumerate() {
+        if fq.is_infinite() {
+            continue;
+        }
+
+        // Remove the parabolas hidden by the one rooted at q
+        let mut s = f64::NEG_INFINITY;
+        while let Some(&p) = v.last() {
+            let (qf, pf) = (q as f64, p as f64);
+            s = ((fq + qf * qf) - (f[p] + pf * pf)) / (2. * (qf - pf));
+
+            if s > z[z.len() - 1] {
+                break;
+            }
+
+            v.pop();
+            z.pop();
+            s = f64::NEG_INFINITY;
+        }
+
+        v.push(q);
+        z.push(s);
+    }
+
+    if v.is_empty() {
+        d.fill(f64::INFINITY);
+        return;
+    }
+
+    let mut k = 0;
+    for (q, dq) in d.iter_mut().enumerate() {
+        while k + 1 < v.len() && z[k + 1] < q as f64 {
+            k += 1;
+        }
+
+        let dx = q as f64 - v[k] as f64;
+        *dq = dx * dx + f[v[k]];
+    }
+}
+
+#[cfg(all(test, feature = "cpu"))]
+mod tests {
+    use super::*;
+    use crate::lcg::Lcg;
+
+    #[test]
+    fn distance_1d_matches_brute_force() {
+        let mut rng = Lcg::new(42);
+
+        for len in [1, 2, 3, 7, 16, 33] {
+            for density in [0., 0.1, 0.5, 1.] {
+                // Random samples, some of them infinite
+                let f: Vec<f64> = (0..len)
+                    .map(|_| {
+                        if rng.next_01() < density {
+                            (rng.next_01() * 10.) as f64
+                        } else {
+                            f64::INFINITY
+                        }
+                    })
+                    .collect();
+
+                let mut d = vec![0.; len];
+                distance_1d(&f, &mut d);
+
+                for (q, &dq) in d.iter().enumerate() {
+                    let expected = f
+                        .iter()
+                        .enumerate()
+                        .map(|(p, &fp)| (q as f64 - p as f64).powi(2) + fp)
+                        .fold(f64::INFINITY, f64::min);
+
+                    assert!(
+                        dq == expected || (dq - expected).abs() < 1e-9,
+                        "{:?} {} {} {}",
+                        f,
+                        q,
+                        dq,
+                        expected
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,245 @@
+use std::cell::RefCell;
+use std::ops::ControlFlow;
+use std::rc::Rc;
+
+use txkit_core::context::Context;
+use txkit_core::image::{Image, ImageDataType, ImageDim};
+use txkit_core::io::{ImageBinding, ImageIo};
+use txkit_core::method::Method;
+use txkit_core::{Error, Result};
+use txkit_impl::{Method, ParamsFor};
+
+#[cfg(feature = "cpu")]
+use cgmath::{vec2, Vector2};
+#[cfg(feature = "cpu")]
+use txkit_core::{
+    context::CpuContext,
+    io::cpu::{CpuImageIoExt, CpuTexture},
+};
+
+#[cfg(feature = "cpu")]
+use crate::cpu::{compute_pixels, Domain};
+
+/// Domain warp: the red and green channels of the displacement field, remapped to [-1, 1], are
+/// the displacement vector
+pub const DOMAIN_WARP_DISPLACEMENT_VECTOR: i32 = 0;
+/// Domain warp: the red channel of the displacement field is the direction of a unit
+/// displacement, in turns
+pub const DOMAIN_WARP_DISPLACEMENT_ANGLE: i32 = 1;
+
+/// Domain warp: output the warped image
+pub const DOMAIN_WARP_OUTPUT_WARPED: i32 = 0;
+/// Domain warp: output the warped texture coordinates in the red and green channels, with a blue
+/// channel of 1, as expected by [Context::set_domain]
+pub const DOMAIN_WARP_OUTPUT_COORDINATES: i32 = 1;
+
+#[derive(Clone, PartialEq, ParamsFor)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[repr(C)]
+#[txkit(program = "DomainWarpProgram")]
+pub struct DomainWarpParams {
+    /// displacement scale, in texture coordinates
+    pub strength: f32,
+    /// number of warping iterations, 0 = no warping
+    pub iterations: i32,
+    /// interpretation of the displacement field
+    #[txkit(values(DOMAIN_WARP_DISPLACEMENT_VECTOR, DOMAIN_WARP_DISPLACEMENT_ANGLE))]
+    pub displacement_mode: i32,
+    /// computed output
+    #[txkit(values(DOMAIN_WARP_OUTPUT_WARPED, DOMAIN_WARP_OUTPUT_COORDINATES))]
+    pub output_mode: i32,
+
+    /// texture inputs
+    #[texture_io(source_texture, displacement_field)]
+    pub io: Box<ImageIo>,
+}
+
+impl Default for DomainWarpParams {
+    fn default() -> Self {
+        Self {
+            strength: 0.1,
+            iterations: 1,
+            displacement_mode: DOMAIN_WARP_DISPLACEMENT_VECTOR,
+            output_mode: DOMAIN_WARP_OUTPUT_WARPED,
+            io: Box::default(),
+        }
+    }
+}
+
+/// Texture unit of the warped image, see `domain_warp.frag`
+pub const DOMAIN_WARP_SOURCE_TEXTURE_UNIT: usize = 0;
+/// Texture unit of the displacement field, see `domain_warp.frag`
+pub const DOMAIN_WARP_DISPLACEMENT_FIELD_UNIT: usize = 1;
+
+/// Domain warping
+///
+/// Resamples the image bound to the `source_texture` texture at coordinates displaced by the
+/// image bound to the `displacement_field` texture. With `iterations` set to n, the sampled
+/// coordinates are `p_n`, where `p_{k+1} = p_0 + strength * displacement(p_k)`. Both textures are
+/// sampled with bilinear filtering and repeat wrapping, so tileable inputs give a tileable
+/// result.
+///
+/// Instead of texture bindings, the warped image and the displacement field can be computed by
+/// the `source` and `displacement` inputs, with the parameters set on them. The displacement
+/// field is computed at the resolution of the target image into an intermediate `Float32` image,
+/// while the source is evaluated directly at the warped coordinates, through the domain of the
+/// context (see [Context::set_domain]), so it isn't resampled.
+#[derive(Default, Method)]
+#[txkit(
+    gpu(
+        name = "DomainWarpGpu",
+        program("shaders/quad.vert", "shaders/domain_warp.frag"),
+        method(run = "program", params = "DomainWarpParams"),
+        dims(Image2D)
+    ),
+    cpu(
+        method(run = "Self::compute_cpu_warp", params = "DomainWarpParams"),
+        dims(Image1D, Image2D)
+    ),
+    method(prepare = "Self::bind_inputs", inputs(source, displacement))
+)]
+pub struct DomainWarp {
+    /// parameters used when computing without explicit parameters
+    params: DomainWarpParams,
+    /// method computing the warped image, replacing the `source_texture` binding
+    source: Option<Box<dyn Method>>,
+    /// method computing the displacement field, replacing the `displacement_field` binding
+    displacement: Option<Box<dyn Method>>,
+    #[cfg(feature = "gpu")]
+    gpu: Option<DomainWarpGpu>,
+}
+
+impl DomainWarp {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Compute the inputs of the method, before computing the target image
+    ///
+    /// The displacement field is bound in place of the `displacement_field` texture. When a source
+    /// is set, the warped coordinates are computed first, and the source is then evaluated at them
+    /// into the target image, so the warping program itself doesn't run.
+    fn bind_inputs(
+        &mut self,
+        ctx: &mut Context,
+        tgt: &mut Image,
+        params: &mut DomainWarpParams,
+    ) -> Result<ControlFlow<()>> {
+        let dim = ImageDim::new(tgt.dim().width, tgt.dim().height, 4);
+
+        if let Some(displacement) = &mut self.displacement {
+            // The field is sampled at warped coordinates, so it is computed over the whole texture
+            let mut image = Self::intermediate_image(ctx, dim)?;
+            let domain = ctx.set_domain(None);
+            let result = displacement.compute(ctx, &mut image, None);
+            ctx.set_domain(domain);
+            result?;
+
+            params.io.set_texture_binding(
+                DOMAIN_WARP_DISPLACEMENT_FIELD_UNIT,
+                ImageBinding::ImageRef(Rc::new(RefCell::new(image))),
+            );
+        }
+
+        let mut source = match self.source.take() {
+            Some(source) => source,
+            None => return Ok(ControlFlow::Continue(())),
+        };
+
+        // Compute the coordinates without the inputs, since the displacement field is bound
+        let displacement = self.displacement.take();
+        let result = self.warp_source(source.as_mut(), ctx, tgt, dim, params);
+        self.source = Some(source);
+        self.displacement = displacement;
+
+        result.map(|()| ControlFlow::Break(()))
+    }
+
+    /// Evaluate a source method at the warped coordinates
+    fn warp_source(
+        &mut self,
+        source: &mut dyn Method,
+        ctx: &mut Context,
+        tgt: &mut Image,
+        dim: ImageDim,
+        params: &DomainWarpParams,
+    ) -> Result<()> {
+        let mut coordinates = Self::intermediate_image(ctx, dim)?;
+        let mut coordinate_params = params.clone();
+        coordinate_params.output_mode = DOMAIN_WARP_OUTPUT_COORDINATES;
+        self.compute(ctx, &mut coordinates, Some(&coordinate_params))?;
+
+        let domain = ctx.set_domain(Some(Rc::new(RefCell::new(coordinates))));
+        let result = source.compute(ctx, tgt, None);
+        ctx.set_domain(domain);
+        result
+    }
+
+    fn intermediate_image(ctx: &Context, dim: ImageDim) -> Result<Image> {
+        Image::new_2d_for(dim, ImageDataType::Float32, ctx)
+            .map_err(|e| Error::MethodInitializationFailed(e.to_string()))
+    }
+}
+
+#[cfg(feature = "cpu")]
+impl DomainWarp {
+    fn compute_cpu_warp(
+        &mut self,
+        ctx: &mut CpuContext,
+        tgt: &mut Image,
+        params: &DomainWarpParams,
+    ) -> txkit_core::Result<()> {
+        let source = params
+            .io
+            .texture_binding_data(DOMAIN_WARP_SOURCE_TEXTURE_UNIT)?;
+        let field = params
+            .io
+            .texture_binding_data(DOMAIN_WARP_DISPLACEMENT_FIELD_UNIT)?;
+
+        // ImageIo can't be shared between threads, copy the scalar parameters
+        let (strength, iterations, mode, output_mode) = (
+            params.strength,
+            params.iterations,
+            params.displacement_mode,
+            params.output_mode,
+        );
+
+        if (mode != DOMAIN_WARP_DISPLACEMENT_VECTOR && mode != DOMAIN_WARP_DISPLACEMENT_ANGLE)
+            || (output_mode != DOMAIN_WARP_OUTPUT_WARPED
+                && output_mode != DOMAIN_WARP_OUTPUT_COORDINATES)
+        {
+            // Invalid enum value
+            return compute_pixels(ctx, tgt, |_, _| [1., 0., 1., 1.]);
+        }
+
+        let domain = Domain::new(ctx, tgt.dim())?;
+
+        compute_pixels(ctx, tgt, |idx, dim| {
+            let uv = domain.uv(idx, dim).truncate();
+
+            // Iterate p_{n+1} = p_0 + strength * displacement(p_n)
+            let mut p = uv;
+            for _ in 0..iterations {
+                p = uv + Self::displacement(&field, p, mode) * strength;
+            }
+
+            if output_mode == DOMAIN_WARP_OUTPUT_COORDINATES {
+                return [p.x, p.y, 1., 1.];
+            }
+
+            source.sample_2d(p.into())
+        })
+    }
+
+    fn displacement(field: &CpuTexture, p: Vector2<f32>, mode: i32) -> Vector2<f32> {
+        let d = field.sample_2d(p.into());
+
+        if mode == DOMAIN_WARP_DISPLACEMENT_ANGLE {
+            let a = 2. * std::f32::consts::PI * d[0];
+            return vec2(a.cos(), a.sin());
+        }
+
+        vec2(2. * d[0] - 1., 2. * d[1] - 1.)
+    }
+}
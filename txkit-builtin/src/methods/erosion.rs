@@ -0,0 +1,453 @@
+use txkit_core::io::ImageIo;
+use txkit_core::method::Progress;
+use txkit_impl::{Method, ParamsFor};
+
+#[cfg(feature = "cpu")]
+use std::collections::HashMap;
+
+#[cfg(feature = "cpu")]
+use cgmath::{vec2, InnerSpace, Vector2};
+#[cfg(feature = "cpu")]
+use rayon::prelude::*;
+#[cfg(feature = "cpu")]
+use txkit_core::{
+    context::CpuContext,
+    hash::{hash2, tofloat},
+    image::Image,
+    io::cpu::CpuImageIoExt,
+};
+
+#[cfg(feature = "cpu")]
+use crate::cpu::compute_pixels;
+#[cfg(feature = "cpu")]
+use crate::shared::pixel_uv;
+
+#[derive(Clone, PartialEq, ParamsFor)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[repr(C)]
+pub struct ErosionParams {
+    /// pseudo-random seed of the droplet positions
+    pub global_seed: u32,
+    /// number of simulation steps
+    pub iterations: i32,
+    /// height of a unit of the height texture, in pixels
+    pub strength: f32,
+    /// fraction of the material above the talus slope moved at every step (0: no thermal erosion)
+    pub thermal_rate: f32,
+    /// steepest stable slope for thermal erosion, in pixels per pixel
+    pub talus_slope: f32,
+    /// number of water droplets per step (0: no hydraulic erosion)
+    pub droplets: i32,
+    /// maximum number of moves of a droplet
+    pub droplet_lifetime: i32,
+    /// fraction of the previous direction kept by droplets at every move
+    pub inertia: f32,
+    /// sediment carried by droplets per unit of water, speed and descent
+    pub sediment_capacity: f32,
+    /// fraction of the free sediment capacity eroded at every move
+    pub erosion_rate: f32,
+    /// fraction of the excess sediment deposited at every move
+    pub deposition_rate: f32,
+    /// fraction of the droplet water evaporated at every move
+    pub evaporation_rate: f32,
+
+    /// texture inputs, the height is bound to unit 0
+    #[texture_io(height)]
+    pub io: Box<ImageIo>,
+}
+
+impl Default for ErosionParams {
+    fn default() -> Self {
+        Self {
+            global_seed: 0,
+            iterations: 32,
+            strength: 32.,
+            thermal_rate: 0.5,
+            talus_slope: 1.,
+            droplets: 1024,
+            droplet_lifetime: 30,
+            inertia: 0.05,
+            sediment_capacity: 4.,
+            erosion_rate: 0.3,
+            deposition_rate: 0.3,
+            evaporation_rate: 0.01,
+            io: Box::default(),
+        }
+    }
+}
+
+/// Texture unit of the height
+#[cfg(feature = "cpu")]
+const HEIGHT_UNIT: usize = 0;
+
+/// Acceleration of the droplets on slopes
+#[cfg(feature = "cpu")]
+const GRAVITY: f32 = 4.;
+
+/// Sediment capacity of droplets on flat areas, in pixels
+#[cfg(feature = "cpu")]
+const MIN_SEDIMENT_CAPACITY: f32 = 0.01;
+
+/// Number of droplets simulated by each task of the thread pool
+#[cfg(feature = "cpu")]
+const DROPLET_CHUNK_SIZE: usize = 64;
+
+/// Thermal and hydraulic erosion of a heightfield
+///
+/// Simulates `iterations` steps of erosion on the red channel of the height texture bound to unit
+/// 0, scaled by `strength` and sampled at the pixels of the output. The heightfield wraps around
+/// its edges, so tileable heights give tileable results.
+///
+/// Every step first moves `thermal_rate` of the material above the talus slope to the lower
+/// neighbors of every pixel, then simulates `droplets` water droplets starting at random
+/// positions. Droplets flow down the slope, eroding the terrain while their sediment capacity
+/// allows it, and depositing sediment otherwise. See Hans Theobald Beyer, Implementation of a
+/// method for hydraulic erosion, 2015.
+///
+/// The output is the eroded height in the red channel, in units of the height texture, the
+/// deposited material in the green channel, in the same units, and the water flow in the blue
+/// channel, mapped to [0, 1) by `1 - exp(-w)` where `w` is the water that went through the pixel
+/// relative to the average over all pixels.
+///
+/// Droplets of a step are simulated in fixed chunks on the thread pool. Droplets of a chunk flow
+/// one after the other on the heightfield of the previous step, and the changes of all chunks are
+/// applied in order at the end of the step, so the result only depends on the parameters and not
+/// on the number of threads. Only the CPU context is supported. As the simulation is long, it
+/// reports the number of completed steps to the callback set with
+/// [Method::set_progress](txkit_core::method::Method::set_progress).
+#[derive(Default, Method)]
+#[txkit(
+    cpu(
+        method(run = "Self::compute_cpu_erosion", params = "ErosionParams"),
+        dims(Image1D, Image2D)
+    ),
+    method(progress = "progress")
+)]
+pub struct Erosion {
+    /// parameters used when computing without explicit parameters
+    params: ErosionParams,
+    /// callback notified after every simulation step
+    progress: Option<Progress>,
+}
+
+impl Erosion {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[cfg(feature = "cpu")]
+impl Erosion {
+    fn compute_cpu_erosion(
+        &mut self,
+        ctx: &mut CpuContext,
+        tgt: &mut Image,
+        params: &ErosionParams,
+    ) -> txkit_core::Result<()> {
+        let height = params.io.texture_binding_data(HEIGHT_UNIT)?;
+        let dim = tgt.dim();
+
+        // Sample the initial heights at the output resolution
+        let strength = params.strength;
+        let heights = ctx.thread_pool.install(|| {
+            (0..dim.width * dim.height)
+                .into_par_iter()
+                .map(|i| {
+                    let uv = pixel_uv((0, i / dim.width, i % dim.width), dim);
+                    strength * height.sample_2d([uv.x, uv.y])[0]
+                })
+                .collect()
+        });
+
+        let mut sim = ErosionSimulation::new(params, dim.width, dim.height, heights);
+        let initial = sim.heights.clone();
+        let iterations = params.iterations.max(0) as usize;
+
+        for iteration in 0..iterations {
+            ctx.thread_pool.install(|| sim.step(iteration as u32));
+
+            if let Some(progress) = &mut self.progress {
+                progress(iteration + 1, iterations);
+            }
+        }
+
+        let width = dim.width;
+        let mean_flow = sim.flow.iter().sum::<f32>() / sim.flow.len() as f32;
+        let flow_scale = if mean_flow > 0. { 1. / mean_flow } else { 0. };
+
+        compute_pixels(ctx, tgt, |(_, j, i), _| {
+            let k = i + width * j;
+            [
+                sim.heights[k] / strength,
+                (sim.heights[k] - initial[k]).max(0.) / strength,
+                1. - (-sim.flow[k] * flow_scale).exp(),
+                1.,
+            ]
+        })
+    }
+}
+
+/// State of the erosion simulation on the CPU
+///
+/// Heights are in pixels, and the grid wraps around its edges.
+#[cfg(feature = "cpu")]
+struct ErosionSimulation {
+    width: usize,
+    height: usize,
+    heights: Vec<f32>,
+    /// water that went through every pixel
+    flow: Vec<f32>,
+    /// parameters of the simulation, without the texture bindings which can't be shared between
+    /// threads
+    global_seed: u32,
+    thermal_rate: f32,
+    talus_slope: f32,
+    droplets: usize,
+    droplet_lifetime: i32,
+    inertia: f32,
+    sediment_capacity: f32,
+    erosion_rate: f32,
+    deposition_rate: f32,
+    evaporation_rate: f32,
+}
+
+/// Changes made by the droplets of a chunk, by pixel index
+#[cfg(feature = "cpu")]
+#[derive(Default)]
+struct DropletChanges {
+    /// height differences
+    heights: HashMap<usize, f32>,
+    /// water that went through the pixels
+    flow: HashMap<usize, f32>,
+}
+
+#[cfg(feature = "cpu")]
+impl DropletChanges {
+    fn add(map: &mut HashMap<usize, f32>, k: usize, value: f32) {
+        *map.entry(k).or_insert(0.) += value;
+    }
+}
+
+#[cfg(feature = "cpu")]
+impl ErosionSimulation {
+    fn new(params: &ErosionParams, width: usize, height: usize, heights: Vec<f32>) -> Self {
+        Self {
+            width,
+            height,
+            heights,
+            flow: vec![0.; width * height],
+            global_seed: params.global_seed,
+            thermal_rate: params.thermal_rate,
+            talus_slope: params.talus_slope,
+            droplets: params.droplets.max(0) as usize,
+            droplet_lifetime: params.droplet_lifetime,
+            inertia: params.inertia,
+            sediment_capacity: params.sediment_capacity,
+            erosion_rate: params.erosion_rate,
+            deposition_rate: params.deposition_rate,
+            evaporation_rate: params.evaporation_rate,
+        }
+    }
+
+    /// Simulate one step of thermal then hydraulic erosion
+    ///
+    /// Must be called from the thread pool the pixels and droplets should be processed on.
+    fn step(&mut self, iteration: u32) {
+        if self.thermal_rate > 0. {
+            self.heights = self.thermal_step();
+        }
+
+        let droplets = self.droplets;
+        let changes: Vec<DropletChanges> = (0..droplets.div_ceil(DROPLET_CHUNK_SIZE))
+            .into_par_iter()
+            .map(|chunk| {
+                let mut changes = DropletChanges::default();
+                let start = chunk * DROPLET_CHUNK_SIZE;
+
+                for droplet in start..(start + DROPLET_CHUNK_SIZE).min(droplets) {
+                    let [x, y] = hash2([droplet as u32, iteration], self.global_seed);
+                    let pos = vec2(
+                        tofloat(x) * self.width as f32,
+                        tofloat(y) * self.height as f32,
+                    );
+
+                    self.droplet(pos, &mut changes);
+                }
+
+                changes
+            })
+            .collect();
+
+        // Apply the changes in chunk order, every pixel appears once per chunk
+        for chunk in changes {
+            for (k, dh) in chunk.heights {
+                self.heights[k] += dh;
+            }
+
+            for (k, water) in chunk.flow {
+                self.flow[k] += water;
+            }
+        }
+    }
+
+    /// Compute the heights after moving the material above the talus slope downhill
+    fn thermal_step(&self) -> Vec<f32> {
+        const NEIGHBORS: [(isize, isize, f32); 8] = [
+            (-1, -1, std::f32::consts::SQRT_2),
+            (0, -1, 1.),
+            (1, -1, std::f32::consts::SQRT_2),
+            (-1, 0, 1.),
+            (1, 0, 1.),
+            (-1, 1, std::f32::consts::SQRT_2),
+            (0, 1, 1.),
+            (1, 1, std::f32::consts::SQRT_2),
+        ];
+
+        let talus = self.talus_slope;
+        let excess =
+            |from: usize, to: usize, d: f32| self.heights[from] - self.heights[to] - talus * d;
+
+        // Material leaving every pixel, and total excess height over its lower neighbors
+        let outflow: Vec<(f32, f32)> = (0..self.heights.len())
+            .into_par_iter()
+            .map(|k| {
+                let (mut total, mut max) = (0f32, 0f32);
+                for &(dx, dy, d) in &NEIGHBORS {
+                    let e = excess(k, self.neighbor(k, dx, dy), d);
+                    if e > 0. {
+                        total += e;
+                        max = max.max(e);
+                    }
+                }
+
+                // Moving half of the largest excess levels the steepest slope
+                (0.5 * self.thermal_rate * max, total)
+            })
+            .collect();
+
+        (0..self.heights.len())
+            .into_par_iter()
+            .map(|k| {
+                let mut h = self.heights[k] - outflow[k].0;
+
+                // Material received from the higher neighbors, in proportion of their excess
+                for &(dx, dy, d) in &NEIGHBORS {
+                    let n = self.neighbor(k, dx, dy);
+                    let e = excess(n, k, d);
+                    if e > 0. {
+                        h += outflow[n].0 * e / outflow[n].1;
+                    }
+                }
+
+                h
+            })
+            .collect()
+    }
+
+    /// Simulate a droplet starting at `pos`, in pixels
+    ///
+    /// The droplet flows on the heights of the previous step, updated with `changes`.
+    fn droplet(&self, mut pos: Vector2<f32>, changes: &mut DropletChanges) {
+        let mut dir = vec2(0., 0.);
+        let (mut speed, mut water, mut sediment) = (1f32, 1f32, 0f32);
+
+        for _ in 0..self.droplet_lifetime {
+            let (corners, weights) = self.corners(pos);
+            let (h, gradient) = self.height_gradient(pos, changes);
+
+            for (&k, &w) in corners.iter().zip(&weights) {
+                DropletChanges::add(&mut changes.flow, k, water * w);
+            }
+
+            // Flow down the slope
+            dir = dir * self.inertia - gradient * (1. - self.inertia);
+            let len = dir.magnitude();
+            if len <= f32::EPSILON {
+                break;
+            }
+
+            dir /= len;
+            pos += dir;
+
+            let dh = self.height_gradient(pos, changes).0 - h;
+            let capacity =
+                (-dh * speed * water * self.sediment_capacity).max(MIN_SEDIMENT_CAPACITY);
+
+            // Deposit when going uphill or over capacity, erode otherwise
+            let amount = if dh > 0. || sediment > capacity {
+                let deposit = if dh > 0. {
+                    dh.min(sediment)
+                } else {
+                    (sediment - capacity) * self.deposition_rate
+                };
+
+                sediment -= deposit;
+                deposit
+            } else {
+                let erode = ((capacity - sediment) * self.erosion_rate).min(-dh);
+                sediment += erode;
+                -erode
+            };
+
+            for (&k, &w) in corners.iter().zip(&weights) {
+                DropletChanges::add(&mut changes.heights, k, amount * w);
+            }
+
+            speed = (speed * speed - dh * GRAVITY).max(0.).sqrt();
+            water *= 1. - self.evaporation_rate;
+        }
+
+        // Drop the remaining sediment where the droplet stops
+        let (corners, weights) = self.corners(pos);
+        for (&k, &w) in corners.iter().zip(&weights) {
+            DropletChanges::add(&mut changes.heights, k, sediment * w);
+        }
+    }
+
+    /// Index of the pixel at an offset from pixel `k`
+    fn neighbor(&self, k: usize, dx: isize, dy: isize) -> usize {
+        let i = (k % self.width) as isize + dx;
+        let j = (k / self.width) as isize + dy;
+
+        i.rem_euclid(self.width as isize) as usize
+            + self.width * j.rem_euclid(self.height as isize) as usize
+    }
+
+    /// Indices and bilinear weights of the pixels around `pos`, in pixels
+    fn corners(&self, pos: Vector2<f32>) -> ([usize; 4], [f32; 4]) {
+        let (fx, fy) = (pos.x - pos.x.floor(), pos.y - pos.y.floor());
+        let k = self.neighbor(0, pos.x.floor() as isize, pos.y.floor() as isize);
+
+        (
+            [
+                k,
+                self.neighbor(k, 1, 0),
+                self.neighbor(k, 0, 1),
+                self.neighbor(k, 1, 1),
+            ],
+            [
+                (1. - fx) * (1. - fy),
+                fx * (1. - fy),
+                (1. - fx) * fy,
+                fx * fy,
+            ],
+        )
+    }
+
+    /// Bilinearly interpolated height and gradient at `pos`, in pixels
+    fn height_gradient(&self, pos: Vector2<f32>, changes: &DropletChanges) -> (f32, Vector2<f32>) {
+        let (fx, fy) = (pos.x - pos.x.floor(), pos.y - pos.y.floor());
+        let ([k00, k10, k01, k11], weights) = self.corners(pos);
+        let height = |k| self.heights[k] + changes.heights.get(&k).unwrap_or(&0.);
+        let (h00, h10, h01, h11) = (height(k00), height(k10), height(k01), height(k11));
+
+        let h = h00 * weights[0] + h10 * weights[1] + h01 * weights[2] + h11 * weights[3];
+        let gradient = vec2(
+            (h10 - h00) * (1. - fy) + (h11 - h01) * fy,
+            (h01 - h00) * (1. - fx) + (h11 - h10) * fx,
+        );
+
+        (h, gradient)
+    }
+}
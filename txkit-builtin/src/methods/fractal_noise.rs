@@ -0,0 +1,301 @@
+use std::ops::ControlFlow;
+
+use txkit_core::context::Context;
+use txkit_core::image::Image;
+use txkit_core::lattice::{
+    check_simplex_lattice_period_4d, LATTICE_DIMENSIONS_2D, LATTICE_DIMENSIONS_3D,
+    LATTICE_DIMENSIONS_4D, STATS_MODE_LOOKAT, STATS_MODE_NORMAL, STATS_MODE_PROCESS,
+};
+use txkit_impl::{Method, ParamsFor};
+
+#[cfg(feature = "cpu")]
+use cgmath::{Array, Vector3, Vector4};
+#[cfg(feature = "cpu")]
+use txkit_core::{
+    context::CpuContext,
+    lattice::{Lattice, LatticeMode, LatticeMode3D, LatticeMode4D},
+};
+
+#[cfg(feature = "cpu")]
+use super::{GradientNoise, SimplexNoise, ValueNoise, WorleyNoise, WorleyNoiseParams};
+#[cfg(feature = "cpu")]
+use crate::cpu::{compute_pixels, Domain};
+#[cfg(feature = "cpu")]
+use crate::shared::to01;
+
+/// Fractal: value noise octaves
+pub const FRACTAL_BASIS_VALUE: i32 = 0;
+/// Fractal: gradient noise octaves
+pub const FRACTAL_BASIS_GRADIENT: i32 = 1;
+/// Fractal: simplex noise octaves
+pub const FRACTAL_BASIS_SIMPLEX: i32 = 2;
+/// Fractal: Worley noise (F1) octaves
+pub const FRACTAL_BASIS_WORLEY: i32 = 3;
+
+/// Fractal: fractional Brownian motion, sum of the octaves
+pub const FRACTAL_VARIANT_FBM: i32 = 0;
+/// Fractal: turbulence, sum of the absolute values of the octaves
+pub const FRACTAL_VARIANT_TURBULENCE: i32 = 1;
+/// Fractal: ridged noise, sum of the squared complements of the absolute values of the octaves
+pub const FRACTAL_VARIANT_RIDGED: i32 = 2;
+
+#[derive(Clone, Copy, PartialEq, ParamsFor)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[repr(C)]
+#[txkit(program = "FractalNoiseProgram")]
+pub struct FractalNoiseParams {
+    /// pseudo-random seed, octave i is seeded with global_seed + i
+    pub global_seed: u32,
+    /// lattice scale of the first octave (size in pixels)
+    pub scale: f32,
+    /// stats mode (0: normal, 1: process, 2: lookat)
+    #[txkit(values(STATS_MODE_NORMAL, STATS_MODE_PROCESS, STATS_MODE_LOOKAT))]
+    pub stats_mode: i32,
+    /// look-at parameter (if stats_mode == lookat) in [0, 1]^2
+    pub stats_look_at: cgmath::Vector2<f32>,
+
+    /// basis noise
+    #[txkit(values(
+        FRACTAL_BASIS_VALUE,
+        FRACTAL_BASIS_GRADIENT,
+        FRACTAL_BASIS_SIMPLEX,
+        FRACTAL_BASIS_WORLEY
+    ))]
+    pub basis: i32,
+    /// octave combination
+    #[txkit(values(
+        FRACTAL_VARIANT_FBM,
+        FRACTAL_VARIANT_TURBULENCE,
+        FRACTAL_VARIANT_RIDGED
+    ))]
+    pub variant: i32,
+    /// number of octaves
+    pub octaves: i32,
+    /// scale multiplier between successive octaves
+    pub lacunarity: f32,
+    /// amplitude multiplier between successive octaves
+    pub gain: f32,
+    /// noise dimensions of the value, gradient and simplex octaves
+    #[txkit(values(LATTICE_DIMENSIONS_2D, LATTICE_DIMENSIONS_3D, LATTICE_DIMENSIONS_4D))]
+    pub dimensions: i32,
+    /// position along the time axis of 4D noise, in lattice cells of the first octave
+    pub time: f32,
+    /// period of 4D noise along the time axis, in lattice cells of the first octave, 0 for no
+    /// looping
+    pub period: f32,
+}
+
+impl Default for FractalNoiseParams {
+    fn default() -> Self {
+        Self {
+            global_seed: 0,
+            scale: 8.,
+            stats_mode: 0,
+            stats_look_at: cgmath::vec2(0., 0.),
+            basis: FRACTAL_BASIS_GRADIENT,
+            variant: FRACTAL_VARIANT_FBM,
+            octaves: 5,
+            lacunarity: 2.,
+            gain: 0.5,
+            dimensions: LATTICE_DIMENSIONS_2D,
+            time: 0.,
+            period: 0.,
+        }
+    }
+}
+
+/// Fractal noise
+///
+/// Sums octaves of a lattice noise, each octave using `lacunarity` times the scale of the
+/// previous one, and `gain` times its amplitude. The result is normalized by the sum of the
+/// amplitudes. The texture tiles as long as the scales of all octaves are integers, which is the
+/// case for an integer `scale` and an integer `lacunarity`.
+///
+/// Value, gradient and simplex octaves follow `dimensions` like the corresponding noise methods,
+/// while Worley octaves are always 2D. The time and period of each octave are scaled like its
+/// lattice, so 4D fractal noise loops over `period` like its first octave. Looping 4D simplex
+/// octaves require the scale and period of the last octave to be at most
+/// [SIMPLEX_LATTICE_4D_MAX_PERIOD](txkit_core::lattice::SIMPLEX_LATTICE_4D_MAX_PERIOD) cells.
+///
+/// The CPU implementation is a port of `fractal_noise.frag`.
+#[derive(Default, Method)]
+#[txkit(
+    gpu(
+        name = "FractalNoiseGpu",
+        program("shaders/quad.vert", "shaders/fractal_noise.frag"),
+        method(run = "program", params = "FractalNoiseParams")
+    ),
+    cpu(method(run = "Self::compute_cpu_fractal", params = "FractalNoiseParams")),
+    method(prepare = "Self::check_period")
+)]
+pub struct FractalNoise {
+    /// parameters used when computing without explicit parameters
+    params: FractalNoiseParams,
+    #[cfg(feature = "gpu")]
+    gpu: Option<FractalNoiseGpu>,
+}
+
+impl FractalNoise {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reject looping 4D simplex lattices too large to be reduced on the GPU
+    fn check_period(
+        &mut self,
+        _ctx: &mut Context,
+        _tgt: &mut Image,
+        params: &mut FractalNoiseParams,
+    ) -> txkit_core::Result<ControlFlow<()>> {
+        if params.basis == FRACTAL_BASIS_SIMPLEX
+            && params.dimensions == LATTICE_DIMENSIONS_4D
+            && params.octaves > 0
+        {
+            // The last octave has the largest scale and period for lacunarities above 1
+            let frequency = params.lacunarity.powi(params.octaves - 1).max(1.);
+            check_simplex_lattice_period_4d(params.scale * frequency, params.period * frequency)?;
+        }
+
+        Ok(ControlFlow::Continue(()))
+    }
+}
+
+#[cfg(feature = "cpu")]
+impl FractalNoise {
+    fn compute_cpu_fractal(
+        &mut self,
+        ctx: &mut CpuContext,
+        tgt: &mut Image,
+        params: &FractalNoiseParams,
+    ) -> txkit_core::Result<()> {
+        let dim = tgt.dim();
+        let worley = WorleyNoiseParams::default();
+
+        // Lattices of the successive octaves, with their frequency relative to the first octave.
+        // Time and period are scaled with the octaves, so all octaves loop together.
+        let mut frequency = 1.;
+        let lattices: Vec<_> = (0..params.octaves.max(0))
+            .map(|i| {
+                let lattice = Lattice::new(
+                    params.scale * frequency,
+                    params.global_seed.wrapping_add(i as u32),
+                    params.stats_mode,
+                    params.stats_look_at,
+                    dim,
+                )
+                .with_period(params.period * frequency);
+
+                let octave = (lattice, frequency);
+                frequency *= params.lacunarity;
+                octave
+            })
+            .collect();
+
+        let domain = Domain::new(ctx, tgt.dim())?;
+
+        compute_pixels(ctx, tgt, |idx, dim| {
+            let uv = domain.uv(idx, dim);
+            Self::noise(uv.extend(params.time), &lattices, &worley, params)
+                .extend(1.)
+                .into()
+        })
+    }
+
+    fn basis_noise(
+        position: Vector4<f32>,
+        lattice: &Lattice,
+        worley: &WorleyNoiseParams,
+        params: &FractalNoiseParams,
+    ) -> f32 {
+        let (xy, xyz) = (position.truncate().truncate(), position.truncate());
+
+        match (params.basis, params.dimensions) {
+            (FRACTAL_BASIS_VALUE, LATTICE_DIMENSIONS_3D) => {
+                2. * ValueNoise::noise_3d(lattice.sample_3d(xyz, LatticeMode3D::Rect3D), lattice)
+                    - 1.
+            }
+            (FRACTAL_BASIS_VALUE, LATTICE_DIMENSIONS_4D) => {
+                let s = lattice.sample_4d(position, LatticeMode4D::Rect4D);
+                2. * ValueNoise::noise_4d(s, lattice) - 1.
+            }
+            (FRACTAL_BASIS_VALUE, _) => {
+                2. * ValueNoise::noise(lattice.sample(xy, LatticeMode::Rect2D), lattice) - 1.
+            }
+            (FRACTAL_BASIS_GRADIENT, LATTICE_DIMENSIONS_3D) => {
+                GradientNoise::noise_3d(lattice.sample_3d(xyz, LatticeMode3D::Rect3D), lattice)
+            }
+            (FRACTAL_BASIS_GRADIENT, LATTICE_DIMENSIONS_4D) => {
+                GradientNoise::noise_4d(lattice.sample_4d(position, LatticeMode4D::Rect4D), lattice)
+            }
+            (FRACTAL_BASIS_GRADIENT, _) => {
+                GradientNoise::noise(lattice.sample(xy, LatticeMode::Rect2D), lattice)
+            }
+            (FRACTAL_BASIS_SIMPLEX, LATTICE_DIMENSIONS_3D) => {
+                let s = lattice.sample_3d(xyz, LatticeMode3D::Simplex3D);
+                SimplexNoise::noise_3d(s, lattice)
+            }
+            (FRACTAL_BASIS_SIMPLEX, LATTICE_DIMENSIONS_4D) => {
+                let s = lattice.sample_4d(position, LatticeMode4D::Simplex4D);
+                SimplexNoise::noise_4d(s, lattice)
+            }
+            (FRACTAL_BASIS_SIMPLEX, _) => {
+                SimplexNoise::noise(lattice.sample(xy, LatticeMode::Simplex2D), lattice)
+            }
+            (FRACTAL_BASIS_WORLEY, _) => {
+                let s = lattice.sample(xy, LatticeMode::Rect2D);
+                2. * WorleyNoise::distances(s, lattice, worley).0 - 1.
+            }
+            _ => 0.,
+        }
+    }
+
+    fn noise(
+        position: Vector4<f32>,
+        lattices: &[(Lattice, f32)],
+        worley: &WorleyNoiseParams,
+        params: &FractalNoiseParams,
+    ) -> Vector3<f32> {
+        let (mut value, mut amplitude, mut total) = (0., 1., 0.);
+
+        for (lattice, frequency) in lattices {
+            let mut position = position;
+            position.w *= frequency;
+
+            let mut n = Self::basis_noise(position, lattice, worley, params);
+
+            if params.variant == FRACTAL_VARIANT_TURBULENCE {
+                n = n.abs();
+            } else if params.variant == FRACTAL_VARIANT_RIDGED {
+                n = 1. - n.abs();
+                n *= n;
+            }
+
+            value += amplitude * n;
+            total += amplitude;
+
+            amplitude *= params.gain;
+        }
+
+        if total != 0. {
+            value /= total;
+        }
+
+        if !(LATTICE_DIMENSIONS_2D..=LATTICE_DIMENSIONS_4D).contains(&params.dimensions) {
+            // Invalid enum value
+            return Vector3::new(1., 0., 1.);
+        }
+
+        match (params.basis, params.variant) {
+            (FRACTAL_BASIS_VALUE..=FRACTAL_BASIS_WORLEY, FRACTAL_VARIANT_FBM) => {
+                Vector3::from_value(to01(value))
+            }
+            (
+                FRACTAL_BASIS_VALUE..=FRACTAL_BASIS_WORLEY,
+                FRACTAL_VARIANT_TURBULENCE | FRACTAL_VARIANT_RIDGED,
+            ) => Vector3::from_value(value),
+            // Invalid enum value
+            _ => Vector3::new(1., 0., 1.),
+        }
+    }
+}
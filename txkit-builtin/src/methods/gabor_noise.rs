@@ -0,0 +1,328 @@
+use std::ops::ControlFlow;
+
+use txkit_core::context::Context;
+use txkit_core::image::Image;
+use txkit_core::io::ImageIo;
+use txkit_core::lattice::{STATS_MODE_LOOKAT, STATS_MODE_NORMAL, STATS_MODE_PROCESS};
+use txkit_core::{Error, Result};
+use txkit_impl::{Method, ParamsFor};
+
+#[cfg(feature = "cpu")]
+use cgmath::{vec2, InnerSpace, Vector2};
+#[cfg(feature = "cpu")]
+use txkit_core::{
+    context::CpuContext,
+    hash::hash_cell,
+    io::cpu::CpuTexture,
+    lattice::{Lattice, LatticeMode, LatticeSample},
+};
+
+use super::{
+    PHASOR_POINTS_HEX_JITTERED, PHASOR_POINTS_POISSON, PHASOR_POINTS_RECT_JITTERED,
+    PHASOR_POINTS_STRAT_POSSION,
+};
+#[cfg(feature = "cpu")]
+use crate::cpu::{compute_pixels, Domain};
+#[cfg(feature = "cpu")]
+use crate::lcg::Lcg;
+#[cfg(feature = "cpu")]
+use crate::points::PointSet;
+#[cfg(feature = "cpu")]
+use crate::shared::to01;
+
+/// Gabor: all kernels have the main orientation
+pub const GABOR_MODE_ANISOTROPIC: i32 = 0;
+/// Gabor: kernels have uniformly random orientations
+pub const GABOR_MODE_ISOTROPIC: i32 = 1;
+/// Gabor: kernels have random orientations within `orientation_spread` of the main orientation
+pub const GABOR_MODE_HYBRID: i32 = 2;
+
+/// Gabor: largest kernel radius, in noise cells
+pub const GABOR_MAX_RADIUS: f32 = 4.;
+
+/// Radius of the kernels relative to 1 / bandwidth, see `gabor_noise.frag`
+const GABOR_RADIUS: f32 = 0.9765;
+
+#[derive(Clone, PartialEq, ParamsFor)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[repr(C)]
+#[txkit(program = "GaborNoiseProgram")]
+pub struct GaborNoiseParams {
+    /// pseudo-random seed
+    pub global_seed: u32,
+    /// lattice scale (size in pixels)
+    pub scale: f32,
+    /// stats mode (0: normal, 1: process, 2: lookat)
+    #[txkit(values(STATS_MODE_NORMAL, STATS_MODE_PROCESS, STATS_MODE_LOOKAT))]
+    pub stats_mode: i32,
+    /// look-at parameter (if stats_mode == lookat) in [0, 1]^2
+    pub stats_look_at: cgmath::Vector2<f32>,
+
+    /// kernel orientation mode
+    #[txkit(values(GABOR_MODE_ANISOTROPIC, GABOR_MODE_ISOTROPIC, GABOR_MODE_HYBRID))]
+    pub mode: i32,
+    /// kernel count per cell
+    pub kernel_count: i32,
+    /// point distribution
+    #[txkit(values(
+        PHASOR_POINTS_STRAT_POSSION,
+        PHASOR_POINTS_POISSON,
+        PHASOR_POINTS_RECT_JITTERED,
+        PHASOR_POINTS_HEX_JITTERED
+    ))]
+    pub noise_point_distribution: i32,
+    /// jittering amount, 0 = no random, 1 = full subcell random
+    pub jitter_amount: f32,
+    /// max jittering subcells, 0 = no limit
+    pub jitter_max: i32,
+
+    /// width of the gaussian envelope in the frequency domain (in 1 / noise cell)
+    pub bandwidth: f32,
+    /// kernel frequency (in oscillations / noise cell)
+    pub frequency: f32,
+    /// main kernel orientation (in radians)
+    pub orientation: f32,
+    /// width of the interval of random orientations in hybrid mode (in radians)
+    pub orientation_spread: f32,
+
+    /// texture inputs
+    #[texture_io(frequency_orientation_field)]
+    pub io: Box<ImageIo>,
+}
+
+impl Default for GaborNoiseParams {
+    fn default() -> Self {
+        Self {
+            global_seed: 0,
+            scale: 16.,
+            stats_mode: 0,
+            stats_look_at: cgmath::vec2(0., 0.),
+            mode: GABOR_MODE_ANISOTROPIC,
+            kernel_count: 8,
+            noise_point_distribution: PHASOR_POINTS_STRAT_POSSION,
+            jitter_amount: 1.,
+            jitter_max: 0,
+            bandwidth: 1.,
+            frequency: 4.,
+            orientation: 0.,
+            orientation_spread: std::f32::consts::FRAC_PI_2,
+            io: Box::default(),
+        }
+    }
+}
+
+/// Gabor noise
+///
+/// Sparse convolution of random impulses with Gabor kernels: a gaussian envelope of width `1 /
+/// bandwidth` cells times a cosine of `frequency` oscillations per cell. Impulse positions
+/// follow the same point distributions as phasor noise, and impulse weights are uniform in
+/// [-1, 1]. Kernels are truncated where their envelope falls below 5% of its peak, and their
+/// radius must not exceed [GABOR_MAX_RADIUS] cells.
+///
+/// The frequency/orientation field bound to the `frequency_orientation_field` texture is sampled
+/// at the impulse locations: the kernel frequency is multiplied by `1 + R` and the main
+/// orientation offset by `G` radians. The noise is normalized so three standard deviations
+/// span [0, 1].
+///
+/// The CPU implementation is a port of `gabor_noise.frag`.
+#[derive(Default, Method)]
+#[txkit(
+    gpu(
+        name = "GaborNoiseGpu",
+        program("shaders/quad.vert", "shaders/gabor_noise.frag"),
+        method(run = "program", params = "GaborNoiseParams"),
+        dims(Image2D)
+    ),
+    cpu(
+        method(run = "Self::compute_cpu_gabor", params = "GaborNoiseParams"),
+        dims(Image1D, Image2D)
+    ),
+    method(prepare = "Self::check_kernel_radius")
+)]
+pub struct GaborNoise {
+    /// parameters used when computing without explicit parameters
+    params: GaborNoiseParams,
+    #[cfg(feature = "gpu")]
+    gpu: Option<GaborNoiseGpu>,
+}
+
+impl GaborNoise {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reject kernels larger than [GABOR_MAX_RADIUS]
+    fn check_kernel_radius(
+        &mut self,
+        _ctx: &mut Context,
+        _tgt: &mut Image,
+        params: &mut GaborNoiseParams,
+    ) -> Result<ControlFlow<()>> {
+        let radius = GABOR_RADIUS / params.bandwidth;
+        if radius.is_nan() || radius <= 0. || radius > GABOR_MAX_RADIUS {
+            return Err(Error::InvalidParameters);
+        }
+
+        Ok(ControlFlow::Continue(()))
+    }
+}
+
+#[cfg(feature = "cpu")]
+impl GaborNoise {
+    fn compute_cpu_gabor(
+        &mut self,
+        ctx: &mut CpuContext,
+        tgt: &mut Image,
+        params: &GaborNoiseParams,
+    ) -> Result<()> {
+        use txkit_core::io::cpu::CpuImageIoExt;
+
+        if params.mode != GABOR_MODE_ANISOTROPIC
+            && params.mode != GABOR_MODE_ISOTROPIC
+            && params.mode != GABOR_MODE_HYBRID
+        {
+            // Invalid enum value
+            return compute_pixels(ctx, tgt, |_, _| [1., 0., 1., 1.]);
+        }
+
+        let sampler = GaborSampler {
+            lattice: Lattice::new(
+                params.scale,
+                params.global_seed,
+                params.stats_mode,
+                params.stats_look_at,
+                tgt.dim(),
+            ),
+            scale: params.scale,
+            mode: params.mode,
+            kernel_count: params.kernel_count,
+            noise_point_distribution: params.noise_point_distribution,
+            jitter_amount: params.jitter_amount,
+            jitter_max: params.jitter_max,
+            bandwidth: params.bandwidth,
+            frequency: params.frequency,
+            orientation: params.orientation,
+            orientation_spread: params.orientation_spread,
+            frequency_orientation_field: params
+                .io
+                .texture_binding_data(FREQUENCY_ORIENTATION_FIELD_UNIT)?,
+        };
+
+        let domain = Domain::new(ctx, tgt.dim())?;
+
+        compute_pixels(ctx, tgt, |idx, dim| {
+            let uv = domain.uv(idx, dim);
+            let v = to01(sampler.noise(sampler.lattice.sample(uv.truncate(), LatticeMode::Rect2D)));
+            [v, v, v, 1.]
+        })
+    }
+}
+
+/// Texture unit of the frequency/orientation field, see `gabor_noise.frag`
+#[cfg(feature = "cpu")]
+const FREQUENCY_ORIENTATION_FIELD_UNIT: usize = 0;
+
+/// Thread-safe copy of [GaborNoiseParams] for evaluating Gabor noise on the CPU
+#[cfg(feature = "cpu")]
+struct GaborSampler {
+    lattice: Lattice,
+    scale: f32,
+    mode: i32,
+    kernel_count: i32,
+    noise_point_distribution: i32,
+    jitter_amount: f32,
+    jitter_max: i32,
+    bandwidth: f32,
+    frequency: f32,
+    orientation: f32,
+    orientation_spread: f32,
+    frequency_orientation_field: CpuTexture,
+}
+
+#[cfg(feature = "cpu")]
+impl GaborSampler {
+    /// Evaluate a Gabor kernel
+    ///
+    /// # Parameters
+    ///
+    /// * `x`: position relative to the kernel center, in cells
+    /// * `f`: frequency vector, in oscillations per cell
+    fn gabor(&self, x: Vector2<f32>, f: Vector2<f32>) -> f32 {
+        use std::f32::consts::PI;
+
+        (-PI * self.bandwidth * self.bandwidth * x.dot(x)).exp() * (2. * PI * x.dot(f)).cos()
+    }
+
+    fn noise_cell(&self, pos: Vector2<f32>, cell: [i32; 2], seed: u32) -> f32 {
+        use std::f32::consts::PI;
+
+        let mut res = 0.;
+        let radius = GABOR_RADIUS / self.bandwidth;
+
+        // Seed the random number generator
+        let mut rng = Lcg::new(hash_cell([cell[0] as u32, cell[1] as u32], seed));
+
+        // Compute impulse count
+        let ps = PointSet::new(
+            &mut rng,
+            self.noise_point_distribution,
+            self.kernel_count,
+            self.jitter_max,
+            self.jitter_amount,
+        );
+
+        for i in 0..ps.count() {
+            let kpos = ps.next(&mut rng, i);
+
+            // Always step the generator so all modes use the same impulses
+            let weight = rng.next_11();
+            let u = rng.next_01();
+
+            let x = pos - kpos;
+            if x.magnitude() > radius {
+                continue;
+            }
+
+            // Kernel parameters at the impulse location
+            let fo = self
+                .frequency_orientation_field
+                .sample_2d(((vec2(cell[0] as f32, cell[1] as f32) + kpos) / self.scale).into());
+
+            let angle = match self.mode {
+                GABOR_MODE_ISOTROPIC => 2. * PI * u,
+                GABOR_MODE_HYBRID => self.orientation + fo[1] + self.orientation_spread * (u - 0.5),
+                _ => self.orientation + fo[1],
+            };
+
+            let f = vec2(angle.cos(), angle.sin()) * (self.frequency * (1. + fo[0]));
+            res += weight * self.gabor(x, f);
+        }
+
+        res
+    }
+
+    fn noise(&self, s: LatticeSample) -> f32 {
+        let mut res = 0.;
+        let lookahead = (GABOR_RADIUS / self.bandwidth).ceil() as i32;
+
+        let [sx, sy] = s.cell;
+        for cx in sx - lookahead..=sx + lookahead {
+            for cy in sy - lookahead..=sy + lookahead {
+                // Make sure the noise tiles correctly
+                let looped_cell = self.lattice.lattice_loop_signed([cx, cy]);
+
+                // Compute the position of the current point relative to the target cell
+                let position = s.position - vec2((cx - sx) as f32, (cy - sy) as f32);
+
+                // Add contribution of target noise cell
+                res += self.noise_cell(position, looped_cell, s.seed);
+            }
+        }
+
+        // Normalize by the standard deviation of the noise, with uniform weights in [-1, 1] and a
+        // kernel energy of 1 / (4 * bandwidth^2)
+        let sigma = (self.kernel_count as f32 / 12.).sqrt() / self.bandwidth;
+        res / (3. * sigma)
+    }
+}
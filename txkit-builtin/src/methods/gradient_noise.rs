@@ -1,6 +1,28 @@
+use cgmath::{vec2, vec3, vec4, Array, InnerSpace, Vector2, Vector3, Vector4, Zero};
+use txkit_core::hash::{hash3, hash4, hash_cell, tofloat11};
+use txkit_core::lattice::{
+    cell_offset, cell_offset_3d, Lattice, LatticeMode, LatticeMode3D, LatticeMode4D, LatticeSample,
+    LatticeSample3D, LatticeSample4D, LATTICE_DIMENSIONS_2D, LATTICE_DIMENSIONS_3D,
+    LATTICE_DIMENSIONS_4D, STATS_MODE_LOOKAT, STATS_MODE_NORMAL, STATS_MODE_PROCESS,
+};
 use txkit_impl::{Method, ParamsFor};
 
+#[cfg(feature = "cpu")]
+use txkit_core::{context::CpuContext, image::Image};
+
+#[cfg(feature = "cpu")]
+use crate::cpu::{compute_pixels, Domain};
+use crate::shared::{corner_weights, mix, to01};
+
+/// Gradient noise: noise value in the RGB channels
+pub const GRADIENT_OUTPUT_VALUE: i32 = 0;
+/// Gradient noise: noise value in the R channel, partial derivatives along x, y and the layers in
+/// the G, B and A channels
+pub const GRADIENT_OUTPUT_DERIVATIVES: i32 = 1;
+
 #[derive(Clone, Copy, PartialEq, ParamsFor)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[repr(C)]
 #[txkit(program = "GradientNoiseProgram")]
 pub struct GradientNoiseParams {
@@ -9,9 +31,20 @@ pub struct GradientNoiseParams {
     /// lattice scale (size in pixels)
     pub scale: f32,
     /// stats mode (0: normal, 1: process, 2: lookat)
+    #[txkit(values(STATS_MODE_NORMAL, STATS_MODE_PROCESS, STATS_MODE_LOOKAT))]
     pub stats_mode: i32,
     /// look-at parameter (if stats_mode == lookat) in [0, 1]^2
     pub stats_look_at: cgmath::Vector2<f32>,
+    /// output channels
+    #[txkit(values(GRADIENT_OUTPUT_VALUE, GRADIENT_OUTPUT_DERIVATIVES))]
+    pub output_mode: i32,
+    /// noise dimensions
+    #[txkit(values(LATTICE_DIMENSIONS_2D, LATTICE_DIMENSIONS_3D, LATTICE_DIMENSIONS_4D))]
+    pub dimensions: i32,
+    /// position along the time axis of 4D noise, in lattice cells
+    pub time: f32,
+    /// period of 4D noise along the time axis, in lattice cells, 0 for no looping
+    pub period: f32,
 }
 
 impl Default for GradientNoiseParams {
@@ -21,10 +54,31 @@ impl Default for GradientNoiseParams {
             scale: 32.,
             stats_mode: 0,
             stats_look_at: cgmath::vec2(0., 0.),
+            output_mode: GRADIENT_OUTPUT_VALUE,
+            dimensions: LATTICE_DIMENSIONS_2D,
+            time: 0.,
+            period: 0.,
         }
     }
 }
 
+/// 2D, 3D and 4D gradient noise
+///
+/// 2D noise is evaluated over (x, y) and repeated on every layer of 3D images, 3D noise over
+/// (x, y, layer), and 4D noise over (x, y, layer, time). With a non-zero `period`, 4D noise loops
+/// along the time axis: the images computed at `time` and `time + period` are the same.
+///
+/// The CPU implementation is a port of `gradient_noise.frag`. For `Float32` targets, its results
+/// match the GPU results within 1e-4, the difference coming from the rasterizer's interpolation
+/// of pixel coordinates and the precision of the GPU's trigonometric functions. For `UInt8`
+/// targets, results may differ by one quantization step since the CPU conversion truncates
+/// instead of rounding.
+///
+/// With `output_mode` set to derivatives, the analytic partial derivatives of the noise with
+/// respect to the lattice coordinates are written alongside the value. Like the value, they are
+/// remapped from [-1, 1] to [0, 1], but their range is wider: use a `Float32` target to keep
+/// them unclamped. The derivative along the layers is zero for 2D noise, and the derivative along
+/// the time axis of 4D noise is not written.
 #[derive(Default, Method)]
 #[txkit(
     gpu(
@@ -32,9 +86,12 @@ impl Default for GradientNoiseParams {
         program("shaders/quad.vert", "shaders/gradient_noise.frag"),
         method(run = "program", params = "GradientNoiseParams")
     ),
+    cpu(method(run = "Self::compute_cpu_gradient", params = "GradientNoiseParams")),
     method()
 )]
 pub struct GradientNoise {
+    /// parameters used when computing without explicit parameters
+    params: GradientNoiseParams,
     #[cfg(feature = "gpu")]
     gpu: Option<GradientNoiseGpu>,
 }
@@ -43,4 +100,239 @@ impl GradientNoise {
     pub fn new() -> Self {
         Self::default()
     }
+
+    fn noisehash(p: [u32; 2], seed: u32) -> Vector2<f32> {
+        let x = 2. * std::f32::consts::PI * tofloat11(hash_cell(p, seed));
+        vec2(x.cos(), x.sin())
+    }
+
+    /// Evaluate gradient noise, in [-1, 1]
+    pub(crate) fn noise(s: LatticeSample, lattice: &Lattice) -> f32 {
+        let i = s.ucell();
+        let f = s.position;
+
+        let u = f.map(|x| x * x * (3. - 2. * x));
+        let g = |dx, dy| {
+            Self::noisehash(lattice.lattice_loop(cell_offset(i, dx, dy)), s.seed)
+                .dot(f - vec2(dx as f32, dy as f32))
+        };
+
+        mix(mix(g(0, 0), g(1, 0), u.x), mix(g(0, 1), g(1, 1), u.x), u.y) * 1.493_152_5
+    }
+
+    /// Evaluate gradient noise, in [-1, 1], followed by its partial derivatives with respect to
+    /// the lattice coordinates
+    pub(crate) fn noise_derivatives(s: LatticeSample, lattice: &Lattice) -> Vector3<f32> {
+        let i = s.ucell();
+        let f = s.position;
+
+        let u = f.map(|x| x * x * (3. - 2. * x));
+        let du = f.map(|x| 6. * x * (1. - x));
+
+        let g = |dx, dy| Self::noisehash(lattice.lattice_loop(cell_offset(i, dx, dy)), s.seed);
+        let (ga, gb, gc, gd) = (g(0, 0), g(1, 0), g(0, 1), g(1, 1));
+
+        let va = ga.dot(f);
+        let vb = gb.dot(f - vec2(1., 0.));
+        let vc = gc.dot(f - vec2(0., 1.));
+        let vd = gd.dot(f - vec2(1., 1.));
+
+        let k = va - vb - vc + vd;
+        let d = ga
+            + (gb - ga) * u.x
+            + (gc - ga) * u.y
+            + (ga - gb - gc + gd) * (u.x * u.y)
+            + vec2(du.x * (u.y * k + vb - va), du.y * (u.x * k + vc - va));
+
+        vec3(mix(mix(va, vb, u.x), mix(vc, vd, u.x), u.y), d.x, d.y) * 1.493_152_5
+    }
+
+    fn noisehash_3d(p: [u32; 3], seed: u32) -> Vector3<f32> {
+        let [x, y, z] = hash3(p, seed);
+        vec3(tofloat11(x), tofloat11(y), tofloat11(z))
+    }
+
+    /// Evaluate 3D gradient noise, in [-1, 1]
+    pub(crate) fn noise_3d(s: LatticeSample3D, lattice: &Lattice) -> f32 {
+        let i = s.ucell();
+        let f = s.position;
+
+        let u = f.map(|x| x * x * (3. - 2. * x));
+        let g = |dx, dy, dz| {
+            Self::noisehash_3d(
+                lattice.lattice_loop_3d(cell_offset_3d(i, dx, dy, dz)),
+                s.seed,
+            )
+            .dot(f - vec3(dx as f32, dy as f32, dz as f32))
+        };
+
+        mix(
+            mix(
+                mix(g(0, 0, 0), g(1, 0, 0), u.x),
+                mix(g(0, 1, 0), g(1, 1, 0), u.x),
+                u.y,
+            ),
+            mix(
+                mix(g(0, 0, 1), g(1, 0, 1), u.x),
+                mix(g(0, 1, 1), g(1, 1, 1), u.x),
+                u.y,
+            ),
+            u.z,
+        ) * 1.3
+    }
+
+    /// Evaluate 3D gradient noise, in [-1, 1], followed by its partial derivatives with respect
+    /// to the lattice coordinates
+    pub(crate) fn noise_3d_derivatives(s: LatticeSample3D, lattice: &Lattice) -> Vector4<f32> {
+        let i = s.ucell();
+        let f = s.position;
+
+        let u = f.map(|x| x * x * (3. - 2. * x));
+        let du = f.map(|x| 6. * x * (1. - x));
+
+        // Sum the contributions of the 8 cell corners
+        let mut res = Vector4::zero();
+        for c in 0..8 {
+            let o = [c & 1, (c >> 1) & 1, (c >> 2) & 1, 0];
+            let cell = cell_offset_3d(i, o[0] as u32, o[1] as u32, o[2] as u32);
+            let g = Self::noisehash_3d(lattice.lattice_loop_3d(cell), s.seed);
+            let v = g.dot(f - vec3(o[0] as f32, o[1] as f32, o[2] as f32));
+
+            // Interpolation weights along each axis, and their derivatives
+            let w = corner_weights(u.extend(0.), o);
+            let dw = corner_derivatives(du.extend(0.), o);
+
+            let wp = w.x * w.y * w.z;
+            let d = g * wp + vec3(dw.x * w.y * w.z, w.x * dw.y * w.z, w.x * w.y * dw.z) * v;
+            res += vec4(wp * v, d.x, d.y, d.z);
+        }
+
+        res * 1.3
+    }
+
+    fn noisehash_4d([x, y, z, w]: [i32; 4], seed: u32) -> Vector4<f32> {
+        let [hx, hy, hz, hw] = hash4([x as u32, y as u32, z as u32, w as u32], seed);
+        vec4(tofloat11(hx), tofloat11(hy), tofloat11(hz), tofloat11(hw))
+    }
+
+    /// Evaluate 4D gradient noise, in [-1, 1], followed by its partial derivatives with respect
+    /// to the spatial lattice coordinates
+    pub(crate) fn noise_4d_derivatives(s: LatticeSample4D, lattice: &Lattice) -> Vector4<f32> {
+        let f = s.position;
+
+        let u = f.map(|x| x * x * (3. - 2. * x));
+        let du = f.map(|x| 6. * x * (1. - x));
+
+        // Sum the contributions of the 16 cell corners
+        let mut res = Vector4::zero();
+        for c in 0..16 {
+            let o = [c & 1, (c >> 1) & 1, (c >> 2) & 1, (c >> 3) & 1];
+            let cell = lattice.lattice_loop_4d([
+                s.cell[0] + o[0],
+                s.cell[1] + o[1],
+                s.cell[2] + o[2],
+                s.cell[3] + o[3],
+            ]);
+            let g = Self::noisehash_4d(cell, s.seed);
+            let v = g.dot(f - vec4(o[0] as f32, o[1] as f32, o[2] as f32, o[3] as f32));
+
+            // Interpolation weights along each axis, and their derivatives
+            let w = corner_weights(u, o);
+            let dw = corner_derivatives(du, o);
+
+            let wp = w.x * w.y * w.z * w.w;
+            let d = g.truncate() * wp
+                + vec3(dw.x * w.y * w.z, w.x * dw.y * w.z, w.x * w.y * dw.z) * (v * w.w);
+            res += vec4(wp * v, d.x, d.y, d.z);
+        }
+
+        res * 1.35
+    }
+
+    /// Evaluate 4D gradient noise, in [-1, 1]
+    pub(crate) fn noise_4d(s: LatticeSample4D, lattice: &Lattice) -> f32 {
+        Self::noise_4d_derivatives(s, lattice).x
+    }
+}
+
+#[cfg(feature = "cpu")]
+impl GradientNoise {
+    fn compute_cpu_gradient(
+        &mut self,
+        ctx: &mut CpuContext,
+        tgt: &mut Image,
+        params: &GradientNoiseParams,
+    ) -> txkit_core::Result<()> {
+        if params.output_mode != GRADIENT_OUTPUT_VALUE
+            && params.output_mode != GRADIENT_OUTPUT_DERIVATIVES
+        {
+            // Invalid enum value
+            return compute_pixels(ctx, tgt, |_, _| [1., 0., 1., 1.]);
+        }
+
+        let lattice = Lattice::new(
+            params.scale,
+            params.global_seed,
+            params.stats_mode,
+            params.stats_look_at,
+            tgt.dim(),
+        );
+        let lattice_4d = lattice.with_period(params.period);
+        let derivatives = params.output_mode == GRADIENT_OUTPUT_DERIVATIVES;
+
+        let domain = Domain::new(ctx, tgt.dim())?;
+
+        compute_pixels(ctx, tgt, |idx, dim| {
+            let uv = domain.uv(idx, dim);
+
+            // Noise value, followed by its partial derivatives
+            let res = match params.dimensions {
+                LATTICE_DIMENSIONS_2D => {
+                    let s = lattice.sample(uv.truncate(), LatticeMode::Rect2D);
+                    if derivatives {
+                        Self::noise_derivatives(s, &lattice).extend(0.)
+                    } else {
+                        Vector4::from_value(Self::noise(s, &lattice))
+                    }
+                }
+                LATTICE_DIMENSIONS_3D => {
+                    let s = lattice.sample_3d(uv, LatticeMode3D::Rect3D);
+                    if derivatives {
+                        Self::noise_3d_derivatives(s, &lattice)
+                    } else {
+                        Vector4::from_value(Self::noise_3d(s, &lattice))
+                    }
+                }
+                LATTICE_DIMENSIONS_4D => {
+                    let s = lattice_4d.sample_4d(uv.extend(params.time), LatticeMode4D::Rect4D);
+                    if derivatives {
+                        Self::noise_4d_derivatives(s, &lattice_4d)
+                    } else {
+                        Vector4::from_value(Self::noise_4d(s, &lattice_4d))
+                    }
+                }
+                // Invalid enum value
+                _ => return [1., 0., 1., 1.],
+            };
+
+            if derivatives {
+                [to01(res.x), to01(res.y), to01(res.z), to01(res.w)]
+            } else {
+                let value = to01(res.x);
+                [value, value, value, 1.]
+            }
+        })
+    }
+}
+
+/// Derivatives of the interpolation weights of a lattice cell corner, as
+/// `mix(-du, du, vec4(o))`
+fn corner_derivatives(du: Vector4<f32>, o: [i32; 4]) -> Vector4<f32> {
+    let dw = |du: f32, o: i32| if o == 1 { du } else { -du };
+    vec4(
+        dw(du.x, o[0]),
+        dw(du.y, o[1]),
+        dw(du.z, o[2]),
+        dw(du.w, o[3]),
+    )
 }
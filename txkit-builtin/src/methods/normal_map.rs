@@ -0,0 +1,118 @@
+use txkit_core::io::ImageIo;
+use txkit_impl::{Method, ParamsFor};
+
+#[cfg(feature = "cpu")]
+use cgmath::{vec2, vec3, InnerSpace};
+#[cfg(feature = "cpu")]
+use txkit_core::{context::CpuContext, image::Image};
+
+#[cfg(feature = "cpu")]
+use crate::cpu::{compute_pixels, Domain};
+#[cfg(feature = "cpu")]
+use crate::heightfield::HeightField;
+
+/// Normal map kernel: central differences of the adjacent pixels
+pub const NORMAL_MAP_KERNEL_CENTRAL_DIFFERENCE: i32 = 0;
+/// Normal map kernel: 3x3 Sobel filter
+pub const NORMAL_MAP_KERNEL_SOBEL: i32 = 1;
+
+#[derive(Clone, PartialEq, ParamsFor)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[repr(C)]
+#[txkit(program = "NormalMapProgram")]
+pub struct NormalMapParams {
+    /// height of a unit of the height texture, in pixels
+    pub strength: f32,
+    /// gradient estimation kernel (0: central differences, 1: Sobel)
+    #[txkit(values(NORMAL_MAP_KERNEL_CENTRAL_DIFFERENCE, NORMAL_MAP_KERNEL_SOBEL))]
+    pub kernel: i32,
+
+    /// texture inputs, the height is bound to unit 0
+    #[texture_io(height)]
+    pub io: Box<ImageIo>,
+}
+
+impl Default for NormalMapParams {
+    fn default() -> Self {
+        Self {
+            strength: 8.,
+            kernel: NORMAL_MAP_KERNEL_CENTRAL_DIFFERENCE,
+            io: Box::default(),
+        }
+    }
+}
+
+/// Tangent-space normal map
+///
+/// Normal of the surface described by the red channel of the height texture bound to unit 0,
+/// scaled by `strength`. The height is sampled at the pixels of the output with repeat wrapping,
+/// so tileable heights give tileable normal maps. The x and y components of the normal follow the
+/// columns and rows of the image, and all components are remapped from [-1, 1] to [0, 1].
+#[derive(Default, Method)]
+#[txkit(
+    gpu(
+        name = "NormalMapGpu",
+        program("shaders/quad.vert", "shaders/normal_map.frag"),
+        method(run = "program", params = "NormalMapParams"),
+        dims(Image2D)
+    ),
+    cpu(
+        method(run = "Self::compute_cpu_normals", params = "NormalMapParams"),
+        dims(Image1D, Image2D)
+    ),
+    method()
+)]
+pub struct NormalMap {
+    /// parameters used when computing without explicit parameters
+    params: NormalMapParams,
+    #[cfg(feature = "gpu")]
+    gpu: Option<NormalMapGpu>,
+}
+
+impl NormalMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[cfg(feature = "cpu")]
+impl NormalMap {
+    fn compute_cpu_normals(
+        &mut self,
+        ctx: &mut CpuContext,
+        tgt: &mut Image,
+        params: &NormalMapParams,
+    ) -> txkit_core::Result<()> {
+        let kernel = params.kernel;
+        if kernel != NORMAL_MAP_KERNEL_CENTRAL_DIFFERENCE && kernel != NORMAL_MAP_KERNEL_SOBEL {
+            // Invalid enum value
+            return compute_pixels(ctx, tgt, |_, _| [1., 0., 1., 1.]);
+        }
+
+        let height = HeightField::new(&params.io, params.strength, tgt.dim())?;
+
+        let domain = Domain::new(ctx, tgt.dim())?;
+
+        compute_pixels(ctx, tgt, |idx, dim| {
+            let p = domain.uv(idx, dim).truncate();
+            let h = |x, y| height.at(p, vec2(x, y));
+            let (l, r, t, b) = (h(-1., 0.), h(1., 0.), h(0., -1.), h(0., 1.));
+
+            // Height gradient, in pixels per pixel
+            let g = if kernel == NORMAL_MAP_KERNEL_CENTRAL_DIFFERENCE {
+                vec2(r - l, b - t) * 0.5
+            } else {
+                let (tl, tr, bl, br) = (h(-1., -1.), h(1., -1.), h(-1., 1.), h(1., 1.));
+
+                vec2(
+                    tr + 2. * r + br - tl - 2. * l - bl,
+                    bl + 2. * b + br - tl - 2. * t - tr,
+                ) / 8.
+            };
+
+            let n = vec3(-g.x, -g.y, 1.).normalize();
+            [0.5 * n.x + 0.5, 0.5 * n.y + 0.5, 0.5 * n.z + 0.5, 1.]
+        })
+    }
+}
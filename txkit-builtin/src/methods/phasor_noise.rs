@@ -1,6 +1,27 @@
 use txkit_core::io::ImageIo;
+use txkit_core::lattice::{STATS_MODE_LOOKAT, STATS_MODE_NORMAL, STATS_MODE_PROCESS};
 use txkit_impl::{Method, ParamsFor};
 
+#[cfg(feature = "cpu")]
+use cgmath::{vec2, vec3, Array, InnerSpace, Vector2, Vector3};
+#[cfg(feature = "cpu")]
+use txkit_core::{
+    context::CpuContext,
+    hash::hash_cell,
+    image::Image,
+    io::cpu::CpuTexture,
+    lattice::{Lattice, LatticeMode, LatticeSample},
+};
+
+#[cfg(feature = "cpu")]
+use crate::cpu::compute_pixels;
+#[cfg(feature = "cpu")]
+use crate::lcg::Lcg;
+#[cfg(feature = "cpu")]
+use crate::points::PointSet;
+#[cfg(feature = "cpu")]
+use crate::shared::{pixel_uv, to01};
+
 /// Phasor: complex sum divided by the kernel count in R and G
 pub const PHASOR_PROFILE_COMPLEX: i32 = 0;
 /// Phasor: real part of the complex sum divided by the kernel count in R
@@ -31,6 +52,8 @@ pub const PHASOR_POINTS_RECT_JITTERED: i32 = 2;
 pub const PHASOR_POINTS_HEX_JITTERED: i32 = 3;
 
 #[derive(Clone, PartialEq, ParamsFor)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[repr(C)]
 #[txkit(program = "PhasorNoiseProgram")]
 pub struct PhasorNoiseParams {
@@ -39,6 +62,7 @@ pub struct PhasorNoiseParams {
     /// lattice scale (size in pixels)
     pub scale: f32,
     /// stats mode (0: normal, 1: process, 2: lookat)
+    #[txkit(values(STATS_MODE_NORMAL, STATS_MODE_PROCESS, STATS_MODE_LOOKAT))]
     pub stats_mode: i32,
     /// look-at parameter (if stats_mode == lookat) in [0, 1]^2
     pub stats_look_at: cgmath::Vector2<f32>,
@@ -48,16 +72,35 @@ pub struct PhasorNoiseParams {
     /// kernel count per cell
     pub kernel_count: i32,
     /// noise profile function
+    #[txkit(values(
+        PHASOR_PROFILE_COMPLEX,
+        PHASOR_PROFILE_REAL,
+        PHASOR_PROFILE_IMAG,
+        PHASOR_PROFILE_SIN,
+        PHASOR_PROFILE_SAW,
+        PHASOR_PROFILE_IMPULSES
+    ))]
     pub noise_profile: i32,
     /// noise weights
+    #[txkit(values(PHASOR_WEIGHTS_NONE, PHASOR_WEIGHTS_BERNOULLI, PHASOR_WEIGHTS_UNIFORM))]
     pub noise_weights: i32,
     /// point distribution
+    #[txkit(values(
+        PHASOR_POINTS_STRAT_POSSION,
+        PHASOR_POINTS_POISSON,
+        PHASOR_POINTS_RECT_JITTERED,
+        PHASOR_POINTS_HEX_JITTERED
+    ))]
     pub noise_point_distribution: i32,
 
     /// noise frequency (in oscillations / noise cell)
     pub noise_frequency: f32,
     /// noise angle (in radians)
     pub noise_angle: f32,
+    /// animation time, in the same unit as `period`
+    pub time: f32,
+    /// animation period, 0 for no animation
+    pub period: f32,
 
     /// jittering amount, 0 = no random, 1 = full subcell random
     pub jitter_amount: f32,
@@ -83,6 +126,8 @@ impl Default for PhasorNoiseParams {
             noise_point_distribution: PHASOR_POINTS_STRAT_POSSION,
             noise_frequency: 4.,
             noise_angle: 0.,
+            time: 0.,
+            period: 0.,
             jitter_amount: 1.,
             jitter_max: 0,
             io: Box::new(Default::default()),
@@ -90,16 +135,32 @@ impl Default for PhasorNoiseParams {
     }
 }
 
+/// Phasor noise
+///
+/// The CPU implementation is a port of `phasor_noise.frag`. The frequency/orientation field is
+/// sampled from the image bound to the `frequency_orientation_field` texture, with bilinear
+/// filtering and repeat wrapping as on the GPU.
+///
+/// With a non-zero `period`, the phases of all kernels are shifted by `time / period` turns, so
+/// the oscillations travel along their directions and the images computed at `time` and
+/// `time + period` are the same.
 #[derive(Default, Method)]
 #[txkit(
     gpu(
         name = "PhasorNoiseGpu",
         program("shaders/quad.vert", "shaders/phasor_noise.frag"),
-        method(run = "program", params = "PhasorNoiseParams")
+        method(run = "program", params = "PhasorNoiseParams"),
+        dims(Image2D)
+    ),
+    cpu(
+        method(run = "Self::compute_cpu_phasor", params = "PhasorNoiseParams"),
+        dims(Image1D, Image2D)
     ),
     method()
 )]
 pub struct PhasorNoise {
+    /// parameters used when computing without explicit parameters
+    params: PhasorNoiseParams,
     #[cfg(feature = "gpu")]
     gpu: Option<PhasorNoiseGpu>,
 }
@@ -109,3 +170,212 @@ impl PhasorNoise {
         Self::default()
     }
 }
+
+#[cfg(feature = "cpu")]
+impl PhasorNoise {
+    fn compute_cpu_phasor(
+        &mut self,
+        ctx: &mut CpuContext,
+        tgt: &mut Image,
+        params: &PhasorNoiseParams,
+    ) -> txkit_core::Result<()> {
+        use txkit_core::io::cpu::CpuImageIoExt;
+
+        let sampler = PhasorSampler {
+            lattice: Lattice::new(
+                params.scale,
+                params.global_seed,
+                params.stats_mode,
+                params.stats_look_at,
+                tgt.dim(),
+            ),
+            scale: params.scale,
+            noise_lookahead: params.noise_lookahead,
+            kernel_count: params.kernel_count,
+            noise_profile: params.noise_profile,
+            noise_weights: params.noise_weights,
+            noise_point_distribution: params.noise_point_distribution,
+            noise_frequency: params.noise_frequency,
+            noise_angle: params.noise_angle,
+            phase: phasor_phase(params.time, params.period),
+            jitter_amount: params.jitter_amount,
+            jitter_max: params.jitter_max,
+            width: tgt.dim().width as f32,
+            frequency_orientation_field: params
+                .io
+                .texture_binding_data(FREQUENCY_ORIENTATION_FIELD_UNIT)?,
+        };
+
+        compute_pixels(ctx, tgt, |idx, dim| {
+            let uv = pixel_uv(idx, dim);
+            let s = sampler.lattice.sample(uv.truncate(), LatticeMode::Rect2D);
+            sampler.noise(s).extend(1.).into()
+        })
+    }
+}
+
+/// Phase shift of the kernels at the given animation time, see `phasor_noise.frag`
+#[cfg(feature = "cpu")]
+fn phasor_phase(time: f32, period: f32) -> f32 {
+    if period > 0. {
+        let t = time / period;
+        2. * std::f32::consts::PI * (t - t.floor())
+    } else {
+        0.
+    }
+}
+
+/// Texture unit of the frequency/orientation field, see `phasor_noise.frag`
+#[cfg(feature = "cpu")]
+const FREQUENCY_ORIENTATION_FIELD_UNIT: usize = 0;
+
+/// Phasor kernel
+#[cfg(feature = "cpu")]
+struct Kernel {
+    pos: Vector2<f32>,
+    weight: f32,
+    angle: Vector2<f32>,
+    phase: f32,
+}
+
+/// Thread-safe copy of [PhasorNoiseParams] for evaluating phasor noise on the CPU
+#[cfg(feature = "cpu")]
+struct PhasorSampler {
+    lattice: Lattice,
+    scale: f32,
+    noise_lookahead: i32,
+    kernel_count: i32,
+    noise_profile: i32,
+    noise_weights: i32,
+    noise_point_distribution: i32,
+    noise_frequency: f32,
+    noise_angle: f32,
+    /// phase shift of the kernels, in radians
+    phase: f32,
+    jitter_amount: f32,
+    jitter_max: i32,
+    /// width of the target image, as `iResolution.x`
+    width: f32,
+    frequency_orientation_field: CpuTexture,
+}
+
+#[cfg(feature = "cpu")]
+impl PhasorSampler {
+    fn phasor(&self, x: Vector2<f32>, k: &Kernel) -> Vector2<f32> {
+        use std::f32::consts::PI;
+
+        if self.noise_profile == PHASOR_PROFILE_IMPULSES {
+            let a = 0.125 * self.width / self.scale;
+            return vec2(1., k.weight) * (-PI * a * a * x.dot(x)).exp();
+        }
+
+        let b = (1. / self.scale) * (1. / self.scale) * PI;
+
+        let gaus = (-b * x.dot(x)).exp();
+        let osc = 2. * PI * x.dot(k.angle) + k.phase;
+
+        vec2(osc.cos(), osc.sin()) * (k.weight * gaus)
+    }
+
+    fn noise_cell(&self, pos: Vector2<f32>, cell: [i32; 2], seed: u32) -> Vector2<f32> {
+        let mut res = vec2(0., 0.);
+
+        // Seed the random number generator
+        let mut rng = Lcg::new(hash_cell([cell[0] as u32, cell[1] as u32], seed));
+
+        // Compute impulse count
+        let ps = PointSet::new(
+            &mut rng,
+            self.noise_point_distribution,
+            self.kernel_count,
+            self.jitter_max,
+            self.jitter_amount,
+        );
+
+        // Sample kernel parameters
+        let fo = self
+            .frequency_orientation_field
+            .sample_2d(((pos + vec2(cell[0] as f32, cell[1] as f32)) / self.scale).into());
+
+        // Fixed number of impulses per cell
+        for i in 0..ps.count() {
+            // Generate a kernel
+            let pos_k = ps.next(&mut rng, i);
+
+            // Compute weighting: always step the generator so we can get the same
+            // image with and without weights
+            let v = rng.next_11();
+            let weight = match self.noise_weights {
+                PHASOR_WEIGHTS_BERNOULLI if v < 0. => -1.,
+                PHASOR_WEIGHTS_UNIFORM => v,
+                _ => 1.,
+            };
+
+            let angle = self.noise_angle + fo[1];
+            let k = Kernel {
+                pos: pos_k,
+                weight,
+                angle: vec2(angle.cos(), angle.sin())
+                    * (self.noise_frequency / self.scale * (1. + fo[0])),
+                phase: self.phase,
+            };
+
+            // Compute contribution
+            res += self.phasor((pos - k.pos) * self.scale, &k);
+        }
+
+        res
+    }
+
+    fn noise(&self, s: LatticeSample) -> Vector3<f32> {
+        use std::f32::consts::PI;
+
+        // Compute complex phasor value
+        let mut res = vec2(0., 0.);
+
+        let [sx, sy] = s.cell;
+        for cx in sx - self.noise_lookahead..=sx + self.noise_lookahead {
+            for cy in sy - self.noise_lookahead..=sy + self.noise_lookahead {
+                // Make sure the noise tiles correctly
+                let looped_cell = self.lattice.lattice_loop_signed([cx, cy]);
+
+                // Compute the position of the current point relative to the target cell
+                let position = s.position - vec2((cx - sx) as f32, (cy - sy) as f32);
+
+                // Add contribution of target noise cell
+                res += self.noise_cell(position, looped_cell, s.seed);
+            }
+        }
+
+        // Apply profile
+        let kc = self.kernel_count as f32;
+        match self.noise_profile {
+            PHASOR_PROFILE_COMPLEX => vec3(to01(res.x / kc), to01(res.y / kc), 0.),
+            PHASOR_PROFILE_REAL => Vector3::from_value(to01(res.x / kc)),
+            PHASOR_PROFILE_IMAG => Vector3::from_value(to01(res.y / kc)),
+            PHASOR_PROFILE_IMPULSES => vec3(
+                res.x,
+                res.y,
+                if s.position.x.min(s.position.y) < self.scale / self.width {
+                    1.
+                } else {
+                    0.
+                },
+            ),
+            _ => {
+                let ph = res.x.atan2(res.y);
+
+                match self.noise_profile {
+                    PHASOR_PROFILE_SIN => Vector3::from_value(to01(ph.sin())),
+                    PHASOR_PROFILE_SAW => {
+                        // GLSL mod, which rounds towards negative infinity
+                        let x = ph + PI;
+                        Vector3::from_value((x - 2. * PI * (x / (2. * PI)).floor()) / (2. * PI))
+                    }
+                    // Invalid enum value
+                    _ => vec3(1., 0., 1.),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,175 @@
+use txkit_impl::{Method, ParamsFor};
+
+#[cfg(feature = "cpu")]
+use cgmath::{vec2, vec3, InnerSpace, Vector2};
+#[cfg(feature = "cpu")]
+use txkit_core::{context::CpuContext, image::Image};
+
+#[cfg(feature = "cpu")]
+use crate::cpu::{compute_pixels, Domain};
+#[cfg(feature = "cpu")]
+use crate::patterns::{filtered_edge, tile_id};
+
+/// Polygon pattern: pointy-top regular hexagons, with axial coordinates
+pub const POLYGON_PATTERN_HEXAGONS: i32 = 0;
+/// Polygon pattern: equilateral triangles, two per cell of the skewed grid
+pub const POLYGON_PATTERN_TRIANGLES: i32 = 1;
+
+#[derive(Clone, Copy, PartialEq, ParamsFor)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[repr(C)]
+#[txkit(program = "PolygonPatternProgram")]
+pub struct PolygonPatternParams {
+    /// pseudo-random seed of the tile IDs
+    pub global_seed: u32,
+    /// number of tiles across the image width
+    pub scale: f32,
+    /// tile shape (0: hexagons, 1: triangles)
+    #[txkit(values(POLYGON_PATTERN_HEXAGONS, POLYGON_PATTERN_TRIANGLES))]
+    pub shape: i32,
+    /// width of the mortar joints, in tile widths
+    pub mortar_width: f32,
+    /// width of the anti-aliasing filter, in pixels (0: no anti-aliasing)
+    pub antialiasing: f32,
+}
+
+impl Default for PolygonPatternParams {
+    fn default() -> Self {
+        Self {
+            global_seed: 0,
+            scale: 8.,
+            shape: POLYGON_PATTERN_HEXAGONS,
+            mortar_width: 0.05,
+            antialiasing: 1.,
+        }
+    }
+}
+
+/// Hexagonal and triangular tiling patterns
+///
+/// Tiling of regular hexagons or equilateral triangles separated by mortar joints, with `scale`
+/// tiles across the image width. Tiles are regular whatever the aspect ratio of the image, so
+/// the pattern does not tile seamlessly. The output is the coverage of the tile (0 on the
+/// mortar), the random ID of the tile in [0, 1], and the integer coordinates of the tile. The
+/// pattern is the same for all layers of 3D images.
+///
+/// Hexagons use axial coordinates. Triangles use the coordinates of their cell on the skewed
+/// grid, where the column is doubled and incremented for upward-pointing triangles.
+#[derive(Default, Method)]
+#[txkit(
+    gpu(
+        name = "PolygonPatternGpu",
+        program("shaders/quad.vert", "shaders/polygon_pattern.frag"),
+        method(run = "program", params = "PolygonPatternParams"),
+        dims(Image2D)
+    ),
+    cpu(
+        method(run = "Self::compute_cpu_polygons", params = "PolygonPatternParams"),
+        dims(Image1D, Image2D)
+    ),
+    method()
+)]
+pub struct PolygonPattern {
+    /// parameters used when computing without explicit parameters
+    params: PolygonPatternParams,
+    #[cfg(feature = "gpu")]
+    gpu: Option<PolygonPatternGpu>,
+}
+
+impl PolygonPattern {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[cfg(feature = "cpu")]
+impl PolygonPattern {
+    fn compute_cpu_polygons(
+        &mut self,
+        ctx: &mut CpuContext,
+        tgt: &mut Image,
+        params: &PolygonPatternParams,
+    ) -> txkit_core::Result<()> {
+        let shape: fn(Vector2<f32>) -> (f32, [i32; 2]) = match params.shape {
+            POLYGON_PATTERN_HEXAGONS => Self::hexagon,
+            POLYGON_PATTERN_TRIANGLES => Self::triangle,
+            // Invalid enum value
+            _ => return compute_pixels(ctx, tgt, |_, _| [1., 0., 1., 1.]),
+        };
+
+        let dim = tgt.dim();
+        let aspect = dim.height as f32 / dim.width as f32;
+        let w = params.antialiasing * params.scale / dim.width as f32;
+
+        let domain = Domain::new(ctx, tgt.dim())?;
+
+        compute_pixels(ctx, tgt, |idx, dim| {
+            let uv = domain.uv(idx, dim);
+            let (d, cell) = shape(vec2(uv.x, uv.y * aspect) * params.scale);
+
+            [
+                filtered_edge(d - 0.5 * params.mortar_width, w),
+                tile_id(cell, params.global_seed),
+                cell[0] as f32,
+                cell[1] as f32,
+            ]
+        })
+    }
+
+    /// Find the hexagon containing `p`, in hexagon widths
+    ///
+    /// Returns the distance to the closest edge of the hexagon, and its axial coordinates.
+    fn hexagon(p: Vector2<f32>) -> (f32, [i32; 2]) {
+        let sqrt3 = 3f32.sqrt();
+
+        // Cube coordinates of the point, rounded to the closest hexagon center
+        let x = p.x - p.y / sqrt3;
+        let z = 2. * p.y / sqrt3;
+        let c = vec3(x, -x - z, z);
+
+        let mut r = vec3(c.x.round(), c.y.round(), c.z.round());
+        let d = vec3((r.x - c.x).abs(), (r.y - c.y).abs(), (r.z - c.z).abs());
+
+        if d.x > d.y && d.x > d.z {
+            r.x = -r.y - r.z;
+        } else if d.y <= d.z {
+            r.z = -r.x - r.y;
+        }
+
+        // Position relative to the hexagon center
+        let l = p - vec2(r.x + 0.5 * r.z, 0.5 * sqrt3 * r.z);
+        let n = vec2(0.5, 0.5 * sqrt3);
+        let d = 0.5
+            - l.x
+                .abs()
+                .max(l.dot(n).abs())
+                .max(l.dot(vec2(-n.x, n.y)).abs());
+
+        (d, [r.x as i32, r.z as i32])
+    }
+
+    /// Find the triangle containing `p`, in triangle sides
+    ///
+    /// Returns the distance to the closest edge of the triangle, and its coordinates.
+    fn triangle(p: Vector2<f32>) -> (f32, [i32; 2]) {
+        let sqrt3 = 3f32.sqrt();
+
+        let s = vec2(p.x - p.y / sqrt3, 2. * p.y / sqrt3);
+        let base = [s.x.floor() as i32, s.y.floor() as i32];
+        let f = vec2(s.x - s.x.floor(), s.y - s.y.floor());
+
+        // Barycentric coordinates of the point in its triangle
+        let (cell, b) = if f.x + f.y > 1. {
+            (
+                [2 * base[0] + 1, base[1]],
+                [f.x + f.y - 1., 1. - f.x, 1. - f.y],
+            )
+        } else {
+            ([2 * base[0], base[1]], [1. - f.x - f.y, f.x, f.y])
+        };
+
+        // Scale by the triangle height
+        (b[0].min(b[1]).min(b[2]) * 0.5 * sqrt3, cell)
+    }
+}
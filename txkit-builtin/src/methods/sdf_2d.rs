@@ -0,0 +1,494 @@
+#[cfg(feature = "gpu")]
+use std::cell::RefCell;
+use std::ops::ControlFlow;
+#[cfg(feature = "gpu")]
+use std::rc::Rc;
+
+use cgmath::{vec2, Vector2};
+
+use txkit_core::context::Context;
+use txkit_core::image::Image;
+#[cfg(feature = "gpu")]
+use txkit_core::io::ImageBinding;
+use txkit_core::io::ImageIo;
+use txkit_core::{Error, Result};
+use txkit_impl::{Method, ParamsFor};
+
+#[cfg(feature = "cpu")]
+use cgmath::InnerSpace;
+#[cfg(feature = "cpu")]
+use txkit_core::context::CpuContext;
+#[cfg(feature = "gpu")]
+use txkit_core::image::{ImageDataType, ImageDim};
+
+#[cfg(feature = "cpu")]
+use crate::cpu::{compute_pixels, Domain};
+#[cfg(feature = "cpu")]
+use crate::patterns::filtered_edge;
+#[cfg(feature = "cpu")]
+use crate::shared::mix;
+
+/// Signed distance field: distance to the shapes, negative inside
+pub const SDF_2D_OUTPUT_DISTANCE: i32 = 0;
+/// Signed distance field: anti-aliased coverage of the shapes
+pub const SDF_2D_OUTPUT_COVERAGE: i32 = 1;
+
+/// Signed distance field shape: circle, data is `[center x, center y, radius]`
+pub const SDF_2D_SHAPE_CIRCLE: i32 = 0;
+/// Signed distance field shape: box, data is `[center x, center y, half width, half height]`
+pub const SDF_2D_SHAPE_BOX: i32 = 1;
+/// Signed distance field shape: rounded box, data is `[center x, center y, half width, half
+/// height, corner radius]`
+pub const SDF_2D_SHAPE_ROUNDED_BOX: i32 = 2;
+/// Signed distance field shape: line segment, data is `[x1, y1, x2, y2, radius]`
+pub const SDF_2D_SHAPE_SEGMENT: i32 = 3;
+/// Signed distance field shape: polygon, data is `[x1, y1, x2, y2, ...]`
+pub const SDF_2D_SHAPE_POLYGON: i32 = 4;
+
+/// Signed distance field operator: union with the previous shapes
+pub const SDF_2D_OP_UNION: i32 = 0;
+/// Signed distance field operator: intersection with the previous shapes
+pub const SDF_2D_OP_INTERSECTION: i32 = 1;
+/// Signed distance field operator: subtraction from the previous shapes
+pub const SDF_2D_OP_SUBTRACTION: i32 = 2;
+
+/// Texture unit of the shape data, see `sdf_2d.frag`
+#[cfg(feature = "gpu")]
+const SHAPE_DATA_UNIT: usize = 0;
+
+/// Distance to the empty set, see `sdf_2d.frag`
+#[cfg(feature = "cpu")]
+const SDF_2D_EMPTY: f32 = 1e9;
+
+/// Primitive of a 2D signed distance field
+///
+/// Positions and distances are measured in image widths, from the top-left corner of the image.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "shape", rename_all = "snake_case"))]
+pub enum Sdf2dPrimitive {
+    Circle {
+        center: Vector2<f32>,
+        radius: f32,
+    },
+    Box {
+        center: Vector2<f32>,
+        half_size: Vector2<f32>,
+    },
+    RoundedBox {
+        center: Vector2<f32>,
+        half_size: Vector2<f32>,
+        radius: f32,
+    },
+    Segment {
+        a: Vector2<f32>,
+        b: Vector2<f32>,
+        radius: f32,
+    },
+    Polygon {
+        vertices: Vec<Vector2<f32>>,
+    },
+}
+
+impl Sdf2dPrimitive {
+    /// Build a primitive from its kind and raw data
+    ///
+    /// # Parameters
+    ///
+    /// * `kind`: kind of primitive, see `SDF_2D_SHAPE_*`
+    /// * `data`: primitive data, see `SDF_2D_SHAPE_*` for the layout
+    pub fn from_data(kind: i32, data: &[f32]) -> Result<Self> {
+        let v = |i: usize| vec2(data[i], data[i + 1]);
+
+        Ok(match (kind, data.len()) {
+            (SDF_2D_SHAPE_CIRCLE, 3) => Self::Circle {
+                center: v(0),
+                radius: data[2],
+            },
+            (SDF_2D_SHAPE_BOX, 4) => Self::Box {
+                center: v(0),
+                half_size: v(2),
+            },
+            (SDF_2D_SHAPE_ROUNDED_BOX, 5) => Self::RoundedBox {
+                center: v(0),
+                half_size: v(2),
+                radius: data[4],
+            },
+            (SDF_2D_SHAPE_SEGMENT, 5) => Self::Segment {
+                a: v(0),
+                b: v(2),
+                radius: data[4],
+            },
+            (SDF_2D_SHAPE_POLYGON, n) if n % 2 == 0 => Self::Polygon {
+                vertices: (0..n).step_by(2).map(v).collect(),
+            },
+            _ => return Err(Error::InvalidParameters),
+        })
+    }
+
+    /// Kind of this primitive, see `SDF_2D_SHAPE_*`
+    pub fn kind(&self) -> i32 {
+        match self {
+            Self::Circle { .. } => SDF_2D_SHAPE_CIRCLE,
+            Self::Box { .. } => SDF_2D_SHAPE_BOX,
+            Self::RoundedBox { .. } => SDF_2D_SHAPE_ROUNDED_BOX,
+            Self::Segment { .. } => SDF_2D_SHAPE_SEGMENT,
+            Self::Polygon { .. } => SDF_2D_SHAPE_POLYGON,
+        }
+    }
+
+    /// Signed distance from `p` to this primitive
+    #[cfg(feature = "cpu")]
+    fn distance(&self, p: Vector2<f32>) -> f32 {
+        match self {
+            Self::Circle { center, radius } => (p - center).magnitude() - radius,
+            Self::Box { center, half_size } => sd_box(p - center, *half_size),
+            Self::RoundedBox {
+                center,
+                half_size,
+                radius,
+            } => {
+                let h = vec2(
+                    (half_size.x - radius).max(0.),
+                    (half_size.y - radius).max(0.),
+                );
+                sd_box(p - center, h) - radius
+            }
+            Self::Segment { a, b, radius } => sd_segment(p, *a, *b) - radius,
+            Self::Polygon { vertices } => sd_polygon(p, vertices),
+        }
+    }
+}
+
+/// Shape of a 2D signed distance field: a primitive, and how it combines with the previous shapes
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Sdf2dShape {
+    /// primitive of this shape
+    pub primitive: Sdf2dPrimitive,
+    /// operator combining this shape with the previous shapes, see `SDF_2D_OP_*`
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub op: i32,
+    /// smoothing radius of the operator, 0 for a sharp boolean operator
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub smoothness: f32,
+}
+
+/// List of shapes of a 2D signed distance field
+///
+/// Shapes are combined in order, starting from the empty set.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct Sdf2dShapes {
+    shapes: Vec<Sdf2dShape>,
+}
+
+impl Sdf2dShapes {
+    /// Create a new empty shape list
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a shape at the end of the list
+    ///
+    /// # Parameters
+    ///
+    /// * `primitive`: primitive of the shape
+    /// * `op`: operator combining the shape with the previous shapes, see `SDF_2D_OP_*`
+    /// * `smoothness`: smoothing radius of the operator, 0 for a sharp boolean operator
+    pub fn push(&mut self, primitive: Sdf2dPrimitive, op: i32, smoothness: f32) {
+        self.shapes.push(Sdf2dShape {
+            primitive,
+            op,
+            smoothness,
+        });
+    }
+
+    /// Remove all shapes from the list
+    pub fn clear(&mut self) {
+        self.shapes.clear();
+    }
+
+    /// Shapes in this list
+    pub fn shapes(&self) -> &[Sdf2dShape] {
+        &self.shapes[..]
+    }
+
+    /// Signed distance from `p` to the combined shapes
+    #[cfg(feature = "cpu")]
+    fn distance(&self, p: Vector2<f32>) -> Option<f32> {
+        let mut d = SDF_2D_EMPTY;
+
+        for shape in &self.shapes {
+            let s = shape.primitive.distance(p);
+            let k = shape.smoothness;
+
+            d = match shape.op {
+                SDF_2D_OP_UNION => smin(d, s, k),
+                SDF_2D_OP_INTERSECTION => -smin(-d, -s, k),
+                SDF_2D_OP_SUBTRACTION => -smin(-d, s, k),
+                _ => return None,
+            };
+        }
+
+        Some(d)
+    }
+
+    /// Pack the shapes into a texture for `sdf_2d.frag`
+    ///
+    /// Every shape starts with a `[kind, op, smoothness, vertex count]` texel followed by its
+    /// data, two vertices per texel for polygons. The list ends with a texel of kind -1.
+    #[cfg(feature = "gpu")]
+    fn to_image(&self) -> Result<Image> {
+        let mut texels: Vec<[f32; 4]> = Vec::new();
+
+        for shape in &self.shapes {
+            let n = match &shape.primitive {
+                Sdf2dPrimitive::Polygon { vertices } => vertices.len(),
+                _ => 0,
+            };
+
+            texels.push([
+                shape.primitive.kind() as f32,
+                shape.op as f32,
+                shape.smoothness,
+                n as f32,
+            ]);
+
+            match &shape.primitive {
+                Sdf2dPrimitive::Circle { center, radius } => {
+                    texels.push([center.x, center.y, *radius, 0.]);
+                }
+                Sdf2dPrimitive::Box { center, half_size } => {
+                    texels.push([center.x, center.y, half_size.x, half_size.y]);
+                }
+                Sdf2dPrimitive::RoundedBox {
+                    center,
+                    half_size,
+                    radius,
+                } => {
+                    texels.push([center.x, center.y, half_size.x, half_size.y]);
+                    texels.push([*radius, 0., 0., 0.]);
+                }
+                Sdf2dPrimitive::Segment { a, b, radius } => {
+                    texels.push([a.x, a.y, b.x, b.y]);
+                    texels.push([*radius, 0., 0., 0.]);
+                }
+                Sdf2dPrimitive::Polygon { vertices } => {
+                    for pair in vertices.chunks(2) {
+                        let b = pair.get(1).unwrap_or(&pair[0]);
+                        texels.push([pair[0].x, pair[0].y, b.x, b.y]);
+                    }
+                }
+            }
+        }
+
+        texels.push([-1., 0., 0., 0.]);
+
+        let mut image = Image::new_cpu(ImageDim::new(texels.len(), 1, 4), ImageDataType::Float32);
+
+        {
+            let mut data = image.data_mut()?;
+            let mut data = data.as_f32_nd_array_mut().unwrap();
+
+            for (i, texel) in texels.iter().enumerate() {
+                for (c, v) in texel.iter().enumerate() {
+                    data[(0, 0, i, c)] = *v;
+                }
+            }
+        }
+
+        Ok(image)
+    }
+}
+
+#[derive(Clone, PartialEq, ParamsFor)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[repr(C)]
+#[txkit(program = "Sdf2dProgram")]
+pub struct Sdf2dParams {
+    /// output values (0: signed distance, 1: coverage)
+    #[txkit(values(SDF_2D_OUTPUT_DISTANCE, SDF_2D_OUTPUT_COVERAGE))]
+    pub output_mode: i32,
+    /// width of the anti-aliasing filter for the coverage, in pixels (0: no anti-aliasing)
+    pub antialiasing: f32,
+
+    /// shapes of the distance field
+    #[txkit(opaque)]
+    pub shapes: Box<Sdf2dShapes>,
+
+    /// texture inputs, the shape data is bound to unit 0 before computing
+    #[texture_io(shape_data)]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub io: Box<ImageIo>,
+}
+
+impl Default for Sdf2dParams {
+    fn default() -> Self {
+        Self {
+            output_mode: SDF_2D_OUTPUT_COVERAGE,
+            antialiasing: 1.,
+            shapes: Box::default(),
+            io: Box::default(),
+        }
+    }
+}
+
+/// 2D signed distance field
+///
+/// Signed distance field of a list of circles, boxes, rounded boxes, line segments and polygons
+/// combined in order with union, intersection and subtraction operators. A non-zero smoothness
+/// replaces the minimum and maximum in the operators with a polynomial smooth minimum.
+///
+/// Positions and distances are measured in image widths, from the top-left corner of the image.
+/// The output is either the signed distance, negative inside the shapes, or the anti-aliased
+/// coverage of the shapes. Pixels outside all the shapes have a distance of 1e9 when the list is
+/// empty. The shapes are the same for all layers of 3D images.
+#[derive(Default, Method)]
+#[txkit(
+    gpu(
+        name = "Sdf2dGpu",
+        program("shaders/quad.vert", "shaders/sdf_2d.frag"),
+        method(run = "program", params = "Sdf2dParams"),
+        dims(Image2D)
+    ),
+    cpu(
+        method(run = "Self::compute_cpu_sdf", params = "Sdf2dParams"),
+        dims(Image1D, Image2D)
+    ),
+    method(prepare = "Self::bind_shape_data")
+)]
+pub struct Sdf2d {
+    /// parameters used when computing without explicit parameters
+    params: Sdf2dParams,
+    #[cfg(feature = "gpu")]
+    gpu: Option<Sdf2dGpu>,
+}
+
+impl Sdf2d {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Bind the shape data for the shader
+    #[cfg_attr(not(feature = "gpu"), allow(unused_variables))]
+    fn bind_shape_data(
+        &mut self,
+        ctx: &mut Context,
+        _tgt: &mut Image,
+        params: &mut Sdf2dParams,
+    ) -> Result<ControlFlow<()>> {
+        #[cfg(feature = "gpu")]
+        if ctx.gpu().is_some() {
+            params.io.set_texture_binding(
+                SHAPE_DATA_UNIT,
+                ImageBinding::ImageRef(Rc::new(RefCell::new(
+                    params.shapes.to_image()?.into_2d_for(ctx)?,
+                ))),
+            );
+        }
+
+        Ok(ControlFlow::Continue(()))
+    }
+}
+
+#[cfg(feature = "cpu")]
+impl Sdf2d {
+    fn compute_cpu_sdf(
+        &mut self,
+        ctx: &mut CpuContext,
+        tgt: &mut Image,
+        params: &Sdf2dParams,
+    ) -> txkit_core::Result<()> {
+        let shapes = &*params.shapes;
+        let output_mode = params.output_mode;
+
+        if (output_mode != SDF_2D_OUTPUT_DISTANCE && output_mode != SDF_2D_OUTPUT_COVERAGE)
+            || shapes.distance(vec2(0., 0.)).is_none()
+        {
+            // Invalid enum value
+            return compute_pixels(ctx, tgt, |_, _| [1., 0., 1., 1.]);
+        }
+
+        let dim = tgt.dim();
+        let aspect = dim.height as f32 / dim.width as f32;
+        let w = params.antialiasing / dim.width as f32;
+
+        let domain = Domain::new(ctx, tgt.dim())?;
+
+        compute_pixels(ctx, tgt, |idx, dim| {
+            let uv = domain.uv(idx, dim);
+            let d = shapes.distance(vec2(uv.x, uv.y * aspect)).unwrap();
+
+            let v = if output_mode == SDF_2D_OUTPUT_DISTANCE {
+                d
+            } else {
+                filtered_edge(-d, w)
+            };
+
+            [v, v, v, 1.]
+        })
+    }
+}
+
+/// Distance from `p` to a box centered on the origin
+#[cfg(feature = "cpu")]
+fn sd_box(p: Vector2<f32>, h: Vector2<f32>) -> f32 {
+    let q = vec2(p.x.abs() - h.x, p.y.abs() - h.y);
+    vec2(q.x.max(0.), q.y.max(0.)).magnitude() + q.x.max(q.y).min(0.)
+}
+
+/// Distance from `p` to the line segment `[a, b]`
+#[cfg(feature = "cpu")]
+fn sd_segment(p: Vector2<f32>, a: Vector2<f32>, b: Vector2<f32>) -> f32 {
+    let pa = p - a;
+    let ba = b - a;
+    let l = ba.dot(ba);
+    let h = if l > 0. {
+        (pa.dot(ba) / l).clamp(0., 1.)
+    } else {
+        0.
+    };
+
+    (pa - ba * h).magnitude()
+}
+
+/// Signed distance from `p` to a polygon, negative inside
+///
+/// See <https://iquilezles.org/articles/distfunctions2d/>
+#[cfg(feature = "cpu")]
+fn sd_polygon(p: Vector2<f32>, vertices: &[Vector2<f32>]) -> f32 {
+    let n = vertices.len();
+    let mut d = SDF_2D_EMPTY;
+    let mut s = 1.;
+
+    for i in 0..n {
+        let vi = vertices[i];
+        let vj = vertices[(i + n - 1) % n];
+
+        d = d.min(sd_segment(p, vi, vj));
+
+        // Winding number test
+        let e = vj - vi;
+        let w = p - vi;
+        let c = [p.y >= vi.y, p.y < vj.y, e.x * w.y > e.y * w.x];
+        if c.iter().all(|&c| c) || c.iter().all(|&c| !c) {
+            s = -s;
+        }
+    }
+
+    s * d
+}
+
+/// Polynomial smooth minimum with smoothing radius `k`, the regular minimum if `k` is 0
+#[cfg(feature = "cpu")]
+fn smin(a: f32, b: f32, k: f32) -> f32 {
+    if k <= 0. {
+        return a.min(b);
+    }
+
+    let h = (0.5 + 0.5 * (b - a) / k).clamp(0., 1.);
+    mix(b, a, h) - k * h * (1. - h)
+}
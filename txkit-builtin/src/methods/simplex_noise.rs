@@ -1,6 +1,33 @@
+use std::ops::ControlFlow;
+
+use cgmath::{vec2, vec3, vec4, Array, InnerSpace, Vector2, Vector3, Vector4, Zero};
+use txkit_core::context::Context;
+use txkit_core::hash::{hash2, hash3, hash4, tofloat11};
+use txkit_core::image::Image;
+use txkit_core::lattice::{
+    check_simplex_lattice_period_4d, Lattice, LatticeMode, LatticeMode3D, LatticeMode4D,
+    LatticeSample, LatticeSample3D, LatticeSample4D, LATTICE_DIMENSIONS_2D, LATTICE_DIMENSIONS_3D,
+    LATTICE_DIMENSIONS_4D, SIMPLEX3D_K2, SIMPLEX4D_K2, SIMPLEX_K2, STATS_MODE_LOOKAT,
+    STATS_MODE_NORMAL, STATS_MODE_PROCESS,
+};
 use txkit_impl::{Method, ParamsFor};
 
+#[cfg(feature = "cpu")]
+use txkit_core::context::CpuContext;
+
+#[cfg(feature = "cpu")]
+use crate::cpu::compute_pixels;
+use crate::shared::{pixel_uv, to01};
+
+/// Simplex noise: noise value in the RGB channels
+pub const SIMPLEX_OUTPUT_VALUE: i32 = 0;
+/// Simplex noise: noise value in the R channel, partial derivatives along x, y and the layers in
+/// the G, B and A channels
+pub const SIMPLEX_OUTPUT_DERIVATIVES: i32 = 1;
+
 #[derive(Clone, Copy, PartialEq, ParamsFor)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[repr(C)]
 #[txkit(program = "SimplexNoiseProgram")]
 pub struct SimplexNoiseParams {
@@ -9,9 +36,20 @@ pub struct SimplexNoiseParams {
     /// lattice scale (size in pixels)
     pub scale: f32,
     /// stats mode (0: normal, 1: process, 2: lookat)
+    #[txkit(values(STATS_MODE_NORMAL, STATS_MODE_PROCESS, STATS_MODE_LOOKAT))]
     pub stats_mode: i32,
     /// look-at parameter (if stats_mode == lookat) in [0, 1]^2
     pub stats_look_at: cgmath::Vector2<f32>,
+    /// output channels
+    #[txkit(values(SIMPLEX_OUTPUT_VALUE, SIMPLEX_OUTPUT_DERIVATIVES))]
+    pub output_mode: i32,
+    /// noise dimensions
+    #[txkit(values(LATTICE_DIMENSIONS_2D, LATTICE_DIMENSIONS_3D, LATTICE_DIMENSIONS_4D))]
+    pub dimensions: i32,
+    /// position along the time axis of 4D noise, in lattice cells
+    pub time: f32,
+    /// period of 4D noise along the time axis, in lattice cells, 0 for no looping
+    pub period: f32,
 }
 
 impl Default for SimplexNoiseParams {
@@ -21,10 +59,32 @@ impl Default for SimplexNoiseParams {
             scale: 32.,
             stats_mode: 0,
             stats_look_at: cgmath::vec2(0., 0.),
+            output_mode: SIMPLEX_OUTPUT_VALUE,
+            dimensions: LATTICE_DIMENSIONS_2D,
+            time: 0.,
+            period: 0.,
         }
     }
 }
 
+/// 2D, 3D and 4D simplex noise
+///
+/// 2D noise is evaluated over (x, y) and repeated on every layer of 3D images, 3D noise over
+/// (x, y, layer), and 4D noise over (x, y, layer, time). With a non-zero `period`, 4D noise loops
+/// along the time axis: the images computed at `time` and `time + period` are the same. Looping
+/// 4D noise requires a `scale` and a `period` of at most
+/// [SIMPLEX_LATTICE_4D_MAX_PERIOD](txkit_core::lattice::SIMPLEX_LATTICE_4D_MAX_PERIOD) cells.
+///
+/// The CPU implementation is a port of `simplex_noise.frag`. For `Float32` targets, its results
+/// match the GPU results within 1e-4, the difference coming from the rasterizer's interpolation
+/// of pixel coordinates. For `UInt8` targets, results may differ by one quantization step since
+/// the CPU conversion truncates instead of rounding.
+///
+/// With `output_mode` set to derivatives, the analytic partial derivatives of the noise with
+/// respect to the lattice coordinates are written alongside the value. Like the value, they are
+/// remapped from [-1, 1] to [0, 1], but their range is wider: use a `Float32` target to keep
+/// them unclamped. The derivative along the layers is zero for 2D noise, and the derivative along
+/// the time axis of 4D noise is not written.
 #[derive(Default, Method)]
 #[txkit(
     gpu(
@@ -32,9 +92,12 @@ impl Default for SimplexNoiseParams {
         program("shaders/quad.vert", "shaders/simplex_noise.frag"),
         method(run = "program", params = "SimplexNoiseParams")
     ),
-    method()
+    cpu(method(run = "Self::compute_cpu_simplex", params = "SimplexNoiseParams")),
+    method(prepare = "Self::check_period")
 )]
 pub struct SimplexNoise {
+    /// parameters used when computing without explicit parameters
+    params: SimplexNoiseParams,
     #[cfg(feature = "gpu")]
     gpu: Option<SimplexNoiseGpu>,
 }
@@ -43,4 +106,285 @@ impl SimplexNoise {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// Reject looping 4D lattices too large to be reduced on the GPU
+    fn check_period(
+        &mut self,
+        _ctx: &mut Context,
+        _tgt: &mut Image,
+        params: &mut SimplexNoiseParams,
+    ) -> txkit_core::Result<ControlFlow<()>> {
+        if params.dimensions == LATTICE_DIMENSIONS_4D {
+            check_simplex_lattice_period_4d(params.scale, params.period)?;
+        }
+
+        Ok(ControlFlow::Continue(()))
+    }
+
+    fn noisehash([x, y]: [i32; 2], seed: u32) -> Vector2<f32> {
+        let [hx, hy] = hash2([x as u32, y as u32], seed);
+        vec2(tofloat11(hx), tofloat11(hy))
+    }
+
+    /// Evaluate simplex noise, in [-1, 1]
+    pub(crate) fn noise(s: LatticeSample, lattice: &Lattice) -> f32 {
+        let [cx, cy] = s.cell;
+        let g = |dx, dy| Self::noisehash(lattice.simplex_lattice_loop([cx + dx, cy + dy]), s.seed);
+
+        let a = s.position;
+        let m = if a.x >= a.y { 1 } else { 0 };
+        let b = a - vec2(m as f32, (1 - m) as f32) + vec2(SIMPLEX_K2, SIMPLEX_K2);
+        let c = a - vec2(1., 1.) + vec2(2. * SIMPLEX_K2, 2. * SIMPLEX_K2);
+
+        let h = vec3(0.5 - a.dot(a), 0.5 - b.dot(b), 0.5 - c.dot(c)).map(|x| x.max(0.));
+        let n = vec3(a.dot(g(0, 0)), b.dot(g(m, 1 - m)), c.dot(g(1, 1)));
+
+        h.map(|x| x * x * x * x).dot(n) * 71.282_59
+    }
+
+    /// Evaluate simplex noise, in [-1, 1], followed by its partial derivatives with respect to
+    /// the lattice coordinates
+    pub(crate) fn noise_derivatives(s: LatticeSample, lattice: &Lattice) -> Vector3<f32> {
+        let [cx, cy] = s.cell;
+        let g = |dx, dy| Self::noisehash(lattice.simplex_lattice_loop([cx + dx, cy + dy]), s.seed);
+
+        let a = s.position;
+        let m = if a.x >= a.y { 1 } else { 0 };
+        let b = a - vec2(m as f32, (1 - m) as f32) + vec2(SIMPLEX_K2, SIMPLEX_K2);
+        let c = a - vec2(1., 1.) + vec2(2. * SIMPLEX_K2, 2. * SIMPLEX_K2);
+
+        let h = vec3(0.5 - a.dot(a), 0.5 - b.dot(b), 0.5 - c.dot(c)).map(|x| x.max(0.));
+        let (ga, gb, gc) = (g(0, 0), g(m, 1 - m), g(1, 1));
+
+        let v = vec3(a.dot(ga), b.dot(gb), c.dot(gc));
+        let h4 = h.map(|x| x * x * x * x);
+
+        // d(h^4 v) = h^4 g - 8 h^3 v x, with h = 0.5 - |x|^2
+        let t = vec3(
+            h.x * h.x * h.x * v.x,
+            h.y * h.y * h.y * v.y,
+            h.z * h.z * h.z * v.z,
+        ) * -8.;
+        let d = a * t.x + b * t.y + c * t.z + ga * h4.x + gb * h4.y + gc * h4.z;
+
+        let d = lattice.simplex_lattice_gradient(d * 71.282_59);
+        vec3(h4.dot(v) * 71.282_59, d.x, d.y)
+    }
+
+    fn noisehash_3d([x, y, z]: [i32; 3], seed: u32) -> Vector3<f32> {
+        let [hx, hy, hz] = hash3([x as u32, y as u32, z as u32], seed);
+        vec3(tofloat11(hx), tofloat11(hy), tofloat11(hz))
+    }
+
+    /// Evaluate 3D simplex noise, in [-1, 1]
+    pub(crate) fn noise_3d(s: LatticeSample3D, lattice: &Lattice) -> f32 {
+        let [cx, cy, cz] = s.cell;
+        let g = |[dx, dy, dz]: [i32; 3]| {
+            Self::noisehash_3d(
+                lattice.simplex_lattice_loop_3d([cx + dx, cy + dy, cz + dz]),
+                s.seed,
+            )
+        };
+
+        let a = s.position;
+
+        // Offsets of the second and third corners of the current simplex, see
+        // `simplex_noise.glsl` for the tie-breaking rule
+        let step = |edge: f32, x: f32| if x >= edge { 1 } else { 0 };
+        let gt = [step(a.y, a.x), step(a.z, a.y), 1 - step(a.z, a.x)];
+        let lt = gt.map(|x| 1 - x);
+        let o1 = [gt[0].min(lt[2]), gt[1].min(lt[0]), gt[2].min(lt[1])];
+        let o2 = [gt[0].max(lt[2]), gt[1].max(lt[0]), gt[2].max(lt[1])];
+
+        let offset = |[x, y, z]: [i32; 3]| vec3(x as f32, y as f32, z as f32);
+        let b = a - offset(o1) + Vector3::from_value(SIMPLEX3D_K2);
+        let c = a - offset(o2) + Vector3::from_value(2. * SIMPLEX3D_K2);
+        let d = a - Vector3::from_value(1.) + Vector3::from_value(3. * SIMPLEX3D_K2);
+
+        let h = vec4(
+            0.5 - a.dot(a),
+            0.5 - b.dot(b),
+            0.5 - c.dot(c),
+            0.5 - d.dot(d),
+        )
+        .map(|x| x.max(0.));
+        let n = vec4(
+            a.dot(g([0, 0, 0])),
+            b.dot(g(o1)),
+            c.dot(g(o2)),
+            d.dot(g([1, 1, 1])),
+        );
+
+        h.map(|x| x * x * x * x).dot(n) * 66.
+    }
+
+    /// Evaluate 3D simplex noise, in [-1, 1], followed by its partial derivatives with respect
+    /// to the lattice coordinates
+    pub(crate) fn noise_3d_derivatives(s: LatticeSample3D, lattice: &Lattice) -> Vector4<f32> {
+        let a = s.position;
+
+        // Offsets of the second and third corners of the current simplex, see
+        // `simplex_noise.glsl` for the tie-breaking rule
+        let step = |edge: f32, x: f32| if x >= edge { 1 } else { 0 };
+        let gt = [step(a.y, a.x), step(a.z, a.y), 1 - step(a.z, a.x)];
+        let lt = gt.map(|x| 1 - x);
+        let o1 = [gt[0].min(lt[2]), gt[1].min(lt[0]), gt[2].min(lt[1])];
+        let o2 = [gt[0].max(lt[2]), gt[1].max(lt[0]), gt[2].max(lt[1])];
+
+        // Positions relative to the corners, and corner offsets
+        let offset = |[x, y, z]: [i32; 3]| vec3(x as f32, y as f32, z as f32);
+        let x = [
+            a,
+            a - offset(o1) + Vector3::from_value(SIMPLEX3D_K2),
+            a - offset(o2) + Vector3::from_value(2. * SIMPLEX3D_K2),
+            a - Vector3::from_value(1.) + Vector3::from_value(3. * SIMPLEX3D_K2),
+        ];
+        let o = [[0, 0, 0], o1, o2, [1, 1, 1]];
+
+        let (mut value, mut d) = (0., Vector3::zero());
+        for (&x, &[dx, dy, dz]) in x.iter().zip(o.iter()) {
+            let [cx, cy, cz] = s.cell;
+            let h = (0.5 - x.dot(x)).max(0.);
+            let g = Self::noisehash_3d(
+                lattice.simplex_lattice_loop_3d([cx + dx, cy + dy, cz + dz]),
+                s.seed,
+            );
+            let v = x.dot(g);
+
+            // d(h^4 v) = h^4 g - 8 h^3 v x, with h = 0.5 - |x|^2
+            value += h * h * h * h * v;
+            d += g * (h * h * h * h) - x * (8. * h * h * h * v);
+        }
+
+        let d = lattice.simplex_lattice_gradient_3d(d * 66.);
+        vec4(value * 66., d.x, d.y, d.z)
+    }
+
+    fn noisehash_4d([x, y, z, w]: [i32; 4], seed: u32) -> Vector4<f32> {
+        let [hx, hy, hz, hw] = hash4([x as u32, y as u32, z as u32, w as u32], seed);
+        vec4(tofloat11(hx), tofloat11(hy), tofloat11(hz), tofloat11(hw))
+    }
+
+    /// Evaluate 4D simplex noise, in [-1, 1], followed by its partial derivatives with respect
+    /// to the spatial lattice coordinates
+    pub(crate) fn noise_4d_derivatives(s: LatticeSample4D, lattice: &Lattice) -> Vector4<f32> {
+        let a = s.position;
+
+        // Rank of each coordinate, to find the corners of the current simplex
+        let step = |edge: f32, x: f32| if x >= edge { 1 } else { 0 };
+        let mut rank = [0; 4];
+        for i in 0..4 {
+            for j in (i + 1)..4 {
+                let gt = step(a[j], a[i]);
+                rank[i] += gt;
+                rank[j] += 1 - gt;
+            }
+        }
+
+        let o1 = rank.map(|r| (r - 2).clamp(0, 1));
+        let o2 = rank.map(|r| (r - 1).clamp(0, 1));
+        let o3 = rank.map(|r| r.clamp(0, 1));
+
+        // Positions relative to the corners, and corner offsets
+        let offset = |[x, y, z, w]: [i32; 4]| vec4(x as f32, y as f32, z as f32, w as f32);
+        let x = [
+            a,
+            a - offset(o1) + Vector4::from_value(SIMPLEX4D_K2),
+            a - offset(o2) + Vector4::from_value(2. * SIMPLEX4D_K2),
+            a - offset(o3) + Vector4::from_value(3. * SIMPLEX4D_K2),
+            a - Vector4::from_value(1.) + Vector4::from_value(4. * SIMPLEX4D_K2),
+        ];
+        let o = [[0, 0, 0, 0], o1, o2, o3, [1, 1, 1, 1]];
+
+        let (mut value, mut d) = (0., Vector4::zero());
+        for (&x, &[dx, dy, dz, dw]) in x.iter().zip(o.iter()) {
+            let [cx, cy, cz, cw] = s.cell;
+            let h = (0.5 - x.dot(x)).max(0.);
+            let g = Self::noisehash_4d(
+                lattice.simplex_lattice_loop_4d([cx + dx, cy + dy, cz + dz, cw + dw]),
+                s.seed,
+            );
+            let v = x.dot(g);
+
+            // d(h^4 v) = h^4 g - 8 h^3 v x, with h = 0.5 - |x|^2
+            value += h * h * h * h * v;
+            d += g * (h * h * h * h) - x * (8. * h * h * h * v);
+        }
+
+        let d = lattice.simplex_lattice_gradient_4d(d);
+        vec4(value, d.x, d.y, d.z) * 62.
+    }
+
+    /// Evaluate 4D simplex noise, in [-1, 1]
+    pub(crate) fn noise_4d(s: LatticeSample4D, lattice: &Lattice) -> f32 {
+        Self::noise_4d_derivatives(s, lattice).x
+    }
+}
+
+#[cfg(feature = "cpu")]
+impl SimplexNoise {
+    fn compute_cpu_simplex(
+        &mut self,
+        ctx: &mut CpuContext,
+        tgt: &mut Image,
+        params: &SimplexNoiseParams,
+    ) -> txkit_core::Result<()> {
+        if params.output_mode != SIMPLEX_OUTPUT_VALUE
+            && params.output_mode != SIMPLEX_OUTPUT_DERIVATIVES
+        {
+            // Invalid enum value
+            return compute_pixels(ctx, tgt, |_, _| [1., 0., 1., 1.]);
+        }
+
+        let lattice = Lattice::new(
+            params.scale,
+            params.global_seed,
+            params.stats_mode,
+            params.stats_look_at,
+            tgt.dim(),
+        );
+        let lattice_4d = lattice.with_period(params.period);
+        let derivatives = params.output_mode == SIMPLEX_OUTPUT_DERIVATIVES;
+
+        compute_pixels(ctx, tgt, |idx, dim| {
+            let uv = pixel_uv(idx, dim);
+
+            // Noise value, followed by its partial derivatives
+            let res = match params.dimensions {
+                LATTICE_DIMENSIONS_2D => {
+                    let s = lattice.sample(uv.truncate(), LatticeMode::Simplex2D);
+                    if derivatives {
+                        Self::noise_derivatives(s, &lattice).extend(0.)
+                    } else {
+                        Vector4::from_value(Self::noise(s, &lattice))
+                    }
+                }
+                LATTICE_DIMENSIONS_3D => {
+                    let s = lattice.sample_3d(uv, LatticeMode3D::Simplex3D);
+                    if derivatives {
+                        Self::noise_3d_derivatives(s, &lattice)
+                    } else {
+                        Vector4::from_value(Self::noise_3d(s, &lattice))
+                    }
+                }
+                LATTICE_DIMENSIONS_4D => {
+                    let s = lattice_4d.sample_4d(uv.extend(params.time), LatticeMode4D::Simplex4D);
+                    if derivatives {
+                        Self::noise_4d_derivatives(s, &lattice_4d)
+                    } else {
+                        Vector4::from_value(Self::noise_4d(s, &lattice_4d))
+                    }
+                }
+                // Invalid enum value
+                _ => return [1., 0., 1., 1.],
+            };
+
+            if derivatives {
+                [to01(res.x), to01(res.y), to01(res.z), to01(res.w)]
+            } else {
+                let value = to01(res.x);
+                [value, value, value, 1.]
+            }
+        })
+    }
 }
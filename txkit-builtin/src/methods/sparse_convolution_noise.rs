@@ -0,0 +1,274 @@
+use std::ops::ControlFlow;
+
+use txkit_core::context::Context;
+use txkit_core::image::Image;
+use txkit_core::lattice::{STATS_MODE_LOOKAT, STATS_MODE_NORMAL, STATS_MODE_PROCESS};
+use txkit_core::{Error, Result};
+use txkit_impl::{Method, ParamsFor};
+
+#[cfg(feature = "cpu")]
+use cgmath::{vec2, InnerSpace, Vector2};
+#[cfg(feature = "cpu")]
+use txkit_core::{
+    context::CpuContext,
+    hash::hash_cell,
+    lattice::{Lattice, LatticeMode, LatticeSample},
+};
+
+use super::{
+    PHASOR_POINTS_HEX_JITTERED, PHASOR_POINTS_POISSON, PHASOR_POINTS_RECT_JITTERED,
+    PHASOR_POINTS_STRAT_POSSION,
+};
+#[cfg(feature = "cpu")]
+use crate::cpu::{compute_pixels, Domain};
+#[cfg(feature = "cpu")]
+use crate::lcg::Lcg;
+#[cfg(feature = "cpu")]
+use crate::points::PointSet;
+#[cfg(feature = "cpu")]
+use crate::shared::to01;
+
+/// Sparse convolution: truncated gaussian kernel
+pub const SPARSE_CONVOLUTION_KERNEL_GAUSSIAN: i32 = 0;
+/// Sparse convolution: raised cosine kernel
+pub const SPARSE_CONVOLUTION_KERNEL_COSINE: i32 = 1;
+/// Sparse convolution: cubic B-spline kernel
+pub const SPARSE_CONVOLUTION_KERNEL_BSPLINE: i32 = 2;
+
+/// Sparse convolution: largest kernel radius, in noise cells
+pub const SPARSE_CONVOLUTION_MAX_KERNEL_RADIUS: f32 = 4.;
+
+#[derive(Clone, Copy, PartialEq, ParamsFor)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[repr(C)]
+#[txkit(program = "SparseConvolutionNoiseProgram")]
+pub struct SparseConvolutionNoiseParams {
+    /// pseudo-random seed
+    pub global_seed: u32,
+    /// lattice scale (size in pixels)
+    pub scale: f32,
+    /// stats mode (0: normal, 1: process, 2: lookat)
+    #[txkit(values(STATS_MODE_NORMAL, STATS_MODE_PROCESS, STATS_MODE_LOOKAT))]
+    pub stats_mode: i32,
+    /// look-at parameter (if stats_mode == lookat) in [0, 1]^2
+    pub stats_look_at: cgmath::Vector2<f32>,
+
+    /// kernel profile
+    #[txkit(values(
+        SPARSE_CONVOLUTION_KERNEL_GAUSSIAN,
+        SPARSE_CONVOLUTION_KERNEL_COSINE,
+        SPARSE_CONVOLUTION_KERNEL_BSPLINE
+    ))]
+    pub kernel: i32,
+    /// kernel count per cell
+    pub kernel_count: i32,
+    /// point distribution
+    #[txkit(values(
+        PHASOR_POINTS_STRAT_POSSION,
+        PHASOR_POINTS_POISSON,
+        PHASOR_POINTS_RECT_JITTERED,
+        PHASOR_POINTS_HEX_JITTERED
+    ))]
+    pub noise_point_distribution: i32,
+    /// jittering amount, 0 = no random, 1 = full subcell random
+    pub jitter_amount: f32,
+    /// max jittering subcells, 0 = no limit
+    pub jitter_max: i32,
+    /// kernel support radius (in noise cells)
+    pub kernel_radius: f32,
+}
+
+impl Default for SparseConvolutionNoiseParams {
+    fn default() -> Self {
+        Self {
+            global_seed: 0,
+            scale: 16.,
+            stats_mode: 0,
+            stats_look_at: cgmath::vec2(0., 0.),
+            kernel: SPARSE_CONVOLUTION_KERNEL_GAUSSIAN,
+            kernel_count: 8,
+            noise_point_distribution: PHASOR_POINTS_STRAT_POSSION,
+            jitter_amount: 1.,
+            jitter_max: 0,
+            kernel_radius: 1.,
+        }
+    }
+}
+
+/// Sparse convolution noise
+///
+/// Sparse convolution of random impulses with a radial kernel of support `kernel_radius` cells,
+/// which must be positive and at most [SPARSE_CONVOLUTION_MAX_KERNEL_RADIUS].
+/// Impulse positions follow the same point distributions as phasor noise, and impulse weights
+/// are uniform in [-1, 1]. The noise is normalized so three standard deviations span [0, 1].
+///
+/// The CPU implementation is a port of `sparse_convolution_noise.frag`.
+#[derive(Default, Method)]
+#[txkit(
+    gpu(
+        name = "SparseConvolutionNoiseGpu",
+        program("shaders/quad.vert", "shaders/sparse_convolution_noise.frag"),
+        method(run = "program", params = "SparseConvolutionNoiseParams"),
+        dims(Image2D)
+    ),
+    cpu(
+        method(
+            run = "Self::compute_cpu_sparse_convolution",
+            params = "SparseConvolutionNoiseParams"
+        ),
+        dims(Image1D, Image2D)
+    ),
+    method(prepare = "Self::check_kernel_radius")
+)]
+pub struct SparseConvolutionNoise {
+    /// parameters used when computing without explicit parameters
+    params: SparseConvolutionNoiseParams,
+    #[cfg(feature = "gpu")]
+    gpu: Option<SparseConvolutionNoiseGpu>,
+}
+
+impl SparseConvolutionNoise {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reject kernels larger than [SPARSE_CONVOLUTION_MAX_KERNEL_RADIUS]
+    fn check_kernel_radius(
+        &mut self,
+        _ctx: &mut Context,
+        _tgt: &mut Image,
+        params: &mut SparseConvolutionNoiseParams,
+    ) -> Result<ControlFlow<()>> {
+        let radius = params.kernel_radius;
+        if radius.is_nan() || radius <= 0. || radius > SPARSE_CONVOLUTION_MAX_KERNEL_RADIUS {
+            return Err(Error::InvalidParameters);
+        }
+
+        Ok(ControlFlow::Continue(()))
+    }
+}
+
+#[cfg(feature = "cpu")]
+impl SparseConvolutionNoise {
+    fn compute_cpu_sparse_convolution(
+        &mut self,
+        ctx: &mut CpuContext,
+        tgt: &mut Image,
+        params: &SparseConvolutionNoiseParams,
+    ) -> Result<()> {
+        if params.kernel != SPARSE_CONVOLUTION_KERNEL_GAUSSIAN
+            && params.kernel != SPARSE_CONVOLUTION_KERNEL_COSINE
+            && params.kernel != SPARSE_CONVOLUTION_KERNEL_BSPLINE
+        {
+            // Invalid enum value
+            return compute_pixels(ctx, tgt, |_, _| [1., 0., 1., 1.]);
+        }
+
+        let lattice = Lattice::new(
+            params.scale,
+            params.global_seed,
+            params.stats_mode,
+            params.stats_look_at,
+            tgt.dim(),
+        );
+
+        let domain = Domain::new(ctx, tgt.dim())?;
+
+        compute_pixels(ctx, tgt, |idx, dim| {
+            let uv = domain.uv(idx, dim);
+            let s = lattice.sample(uv.truncate(), LatticeMode::Rect2D);
+            let v = to01(Self::noise(s, &lattice, params));
+            [v, v, v, 1.]
+        })
+    }
+
+    /// Evaluate the radial kernel
+    ///
+    /// # Parameters
+    ///
+    /// * `s`: distance to the kernel center, relative to the kernel radius
+    /// * `kernel`: kernel profile
+    fn kernel_profile(s: f32, kernel: i32) -> f32 {
+        use std::f32::consts::PI;
+
+        match kernel {
+            SPARSE_CONVOLUTION_KERNEL_GAUSSIAN => (-4. * PI * s * s).exp(),
+            SPARSE_CONVOLUTION_KERNEL_COSINE => 0.5 * (1. + (PI * s).cos()),
+            _ => {
+                let t = 2. * s;
+                if t < 1. {
+                    (4. - 6. * t * t + 3. * t * t * t) / 4.
+                } else {
+                    (2. - t) * (2. - t) * (2. - t) / 4.
+                }
+            }
+        }
+    }
+
+    /// Integral of the squared kernel over the plane, for a unit radius
+    fn kernel_energy(kernel: i32) -> f32 {
+        match kernel {
+            SPARSE_CONVOLUTION_KERNEL_GAUSSIAN => 0.125,
+            SPARSE_CONVOLUTION_KERNEL_COSINE => 0.54148,
+            _ => 0.28891,
+        }
+    }
+
+    fn noise_cell(
+        pos: Vector2<f32>,
+        cell: [i32; 2],
+        seed: u32,
+        params: &SparseConvolutionNoiseParams,
+    ) -> f32 {
+        let mut res = 0.;
+
+        // Seed the random number generator
+        let mut rng = Lcg::new(hash_cell([cell[0] as u32, cell[1] as u32], seed));
+
+        // Compute impulse count
+        let ps = PointSet::new(
+            &mut rng,
+            params.noise_point_distribution,
+            params.kernel_count,
+            params.jitter_max,
+            params.jitter_amount,
+        );
+
+        for i in 0..ps.count() {
+            let kpos = ps.next(&mut rng, i);
+            let weight = rng.next_11();
+
+            let s = (pos - kpos).magnitude() / params.kernel_radius;
+            if s < 1. {
+                res += weight * Self::kernel_profile(s, params.kernel);
+            }
+        }
+
+        res
+    }
+
+    fn noise(s: LatticeSample, lattice: &Lattice, params: &SparseConvolutionNoiseParams) -> f32 {
+        let mut res = 0.;
+        let lookahead = params.kernel_radius.ceil() as i32;
+
+        let [sx, sy] = s.cell;
+        for cx in sx - lookahead..=sx + lookahead {
+            for cy in sy - lookahead..=sy + lookahead {
+                // Make sure the noise tiles correctly
+                let looped_cell = lattice.lattice_loop_signed([cx, cy]);
+
+                // Compute the position of the current point relative to the target cell
+                let position = s.position - vec2((cx - sx) as f32, (cy - sy) as f32);
+
+                // Add contribution of target noise cell
+                res += Self::noise_cell(position, looped_cell, s.seed, params);
+            }
+        }
+
+        // Normalize by the standard deviation of the noise, with uniform weights in [-1, 1]
+        let sigma = params.kernel_radius
+            * (params.kernel_count as f32 * Self::kernel_energy(params.kernel) / 3.).sqrt();
+        res / (3. * sigma)
+    }
+}
@@ -0,0 +1,106 @@
+use txkit_impl::{Method, ParamsFor};
+
+#[cfg(feature = "cpu")]
+use cgmath::{vec2, InnerSpace};
+#[cfg(feature = "cpu")]
+use txkit_core::{context::CpuContext, image::Image};
+
+#[cfg(feature = "cpu")]
+use crate::cpu::{compute_pixels, Domain};
+#[cfg(feature = "cpu")]
+use crate::patterns::{filtered_pulse, tile_id};
+
+#[derive(Clone, Copy, PartialEq, ParamsFor)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[repr(C)]
+#[txkit(program = "StripePatternProgram")]
+pub struct StripePatternParams {
+    /// pseudo-random seed of the stripe IDs
+    pub global_seed: u32,
+    /// number of stripe periods across the image
+    pub scale: f32,
+    /// direction across the stripes (in radians, 0: vertical stripes)
+    pub orientation: f32,
+    /// fraction of the period covered by the stripe
+    pub duty: f32,
+    /// width of the anti-aliasing filter, in pixels (0: no anti-aliasing)
+    pub antialiasing: f32,
+}
+
+impl Default for StripePatternParams {
+    fn default() -> Self {
+        Self {
+            global_seed: 0,
+            scale: 8.,
+            orientation: 0.,
+            duty: 0.5,
+            antialiasing: 1.,
+        }
+    }
+}
+
+/// Stripe pattern
+///
+/// Stripes of width `duty` periods, with `scale` periods across the image along the direction
+/// given by `orientation`. The output is the pattern value, the random ID of the period in [0,
+/// 1], and the index of the period followed by 0. The pattern is the same for all layers of 3D
+/// images, and only tiles seamlessly for axis-aligned stripes.
+#[derive(Default, Method)]
+#[txkit(
+    gpu(
+        name = "StripePatternGpu",
+        program("shaders/quad.vert", "shaders/stripe_pattern.frag"),
+        method(run = "program", params = "StripePatternParams"),
+        dims(Image2D)
+    ),
+    cpu(
+        method(run = "Self::compute_cpu_stripes", params = "StripePatternParams"),
+        dims(Image1D, Image2D)
+    ),
+    method()
+)]
+pub struct StripePattern {
+    /// parameters used when computing without explicit parameters
+    params: StripePatternParams,
+    #[cfg(feature = "gpu")]
+    gpu: Option<StripePatternGpu>,
+}
+
+impl StripePattern {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[cfg(feature = "cpu")]
+impl StripePattern {
+    fn compute_cpu_stripes(
+        &mut self,
+        ctx: &mut CpuContext,
+        tgt: &mut Image,
+        params: &StripePatternParams,
+    ) -> txkit_core::Result<()> {
+        let d = vec2(params.orientation.cos(), params.orientation.sin());
+        let dim = tgt.dim();
+
+        // Footprint of a pixel along the stripe direction
+        let w = params.antialiasing
+            * params.scale
+            * (d.x.abs() / dim.width as f32 + d.y.abs() / dim.height as f32);
+
+        let domain = Domain::new(ctx, tgt.dim())?;
+
+        compute_pixels(ctx, tgt, |idx, dim| {
+            let t = (domain.uv(idx, dim).truncate() * params.scale).dot(d);
+            let cell = [t.floor() as i32, 0];
+
+            [
+                filtered_pulse(t, params.duty, w),
+                tile_id(cell, params.global_seed),
+                cell[0] as f32,
+                cell[1] as f32,
+            ]
+        })
+    }
+}
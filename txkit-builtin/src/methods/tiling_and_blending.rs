@@ -0,0 +1,482 @@
+use std::cell::RefCell;
+use std::ops::ControlFlow;
+use std::rc::Rc;
+
+use txkit_core::context::Context;
+use txkit_core::image::{Image, ImageDataType, ImageDim};
+use txkit_core::io::{ImageBinding, ImageIo};
+use txkit_core::{Error, Result};
+use txkit_impl::{Method, ParamsFor};
+
+#[cfg(feature = "cpu")]
+use cgmath::{vec2, vec3, InnerSpace, Vector2, Vector3};
+#[cfg(feature = "cpu")]
+use txkit_core::{
+    context::CpuContext,
+    hash::{hash2, tofloat},
+    io::cpu::{CpuImageIoExt, CpuTexture},
+};
+
+#[cfg(feature = "cpu")]
+use crate::cpu::{compute_pixels, Domain};
+
+#[derive(Clone, PartialEq, ParamsFor)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[repr(C)]
+#[txkit(program = "TilingAndBlendingProgram")]
+pub struct TilingAndBlendingParams {
+    /// pseudo-random seed of the tile offsets
+    pub global_seed: u32,
+    /// number of exemplar widths across the output image
+    pub repeat: f32,
+    /// size of the triangular tiles, in exemplar widths
+    pub tile_size: f32,
+
+    /// texture inputs: the exemplar, or its precomputed gaussian exemplar and inverse histogram
+    #[texture_io(exemplar(cpu), gaussian_exemplar, inverse_histogram)]
+    pub io: Box<ImageIo>,
+}
+
+impl Default for TilingAndBlendingParams {
+    fn default() -> Self {
+        Self {
+            global_seed: 0,
+            repeat: 4.,
+            tile_size: 0.25,
+            io: Box::default(),
+        }
+    }
+}
+
+/// Texture unit of the exemplar
+pub const TILING_AND_BLENDING_EXEMPLAR_UNIT: usize = 0;
+/// Texture unit of the gaussianized exemplar, see [TilingAndBlending::precompute]
+pub const TILING_AND_BLENDING_GAUSSIAN_EXEMPLAR_UNIT: usize = 1;
+/// Texture unit of the inverse histogram lookup table, see [TilingAndBlending::precompute]
+pub const TILING_AND_BLENDING_INVERSE_HISTOGRAM_UNIT: usize = 2;
+
+/// Number of entries in the inverse histogram lookup table
+pub const TILING_AND_BLENDING_INVERSE_HISTOGRAM_SIZE: usize = 1024;
+
+/// Standard deviation of the gaussianized exemplar
+const GAUSSIAN_STD_DEV: f64 = 1. / 6.;
+
+/// By-example texture synthesis with histogram-preserving tiling and blending
+///
+/// Synthesizes an arbitrarily large, non-repeating texture from the exemplar bound to texture
+/// unit 0. The output is tiled with a triangle grid of `tile_size` exemplar widths, each vertex of
+/// which gets a random offset in the exemplar. Pixels blend the three offset copies of their
+/// triangle with a variance-preserving operator on the gaussianized exemplar, and the result is
+/// mapped back to the exemplar histogram.
+///
+/// Both lookup tables, the gaussianized exemplar and the inverse histogram transform, are
+/// computed on the CPU by [TilingAndBlending::precompute] for every call, unless they are bound
+/// to units 1 and 2. GPU exemplars are downloaded first, and on GPU contexts, the lookup tables
+/// are uploaded to GPU images before computing.
+///
+/// See Heitz and Neyret, High-Performance By-Example Noise using a Histogram-Preserving Blending
+/// Operator, 2018.
+#[derive(Default, Method)]
+#[txkit(
+    gpu(
+        name = "TilingAndBlendingGpu",
+        program("shaders/quad.vert", "shaders/tiling_and_blending.frag"),
+        method(run = "program", params = "TilingAndBlendingParams"),
+        dims(Image2D)
+    ),
+    cpu(
+        method(run = "Self::compute_cpu_tiling", params = "TilingAndBlendingParams"),
+        dims(Image1D, Image2D)
+    ),
+    method(prepare = "Self::bind_lookup_tables")
+)]
+pub struct TilingAndBlending {
+    /// parameters used when computing without explicit parameters
+    params: TilingAndBlendingParams,
+    #[cfg(feature = "gpu")]
+    gpu: Option<TilingAndBlendingGpu>,
+}
+
+impl TilingAndBlending {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Compute the lookup tables of histogram-preserving blending for an exemplar
+    ///
+    /// Returns the gaussianized exemplar, with the same size as the first layer of the exemplar,
+    /// and the inverse histogram transform, a
+    /// [TILING_AND_BLENDING_INVERSE_HISTOGRAM_SIZE]x1 image mapping gaussianized values back to
+    /// exemplar values. Both are 4 channel `Float32` CPU images. Channels missing from the
+    /// exemplar read as `(0, 0, 0, 1)`. Fails with [Error::InvalidParameters] for an empty
+    /// exemplar.
+    ///
+    /// # Parameters
+    ///
+    /// * `exemplar`: exemplar image. GPU images must have been downloaded first.
+    pub fn precompute(exemplar: &Image) -> Result<(Image, Image)> {
+        let dim = exemplar.dim();
+        let data = exemplar.data()?;
+
+        let values = if let Some(data) = data.as_f32_nd_array() {
+            data.to_owned()
+        } else if let Some(data) = data.as_u8_nd_array() {
+            data.mapv(|x| x as f32 / 255.)
+        } else {
+            return Err(Error::FormatNotSupported);
+        };
+
+        let (width, height) = (dim.width, dim.height);
+        let n = width * height;
+        if n == 0 {
+            return Err(Error::InvalidParameters);
+        }
+
+        let hist_size = TILING_AND_BLENDING_INVERSE_HISTOGRAM_SIZE;
+
+        let mut gaussian = Image::new_cpu(ImageDim::new(width, height, 4), ImageDataType::Float32);
+        let mut inverse = Image::new_cpu(ImageDim::new(hist_size, 1, 4), ImageDataType::Float32);
+
+        {
+            let mut gaussian_data = gaussian.data_mut()?;
+            let mut gaussian = gaussian_data.as_f32_nd_array_mut().unwrap();
+            let mut inverse_data = inverse.data_mut()?;
+            let mut inverse = inverse_data.as_f32_nd_array_mut().unwrap();
+
+            for c in 0..4 {
+                if c >= dim.channels {
+                    // Missing channels are constant
+                    let value = if c == 3 { 1. } else { 0. };
+                    gaussian.slice_mut(ndarray::s![.., .., .., c]).fill(0.5);
+                    inverse.slice_mut(ndarray::s![.., .., .., c]).fill(value);
+                    continue;
+                }
+
+                // Sort the pixels of the channel by value, NaNs last, and ties by index
+                let mut sorted: Vec<(f32, usize)> = (0..n)
+                    .map(|i| (values[[0, i / width, i % width, c]], i))
+                    .collect();
+                sorted.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+
+                // Gaussianize: map the rank of each pixel to the matching gaussian quantile
+                for (rank, &(_, i)) in sorted.iter().enumerate() {
+                    let u = (rank as f64 + 0.5) / n as f64;
+                    gaussian[[0, i / width, i % width, c]] =
+                        (0.5 + GAUSSIAN_STD_DEV * inverse_normal_cdf(u)) as f32;
+                }
+
+                // Inverse transform: map gaussian values to the exemplar value of the same rank
+                for i in 0..hist_size {
+                    let g = (i as f64 + 0.5) / hist_size as f64;
+                    let u = normal_cdf((g - 0.5) / GAUSSIAN_STD_DEV);
+                    let rank = ((u * n as f64) as usize).min(n - 1);
+                    inverse[[0, 0, i, c]] = sorted[rank].0;
+                }
+            }
+        }
+
+        Ok((gaussian, inverse))
+    }
+
+    /// Compute the lookup tables and bind them if they were not provided
+    fn bind_lookup_tables(
+        &mut self,
+        ctx: &mut Context,
+        _tgt: &mut Image,
+        params: &mut TilingAndBlendingParams,
+    ) -> Result<ControlFlow<()>> {
+        if let Some([gaussian, inverse]) = Self::lookup_tables(ctx, params)? {
+            params.io.set_texture_binding(
+                TILING_AND_BLENDING_GAUSSIAN_EXEMPLAR_UNIT,
+                ImageBinding::ImageRef(Rc::new(RefCell::new(gaussian))),
+            );
+            params.io.set_texture_binding(
+                TILING_AND_BLENDING_INVERSE_HISTOGRAM_UNIT,
+                ImageBinding::ImageRef(Rc::new(RefCell::new(inverse))),
+            );
+        }
+
+        Ok(ControlFlow::Continue(()))
+    }
+
+    /// Get the lookup tables bound to `params`, or compute them from the exemplar
+    fn lookup_tables(
+        ctx: &Context,
+        params: &TilingAndBlendingParams,
+    ) -> Result<Option<[Image; 2]>> {
+        let bound = |unit| !matches!(params.io.get_texture_binding(unit), ImageBinding::None);
+
+        if bound(TILING_AND_BLENDING_GAUSSIAN_EXEMPLAR_UNIT)
+            && bound(TILING_AND_BLENDING_INVERSE_HISTOGRAM_UNIT)
+        {
+            return Ok(None);
+        }
+
+        let (gaussian, inverse) = match params
+            .io
+            .get_texture_binding(TILING_AND_BLENDING_EXEMPLAR_UNIT)
+        {
+            ImageBinding::None => {
+                return Err(Error::MethodInitializationFailed(
+                    "no exemplar bound to texture unit 0".to_owned(),
+                ))
+            }
+            ImageBinding::ImageRef(img) => Self::download_and_precompute(
+                &mut *img
+                    .try_borrow_mut()
+                    .map_err(|e| Error::MethodInitializationFailed(e.to_string()))?,
+            )?,
+            ImageBinding::ImagePtr(img) => {
+                Self::download_and_precompute(unsafe { img.as_mut() }.ok_or_else(|| {
+                    Error::MethodInitializationFailed("null exemplar image pointer".to_owned())
+                })?)?
+            }
+            ImageBinding::Named(name) => return Err(Error::UnresolvedImageBinding(name.clone())),
+        };
+
+        Ok(Some([
+            gaussian.into_2d_for(ctx)?,
+            inverse.into_2d_for(ctx)?,
+        ]))
+    }
+
+    /// Download an exemplar from the GPU if needed, and compute its lookup tables
+    fn download_and_precompute(exemplar: &mut Image) -> Result<(Image, Image)> {
+        exemplar.download()?;
+        Self::precompute(exemplar)
+    }
+}
+
+#[cfg(feature = "cpu")]
+impl TilingAndBlending {
+    fn compute_cpu_tiling(
+        &mut self,
+        ctx: &mut CpuContext,
+        tgt: &mut Image,
+        params: &TilingAndBlendingParams,
+    ) -> txkit_core::Result<()> {
+        let exemplar_size = match params
+            .io
+            .get_texture_binding(TILING_AND_BLENDING_GAUSSIAN_EXEMPLAR_UNIT)
+        {
+            ImageBinding::ImageRef(img) => img.borrow().dim(),
+            ImageBinding::ImagePtr(img) => unsafe { img.as_ref() }
+                .ok_or_else(|| {
+                    Error::MethodInitializationFailed(
+                        "null gaussian exemplar image pointer".to_owned(),
+                    )
+                })?
+                .dim(),
+            ImageBinding::Named(name) => return Err(Error::UnresolvedImageBinding(name.clone())),
+            ImageBinding::None => {
+                return Err(Error::MethodInitializationFailed(
+                    "no gaussian exemplar bound to texture unit 1".to_owned(),
+                ))
+            }
+        };
+
+        let sampler = TilingSampler {
+            global_seed: params.global_seed,
+            repeat: params.repeat,
+            tile_size: params.tile_size,
+            exemplar_size: vec2(exemplar_size.width as f32, exemplar_size.height as f32),
+            gaussian_exemplar: params
+                .io
+                .texture_binding_data(TILING_AND_BLENDING_GAUSSIAN_EXEMPLAR_UNIT)?,
+            inverse_histogram: params
+                .io
+                .texture_binding_data(TILING_AND_BLENDING_INVERSE_HISTOGRAM_UNIT)?,
+        };
+
+        let domain = Domain::new(ctx, tgt.dim())?;
+
+        compute_pixels(ctx, tgt, |idx, dim| {
+            let uv = domain.uv(idx, dim).truncate();
+            sampler.sample(uv, vec2(dim.width as f32, dim.height as f32))
+        })
+    }
+}
+
+/// Thread-safe copy of [TilingAndBlendingParams] for evaluating the synthesis on the CPU
+#[cfg(feature = "cpu")]
+struct TilingSampler {
+    global_seed: u32,
+    repeat: f32,
+    tile_size: f32,
+    exemplar_size: Vector2<f32>,
+    gaussian_exemplar: CpuTexture,
+    inverse_histogram: CpuTexture,
+}
+
+#[cfg(feature = "cpu")]
+impl TilingSampler {
+    /// Find the vertices of the triangle grid cell containing a point, see
+    /// `tiling_and_blending.frag`
+    fn triangle_grid(p: Vector2<f32>) -> (Vector3<f32>, [[i32; 2]; 3]) {
+        // Skew the grid so equilateral triangles become right triangles
+        let skewed = vec2(p.x - 0.577_350_3 * p.y, 1.154_700_5 * p.y);
+        let base = [skewed.x.floor() as i32, skewed.y.floor() as i32];
+        let (tx, ty) = (skewed.x - skewed.x.floor(), skewed.y - skewed.y.floor());
+        let tz = 1. - tx - ty;
+
+        if tz > 0. {
+            (
+                vec3(tz, ty, tx),
+                [base, [base[0], base[1] + 1], [base[0] + 1, base[1]]],
+            )
+        } else {
+            (
+                vec3(-tz, 1. - ty, 1. - tx),
+                [
+                    [base[0] + 1, base[1] + 1],
+                    [base[0] + 1, base[1]],
+                    [base[0], base[1] + 1],
+                ],
+            )
+        }
+    }
+
+    fn sample(&self, uv: Vector2<f32>, resolution: Vector2<f32>) -> [f32; 4] {
+        // Position in exemplar pixels
+        let p = vec2(uv.x * resolution.x, uv.y * resolution.y) * self.repeat * self.exemplar_size.x
+            / resolution.x;
+
+        let (w, vertices) = Self::triangle_grid(p / (self.tile_size * self.exemplar_size.x));
+
+        // Variance-preserving blend of the gaussianized samples
+        let t = vec2(p.x / self.exemplar_size.x, p.y / self.exemplar_size.y);
+        let mut g = [0.; 4];
+        for (wi, v) in [w.x, w.y, w.z].iter().zip(vertices.iter()) {
+            let [ox, oy] = hash2([v[0] as u32, v[1] as u32], self.global_seed);
+            let s = self
+                .gaussian_exemplar
+                .sample_2d([t.x + tofloat(ox), t.y + tofloat(oy)]);
+
+            for (g, s) in g.iter_mut().zip(s.iter()) {
+                *g += wi * s;
+            }
+        }
+
+        let norm = 1. / w.magnitude();
+        let n = TILING_AND_BLENDING_INVERSE_HISTOGRAM_SIZE as f32;
+
+        let mut res = [0.; 4];
+        for (c, (r, g)) in res.iter_mut().zip(g.iter()).enumerate() {
+            // Clamp to texel centers to avoid wrapping around the table
+            let g = ((g - 0.5) * norm + 0.5).max(0.5 / n).min(1. - 0.5 / n);
+            *r = self.inverse_histogram.sample_2d([g, 0.5])[c];
+        }
+
+        res
+    }
+}
+
+/// Standard normal cumulative distribution function
+///
+/// Uses the approximation 7.1.26 of the error function in Abramowitz and Stegun, with an absolute
+/// error below 1.5e-7.
+fn normal_cdf(x: f64) -> f64 {
+    let z = x.abs() / std::f64::consts::SQRT_2;
+    let t = 1. / (1. + 0.3275911 * z);
+    let poly = t
+        * (0.254829592
+            + t * (-0.284496736 + t * (1.421413741 + t * (-1.453152027 + t * 1.061405429))));
+    let erf = 1. - poly * (-z * z).exp();
+
+    if x >= 0. {
+        0.5 * (1. + erf)
+    } else {
+        0.5 * (1. - erf)
+    }
+}
+
+/// Inverse of the standard normal cumulative distribution function
+///
+/// Uses Acklam's rational approximation, with a relative error below 1.15e-9.
+fn inverse_normal_cdf(p: f64) -> f64 {
+    const A: [f64; 6] = [
+        -3.969683028665376e+01,
+        2.209460984245205e+02,
+        -2.759285104469687e+02,
+        1.38357751867269e+02,
+        -3.066479806614716e+01,
+        2.506628277459239e+00,
+    ];
+    const B: [f64; 5] = [
+        -5.447609879822406e+01,
+        1.615858368580409e+02,
+        -1.556989798598866e+02,
+        6.680131188771972e+01,
+        -1.328068155288572e+01,
+    ];
+    const C: [f64; 6] = [
+        -7.784894002430293e-03,
+        -3.223964580411365e-01,
+        -2.400758277161838e+00,
+        -2.549732539343734e+00,
+        4.374664141464968e+00,
+        2.938163982698783e+00,
+    ];
+    const D: [f64; 4] = [
+        7.784695709041462e-03,
+        3.224671290700398e-01,
+        2.445134137142996e+00,
+        3.754408661907416e+00,
+    ];
+    const P_LOW: f64 = 0.02425;
+
+    let tail = |q: f64| {
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.)
+    };
+
+    if p < P_LOW {
+        tail((-2. * p.ln()).sqrt())
+    } else if p > 1. - P_LOW {
+        -tail((-2. * (1. - p).ln()).sqrt())
+    } else {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.)
+    }
+}
+
+#[cfg(all(test, feature = "cpu"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_exemplars_are_rejected() {
+        for (width, height) in [(0, 0), (0, 4), (4, 0)] {
+            let exemplar = Image::new_cpu(ImageDim::new(width, height, 4), ImageDataType::Float32);
+            assert!(matches!(
+                TilingAndBlending::precompute(&exemplar),
+                Err(Error::InvalidParameters)
+            ));
+        }
+    }
+
+    #[test]
+    fn nan_exemplars_are_sorted() {
+        let mut exemplar = Image::new_cpu(ImageDim::new(8, 8, 1), ImageDataType::Float32);
+        {
+            let mut data = exemplar.data_mut().unwrap();
+            let mut values = data.as_f32_nd_array_mut().unwrap();
+            for (i, v) in values.iter_mut().enumerate() {
+                *v = if i % 3 == 0 { f32::NAN } else { i as f32 / 64. };
+            }
+        }
+
+        let (_, inverse) = TilingAndBlending::precompute(&exemplar).unwrap();
+        let inverse = inverse.data().unwrap();
+        let inverse = inverse.as_f32_nd_array().unwrap();
+
+        // The inverse histogram is sorted, with the NaN pixels at the top
+        let red: Vec<f32> = inverse.slice(ndarray::s![0, 0, .., 0]).to_vec();
+        let finite: Vec<f32> = red.iter().copied().take_while(|v| !v.is_nan()).collect();
+        assert!(finite.windows(2).all(|w| w[0] <= w[1]));
+        assert!(red[finite.len()..].iter().all(|v| v.is_nan()));
+    }
+}
@@ -0,0 +1,124 @@
+use txkit_impl::{Method, ParamsFor};
+
+#[cfg(feature = "cpu")]
+use cgmath::{vec2, InnerSpace};
+#[cfg(feature = "cpu")]
+use txkit_core::{context::CpuContext, image::Image};
+
+#[cfg(feature = "cpu")]
+use crate::cpu::{compute_pixels, Domain};
+#[cfg(feature = "cpu")]
+use crate::patterns::{filtered_edge, tile_id};
+
+/// Truchet tiles: two quarter circles joining the midpoints of adjacent edges
+pub const TRUCHET_PATTERN_ARCS: i32 = 0;
+/// Truchet tiles: square split along a diagonal
+pub const TRUCHET_PATTERN_TRIANGLES: i32 = 1;
+
+#[derive(Clone, Copy, PartialEq, ParamsFor)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[repr(C)]
+#[txkit(program = "TruchetPatternProgram")]
+pub struct TruchetPatternParams {
+    /// pseudo-random seed of the tile rotations
+    pub global_seed: u32,
+    /// number of tiles across the image
+    pub scale: f32,
+    /// tile motif (0: arcs, 1: triangles)
+    #[txkit(values(TRUCHET_PATTERN_ARCS, TRUCHET_PATTERN_TRIANGLES))]
+    pub tile: i32,
+    /// width of the arcs, in tile widths
+    pub line_width: f32,
+    /// width of the anti-aliasing filter, in pixels (0: no anti-aliasing)
+    pub antialiasing: f32,
+}
+
+impl Default for TruchetPatternParams {
+    fn default() -> Self {
+        Self {
+            global_seed: 0,
+            scale: 8.,
+            tile: TRUCHET_PATTERN_ARCS,
+            line_width: 0.1,
+            antialiasing: 1.,
+        }
+    }
+}
+
+/// Truchet tile pattern
+///
+/// Grid of `scale` square tiles across the image, each rotated by a multiple of 90 degrees given
+/// by its random ID. The output is the coverage of the motif, the random ID of the tile in [0,
+/// 1], and the integer coordinates of the tile. The pattern is the same for all layers of 3D
+/// images.
+#[derive(Default, Method)]
+#[txkit(
+    gpu(
+        name = "TruchetPatternGpu",
+        program("shaders/quad.vert", "shaders/truchet_pattern.frag"),
+        method(run = "program", params = "TruchetPatternParams"),
+        dims(Image2D)
+    ),
+    cpu(
+        method(run = "Self::compute_cpu_truchet", params = "TruchetPatternParams"),
+        dims(Image1D, Image2D)
+    ),
+    method()
+)]
+pub struct TruchetPattern {
+    /// parameters used when computing without explicit parameters
+    params: TruchetPatternParams,
+    #[cfg(feature = "gpu")]
+    gpu: Option<TruchetPatternGpu>,
+}
+
+impl TruchetPattern {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[cfg(feature = "cpu")]
+impl TruchetPattern {
+    fn compute_cpu_truchet(
+        &mut self,
+        ctx: &mut CpuContext,
+        tgt: &mut Image,
+        params: &TruchetPatternParams,
+    ) -> txkit_core::Result<()> {
+        if params.tile != TRUCHET_PATTERN_ARCS && params.tile != TRUCHET_PATTERN_TRIANGLES {
+            // Invalid enum value
+            return compute_pixels(ctx, tgt, |_, _| [1., 0., 1., 1.]);
+        }
+
+        let dim = tgt.dim();
+        let w = params.antialiasing * params.scale / dim.width.min(dim.height) as f32;
+
+        let domain = Domain::new(ctx, tgt.dim())?;
+
+        compute_pixels(ctx, tgt, |idx, dim| {
+            let p = domain.uv(idx, dim).truncate() * params.scale;
+            let cell = [p.x.floor() as i32, p.y.floor() as i32];
+            let mut f = vec2(p.x - p.x.floor(), p.y - p.y.floor());
+            let id = tile_id(cell, params.global_seed);
+
+            // Rotate the tile by a random multiple of 90 degrees
+            let rotation = (id * 4.) as i32 & 3;
+            for _ in 0..rotation {
+                f = vec2(1. - f.y, f.x);
+            }
+
+            let value = if params.tile == TRUCHET_PATTERN_ARCS {
+                let d = (f.magnitude() - 0.5)
+                    .abs()
+                    .min(((f - vec2(1., 1.)).magnitude() - 0.5).abs());
+                filtered_edge(0.5 * params.line_width - d, w)
+            } else {
+                filtered_edge((f.x - f.y) / 2f32.sqrt(), w)
+            };
+
+            [value, id, cell[0] as f32, cell[1] as f32]
+        })
+    }
+}
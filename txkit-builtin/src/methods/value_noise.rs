@@ -1,6 +1,21 @@
+use txkit_core::hash::{hash_cell, hash_cell3, hash_cell4, tofloat};
+use txkit_core::lattice::{
+    cell_offset, cell_offset_3d, Lattice, LatticeMode, LatticeMode3D, LatticeMode4D, LatticeSample,
+    LatticeSample3D, LatticeSample4D, LATTICE_DIMENSIONS_2D, LATTICE_DIMENSIONS_3D,
+    LATTICE_DIMENSIONS_4D, STATS_MODE_LOOKAT, STATS_MODE_NORMAL, STATS_MODE_PROCESS,
+};
 use txkit_impl::{Method, ParamsFor};
 
+#[cfg(feature = "cpu")]
+use txkit_core::{context::CpuContext, image::Image};
+
+#[cfg(feature = "cpu")]
+use crate::cpu::{compute_pixels, Domain};
+use crate::shared::{corner_weights, mix};
+
 #[derive(Clone, Copy, PartialEq, ParamsFor)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[repr(C)]
 #[txkit(program = "ValueNoiseProgram")]
 pub struct ValueNoiseParams {
@@ -9,9 +24,17 @@ pub struct ValueNoiseParams {
     /// lattice scale (size in pixels)
     pub scale: f32,
     /// stats mode (0: normal, 1: process, 2: lookat)
+    #[txkit(values(STATS_MODE_NORMAL, STATS_MODE_PROCESS, STATS_MODE_LOOKAT))]
     pub stats_mode: i32,
     /// look-at parameter (if stats_mode == lookat) in [0, 1]^2
     pub stats_look_at: cgmath::Vector2<f32>,
+    /// noise dimensions
+    #[txkit(values(LATTICE_DIMENSIONS_2D, LATTICE_DIMENSIONS_3D, LATTICE_DIMENSIONS_4D))]
+    pub dimensions: i32,
+    /// position along the time axis of 4D noise, in lattice cells
+    pub time: f32,
+    /// period of 4D noise along the time axis, in lattice cells, 0 for no looping
+    pub period: f32,
 }
 
 impl Default for ValueNoiseParams {
@@ -21,10 +44,23 @@ impl Default for ValueNoiseParams {
             scale: 32.,
             stats_mode: 0,
             stats_look_at: cgmath::vec2(0., 0.),
+            dimensions: LATTICE_DIMENSIONS_2D,
+            time: 0.,
+            period: 0.,
         }
     }
 }
 
+/// 2D, 3D and 4D value noise
+///
+/// 2D noise is evaluated over (x, y) and repeated on every layer of 3D images, 3D noise over
+/// (x, y, layer), and 4D noise over (x, y, layer, time). With a non-zero `period`, 4D noise loops
+/// along the time axis: the images computed at `time` and `time + period` are the same.
+///
+/// The CPU implementation is a port of `value_noise.frag`. For `Float32` targets, its results
+/// match the GPU results within 1e-4, the difference coming from the rasterizer's interpolation
+/// of pixel coordinates. For `UInt8` targets, results may differ by one quantization step since
+/// the CPU conversion truncates instead of rounding.
 #[derive(Default, Method)]
 #[txkit(
     gpu(
@@ -32,9 +68,12 @@ impl Default for ValueNoiseParams {
         program("shaders/quad.vert", "shaders/value_noise.frag"),
         method(run = "program", params = "ValueNoiseParams")
     ),
+    cpu(method(run = "Self::compute_cpu_value", params = "ValueNoiseParams")),
     method()
 )]
 pub struct ValueNoise {
+    /// parameters used when computing without explicit parameters
+    params: ValueNoiseParams,
     #[cfg(feature = "gpu")]
     gpu: Option<ValueNoiseGpu>,
 }
@@ -43,4 +82,120 @@ impl ValueNoise {
     pub fn new() -> Self {
         Self::default()
     }
+
+    fn noisehash(p: [u32; 2], seed: u32) -> f32 {
+        tofloat(hash_cell(p, seed))
+    }
+
+    /// Evaluate value noise, in [0, 1]
+    pub(crate) fn noise(s: LatticeSample, lattice: &Lattice) -> f32 {
+        let i = s.ucell();
+        let f = s.position;
+
+        let u = f.map(|x| x * x * (3. - 2. * x));
+        let h = |dx, dy| Self::noisehash(lattice.lattice_loop(cell_offset(i, dx, dy)), s.seed);
+
+        mix(mix(h(0, 0), h(1, 0), u.x), mix(h(0, 1), h(1, 1), u.x), u.y)
+    }
+
+    fn noisehash_3d(p: [u32; 3], seed: u32) -> f32 {
+        tofloat(hash_cell3(p, seed))
+    }
+
+    /// Evaluate 3D value noise, in [0, 1]
+    pub(crate) fn noise_3d(s: LatticeSample3D, lattice: &Lattice) -> f32 {
+        let i = s.ucell();
+        let f = s.position;
+
+        let u = f.map(|x| x * x * (3. - 2. * x));
+        let h = |dx, dy, dz| {
+            Self::noisehash_3d(
+                lattice.lattice_loop_3d(cell_offset_3d(i, dx, dy, dz)),
+                s.seed,
+            )
+        };
+
+        mix(
+            mix(
+                mix(h(0, 0, 0), h(1, 0, 0), u.x),
+                mix(h(0, 1, 0), h(1, 1, 0), u.x),
+                u.y,
+            ),
+            mix(
+                mix(h(0, 0, 1), h(1, 0, 1), u.x),
+                mix(h(0, 1, 1), h(1, 1, 1), u.x),
+                u.y,
+            ),
+            u.z,
+        )
+    }
+
+    fn noisehash_4d([x, y, z, w]: [i32; 4], seed: u32) -> f32 {
+        tofloat(hash_cell4([x as u32, y as u32, z as u32, w as u32], seed))
+    }
+
+    /// Evaluate 4D value noise, in [0, 1]
+    pub(crate) fn noise_4d(s: LatticeSample4D, lattice: &Lattice) -> f32 {
+        let f = s.position;
+        let u = f.map(|x| x * x * (3. - 2. * x));
+
+        // Sum the contributions of the 16 cell corners
+        let mut res = 0.;
+        for c in 0..16 {
+            let o = [c & 1, (c >> 1) & 1, (c >> 2) & 1, (c >> 3) & 1];
+            let w = corner_weights(u, o);
+            let cell = lattice.lattice_loop_4d([
+                s.cell[0] + o[0],
+                s.cell[1] + o[1],
+                s.cell[2] + o[2],
+                s.cell[3] + o[3],
+            ]);
+
+            res += w.x * w.y * w.z * w.w * Self::noisehash_4d(cell, s.seed);
+        }
+
+        res
+    }
+}
+
+#[cfg(feature = "cpu")]
+impl ValueNoise {
+    fn compute_cpu_value(
+        &mut self,
+        ctx: &mut CpuContext,
+        tgt: &mut Image,
+        params: &ValueNoiseParams,
+    ) -> txkit_core::Result<()> {
+        let lattice = Lattice::new(
+            params.scale,
+            params.global_seed,
+            params.stats_mode,
+            params.stats_look_at,
+            tgt.dim(),
+        );
+        let lattice_4d = lattice.with_period(params.period);
+
+        let domain = Domain::new(ctx, tgt.dim())?;
+
+        compute_pixels(ctx, tgt, |idx, dim| {
+            let uv = domain.uv(idx, dim);
+
+            let value = match params.dimensions {
+                LATTICE_DIMENSIONS_2D => {
+                    Self::noise(lattice.sample(uv.truncate(), LatticeMode::Rect2D), &lattice)
+                }
+                LATTICE_DIMENSIONS_3D => {
+                    Self::noise_3d(lattice.sample_3d(uv, LatticeMode3D::Rect3D), &lattice)
+                }
+                LATTICE_DIMENSIONS_4D => {
+                    let s = lattice_4d.sample_4d(uv.extend(params.time), LatticeMode4D::Rect4D);
+                    Self::noise_4d(s, &lattice_4d)
+                }
+                // Invalid enum value
+                _ => return [1., 0., 1., 1.],
+            } * 0.735_820_6;
+
+            [value, value, value, 1.]
+        })
+    }
 }
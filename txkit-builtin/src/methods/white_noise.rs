@@ -1,14 +1,77 @@
+use txkit_core::hash::{hash, tofloat};
 use txkit_core::image::ImageDim;
 use txkit_impl::{Method, ParamsFor};
 
-#[derive(Default, Clone, Copy, PartialEq, ParamsFor)]
+/// White noise: uniform distribution over [0, 1)
+pub const WHITE_NOISE_DISTRIBUTION_UNIFORM: i32 = 0;
+/// White noise: normal distribution with the given mean and standard deviation
+pub const WHITE_NOISE_DISTRIBUTION_GAUSSIAN: i32 = 1;
+/// White noise: exponential distribution with the given rate
+pub const WHITE_NOISE_DISTRIBUTION_EXPONENTIAL: i32 = 2;
+/// White noise: 1 with the given probability, 0 otherwise
+pub const WHITE_NOISE_DISTRIBUTION_BERNOULLI: i32 = 3;
+/// White noise: uniform distribution over the given number of levels in [0, 1]
+pub const WHITE_NOISE_DISTRIBUTION_DISCRETE: i32 = 4;
+
+/// White noise: every channel is sampled independently
+pub const WHITE_NOISE_CHANNELS_INDEPENDENT: i32 = 0;
+/// White noise: all the channels of a pixel share the same sample
+pub const WHITE_NOISE_CHANNELS_SHARED: i32 = 1;
+
+#[derive(Clone, Copy, PartialEq, ParamsFor)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[repr(C)]
 #[txkit(program = "WhiteNoiseProgram")]
 pub struct WhiteNoiseParams {
     /// pseudo-random seed
     pub global_seed: u32,
+    /// distribution of the samples (0: uniform, 1: gaussian, 2: exponential, 3: bernoulli, 4:
+    /// discrete)
+    #[txkit(values(
+        WHITE_NOISE_DISTRIBUTION_UNIFORM,
+        WHITE_NOISE_DISTRIBUTION_GAUSSIAN,
+        WHITE_NOISE_DISTRIBUTION_EXPONENTIAL,
+        WHITE_NOISE_DISTRIBUTION_BERNOULLI,
+        WHITE_NOISE_DISTRIBUTION_DISCRETE
+    ))]
+    pub distribution: i32,
+    /// channel sampling (0: independent, 1: shared)
+    #[txkit(values(WHITE_NOISE_CHANNELS_INDEPENDENT, WHITE_NOISE_CHANNELS_SHARED))]
+    pub channels: i32,
+    /// mean of the gaussian distribution
+    pub mean: f32,
+    /// standard deviation of the gaussian distribution
+    pub std_dev: f32,
+    /// rate of the exponential distribution
+    pub rate: f32,
+    /// probability of 1 for the bernoulli distribution
+    pub probability: f32,
+    /// number of levels of the discrete distribution
+    pub levels: u32,
 }
 
+impl Default for WhiteNoiseParams {
+    fn default() -> Self {
+        Self {
+            global_seed: 0,
+            distribution: WHITE_NOISE_DISTRIBUTION_UNIFORM,
+            channels: WHITE_NOISE_CHANNELS_INDEPENDENT,
+            mean: 0.5,
+            std_dev: 0.125,
+            rate: 4.,
+            probability: 0.5,
+            levels: 2,
+        }
+    }
+}
+
+/// White noise
+///
+/// Independent samples of the chosen distribution for every pixel, and for every channel unless
+/// `channels` is shared. Non-uniform distributions are obtained by inverse transform sampling of
+/// the uniform samples, or with the Box-Muller transform of two samples for the gaussian
+/// distribution. Values outside of [0, 1] are clamped in UInt8 images.
 #[derive(Default, Method)]
 #[txkit(
     gpu(
@@ -20,6 +83,8 @@ pub struct WhiteNoiseParams {
     method()
 )]
 pub struct WhiteNoise {
+    /// parameters used when computing without explicit parameters
+    params: WhiteNoiseParams,
     #[cfg(feature = "gpu")]
     gpu: Option<WhiteNoiseGpu>,
 }
@@ -34,8 +99,14 @@ impl WhiteNoise {
         sz: ImageDim,
         params: &WhiteNoiseParams,
     ) -> f32 {
+        let c = if params.channels == WHITE_NOISE_CHANNELS_SHARED {
+            0
+        } else {
+            l
+        };
+
         let mut x = ((i + j * sz.width + k * sz.width * sz.height) * sz.channels
-            + l
+            + c
             + params.global_seed as usize) as u32;
 
         // Hash
@@ -44,6 +115,32 @@ impl WhiteNoise {
         x = (x >> 16) ^ x;
 
         // Convert to float
-        f32::from_bits(0x7fu32 << 23 | x >> 9) - 1.0f32
+        let u = tofloat(x);
+
+        match params.distribution {
+            WHITE_NOISE_DISTRIBUTION_UNIFORM => u,
+            WHITE_NOISE_DISTRIBUTION_GAUSSIAN => {
+                // Box-Muller transform, with a second sample from the hash of the first one
+                let v = tofloat(hash(x));
+                params.mean
+                    + params.std_dev
+                        * (-2. * (1. - u).ln()).sqrt()
+                        * (2. * std::f32::consts::PI * v).cos()
+            }
+            WHITE_NOISE_DISTRIBUTION_EXPONENTIAL => -(1. - u).ln() / params.rate,
+            WHITE_NOISE_DISTRIBUTION_BERNOULLI => {
+                if u < params.probability {
+                    1.
+                } else {
+                    0.
+                }
+            }
+            WHITE_NOISE_DISTRIBUTION_DISCRETE => {
+                let levels = params.levels.max(2);
+                (u * levels as f32).floor().min((levels - 1) as f32) / (levels - 1) as f32
+            }
+            // Invalid enum value
+            _ => [1., 0., 1., 1.][l],
+        }
     }
 }
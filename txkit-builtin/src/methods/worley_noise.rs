@@ -0,0 +1,255 @@
+use txkit_core::lattice::{STATS_MODE_LOOKAT, STATS_MODE_NORMAL, STATS_MODE_PROCESS};
+use txkit_impl::{Method, ParamsFor};
+
+#[cfg(feature = "cpu")]
+use cgmath::{vec2, Array, InnerSpace, Vector2, Vector3};
+#[cfg(feature = "cpu")]
+use txkit_core::{
+    context::CpuContext,
+    hash::{hash, hash_cell, tofloat},
+    image::Image,
+    lattice::{Lattice, LatticeMode, LatticeSample},
+};
+
+#[cfg(feature = "cpu")]
+use crate::cpu::{compute_pixels, Domain};
+#[cfg(feature = "cpu")]
+use crate::lcg::Lcg;
+#[cfg(feature = "cpu")]
+use crate::points::PointSet;
+
+/// Worley: constant number of feature points per cell
+pub const WORLEY_POINTS_STRAT_POISSON: i32 = 0;
+/// Worley: Poisson-distributed number of feature points per cell
+pub const WORLEY_POINTS_POISSON: i32 = 1;
+/// Worley: rectangular jittered grid
+pub const WORLEY_POINTS_RECT_JITTERED: i32 = 2;
+/// Worley: hexagonal jittered grid
+pub const WORLEY_POINTS_HEX_JITTERED: i32 = 3;
+
+/// Worley: Euclidean distance
+pub const WORLEY_METRIC_EUCLIDEAN: i32 = 0;
+/// Worley: Manhattan distance
+pub const WORLEY_METRIC_MANHATTAN: i32 = 1;
+/// Worley: Chebyshev distance
+pub const WORLEY_METRIC_CHEBYSHEV: i32 = 2;
+
+/// Worley: distance to the closest feature point
+pub const WORLEY_OUTPUT_F1: i32 = 0;
+/// Worley: distance to the second closest feature point
+pub const WORLEY_OUTPUT_F2: i32 = 1;
+/// Worley: difference between the distances to the two closest feature points
+pub const WORLEY_OUTPUT_F2_MINUS_F1: i32 = 2;
+/// Worley: random value in [0, 1] identifying the closest feature point
+pub const WORLEY_OUTPUT_CELL_ID: i32 = 3;
+
+/// Worley: distance returned when no feature point is within the lookahead
+pub const WORLEY_MAX_DISTANCE: f32 = 1e9;
+
+#[derive(Clone, Copy, PartialEq, ParamsFor)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[repr(C)]
+#[txkit(program = "WorleyNoiseProgram")]
+pub struct WorleyNoiseParams {
+    /// pseudo-random seed
+    pub global_seed: u32,
+    /// lattice scale (size in pixels)
+    pub scale: f32,
+    /// stats mode (0: normal, 1: process, 2: lookat)
+    #[txkit(values(STATS_MODE_NORMAL, STATS_MODE_PROCESS, STATS_MODE_LOOKAT))]
+    pub stats_mode: i32,
+    /// look-at parameter (if stats_mode == lookat) in [0, 1]^2
+    pub stats_look_at: cgmath::Vector2<f32>,
+
+    /// cell lookahead: number of cells to check for feature points
+    pub lookahead: i32,
+    /// feature point count per cell
+    pub point_count: i32,
+    /// point distribution
+    #[txkit(values(
+        WORLEY_POINTS_STRAT_POISSON,
+        WORLEY_POINTS_POISSON,
+        WORLEY_POINTS_RECT_JITTERED,
+        WORLEY_POINTS_HEX_JITTERED
+    ))]
+    pub point_distribution: i32,
+    /// jittering amount, 0 = no random, 1 = full subcell random
+    pub jitter_amount: f32,
+    /// max jittering subcells, 0 = no limit
+    pub jitter_max: i32,
+
+    /// distance metric
+    #[txkit(values(
+        WORLEY_METRIC_EUCLIDEAN,
+        WORLEY_METRIC_MANHATTAN,
+        WORLEY_METRIC_CHEBYSHEV
+    ))]
+    pub metric: i32,
+    /// output value
+    #[txkit(values(
+        WORLEY_OUTPUT_F1,
+        WORLEY_OUTPUT_F2,
+        WORLEY_OUTPUT_F2_MINUS_F1,
+        WORLEY_OUTPUT_CELL_ID
+    ))]
+    pub output_mode: i32,
+}
+
+impl Default for WorleyNoiseParams {
+    fn default() -> Self {
+        Self {
+            global_seed: 0,
+            scale: 8.,
+            stats_mode: 0,
+            stats_look_at: cgmath::vec2(0., 0.),
+            lookahead: 1,
+            point_count: 1,
+            point_distribution: WORLEY_POINTS_RECT_JITTERED,
+            jitter_amount: 1.,
+            jitter_max: 0,
+            metric: WORLEY_METRIC_EUCLIDEAN,
+            output_mode: WORLEY_OUTPUT_F1,
+        }
+    }
+}
+
+/// Worley (cellular) noise
+///
+/// Distances are expressed in lattice cells. With the default parameters, each cell holds one
+/// feature point jittered over the whole cell, as in the original cellular texture basis
+/// function. Pixels with no feature point within `lookahead` cells get [WORLEY_MAX_DISTANCE].
+///
+/// Unlike the value, gradient and simplex noises, Worley noise is only defined in 2D: it has no
+/// `dimensions`, `time` and `period` parameters, and can't be animated as a loop. Animated
+/// cellular patterns need feature points in 4D cells, which the point distributions shared with
+/// phasor noise don't support.
+///
+/// The CPU implementation is a port of `worley_noise.frag`.
+#[derive(Default, Method)]
+#[txkit(
+    gpu(
+        name = "WorleyNoiseGpu",
+        program("shaders/quad.vert", "shaders/worley_noise.frag"),
+        method(run = "program", params = "WorleyNoiseParams"),
+        dims(Image2D)
+    ),
+    cpu(
+        method(run = "Self::compute_cpu_worley", params = "WorleyNoiseParams"),
+        dims(Image1D, Image2D)
+    ),
+    method()
+)]
+pub struct WorleyNoise {
+    /// parameters used when computing without explicit parameters
+    params: WorleyNoiseParams,
+    #[cfg(feature = "gpu")]
+    gpu: Option<WorleyNoiseGpu>,
+}
+
+impl WorleyNoise {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[cfg(feature = "cpu")]
+impl WorleyNoise {
+    fn compute_cpu_worley(
+        &mut self,
+        ctx: &mut CpuContext,
+        tgt: &mut Image,
+        params: &WorleyNoiseParams,
+    ) -> txkit_core::Result<()> {
+        let lattice = Lattice::new(
+            params.scale,
+            params.global_seed,
+            params.stats_mode,
+            params.stats_look_at,
+            tgt.dim(),
+        );
+
+        let domain = Domain::new(ctx, tgt.dim())?;
+
+        compute_pixels(ctx, tgt, |idx, dim| {
+            let uv = domain.uv(idx, dim);
+            let s = lattice.sample(uv.truncate(), LatticeMode::Rect2D);
+            Self::noise(s, &lattice, params).extend(1.).into()
+        })
+    }
+
+    fn distance(d: Vector2<f32>, metric: i32) -> f32 {
+        match metric {
+            WORLEY_METRIC_MANHATTAN => d.x.abs() + d.y.abs(),
+            WORLEY_METRIC_CHEBYSHEV => d.x.abs().max(d.y.abs()),
+            _ => d.magnitude(),
+        }
+    }
+
+    /// Find the two closest feature points to a lattice sample
+    ///
+    /// # Returns
+    ///
+    /// Distances to the closest and second closest feature points, and identifier of the
+    /// closest feature point.
+    pub(crate) fn distances(
+        s: LatticeSample,
+        lattice: &Lattice,
+        params: &WorleyNoiseParams,
+    ) -> (f32, f32, u32) {
+        // Distances to the two closest feature points
+        let (mut f1, mut f2) = (WORLEY_MAX_DISTANCE, WORLEY_MAX_DISTANCE);
+        // Identifier of the closest feature point
+        let mut id = 0;
+
+        let [sx, sy] = s.cell;
+        for cx in sx - params.lookahead..=sx + params.lookahead {
+            for cy in sy - params.lookahead..=sy + params.lookahead {
+                // Make sure the noise tiles correctly
+                let [lx, ly] = lattice.lattice_loop_signed([cx, cy]);
+
+                // Compute the position of the current point relative to the target cell
+                let position = s.position - vec2((cx - sx) as f32, (cy - sy) as f32);
+
+                // Seed the random number generator
+                let cell_seed = hash_cell([lx as u32, ly as u32], s.seed);
+                let mut rng = Lcg::new(cell_seed);
+
+                let ps = PointSet::new(
+                    &mut rng,
+                    params.point_distribution,
+                    params.point_count,
+                    params.jitter_max,
+                    params.jitter_amount,
+                );
+
+                for i in 0..ps.count() {
+                    let d = Self::distance(ps.next(&mut rng, i) - position, params.metric);
+
+                    if d < f1 {
+                        f2 = f1;
+                        f1 = d;
+                        id = hash(cell_seed.wrapping_add(i as u32));
+                    } else if d < f2 {
+                        f2 = d;
+                    }
+                }
+            }
+        }
+
+        (f1, f2, id)
+    }
+
+    fn noise(s: LatticeSample, lattice: &Lattice, params: &WorleyNoiseParams) -> Vector3<f32> {
+        let (f1, f2, id) = Self::distances(s, lattice, params);
+
+        match params.output_mode {
+            WORLEY_OUTPUT_F1 => Vector3::from_value(f1),
+            WORLEY_OUTPUT_F2 => Vector3::from_value(f2),
+            WORLEY_OUTPUT_F2_MINUS_F1 => Vector3::from_value(f2 - f1),
+            WORLEY_OUTPUT_CELL_ID => Vector3::from_value(tofloat(id)),
+            // Invalid enum value
+            _ => Vector3::new(1., 0., 1.),
+        }
+    }
+}
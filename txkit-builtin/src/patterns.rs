@@ -0,0 +1,44 @@
+//! CPU port of the shared pattern definitions in `patterns.glsl`
+
+use txkit_core::hash::{hash2, tofloat};
+
+/// Compute the random ID of a tile, in [0, 1]
+///
+/// # Parameters
+///
+/// * `cell`: integer coordinates of the tile
+/// * `seed`: pseudo-random seed
+pub fn tile_id(cell: [i32; 2], seed: u32) -> f32 {
+    tofloat(hash2([cell[0] as u32, cell[1] as u32], seed)[0])
+}
+
+/// Average of a pulse train over `[x - w / 2, x + w / 2]`
+///
+/// # Parameters
+///
+/// * `x`: coordinate along the pulse train, in periods
+/// * `duty`: fraction of the period where the pulse is 1
+/// * `w`: width of the box filter, in periods
+pub fn filtered_pulse(x: f32, duty: f32, w: f32) -> f32 {
+    if w <= 0. {
+        return if x - x.floor() < duty { 1. } else { 0. };
+    }
+
+    // Integral of the pulse train
+    let integral = |t: f32| t.floor() * duty + (t - t.floor()).min(duty);
+    (integral(x + 0.5 * w) - integral(x - 0.5 * w)) / w
+}
+
+/// Coverage of the inside of a box-filtered edge
+///
+/// # Parameters
+///
+/// * `d`: signed distance to the edge, positive inside
+/// * `w`: width of the box filter, in distance units
+pub fn filtered_edge(d: f32, w: f32) -> f32 {
+    if w <= 0. {
+        return if d >= 0. { 1. } else { 0. };
+    }
+
+    (d / w + 0.5).clamp(0., 1.)
+}
@@ -0,0 +1,122 @@
+//! CPU port of the point distributions in `points.glsl`
+
+use cgmath::{vec2, Vector2};
+
+use crate::lcg::Lcg;
+
+// POINTS_STRAT_POISSON (0), a constant number of points per cell, is the default behavior
+
+/// Poisson-distributed number of points per cell
+pub const POINTS_POISSON: i32 = 1;
+/// Rectangular jittered grid
+pub const POINTS_RECT_JITTERED: i32 = 2;
+/// Hexagonal jittered grid
+pub const POINTS_HEX_JITTERED: i32 = 3;
+
+/// Set of points in a lattice cell
+#[derive(Debug, Clone, Copy)]
+pub struct PointSet {
+    /// Distribution of the points, see `POINTS_*`
+    distribution: i32,
+    /// Number of points in the cell
+    count: i32,
+    /// Number of subcells in jittered grids
+    grid: [i32; 2],
+    /// Jittering amount, 0 = no random, 1 = full subcell random
+    jitter_amount: f32,
+}
+
+impl PointSet {
+    /// Initialize the set of points in a lattice cell
+    ///
+    /// # Parameters
+    ///
+    /// * `rng`: random number generator seeded for the current cell
+    /// * `distribution`: distribution of the points, see `POINTS_*`
+    /// * `count`: requested number of points
+    /// * `jitter_max`: max jittering subcells, 0 = no limit
+    /// * `jitter_amount`: jittering amount
+    pub fn new(
+        rng: &mut Lcg,
+        distribution: i32,
+        count: i32,
+        jitter_max: i32,
+        jitter_amount: f32,
+    ) -> Self {
+        let mut ps = Self {
+            distribution,
+            count,
+            grid: [1, 1],
+            jitter_amount,
+        };
+
+        if distribution == POINTS_POISSON {
+            ps.count = rng.poisson(count as f32);
+        } else if ps.is_jittered() {
+            let bx = (count as f32).sqrt() as i32;
+            ps.grid[0] = if jitter_max == 0 {
+                bx
+            } else {
+                jitter_max.min(bx)
+            }
+            .max(1);
+            ps.grid[1] = if jitter_max == 0 {
+                count / ps.grid[0]
+            } else {
+                jitter_max.min(count / ps.grid[0])
+            };
+
+            let tc = ps.grid[0] * ps.grid[1];
+            ps.count = if tc > 0 { (count / tc) * tc } else { 0 };
+        }
+
+        ps
+    }
+
+    /// Number of points in the cell
+    pub fn count(&self) -> i32 {
+        self.count
+    }
+
+    fn is_jittered(&self) -> bool {
+        self.distribution == POINTS_RECT_JITTERED || self.distribution == POINTS_HEX_JITTERED
+    }
+
+    /// Generate the next point of the set
+    ///
+    /// # Parameters
+    ///
+    /// * `rng`: random number generator used to initialize the set
+    /// * `i`: index of the point, in `[0, self.count())`
+    ///
+    /// # Returns
+    ///
+    /// Position of the point in the cell, in [0, 1]^2.
+    pub fn next(&self, rng: &mut Lcg, i: i32) -> Vector2<f32> {
+        let mut pos = vec2(0.5, 0.5)
+            + (vec2(rng.next_01(), rng.next_01()) - vec2(0.5, 0.5)) * self.jitter_amount;
+
+        if self.is_jittered() {
+            let tc = self.grid[0] * self.grid[1];
+            let col = (i % tc) % self.grid[0];
+            let row = (i % tc) / self.grid[0];
+
+            if self.distribution == POINTS_HEX_JITTERED {
+                // Triangle transform
+                pos = vec2(0.25 * (pos.x - pos.y), 0.5 * (pos.x + pos.y).abs());
+
+                // Offset one every two triangles
+                if (self.count + row) % 2 == 0 {
+                    pos = vec2(pos.x + 0.5, 1. - pos.y);
+                }
+            }
+
+            pos = vec2(
+                (pos.x + col as f32) / self.grid[0] as f32,
+                (pos.y + row as f32) / self.grid[1] as f32,
+            );
+        }
+
+        pos
+    }
+}
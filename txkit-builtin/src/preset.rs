@@ -0,0 +1,430 @@
+//! Method presets stored as JSON, TOML or RON files
+//!
+//! A preset names a builtin method and the values of its parameters:
+//!
+//! ```toml
+//! method = "phasor_noise"
+//!
+//! [params]
+//! scale = 16.0
+//! noise_profile = 3
+//!
+//! [params.io.texture_bindings]
+//! 0 = "orientation"
+//! ```
+//!
+//! Missing parameters take their default values. Image bindings refer to images by name, and
+//! must be resolved with [Preset::resolve] before computing.
+//!
+//! Methods with inputs computed by inner methods have their parameters and the presets of their
+//! inputs nested:
+//!
+//! ```toml
+//! method = "domain_warp"
+//!
+//! [params.params]
+//! strength = 0.2
+//!
+//! [params.source]
+//! method = "gradient_noise"
+//!
+//! [params.displacement]
+//! method = "fractal_noise"
+//! params = { scale = 4.0 }
+//! ```
+
+use std::any::Any;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use txkit_core::io::ImageBinding;
+use txkit_core::method::{Method, MethodParams, ParamValue};
+use txkit_core::{Error, Result};
+
+use crate::methods::*;
+
+/// Parameters of a builtin method, tagged with the method name
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "method", content = "params", rename_all = "snake_case")]
+pub enum Preset {
+    Debug(DebugParams),
+    WhiteNoise(WhiteNoiseParams),
+    ValueNoise(ValueNoiseParams),
+    GradientNoise(GradientNoiseParams),
+    SimplexNoise(SimplexNoiseParams),
+    PhasorNoise(PhasorNoiseParams),
+    WorleyNoise(WorleyNoiseParams),
+    FractalNoise(FractalNoiseParams),
+    DomainWarp(DomainWarpPreset),
+    CurlNoise(CurlNoiseParams),
+    BlueNoise(BlueNoiseParams),
+    GaborNoise(GaborNoiseParams),
+    SparseConvolutionNoise(SparseConvolutionNoiseParams),
+    TilingAndBlending(TilingAndBlendingParams),
+    CheckerPattern(CheckerPatternParams),
+    StripePattern(StripePatternParams),
+    BrickPattern(BrickPatternParams),
+    PolygonPattern(PolygonPatternParams),
+    TruchetPattern(TruchetPatternParams),
+    #[serde(rename = "sdf_2d")]
+    Sdf2d(Sdf2dParams),
+    DistanceTransform(DistanceTransformParams),
+    NormalMap(NormalMapParams),
+    Curvature(CurvatureParams),
+    AmbientOcclusion(AmbientOcclusionParams),
+    Erosion(ErosionParams),
+}
+
+/// Parameters of a domain warp, with the presets of its inputs
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DomainWarpPreset {
+    /// parameters of the domain warp
+    #[serde(default)]
+    pub params: DomainWarpParams,
+    /// preset of the method computing the warped image
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<Box<Preset>>,
+    /// preset of the method computing the displacement field
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub displacement: Option<Box<Preset>>,
+}
+
+impl Preset {
+    /// Load a preset file
+    ///
+    /// The format is detected from the file extension: `json`, `toml` or `ron`.
+    ///
+    /// # Parameters
+    ///
+    /// * `path`: path to the preset file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| Error::PresetLoadFailed(format!("{}: {}", path.display(), e)))?;
+
+        let result = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => serde_json::from_str(&contents).map_err(|e| e.to_string()),
+            Some("toml") => toml::from_str(&contents).map_err(|e| e.to_string()),
+            Some("ron") => ron::from_str(&contents).map_err(|e| e.to_string()),
+            _ => Err("unknown preset format, expected json, toml or ron".to_owned()),
+        };
+
+        result.map_err(|e| Error::PresetLoadFailed(format!("{}: {}", path.display(), e)))
+    }
+
+    /// Name of the method in the registry returned by [new_registry]
+    pub fn method_name(&self) -> &'static str {
+        match self {
+            Self::Debug(_) => "debug",
+            Self::WhiteNoise(_) => "white_noise",
+            Self::ValueNoise(_) => "value_noise",
+            Self::GradientNoise(_) => "gradient_noise",
+            Self::SimplexNoise(_) => "simplex_noise",
+            Self::PhasorNoise(_) => "phasor_noise",
+            Self::WorleyNoise(_) => "worley_noise",
+            Self::FractalNoise(_) => "fractal_noise",
+            Self::DomainWarp(_) => "domain_warp",
+            Self::CurlNoise(_) => "curl_noise",
+            Self::BlueNoise(_) => "blue_noise",
+            Self::GaborNoise(_) => "gabor_noise",
+            Self::SparseConvolutionNoise(_) => "sparse_convolution_noise",
+            Self::TilingAndBlending(_) => "tiling_and_blending",
+            Self::CheckerPattern(_) => "checker_pattern",
+            Self::StripePattern(_) => "stripe_pattern",
+            Self::BrickPattern(_) => "brick_pattern",
+            Self::PolygonPattern(_) => "polygon_pattern",
+            Self::TruchetPattern(_) => "truchet_pattern",
+            Self::Sdf2d(_) => "sdf_2d",
+            Self::DistanceTransform(_) => "distance_transform",
+            Self::NormalMap(_) => "normal_map",
+            Self::Curvature(_) => "curvature",
+            Self::AmbientOcclusion(_) => "ambient_occlusion",
+            Self::Erosion(_) => "erosion",
+        }
+    }
+
+    /// Replace the named image bindings of this preset with the images they refer to
+    ///
+    /// # Parameters
+    ///
+    /// * `resolver`: function returning the binding for an image name, or `None` if there is no
+    ///   image with this name
+    pub fn resolve(
+        &mut self,
+        mut resolver: impl FnMut(&str) -> Option<ImageBinding>,
+    ) -> Result<()> {
+        match self {
+            Self::PhasorNoise(params) => params.io.resolve(resolver),
+            Self::DomainWarp(preset) => preset.resolve(&mut resolver),
+            Self::GaborNoise(params) => params.io.resolve(resolver),
+            Self::TilingAndBlending(params) => params.io.resolve(resolver),
+            Self::DistanceTransform(params) => params.io.resolve(resolver),
+            Self::NormalMap(params) => params.io.resolve(resolver),
+            Self::Curvature(params) => params.io.resolve(resolver),
+            Self::AmbientOcclusion(params) => params.io.resolve(resolver),
+            Self::Erosion(params) => params.io.resolve(resolver),
+            _ => Ok(()),
+        }
+    }
+
+    /// Set the value of a parameter of this preset by name
+    ///
+    /// # Parameters
+    ///
+    /// * `name`: name of the parameter
+    /// * `value`: new value, which must have the same type as the parameter
+    pub fn set_param(&mut self, name: &str, value: ParamValue) -> Result<()> {
+        match self {
+            Self::Debug(params) => params.set_param(name, value),
+            Self::WhiteNoise(params) => params.set_param(name, value),
+            Self::ValueNoise(params) => params.set_param(name, value),
+            Self::GradientNoise(params) => params.set_param(name, value),
+            Self::SimplexNoise(params) => params.set_param(name, value),
+            Self::PhasorNoise(params) => params.set_param(name, value),
+            Self::WorleyNoise(params) => params.set_param(name, value),
+            Self::FractalNoise(params) => params.set_param(name, value),
+            Self::DomainWarp(preset) => preset.params.set_param(name, value),
+            Self::CurlNoise(params) => params.set_param(name, value),
+            Self::BlueNoise(params) => params.set_param(name, value),
+            Self::GaborNoise(params) => params.set_param(name, value),
+            Self::SparseConvolutionNoise(params) => params.set_param(name, value),
+            Self::TilingAndBlending(params) => params.set_param(name, value),
+            Self::CheckerPattern(params) => params.set_param(name, value),
+            Self::StripePattern(params) => params.set_param(name, value),
+            Self::BrickPattern(params) => params.set_param(name, value),
+            Self::PolygonPattern(params) => params.set_param(name, value),
+            Self::TruchetPattern(params) => params.set_param(name, value),
+            Self::Sdf2d(params) => params.set_param(name, value),
+            Self::DistanceTransform(params) => params.set_param(name, value),
+            Self::NormalMap(params) => params.set_param(name, value),
+            Self::Curvature(params) => params.set_param(name, value),
+            Self::AmbientOcclusion(params) => params.set_param(name, value),
+            Self::Erosion(params) => params.set_param(name, value),
+        }
+    }
+
+    /// Get the value of a parameter of this preset by name
+    ///
+    /// # Parameters
+    ///
+    /// * `name`: name of the parameter
+    pub fn get_param(&self, name: &str) -> Result<ParamValue> {
+        match self {
+            Self::Debug(params) => params.get_param(name),
+            Self::WhiteNoise(params) => params.get_param(name),
+            Self::ValueNoise(params) => params.get_param(name),
+            Self::GradientNoise(params) => params.get_param(name),
+            Self::SimplexNoise(params) => params.get_param(name),
+            Self::PhasorNoise(params) => params.get_param(name),
+            Self::WorleyNoise(params) => params.get_param(name),
+            Self::FractalNoise(params) => params.get_param(name),
+            Self::DomainWarp(preset) => preset.params.get_param(name),
+            Self::CurlNoise(params) => params.get_param(name),
+            Self::BlueNoise(params) => params.get_param(name),
+            Self::GaborNoise(params) => params.get_param(name),
+            Self::SparseConvolutionNoise(params) => params.get_param(name),
+            Self::TilingAndBlending(params) => params.get_param(name),
+            Self::CheckerPattern(params) => params.get_param(name),
+            Self::StripePattern(params) => params.get_param(name),
+            Self::BrickPattern(params) => params.get_param(name),
+            Self::PolygonPattern(params) => params.get_param(name),
+            Self::TruchetPattern(params) => params.get_param(name),
+            Self::Sdf2d(params) => params.get_param(name),
+            Self::DistanceTransform(params) => params.get_param(name),
+            Self::NormalMap(params) => params.get_param(name),
+            Self::Curvature(params) => params.get_param(name),
+            Self::AmbientOcclusion(params) => params.get_param(name),
+            Self::Erosion(params) => params.get_param(name),
+        }
+    }
+
+    /// Split this preset into the method name and its parameters
+    ///
+    /// The parameters can be passed to [Method::compute] on the method built by
+    /// [MethodRegistry::build](txkit_core::method::MethodRegistry::build). The presets of the
+    /// inputs are dropped, see [Preset::build] to build them as well.
+    pub fn into_parts(self) -> (&'static str, Box<dyn Any>) {
+        let name = self.method_name();
+
+        let params: Box<dyn Any> = match self {
+            Self::Debug(params) => Box::new(params),
+            Self::WhiteNoise(params) => Box::new(params),
+            Self::ValueNoise(params) => Box::new(params),
+            Self::GradientNoise(params) => Box::new(params),
+            Self::SimplexNoise(params) => Box::new(params),
+            Self::PhasorNoise(params) => Box::new(params),
+            Self::WorleyNoise(params) => Box::new(params),
+            Self::FractalNoise(params) => Box::new(params),
+            Self::DomainWarp(preset) => Box::new(preset.params),
+            Self::CurlNoise(params) => Box::new(params),
+            Self::BlueNoise(params) => Box::new(params),
+            Self::GaborNoise(params) => Box::new(params),
+            Self::SparseConvolutionNoise(params) => Box::new(params),
+            Self::TilingAndBlending(params) => Box::new(params),
+            Self::CheckerPattern(params) => Box::new(params),
+            Self::StripePattern(params) => Box::new(params),
+            Self::BrickPattern(params) => Box::new(params),
+            Self::PolygonPattern(params) => Box::new(params),
+            Self::TruchetPattern(params) => Box::new(params),
+            Self::Sdf2d(params) => Box::new(params),
+            Self::DistanceTransform(params) => Box::new(params),
+            Self::NormalMap(params) => Box::new(params),
+            Self::Curvature(params) => Box::new(params),
+            Self::AmbientOcclusion(params) => Box::new(params),
+            Self::Erosion(params) => Box::new(params),
+        };
+
+        (name, params)
+    }
+
+    /// Build the method of this preset, with its parameters and inputs set
+    ///
+    /// Image bindings should be resolved with [Preset::resolve] first.
+    pub fn build(self) -> Result<Box<dyn Method>> {
+        let inputs = match &self {
+            Self::DomainWarp(preset) => vec![
+                ("source", preset.source.clone()),
+                ("displacement", preset.displacement.clone()),
+            ],
+            _ => Vec::new(),
+        };
+
+        let (name, params) = self.into_parts();
+        let mut method = new_registry().build(name).ok_or(Error::MethodNotFound)?;
+        method.set_params(params.as_ref())?;
+
+        for (input, preset) in inputs {
+            if let Some(preset) = preset {
+                method.set_input(input, Some(preset.build()?))?;
+            }
+        }
+
+        Ok(method)
+    }
+}
+
+impl DomainWarpPreset {
+    /// Resolve the image bindings of the parameters and of the inputs, see [Preset::resolve]
+    fn resolve(&mut self, resolver: &mut dyn FnMut(&str) -> Option<ImageBinding>) -> Result<()> {
+        self.params.io.resolve(&mut *resolver)?;
+
+        for preset in self.source.iter_mut().chain(self.displacement.iter_mut()) {
+            preset.resolve(&mut *resolver)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cgmath::vec2;
+    use txkit_core::io::ImageIo;
+
+    use super::*;
+
+    /// Presets with non-default values, nested structures and named image bindings
+    fn presets() -> Vec<Preset> {
+        let mut phasor = PhasorNoiseParams {
+            scale: 16.,
+            noise_profile: 3,
+            ..Default::default()
+        };
+        phasor
+            .io
+            .set_texture_binding(0, ImageBinding::Named("orientation".to_owned()));
+
+        let mut shapes = Sdf2dShapes::new();
+        shapes.push(
+            Sdf2dPrimitive::Circle {
+                center: vec2(0.5, 0.5),
+                radius: 0.25,
+            },
+            SDF_2D_OP_UNION,
+            0.,
+        );
+        shapes.push(
+            Sdf2dPrimitive::Polygon {
+                vertices: vec![vec2(0.1, 0.1), vec2(0.9, 0.2), vec2(0.4, 0.8)],
+            },
+            SDF_2D_OP_SUBTRACTION,
+            0.05,
+        );
+
+        let mut io = ImageIo::new();
+        io.set_texture_binding(0, ImageBinding::Named("height".to_owned()));
+
+        vec![
+            Preset::ValueNoise(ValueNoiseParams {
+                global_seed: 7,
+                period: 2.5,
+                ..Default::default()
+            }),
+            Preset::PhasorNoise(phasor),
+            Preset::Sdf2d(Sdf2dParams {
+                shapes: Box::new(shapes),
+                ..Default::default()
+            }),
+            Preset::DomainWarp(DomainWarpPreset {
+                params: DomainWarpParams {
+                    strength: 0.2,
+                    ..Default::default()
+                },
+                source: Some(Box::new(Preset::GradientNoise(Default::default()))),
+                displacement: Some(Box::new(Preset::FractalNoise(FractalNoiseParams {
+                    scale: 4.,
+                    ..Default::default()
+                }))),
+            }),
+            Preset::Erosion(ErosionParams {
+                iterations: 3,
+                io: Box::new(io),
+                ..Default::default()
+            }),
+        ]
+    }
+
+    #[test]
+    fn round_trip_json() {
+        for preset in presets() {
+            let text = serde_json::to_string(&preset).unwrap();
+            let loaded: Preset = serde_json::from_str(&text).unwrap();
+            assert!(loaded == preset, "{}: {}", preset.method_name(), text);
+        }
+    }
+
+    #[test]
+    fn round_trip_toml() {
+        for preset in presets() {
+            // Serializing through a value writes tables after the other fields, as TOML requires
+            let text = toml::Value::try_from(&preset).unwrap().to_string();
+            let loaded: Preset = toml::from_str(&text).unwrap();
+            assert!(loaded == preset, "{}: {}", preset.method_name(), text);
+        }
+    }
+
+    #[test]
+    fn build_sets_params_and_inputs() {
+        let preset = presets()
+            .into_iter()
+            .find(|preset| preset.method_name() == "domain_warp")
+            .unwrap();
+
+        let mut method = preset.build().unwrap();
+        assert_eq!(method.get_param("strength").unwrap(), ParamValue::F32(0.2));
+        assert!(matches!(
+            method.set_input("not_an_input", None),
+            Err(Error::ParamNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn round_trip_ron() {
+        for preset in presets() {
+            let text = ron::to_string(&preset).unwrap();
+            let loaded: Preset = ron::from_str(&text).unwrap();
+            assert!(loaded == preset, "{}: {}", preset.method_name(), text);
+        }
+    }
+}
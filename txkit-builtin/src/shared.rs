@@ -0,0 +1,42 @@
+//! CPU port of the shared shader utilities in `shared.glsl`
+//!
+//! Hashing functions are in [txkit_core::hash], since the lattice sampler in
+//! [txkit_core::lattice] needs them too.
+
+use cgmath::{vec3, vec4, Vector3, Vector4};
+use txkit_core::image::ImageDim;
+
+/// Compute the interpolated `uv` coordinates of the center of a pixel, as produced by `quad.vert`
+///
+/// # Parameters
+///
+/// * `(k, j, i)`: pixel coordinates (layer, row, column)
+/// * `dim`: dimensions of the target image
+pub fn pixel_uv((k, j, i): (usize, usize, usize), dim: ImageDim) -> Vector3<f32> {
+    vec3(
+        (i as f32 + 0.5) / dim.width as f32,
+        (j as f32 + 0.5) / dim.height as f32,
+        (k as f32 + 0.5) / dim.depth as f32,
+    )
+}
+
+/// Convert a float value from [-1, 1] to [0, 1]
+pub fn to01(x: f32) -> f32 {
+    0.5 * x + 0.5
+}
+
+/// Linear interpolation, as GLSL's `mix`
+pub fn mix(x: f32, y: f32, a: f32) -> f32 {
+    x * (1. - a) + y * a
+}
+
+/// Interpolation weights of a lattice cell corner along each axis, as `mix(1. - u, u, vec4(o))`
+///
+/// # Parameters
+///
+/// * `u`: interpolation factors along each axis
+/// * `o`: offset of the corner in the cell, 0 or 1 along each axis
+pub fn corner_weights(u: Vector4<f32>, o: [i32; 4]) -> Vector4<f32> {
+    let w = |u: f32, o: i32| if o == 1 { u } else { 1. - u };
+    vec4(w(u.x, o[0]), w(u.y, o[1]), w(u.z, o[2]), w(u.w, o[3]))
+}
@@ -0,0 +1,119 @@
+//! Comparison of the CPU and GPU implementations of the lattice noises
+//!
+//! The tests are skipped when no GPU context can be created, e.g. on machines without a display.
+
+#![cfg(all(feature = "cpu", feature = "gpu"))]
+
+use txkit_builtin::methods::{
+    new_registry, GRADIENT_OUTPUT_DERIVATIVES, GRADIENT_OUTPUT_VALUE, SIMPLEX_OUTPUT_DERIVATIVES,
+    SIMPLEX_OUTPUT_VALUE,
+};
+use txkit_core::context::Context;
+use txkit_core::image::{Image, ImageDataType, ImageDim};
+use txkit_core::lattice::{LATTICE_DIMENSIONS_2D, LATTICE_DIMENSIONS_3D, LATTICE_DIMENSIONS_4D};
+use txkit_core::method::{Method, ParamValue};
+
+/// Tolerance documented on the CPU implementations, for `Float32` targets
+///
+/// Derivatives have a wider range than values, so the tolerance is relative beyond 1.
+const TOLERANCE: f32 = 1e-4;
+
+/// Compute a method into a `Float32` image and return its values
+fn render(
+    ctx: &mut Context,
+    method: &str,
+    params: &[(&str, ParamValue)],
+    dim: ImageDim,
+) -> Vec<f32> {
+    let registry = new_registry();
+    let mut method = registry.build(method).expect("unknown method");
+    for (name, value) in params {
+        method
+            .set_param(name, *value)
+            .expect("failed to set parameter");
+    }
+
+    let mut tgt = match ctx {
+        Context::Gpu(_) => {
+            Image::new_gpu_3d(dim, ImageDataType::Float32, ctx).expect("failed to create GPU image")
+        }
+        Context::Cpu(_) => Image::new_cpu(dim, ImageDataType::Float32),
+    };
+
+    method
+        .compute(ctx, &mut tgt, None)
+        .expect("failed to compute method");
+    tgt.download().expect("failed to download image");
+
+    let data = tgt.data().expect("failed to map image");
+    let values = data.as_f32_nd_array().unwrap().iter().copied().collect();
+    values
+}
+
+#[test]
+fn lattice_noises_match() {
+    let mut gpu = match Context::new_gpu() {
+        Ok(ctx) => ctx,
+        Err(error) => {
+            eprintln!("skipping CPU/GPU comparison: {}", error);
+            return;
+        }
+    };
+
+    let mut cpu = Context::new_cpu().expect("failed to create CPU context");
+    let dim = ImageDim::new_3d(64, 48, 3, 4);
+
+    // Output modes of each method: only gradient and simplex noise output derivatives
+    let methods: [(&str, &[i32]); 3] = [
+        ("value_noise", &[0]),
+        (
+            "gradient_noise",
+            &[GRADIENT_OUTPUT_VALUE, GRADIENT_OUTPUT_DERIVATIVES],
+        ),
+        (
+            "simplex_noise",
+            &[SIMPLEX_OUTPUT_VALUE, SIMPLEX_OUTPUT_DERIVATIVES],
+        ),
+    ];
+
+    for &(method, output_modes) in &methods {
+        for &output_mode in output_modes {
+            for &dimensions in &[
+                LATTICE_DIMENSIONS_2D,
+                LATTICE_DIMENSIONS_3D,
+                LATTICE_DIMENSIONS_4D,
+            ] {
+                let mut params = vec![
+                    ("global_seed", ParamValue::U32(42)),
+                    ("scale", ParamValue::F32(8.)),
+                    ("dimensions", ParamValue::I32(dimensions)),
+                    ("time", ParamValue::F32(1.5)),
+                    ("period", ParamValue::F32(4.)),
+                ];
+
+                if method != "value_noise" {
+                    params.push(("output_mode", ParamValue::I32(output_mode)));
+                }
+
+                let expected = render(&mut gpu, method, &params, dim);
+                let actual = render(&mut cpu, method, &params, dim);
+
+                let error = expected
+                    .iter()
+                    .zip(&actual)
+                    .map(|(a, b)| (a - b).abs() / a.abs().max(1.))
+                    .fold(0f32, f32::max);
+
+                assert!(
+                    error <= TOLERANCE,
+                    "{} ({}D, output mode {}): max error {} > {}",
+                    method,
+                    dimensions,
+                    output_mode,
+                    error,
+                    TOLERANCE
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,173 @@
+//! Analytic derivatives of the noise methods, compared against finite differences
+
+#![cfg(feature = "cpu")]
+
+use ndarray::Array4;
+
+use txkit_builtin::methods::*;
+use txkit_core::context::Context;
+use txkit_core::image::{Image, ImageDataType, ImageDim};
+use txkit_core::lattice::{LATTICE_DIMENSIONS_2D, LATTICE_DIMENSIONS_3D, LATTICE_DIMENSIONS_4D};
+use txkit_core::method::Method;
+
+/// Lattice scale of the rendered noises
+const SCALE: f32 = 2.;
+
+/// Compute a method into a `Float32` CPU image and return its values, decoded from [0, 1] to
+/// [-1, 1]
+fn render(ctx: &mut Context, method: &mut dyn Method, dim: ImageDim) -> Array4<f32> {
+    let mut tgt = Image::new_cpu(dim, ImageDataType::Float32);
+    method
+        .compute(ctx, &mut tgt, None)
+        .expect("failed to compute method");
+
+    let data = tgt.data().expect("failed to map image");
+    let values = data.as_f32_nd_array().unwrap().map(|v| 2. * v - 1.);
+    values
+}
+
+/// Check the derivatives output of a noise method against central differences of its value
+/// output, along the x and y axes
+fn check_derivatives(method: &str, value_mode: i32, derivatives_mode: i32) {
+    let mut ctx = Context::new_cpu().unwrap();
+    let dim = ImageDim::new(256, 256, 4);
+
+    for &dimensions in &[
+        LATTICE_DIMENSIONS_2D,
+        LATTICE_DIMENSIONS_3D,
+        LATTICE_DIMENSIONS_4D,
+    ] {
+        let mut noise = new_registry().build(method).unwrap();
+        noise.set_param("global_seed", 42u32).unwrap();
+        noise.set_param("scale", SCALE).unwrap();
+        noise.set_param("dimensions", dimensions).unwrap();
+        noise.set_param("time", 1.5f32).unwrap();
+
+        noise.set_param("output_mode", value_mode).unwrap();
+        let values = render(&mut ctx, noise.as_mut(), dim);
+        noise.set_param("output_mode", derivatives_mode).unwrap();
+        let derivatives = render(&mut ctx, noise.as_mut(), dim);
+
+        // Derivatives are in lattice units, pixels are SCALE / size lattice units apart
+        let hx = 2. * SCALE / dim.width as f32;
+        let hy = 2. * SCALE / dim.height as f32;
+
+        let mut error = 0f32;
+        let mut largest = 0f32;
+        for j in 1..dim.height - 1 {
+            for i in 1..dim.width - 1 {
+                let dx = (values[(0, j, i + 1, 0)] - values[(0, j, i - 1, 0)]) / hx;
+                let dy = (values[(0, j + 1, i, 0)] - values[(0, j - 1, i, 0)]) / hy;
+
+                for &(expected, actual) in &[
+                    (dx, derivatives[(0, j, i, 1)]),
+                    (dy, derivatives[(0, j, i, 2)]),
+                ] {
+                    error = error.max((expected - actual).abs() / expected.abs().max(1.));
+                    largest = largest.max(actual.abs());
+                }
+
+                // The value is written alongside the derivatives
+                assert!((values[(0, j, i, 0)] - derivatives[(0, j, i, 0)]).abs() < 1e-5);
+            }
+        }
+
+        assert!(
+            largest > 0.1,
+            "{} ({}D): flat derivatives",
+            method,
+            dimensions
+        );
+        assert!(
+            error < 1e-2,
+            "{} ({}D): max error {}",
+            method,
+            dimensions,
+            error
+        );
+    }
+}
+
+#[test]
+fn gradient_noise_derivatives_match_finite_differences() {
+    check_derivatives(
+        "gradient_noise",
+        GRADIENT_OUTPUT_VALUE,
+        GRADIENT_OUTPUT_DERIVATIVES,
+    );
+}
+
+#[test]
+fn simplex_noise_derivatives_match_finite_differences() {
+    check_derivatives(
+        "simplex_noise",
+        SIMPLEX_OUTPUT_VALUE,
+        SIMPLEX_OUTPUT_DERIVATIVES,
+    );
+}
+
+/// Check that the finite-difference divergence of a curl noise field stays near zero
+fn check_divergence(basis: i32, dim: ImageDim) {
+    const AMPLITUDE: f32 = 0.25;
+
+    let mut ctx = Context::new_cpu().unwrap();
+
+    let mut curl = CurlNoise::new();
+    curl.set_param("global_seed", 42u32).unwrap();
+    curl.set_param("scale", SCALE).unwrap();
+    curl.set_param("basis", basis).unwrap();
+    curl.set_param("amplitude", AMPLITUDE).unwrap();
+
+    let field = render(&mut ctx, &mut curl, dim) / AMPLITUDE;
+
+    // Field components are in lattice units, voxels are SCALE / size lattice units apart
+    let h = [
+        2. * SCALE / dim.width as f32,
+        2. * SCALE / dim.height as f32,
+        2. * SCALE / dim.depth as f32,
+    ];
+    let layers = if dim.depth > 1 {
+        1..dim.depth - 1
+    } else {
+        0..1
+    };
+
+    let mut divergence = 0f32;
+    let mut largest = 0f32;
+    for k in layers {
+        for j in 1..dim.height - 1 {
+            for i in 1..dim.width - 1 {
+                let mut partials = vec![
+                    (field[(k, j, i + 1, 0)] - field[(k, j, i - 1, 0)]) / h[0],
+                    (field[(k, j + 1, i, 1)] - field[(k, j - 1, i, 1)]) / h[1],
+                ];
+
+                if dim.depth > 1 {
+                    partials.push((field[(k + 1, j, i, 2)] - field[(k - 1, j, i, 2)]) / h[2]);
+                }
+
+                divergence = divergence.max(partials.iter().sum::<f32>().abs());
+                largest = partials.iter().fold(largest, |m, d| m.max(d.abs()));
+            }
+        }
+    }
+
+    // The divergence is only zero up to the truncation error of the finite differences, so it
+    // is compared to the partial derivatives it sums
+    assert!(largest > 1., "curl noise ({:?}): flat field", dim);
+    assert!(
+        divergence < 5e-2 * largest,
+        "curl noise ({:?}): max divergence {}, max partial derivative {}",
+        dim,
+        divergence,
+        largest
+    );
+}
+
+#[test]
+fn curl_noise_is_divergence_free() {
+    for &basis in &[CURL_BASIS_GRADIENT, CURL_BASIS_SIMPLEX] {
+        check_divergence(basis, ImageDim::new(256, 256, 4));
+        check_divergence(basis, ImageDim::new_3d(64, 64, 64, 4));
+    }
+}
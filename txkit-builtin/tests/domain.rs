@@ -0,0 +1,69 @@
+//! Evaluation of methods at the texture coordinates of a context domain
+
+#![cfg(feature = "cpu")]
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use txkit_builtin::methods::*;
+use txkit_core::context::Context;
+use txkit_core::image::{Image, ImageDataType, ImageDim};
+use txkit_core::method::Method;
+
+/// Compute a method into a `Float32` CPU image and return its values
+fn render(ctx: &mut Context, method: &mut dyn Method, dim: ImageDim) -> Vec<f32> {
+    let mut tgt = Image::new_cpu(dim, ImageDataType::Float32);
+    method
+        .compute(ctx, &mut tgt, None)
+        .expect("failed to compute method");
+
+    let data = tgt.data().expect("failed to map image");
+    let values = data.as_f32_nd_array().unwrap().iter().copied().collect();
+    values
+}
+
+/// Build a domain warp of gradient noise, displaced by fractal noise
+fn warp(strength: f32) -> DomainWarp {
+    let mut warp = DomainWarp::new();
+    warp.set_param("strength", strength).unwrap();
+    warp.set_input("source", Some(Box::new(GradientNoise::new())))
+        .unwrap();
+    warp.set_input("displacement", Some(Box::new(FractalNoise::new())))
+        .unwrap();
+    warp
+}
+
+#[test]
+fn unwarped_source_is_unchanged() {
+    let mut ctx = Context::new_cpu().unwrap();
+    let dim = ImageDim::new(64, 48, 4);
+
+    let expected = render(&mut ctx, &mut GradientNoise::new(), dim);
+    assert_eq!(render(&mut ctx, &mut warp(0.), dim), expected);
+    assert_ne!(render(&mut ctx, &mut warp(0.1), dim), expected);
+}
+
+#[test]
+fn warped_source_is_evaluated_at_warped_coordinates() {
+    let mut ctx = Context::new_cpu().unwrap();
+    let dim = ImageDim::new(64, 48, 4);
+
+    // Warped coordinates, from the displacement field computed over the whole texture
+    let mut coordinates = DomainWarp::new();
+    coordinates
+        .set_param("output_mode", DOMAIN_WARP_OUTPUT_COORDINATES)
+        .unwrap();
+    coordinates
+        .set_input("displacement", Some(Box::new(FractalNoise::new())))
+        .unwrap();
+
+    let mut domain = Image::new_cpu(dim, ImageDataType::Float32);
+    coordinates.compute(&mut ctx, &mut domain, None).unwrap();
+
+    ctx.set_domain(Some(Rc::new(RefCell::new(domain))));
+    let expected = render(&mut ctx, &mut GradientNoise::new(), dim);
+    ctx.set_domain(None);
+
+    assert_eq!(render(&mut ctx, &mut warp(0.1), dim), expected);
+    assert!(ctx.domain().is_none());
+}
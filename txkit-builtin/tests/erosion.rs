@@ -0,0 +1,87 @@
+//! Determinism and progress reports of the erosion simulation
+
+#![cfg(feature = "cpu")]
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use txkit_builtin::methods::*;
+use txkit_core::context::{Context, CpuContext};
+use txkit_core::image::{Image, ImageDataType, ImageDim};
+use txkit_core::io::ImageBinding;
+use txkit_core::method::Method;
+
+/// Compute a method into a `Float32` CPU image
+fn compute(ctx: &mut Context, method: &mut dyn Method, dim: ImageDim) -> Image {
+    let mut tgt = Image::new_cpu(dim, ImageDataType::Float32);
+    method
+        .compute(ctx, &mut tgt, None)
+        .expect("failed to compute method");
+    tgt
+}
+
+/// Build a CPU context computing on the given number of threads
+fn context(threads: usize) -> Context {
+    Context::Cpu(CpuContext {
+        thread_pool: rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .unwrap(),
+        domain: None,
+    })
+}
+
+/// Erode gradient noise, and return the output values
+fn erode(ctx: &mut Context, global_seed: u32) -> Vec<f32> {
+    let dim = ImageDim::new(48, 32, 4);
+    let height = compute(ctx, &mut GradientNoise::new(), dim);
+
+    let mut erosion = new_registry().build("erosion").unwrap();
+    erosion.set_param("global_seed", global_seed).unwrap();
+    erosion.set_param("iterations", 4).unwrap();
+    erosion.set_param("droplets", 300).unwrap();
+    erosion
+        .set_texture_binding(
+            "height",
+            ImageBinding::ImageRef(Rc::new(RefCell::new(height))),
+        )
+        .unwrap();
+
+    let tgt = compute(ctx, &mut erosion, dim);
+    let data = tgt.data().expect("failed to map image");
+    let values = data.as_f32_nd_array().unwrap().iter().copied().collect();
+    values
+}
+
+#[test]
+fn erosion_is_deterministic_per_seed() {
+    let expected = erode(&mut context(4), 7);
+    assert!(expected.iter().all(|v| v.is_finite()));
+
+    assert_eq!(erode(&mut context(4), 7), expected);
+    assert_eq!(erode(&mut context(1), 7), expected);
+    assert_eq!(erode(&mut context(3), 7), expected);
+    assert_ne!(erode(&mut context(4), 8), expected);
+}
+
+#[test]
+fn erosion_reports_progress() {
+    let mut ctx = Context::new_cpu().unwrap();
+    let dim = ImageDim::new(16, 16, 4);
+
+    let reports = Rc::new(RefCell::new(Vec::new()));
+    let mut erosion = new_registry().build("erosion").unwrap();
+    erosion.set_param("iterations", 3).unwrap();
+    erosion.set_progress(Some(Box::new({
+        let reports = reports.clone();
+        move |completed, total| reports.borrow_mut().push((completed, total))
+    })));
+
+    compute(&mut ctx, &mut erosion, dim);
+    assert_eq!(*reports.borrow(), vec![(1, 3), (2, 3), (3, 3)]);
+
+    // Progress reports can be disabled
+    erosion.set_progress(None);
+    compute(&mut ctx, &mut erosion, dim);
+    assert_eq!(reports.borrow().len(), 3);
+}
@@ -0,0 +1,430 @@
+//! Runtime schemas of the built-in parameter structures, and access to parameters by name
+
+use txkit_builtin::methods::*;
+use txkit_core::io::ImageBinding;
+use txkit_core::method::{Method, MethodParams, ParamConstant, ParamValue};
+use txkit_core::Error;
+
+/// Check that the schema of a parameter structure lists the given fields, in order, at the
+/// offsets the compiler assigned to them
+macro_rules! check_schema {
+    ($($params:ident { $($field:ident),* $(,)? }),* $(,)?) => {
+        $(
+            let schema = <$params as MethodParams>::schema();
+            assert_eq!(schema.name, stringify!($params));
+            assert_eq!(schema.size, std::mem::size_of::<$params>(), "{}", schema.name);
+
+            let expected: Vec<(&str, usize)> = vec![
+                $((stringify!($field), std::mem::offset_of!($params, $field))),*
+            ];
+            let actual: Vec<(&str, usize)> = schema
+                .fields
+                .iter()
+                .map(|field| (field.name, field.offset))
+                .collect();
+
+            assert_eq!(actual, expected, "{}", schema.name);
+        )*
+    };
+}
+
+#[test]
+fn schema_offsets_match_layout() {
+    check_schema!(
+        AmbientOcclusionParams {
+            strength,
+            radius,
+            directions,
+            steps,
+            io
+        },
+        BlueNoiseParams {
+            global_seed,
+            sigma,
+            initial_density
+        },
+        BrickPatternParams {
+            global_seed,
+            scale,
+            row_offset,
+            mortar_width,
+            antialiasing
+        },
+        CheckerPatternParams {
+            global_seed,
+            scale,
+            antialiasing
+        },
+        CurlNoiseParams {
+            global_seed,
+            scale,
+            basis,
+            amplitude
+        },
+        CurvatureParams {
+            strength,
+            radius,
+            io
+        },
+        DebugParams { alpha_value },
+        DistanceTransformParams { threshold, io },
+        DomainWarpParams {
+            strength,
+            iterations,
+            displacement_mode,
+            output_mode,
+            io
+        },
+        ErosionParams {
+            global_seed,
+            iterations,
+            strength,
+            thermal_rate,
+            talus_slope,
+            droplets,
+            droplet_lifetime,
+            inertia,
+            sediment_capacity,
+            erosion_rate,
+            deposition_rate,
+            evaporation_rate,
+            io,
+        },
+        FractalNoiseParams {
+            global_seed,
+            scale,
+            stats_mode,
+            stats_look_at,
+            basis,
+            variant,
+            octaves,
+            lacunarity,
+            gain,
+            dimensions,
+            time,
+            period,
+        },
+        GaborNoiseParams {
+            global_seed,
+            scale,
+            stats_mode,
+            stats_look_at,
+            mode,
+            kernel_count,
+            noise_point_distribution,
+            jitter_amount,
+            jitter_max,
+            bandwidth,
+            frequency,
+            orientation,
+            orientation_spread,
+            io,
+        },
+        GradientNoiseParams {
+            global_seed,
+            scale,
+            stats_mode,
+            stats_look_at,
+            output_mode,
+            dimensions,
+            time,
+            period,
+        },
+        NormalMapParams {
+            strength,
+            kernel,
+            io
+        },
+        PhasorNoiseParams {
+            global_seed,
+            scale,
+            stats_mode,
+            stats_look_at,
+            noise_lookahead,
+            kernel_count,
+            noise_profile,
+            noise_weights,
+            noise_point_distribution,
+            noise_frequency,
+            noise_angle,
+            time,
+            period,
+            jitter_amount,
+            jitter_max,
+            io,
+        },
+        PolygonPatternParams {
+            global_seed,
+            scale,
+            shape,
+            mortar_width,
+            antialiasing
+        },
+        Sdf2dParams {
+            output_mode,
+            antialiasing,
+            shapes,
+            io
+        },
+        SimplexNoiseParams {
+            global_seed,
+            scale,
+            stats_mode,
+            stats_look_at,
+            output_mode,
+            dimensions,
+            time,
+            period,
+        },
+        SparseConvolutionNoiseParams {
+            global_seed,
+            scale,
+            stats_mode,
+            stats_look_at,
+            kernel,
+            kernel_count,
+            noise_point_distribution,
+            jitter_amount,
+            jitter_max,
+            kernel_radius,
+        },
+        StripePatternParams {
+            global_seed,
+            scale,
+            orientation,
+            duty,
+            antialiasing
+        },
+        TilingAndBlendingParams {
+            global_seed,
+            repeat,
+            tile_size,
+            io
+        },
+        TruchetPatternParams {
+            global_seed,
+            scale,
+            tile,
+            line_width,
+            antialiasing
+        },
+        ValueNoiseParams {
+            global_seed,
+            scale,
+            stats_mode,
+            stats_look_at,
+            dimensions,
+            time,
+            period,
+        },
+        WhiteNoiseParams {
+            global_seed,
+            distribution,
+            channels,
+            mean,
+            std_dev,
+            rate,
+            probability,
+            levels,
+        },
+        WorleyNoiseParams {
+            global_seed,
+            scale,
+            stats_mode,
+            stats_look_at,
+            lookahead,
+            point_count,
+            point_distribution,
+            jitter_amount,
+            jitter_max,
+            metric,
+            output_mode,
+        },
+    );
+}
+
+/// Get a value of the same type as `value`, but different from it
+fn other_value(value: ParamValue, constants: &[ParamConstant]) -> ParamValue {
+    if let Some(constant) = constants.iter().find(|c| c.value != value) {
+        return constant.value;
+    }
+
+    match value {
+        ParamValue::U32(v) => ParamValue::U32(v + 1),
+        ParamValue::I32(v) => ParamValue::I32(v + 1),
+        ParamValue::F32(v) => ParamValue::F32(v + 0.5),
+        ParamValue::Vec2(v) => ParamValue::Vec2(v.map(|x| x + 0.5)),
+        ParamValue::Vec3(v) => ParamValue::Vec3(v.map(|x| x + 0.5)),
+        ParamValue::Vec4(v) => ParamValue::Vec4(v.map(|x| x + 0.5)),
+    }
+}
+
+#[test]
+fn params_round_trip_by_name() {
+    let registry = new_registry();
+
+    for name in registry.names() {
+        let mut method = registry.build(name).unwrap();
+        let schema = method.params_schema();
+
+        for field in &schema.fields {
+            match field.default {
+                Some(default) => {
+                    assert_eq!(method.get_param(field.name).unwrap(), default, "{}", name);
+
+                    let value = other_value(default, &field.constants);
+                    method.set_param(field.name, value).unwrap();
+                    assert_eq!(method.get_param(field.name).unwrap(), value, "{}", name);
+
+                    let wrong = match value {
+                        ParamValue::U32(_) => ParamValue::I32(0),
+                        _ => ParamValue::U32(0),
+                    };
+                    assert!(matches!(
+                        method.set_param(field.name, wrong),
+                        Err(Error::InvalidParamType { .. })
+                    ));
+                    assert_eq!(method.get_param(field.name).unwrap(), value, "{}", name);
+                }
+                None => {
+                    assert!(matches!(
+                        method.get_param(field.name),
+                        Err(Error::ParamNotAccessible(_))
+                    ));
+                }
+            }
+
+            for binding in &field.bindings {
+                method
+                    .set_texture_binding(binding, ImageBinding::Named("input".to_string()))
+                    .unwrap();
+            }
+        }
+
+        assert!(matches!(
+            method.get_param("not_a_param"),
+            Err(Error::ParamNotFound(_))
+        ));
+        assert!(matches!(
+            method.set_texture_binding("not_a_binding", ImageBinding::None),
+            Err(Error::ParamNotFound(_))
+        ));
+    }
+}
+
+#[test]
+fn inputs_are_set_by_name() {
+    let registry = new_registry();
+
+    for name in registry.names() {
+        let mut method = registry.build(name).unwrap();
+
+        for input in method.metadata().inputs {
+            let inner = registry.build("value_noise").unwrap();
+            method.set_input(input, Some(inner)).unwrap();
+            method.set_input(input, None).unwrap();
+        }
+
+        assert!(matches!(
+            method.set_input("not_an_input", None),
+            Err(Error::ParamNotFound(_))
+        ));
+    }
+
+    assert_eq!(
+        registry.build("domain_warp").unwrap().metadata().inputs,
+        vec!["source", "displacement"]
+    );
+}
+
+#[test]
+fn set_param_converts_values() {
+    let mut method = new_registry().build("value_noise").unwrap();
+
+    method.set_param("scale", 16.0).unwrap();
+    method.set_param("global_seed", 7u32).unwrap();
+    method.set_param("dimensions", 3).unwrap();
+
+    assert_eq!(method.get_param("scale").unwrap(), ParamValue::F32(16.));
+    assert_eq!(method.get_param("global_seed").unwrap(), ParamValue::U32(7));
+    assert_eq!(method.get_param("dimensions").unwrap(), ParamValue::I32(3));
+}
+
+#[test]
+fn opaque_params_are_set_by_name() {
+    let mut shapes = Sdf2dShapes::new();
+    shapes.push(
+        Sdf2dPrimitive::from_data(SDF_2D_SHAPE_CIRCLE, &[0.5, 0.5, 0.25]).unwrap(),
+        SDF_2D_OP_UNION,
+        0.,
+    );
+
+    let mut params = Sdf2dParams::default();
+    params.set_opaque_param("shapes", &shapes).unwrap();
+    assert_eq!(*params.shapes, shapes);
+
+    assert!(matches!(
+        params.set_opaque_param("shapes", &0.5f32),
+        Err(Error::InvalidParameters)
+    ));
+    assert!(matches!(
+        params.set_opaque_param("antialiasing", &0.5f32),
+        Err(Error::ParamNotAccessible(_))
+    ));
+
+    let mut method = new_registry().build("sdf_2d").unwrap();
+    method.set_opaque_param("shapes", &shapes).unwrap();
+    assert!(matches!(
+        method.set_opaque_param("not_a_param", &shapes),
+        Err(Error::ParamNotFound(_))
+    ));
+}
+
+#[test]
+fn texture_bindings_are_named_by_unit() {
+    let schema = <TilingAndBlendingParams as MethodParams>::schema();
+    let io = schema.field("io").unwrap();
+
+    assert_eq!(
+        io.bindings,
+        vec!["exemplar", "gaussian_exemplar", "inverse_histogram"]
+    );
+    assert_eq!(io.bindings[TILING_AND_BLENDING_EXEMPLAR_UNIT], "exemplar");
+
+    // Units bound by the method itself can't be set by name
+    let schema = <DistanceTransformParams as MethodParams>::schema();
+    assert_eq!(schema.field("io").unwrap().bindings, vec!["mask"]);
+
+    let mut params = TilingAndBlendingParams::default();
+    let binding = ImageBinding::Named("input".to_string());
+    params
+        .set_texture_binding("inverse_histogram", binding.clone())
+        .unwrap();
+
+    assert_eq!(
+        params
+            .io
+            .get_texture_binding(TILING_AND_BLENDING_INVERSE_HISTOGRAM_UNIT),
+        &binding
+    );
+}
+
+#[cfg(feature = "cpu")]
+#[test]
+fn null_image_pointers_are_rejected() {
+    use txkit_core::context::Context;
+    use txkit_core::image::{Image, ImageDataType, ImageDim};
+
+    let mut ctx = Context::new_cpu().unwrap();
+    let mut tgt = Image::new_cpu(ImageDim::new(8, 8, 4), ImageDataType::Float32);
+
+    let mut method = NormalMap::new();
+    method
+        .set_texture_binding("height", ImageBinding::ImagePtr(std::ptr::null_mut()))
+        .unwrap();
+
+    assert!(matches!(
+        method.compute(&mut ctx, &mut tgt, None),
+        Err(Error::InvalidParameters)
+    ));
+}
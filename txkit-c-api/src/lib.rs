@@ -1,13 +1,16 @@
 use std::any::Any;
+use std::ffi::CString;
 
 use txkit_core::{
     context::Context,
     image::{Image, ImageDataType, ImageDim, MappedImageData, MappedImageDataMut},
     io::{ImageBinding, ImageIo},
-    method::{Method, MethodRegistry},
+    method::{Method, MethodRegistry, ParamType, ParamValue},
     Error,
 };
 
+use txkit_builtin::methods::{Sdf2dPrimitive, Sdf2dShapes};
+
 pub mod config {
     include!(concat!(env!("OUT_DIR"), "/config.rs"));
 }
@@ -26,7 +29,8 @@ pub struct MethodBox {
 /// * `ctx`: context to use for computing the image
 /// * `method`: texturing method
 /// * `tgt`: target image to be computed
-/// * `params`: pointer to the parameter structure for this method
+/// * `params`: pointer to the parameter structure for this method, or null to use the parameters
+///   set with the txkit_method_set_param_* functions
 /// * `params_size`: size of the parameter structure
 ///
 /// # Returns
@@ -61,9 +65,428 @@ pub unsafe extern "C" fn txkit_method_destroy(method: *mut MethodBox) {
     std::mem::drop(Box::from_raw(method))
 }
 
+/// Check if a method can compute into an image in a context
+///
+/// # Parameters
+///
+/// * `ctx`: context to compute in
+/// * `method`: texturing method
+/// * `tgt`: target image
+///
+/// # Returns
+///
+/// true if the method supports this context and image, false otherwise.
+#[no_mangle]
+pub extern "C" fn txkit_method_supports(ctx: &Context, method: &MethodBox, tgt: &Image) -> bool {
+    crate::api::wrap(|| method.method.supports(ctx, tgt)).unwrap_or(false)
+}
+
+/// Convert a parameter or binding name from a C string
+///
+/// # Parameters
+///
+/// * `name`: null-terminated name
+fn param_name<'n>(name: *const libc::c_char) -> txkit_core::Result<&'n str> {
+    if name.is_null() {
+        return Err(Error::InvalidParamName);
+    }
+
+    unsafe { std::ffi::CStr::from_ptr(name as *const _) }
+        .to_str()
+        .map_err(|_| Error::InvalidParamName)
+}
+
+/// Set a parameter of a method by name
+///
+/// # Parameters
+///
+/// * `method`: method to update
+/// * `name`: name of the parameter
+/// * `value`: new value of the parameter
+fn set_param(method: &mut MethodBox, name: *const libc::c_char, value: ParamValue) -> i32 {
+    crate::api::wrap_result_code(|| method.method.set_param(param_name(name)?, value))
+}
+
+/// Get a parameter of a method by name
+///
+/// # Parameters
+///
+/// * `method`: method to query
+/// * `name`: name of the parameter
+/// * `expected`: type of the parameter expected by the caller
+/// * `write`: function writing the value of the parameter to the caller
+fn get_param(
+    method: &MethodBox,
+    name: *const libc::c_char,
+    expected: ParamType,
+    write: impl FnOnce(ParamValue),
+) -> i32 {
+    crate::api::wrap_result_code(|| {
+        let name = param_name(name)?;
+        let value = method.method.get_param(name)?;
+
+        if value.ty() != expected {
+            return Err(Error::InvalidParamType {
+                name: name.to_string(),
+                expected,
+                actual: value.ty(),
+            });
+        }
+
+        write(value);
+        Ok(())
+    })
+}
+
+/// Set the method computing an input of a method by name
+///
+/// The inputs of a method are listed in its metadata, e.g. `source` and `displacement` for
+/// `domain_warp`. Inner methods are computed with the parameters set on them.
+///
+/// # Parameters
+///
+/// * `method`: method to update
+/// * `name`: name of the input
+/// * `input`: inner method, or NULL to use the texture binding of the input instead
+///
+/// # Returns
+///
+/// TxKit_SUCCESS if no error occurred, else a non-zero code.
+///
+/// # Safety
+///
+/// `input` must be NULL or a method returned by txkit_method_new. It is consumed by this call,
+/// even if it fails, and must not be used or destroyed afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn txkit_method_set_input(
+    method: &mut MethodBox,
+    name: *const libc::c_char,
+    input: *mut MethodBox,
+) -> i32 {
+    let input = if input.is_null() {
+        None
+    } else {
+        Some(Box::from_raw(input).method)
+    };
+
+    crate::api::wrap_result_code(|| method.method.set_input(param_name(name)?, input))
+}
+
+/// Set a float parameter of a method by name
+///
+/// The parameters set on a method are used when calling txkit_method_compute without a parameter
+/// structure.
+///
+/// # Parameters
+///
+/// * `method`: method to update
+/// * `name`: name of the parameter
+/// * `value`: new value of the parameter
+///
+/// # Returns
+///
+/// TxKit_SUCCESS if no error occurred, else a non-zero code.
+#[no_mangle]
+pub extern "C" fn txkit_method_set_param_f32(
+    method: &mut MethodBox,
+    name: *const libc::c_char,
+    value: f32,
+) -> i32 {
+    set_param(method, name, value.into())
+}
+
+/// Set a signed integer parameter of a method by name
+///
+/// The parameters set on a method are used when calling txkit_method_compute without a parameter
+/// structure.
+///
+/// # Parameters
+///
+/// * `method`: method to update
+/// * `name`: name of the parameter
+/// * `value`: new value of the parameter
+///
+/// # Returns
+///
+/// TxKit_SUCCESS if no error occurred, else a non-zero code.
+#[no_mangle]
+pub extern "C" fn txkit_method_set_param_i32(
+    method: &mut MethodBox,
+    name: *const libc::c_char,
+    value: i32,
+) -> i32 {
+    set_param(method, name, value.into())
+}
+
+/// Set an unsigned integer parameter of a method by name
+///
+/// The parameters set on a method are used when calling txkit_method_compute without a parameter
+/// structure.
+///
+/// # Parameters
+///
+/// * `method`: method to update
+/// * `name`: name of the parameter
+/// * `value`: new value of the parameter
+///
+/// # Returns
+///
+/// TxKit_SUCCESS if no error occurred, else a non-zero code.
+#[no_mangle]
+pub extern "C" fn txkit_method_set_param_u32(
+    method: &mut MethodBox,
+    name: *const libc::c_char,
+    value: u32,
+) -> i32 {
+    set_param(method, name, value.into())
+}
+
+/// Set a 2D vector parameter of a method by name
+///
+/// The parameters set on a method are used when calling txkit_method_compute without a parameter
+/// structure.
+///
+/// # Parameters
+///
+/// * `method`: method to update
+/// * `name`: name of the parameter
+/// * `x`: new value of the first component of the parameter
+/// * `y`: new value of the second component of the parameter
+///
+/// # Returns
+///
+/// TxKit_SUCCESS if no error occurred, else a non-zero code.
+#[no_mangle]
+pub extern "C" fn txkit_method_set_param_vec2(
+    method: &mut MethodBox,
+    name: *const libc::c_char,
+    x: f32,
+    y: f32,
+) -> i32 {
+    set_param(method, name, ParamValue::Vec2([x, y]))
+}
+
+/// Get a float parameter of a method by name
+///
+/// # Parameters
+///
+/// * `method`: method to query
+/// * `name`: name of the parameter
+/// * `value`: pointer to the value to write
+///
+/// # Returns
+///
+/// TxKit_SUCCESS if no error occurred, else a non-zero code.
+///
+/// # Safety
+///
+/// `value` must be a valid pointer to write the value to.
+#[no_mangle]
+pub unsafe extern "C" fn txkit_method_get_param_f32(
+    method: &MethodBox,
+    name: *const libc::c_char,
+    value: *mut f32,
+) -> i32 {
+    get_param(method, name, ParamType::F32, |v| {
+        if let ParamValue::F32(v) = v {
+            *value = v;
+        }
+    })
+}
+
+/// Get a signed integer parameter of a method by name
+///
+/// # Parameters
+///
+/// * `method`: method to query
+/// * `name`: name of the parameter
+/// * `value`: pointer to the value to write
+///
+/// # Returns
+///
+/// TxKit_SUCCESS if no error occurred, else a non-zero code.
+///
+/// # Safety
+///
+/// `value` must be a valid pointer to write the value to.
+#[no_mangle]
+pub unsafe extern "C" fn txkit_method_get_param_i32(
+    method: &MethodBox,
+    name: *const libc::c_char,
+    value: *mut i32,
+) -> i32 {
+    get_param(method, name, ParamType::I32, |v| {
+        if let ParamValue::I32(v) = v {
+            *value = v;
+        }
+    })
+}
+
+/// Get an unsigned integer parameter of a method by name
+///
+/// # Parameters
+///
+/// * `method`: method to query
+/// * `name`: name of the parameter
+/// * `value`: pointer to the value to write
+///
+/// # Returns
+///
+/// TxKit_SUCCESS if no error occurred, else a non-zero code.
+///
+/// # Safety
+///
+/// `value` must be a valid pointer to write the value to.
+#[no_mangle]
+pub unsafe extern "C" fn txkit_method_get_param_u32(
+    method: &MethodBox,
+    name: *const libc::c_char,
+    value: *mut u32,
+) -> i32 {
+    get_param(method, name, ParamType::U32, |v| {
+        if let ParamValue::U32(v) = v {
+            *value = v;
+        }
+    })
+}
+
+/// Get a 2D vector parameter of a method by name
+///
+/// # Parameters
+///
+/// * `method`: method to query
+/// * `name`: name of the parameter
+/// * `x`: pointer to the first component of the value to write
+/// * `y`: pointer to the second component of the value to write
+///
+/// # Returns
+///
+/// TxKit_SUCCESS if no error occurred, else a non-zero code.
+///
+/// # Safety
+///
+/// `x` and `y` must be valid pointers to floats.
+#[no_mangle]
+pub unsafe extern "C" fn txkit_method_get_param_vec2(
+    method: &MethodBox,
+    name: *const libc::c_char,
+    x: *mut f32,
+    y: *mut f32,
+) -> i32 {
+    get_param(method, name, ParamType::Vec2, |v| {
+        if let ParamValue::Vec2([vx, vy]) = v {
+            *x = vx;
+            *y = vy;
+        }
+    })
+}
+
+/// Set a shape list parameter of a method by name, e.g. `shapes` for `sdf_2d`
+///
+/// The shapes are copied, so the shape list can be changed or destroyed afterwards. The
+/// parameters set on a method are used when calling txkit_method_compute without a parameter
+/// structure.
+///
+/// # Parameters
+///
+/// * `method`: method to update
+/// * `name`: name of the parameter
+/// * `shapes`: shape list to copy
+///
+/// # Returns
+///
+/// TxKit_SUCCESS if no error occurred, else a non-zero code.
+#[no_mangle]
+pub extern "C" fn txkit_method_set_sdf_2d_shapes(
+    method: &mut MethodBox,
+    name: *const libc::c_char,
+    shapes: &Sdf2dShapes,
+) -> i32 {
+    crate::api::wrap_result_code(|| method.method.set_opaque_param(param_name(name)?, shapes))
+}
+
+/// Callback receiving the number of completed steps, the total number of steps, and the user
+/// data pointer given to txkit_method_set_progress
+pub type ProgressCallback =
+    extern "C" fn(completed: usize, total: usize, user_data: *mut std::ffi::c_void);
+
+/// Set the callback notified of the progress of long computations of a method, e.g. `erosion`
+///
+/// The callback is called on the thread calling txkit_method_compute. Methods which complete
+/// quickly don't report progress, and ignore the callback.
+///
+/// # Parameters
+///
+/// * `method`: method to update
+/// * `callback`: progress callback, or NULL to disable progress reports
+/// * `user_data`: pointer passed to every call of the callback
+///
+/// # Safety
+///
+/// `user_data` must remain valid until the callback is replaced or the method is destroyed.
+#[no_mangle]
+pub unsafe extern "C" fn txkit_method_set_progress(
+    method: &mut MethodBox,
+    callback: Option<ProgressCallback>,
+    user_data: *mut std::ffi::c_void,
+) {
+    method.method.set_progress(callback.map(|callback| {
+        Box::new(move |completed, total| callback(completed, total, user_data))
+            as txkit_core::method::Progress
+    }));
+}
+
+/// Bind an image to a texture unit of a method by name
+///
+/// The bindings set on a method are used when calling txkit_method_compute without a parameter
+/// structure. The image must outlive the binding.
+///
+/// # Parameters
+///
+/// * `method`: method to update
+/// * `name`: name of the texture unit, e.g. `height`
+/// * `image`: image to bind, or NULL to clear the binding
+///
+/// # Returns
+///
+/// TxKit_SUCCESS if no error occurred, else a non-zero code.
+#[no_mangle]
+pub extern "C" fn txkit_method_set_texture_binding(
+    method: &mut MethodBox,
+    name: *const libc::c_char,
+    image: *mut Image,
+) -> i32 {
+    crate::api::wrap_result_code(|| {
+        method.method.set_texture_binding(
+            param_name(name)?,
+            if image.is_null() {
+                ImageBinding::None
+            } else {
+                ImageBinding::ImagePtr(image)
+            },
+        )
+    })
+}
+
 /// Wrapped registry for FFI
 pub struct RegistryBox {
     registry: Box<MethodRegistry>,
+    /// Method names, as C strings owned by the registry
+    names: Vec<CString>,
+}
+
+impl RegistryBox {
+    fn new(registry: MethodRegistry) -> Self {
+        let names = registry
+            .names()
+            .map(|name| CString::new(name).expect("invalid method name"))
+            .collect();
+
+        Self {
+            registry: Box::new(registry),
+            names,
+        }
+    }
 }
 
 /// Create a new registry with txkit built-in methods registered
@@ -74,9 +497,9 @@ pub struct RegistryBox {
 #[no_mangle]
 pub extern "C" fn txkit_registry_new_builtin() -> *mut RegistryBox {
     crate::api::wrap(|| {
-        Box::into_raw(Box::new(RegistryBox {
-            registry: Box::new(txkit_builtin::methods::new_registry()),
-        }))
+        Box::into_raw(Box::new(RegistryBox::new(
+            txkit_builtin::methods::new_registry(),
+        )))
     })
     .unwrap_or(std::ptr::null_mut())
 }
@@ -116,6 +539,45 @@ pub extern "C" fn txkit_method_new(
     .unwrap_or(std::ptr::null_mut())
 }
 
+/// Get the number of methods in a registry
+///
+/// # Parameters
+///
+/// * `registry`: registry to query
+///
+/// # Returns
+///
+/// Number of methods in the registry.
+#[no_mangle]
+pub extern "C" fn txkit_registry_count(registry: &RegistryBox) -> usize {
+    registry.names.len()
+}
+
+/// Get the name of a method in a registry
+///
+/// Methods are sorted by name.
+///
+/// # Parameters
+///
+/// * `registry`: registry to query
+/// * `index`: index of the method, less than `txkit_registry_count(registry)`
+///
+/// # Returns
+///
+/// Null pointer if the index is out of range, otherwise pointer to the name of the method. The
+/// name is owned by the registry.
+#[no_mangle]
+pub extern "C" fn txkit_registry_name_at(
+    registry: &RegistryBox,
+    index: usize,
+) -> *const libc::c_char {
+    registry
+        .names
+        .get(index)
+        .map(|name| name.as_ptr())
+        .unwrap_or(std::ptr::null())
+}
+
 /// Destroy a registry
 ///
 /// # Parameters
@@ -399,6 +861,19 @@ pub unsafe extern "C" fn txkit_image_unmap_write(write_map: *mut MappedImageData
     std::mem::drop(Box::from_raw(write_map))
 }
 
+/// Create a new context, preferring the GPU
+///
+/// A CPU context is created if a GPU context can't be created.
+///
+/// # Returns
+///
+/// Pointer to the created context, or null if the creation failed.
+#[no_mangle]
+pub extern "C" fn txkit_context_new_auto() -> *mut Context {
+    crate::api::wrap_result(|| Context::new_auto().map(Box::new).map(Box::into_raw))
+        .unwrap_or(std::ptr::null_mut())
+}
+
 /// Create a new CPU context
 ///
 /// # Returns
@@ -508,3 +983,72 @@ pub unsafe extern "C" fn txkit_image_io_set_texture_binding(
 pub unsafe extern "C" fn txkit_image_io_destroy(io: *mut ImageIo) {
     std::mem::drop(Box::from_raw(io))
 }
+
+/// Create a new empty shape list for the Sdf2d method
+#[no_mangle]
+pub extern "C" fn txkit_sdf_2d_shapes_new() -> *mut Sdf2dShapes {
+    Box::into_raw(Box::new(Sdf2dShapes::new()))
+}
+
+/// Add a shape at the end of a shape list
+///
+/// # Parameters
+///
+/// * `shapes`: shape list to change
+/// * `kind`: kind of primitive, see `SDF_2D_SHAPE_*`
+/// * `op`: operator combining the shape with the previous shapes, see `SDF_2D_OP_*`
+/// * `smoothness`: smoothing radius of the operator, 0 for a sharp boolean operator
+/// * `data`: pointer to the primitive data, see `SDF_2D_SHAPE_*` for the layout
+/// * `len`: number of elements in `data`
+///
+/// # Returns
+///
+/// TxKit_SUCCESS on success, non-zero on error
+///
+/// # Safety
+///
+/// `data` must point to at least `len` floats.
+#[no_mangle]
+pub unsafe extern "C" fn txkit_sdf_2d_shapes_add(
+    shapes: &mut Sdf2dShapes,
+    kind: i32,
+    op: i32,
+    smoothness: f32,
+    data: *const f32,
+    len: usize,
+) -> i32 {
+    crate::api::wrap_result_code(|| -> txkit_core::Result<()> {
+        let data = if len == 0 {
+            &[]
+        } else {
+            std::slice::from_raw_parts(data, len)
+        };
+
+        shapes.push(Sdf2dPrimitive::from_data(kind, data)?, op, smoothness);
+        Ok(())
+    })
+}
+
+/// Remove all the shapes of a shape list
+///
+/// # Parameters
+///
+/// * `shapes`: shape list to clear
+#[no_mangle]
+pub extern "C" fn txkit_sdf_2d_shapes_clear(shapes: &mut Sdf2dShapes) {
+    shapes.clear();
+}
+
+/// Destroy a shape list
+///
+/// # Parameters
+///
+/// * `shapes`: shape list to destroy
+///
+/// # Safety
+///
+/// `shapes` must have been returned by txkit_sdf_2d_shapes_new, and not destroyed yet.
+#[no_mangle]
+pub unsafe extern "C" fn txkit_sdf_2d_shapes_destroy(shapes: *mut Sdf2dShapes) {
+    std::mem::drop(Box::from_raw(shapes))
+}
@@ -2,18 +2,100 @@
 extern crate log;
 
 use std::io::prelude::*;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use argh::FromArgs;
-use color_eyre::eyre::Result;
+use color_eyre::eyre::{eyre, Result};
+
+use txkit_core::context::{Context, ContextKind};
+use txkit_core::image::{Image, ImageDataType, ImageDim};
+use txkit_core::lattice::LATTICE_DIMENSIONS_4D;
+use txkit_core::method::{Method, ParamType, ParamValue};
+
+/// Path of an animation frame: the output path with the frame index appended to its file name
+fn frame_path(output_path: &Path, frame: usize) -> PathBuf {
+    let stem = output_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy())
+        .unwrap_or_default();
+
+    let mut file_name = format!("{}_{:04}", stem, frame);
+    if let Some(extension) = output_path.extension() {
+        file_name.push('.');
+        file_name.push_str(&extension.to_string_lossy());
+    }
+
+    output_path.with_file_name(file_name)
+}
+
+/// Time of an animation frame: frames are evenly spaced over one period, starting at `time`
+fn frame_time(time: ParamValue, period: ParamValue, frame: usize, frames: usize) -> Result<f32> {
+    match (time, period) {
+        (ParamValue::F32(time), ParamValue::F32(period)) if period > 0. => {
+            Ok(time + period * frame as f32 / frames as f32)
+        }
+        _ => Err(eyre!(
+            "rendering frames requires a positive period, e.g. --param period=4"
+        )),
+    }
+}
+
+/// Parse a `name=value` parameter assignment, converting the value to the type of the parameter
+///
+/// Vector values are written as comma-separated components, e.g. `stats_look_at=0.5,0.5`.
+fn parse_param(method: &dyn Method, assignment: &str) -> Result<(String, ParamValue)> {
+    let (name, value) = assignment
+        .split_once('=')
+        .ok_or_else(|| eyre!("invalid parameter {:?}, expected name=value", assignment))?;
+    let (name, value) = (name.trim(), value.trim());
+
+    let components = || -> Result<Vec<f32>> {
+        value
+            .split(',')
+            .map(|c| Ok(c.trim().parse::<f32>()?))
+            .collect()
+    };
+
+    let value = match method.get_param(name)?.ty() {
+        ParamType::U32 => ParamValue::U32(value.parse()?),
+        ParamType::I32 => ParamValue::I32(value.parse()?),
+        ParamType::F32 => ParamValue::F32(value.parse()?),
+        ty @ (ParamType::Vec2 | ParamType::Vec3 | ParamType::Vec4) => {
+            let c = components()?;
+            match (ty, c.as_slice()) {
+                (ParamType::Vec2, &[x, y]) => ParamValue::Vec2([x, y]),
+                (ParamType::Vec3, &[x, y, z]) => ParamValue::Vec3([x, y, z]),
+                (ParamType::Vec4, &[x, y, z, w]) => ParamValue::Vec4([x, y, z, w]),
+                _ => {
+                    return Err(eyre!(
+                        "invalid value {:?} for parameter {} of type {:?}",
+                        value,
+                        name,
+                        ty
+                    ))
+                }
+            }
+        }
+        ty => return Err(eyre!("parameter {} of type {:?} can't be set", name, ty)),
+    };
+
+    Ok((name.to_owned(), value))
+}
 
 fn write_method_result(
     width: u32,
     height: u32,
     data: &dyn txkit_core::image::MappedImageData,
     args: &Args,
+    frame: usize,
 ) -> Result<()> {
     if let Some(output_path) = &args.output_path {
+        let output_path = if args.frames > 1 {
+            frame_path(output_path, frame)
+        } else {
+            output_path.clone()
+        };
+
         image::save_buffer(
             &output_path,
             data.as_u8_nd_array().unwrap().as_slice().unwrap(),
@@ -73,61 +155,53 @@ fn write_method_result(
     Ok(())
 }
 
-fn write_gpu_method_result(
-    mut method: Box<dyn txkit_core::method::Method>,
-    args: &Args,
-) -> Result<()> {
-    let width = args.size;
-    let height = args.size;
-
-    // Create context
-    let mut ctx = txkit_core::context::Context::new_gpu()?;
-
-    // Create target image
-    let mut img = txkit_core::image::Image::new_gpu_2d(
-        txkit_core::image::ImageDim::new(width, height, 4),
-        txkit_core::image::ImageDataType::UInt8,
-        &ctx,
-    )?;
-
-    // Compute resulting image
-    method.compute(&mut ctx, &mut img, None)?;
-
-    // Sync image
-    img.download()?;
-
-    // Map it for reading
-    let data = img.data()?;
-    write_method_result(width as u32, height as u32, &*data, args)?;
-
-    Ok(())
+/// Create an image to compute into in the given context
+fn new_target(ctx: &Context, dim: ImageDim) -> Result<Image> {
+    Ok(match ctx {
+        Context::Cpu(_) => Image::new_cpu(dim, ImageDataType::UInt8),
+        Context::Gpu(_) => Image::new_gpu_2d(dim, ImageDataType::UInt8, ctx)?,
+    })
 }
 
-fn write_cpu_method_result(
-    mut method: Box<dyn txkit_core::method::Method>,
+fn write_computed_method_result(
+    mut method: Box<dyn Method>,
+    mut set_frame: impl FnMut(&mut dyn Method, usize) -> Result<()>,
     args: &Args,
 ) -> Result<()> {
     let width = args.size;
     let height = args.size;
+    let dim = ImageDim::new(width, height, 4);
 
     // Create context
-    let mut ctx = txkit_core::context::Context::new_cpu()?;
+    let mut ctx = if args.cpu {
+        Context::new_cpu()?
+    } else {
+        Context::new_auto()?
+    };
 
     // Create target image
-    let mut img = txkit_core::image::Image::new_cpu(
-        txkit_core::image::ImageDim::new(width, height, 4),
-        txkit_core::image::ImageDataType::UInt8,
-    );
+    let mut img = new_target(&ctx, dim)?;
+
+    // Fall back to the CPU for methods without a GPU implementation
+    if !method.supports(&ctx, &img) && ctx.kind() == ContextKind::Gpu {
+        warn!("method not supported on the GPU, falling back to the CPU");
 
-    // Compute resulting image
-    method.compute(&mut ctx, &mut img, None)?;
+        ctx = Context::new_cpu()?;
+        img = new_target(&ctx, dim)?;
+    }
+
+    for frame in 0..args.frames.max(1) {
+        // Compute resulting image
+        set_frame(&mut *method, frame)?;
+        method.compute(&mut ctx, &mut img, None)?;
 
-    // Sync image
-    img.download()?;
+        // Sync image
+        img.download()?;
 
-    // Map it for reading
-    let data = img.data()?;
-    write_method_result(width as u32, height as u32, &*data, args)?;
+        // Map it for reading
+        let data = img.data()?;
+        write_method_result(width as u32, height as u32, &*data, args, frame)?;
+    }
 
     Ok(())
 }
@@ -137,7 +211,12 @@ fn write_cpu_method_result(
 struct Args {
     #[argh(option, short = 'm')]
     /// built-in method to render
-    method: String,
+    method: Option<String>,
+
+    #[cfg(feature = "serde")]
+    #[argh(option, short = 'p')]
+    /// preset file (json, toml or ron) with the method to render and its parameters
+    preset: Option<PathBuf>,
 
     #[argh(option, short = 'o')]
     /// output path
@@ -150,6 +229,15 @@ struct Args {
     #[argh(switch)]
     /// force use of the CPU for computing results
     cpu: bool,
+
+    #[argh(option)]
+    /// set a parameter of the method, as name=value (vectors as x,y,...), can be repeated
+    param: Vec<String>,
+
+    #[argh(option, short = 'n', default = "1")]
+    /// number of frames of a looping animation: frames are evenly spaced over one period of the
+    /// method, and the frame index is appended to the output file name
+    frames: usize,
 }
 
 fn main() -> Result<()> {
@@ -162,19 +250,70 @@ fn main() -> Result<()> {
     let args: Args = argh::from_env();
     let registry = txkit_builtin::methods::new_registry();
 
-    if args.cpu {
-        write_cpu_method_result(
-            registry
-                .build(args.method.as_str())
-                .ok_or(txkit_core::Error::MethodNotFound)?,
-            &args,
-        )
-    } else {
-        write_gpu_method_result(
+    #[cfg(feature = "serde")]
+    let preset = args
+        .preset
+        .as_ref()
+        .map(|path| -> Result<_> {
+            let mut preset = txkit_builtin::preset::Preset::load(path)?;
+            // No images are available from the command line
+            preset.resolve(|_| None)?;
+            Ok(preset)
+        })
+        .transpose()?;
+
+    #[cfg(feature = "serde")]
+    let method = preset.map(|preset| preset.build()).transpose()?;
+    #[cfg(not(feature = "serde"))]
+    let method: Option<Box<dyn Method>> = None;
+
+    let mut method = match method {
+        Some(method) => method,
+        None => {
+            let method_name = args
+                .method
+                .as_deref()
+                .ok_or_else(|| eyre!("a method or a preset is required"))?;
+
             registry
-                .build(args.method.as_str())
-                .ok_or(txkit_core::Error::MethodNotFound)?,
-            &args,
-        )
+                .build(method_name)
+                .ok_or(txkit_core::Error::MethodNotFound)?
+        }
+    };
+
+    for assignment in &args.param {
+        let (name, value) = parse_param(&*method, assignment)?;
+        method.set_param_value(&name, value)?;
     }
+
+    // Long computations, e.g. erosion, report their progress
+    method.set_progress(Some(Box::new(|completed, total| {
+        info!("step {}/{}", completed, total);
+    })));
+
+    // Animation frames are rendered by setting the time parameter, later frames are offset from
+    // the time the method had before the first frame
+    let frames = args.frames;
+    let mut start_time = None;
+    let set_frame = move |method: &mut dyn Method, frame: usize| -> Result<()> {
+        if frames > 1 {
+            // Lattice noises only depend on time in 4D
+            if let Ok(ParamValue::I32(dimensions)) = method.get_param("dimensions") {
+                if dimensions != LATTICE_DIMENSIONS_4D {
+                    return Err(eyre!(
+                        "rendering frames requires 4D noise, use --param dimensions={}",
+                        LATTICE_DIMENSIONS_4D
+                    ));
+                }
+            }
+
+            let start = *start_time.get_or_insert(method.get_param("time")?);
+            let time = frame_time(start, method.get_param("period")?, frame, frames)?;
+            method.set_param_value("time", time.into())?;
+        }
+
+        Ok(())
+    };
+
+    write_computed_method_result(method, set_frame, &args)
 }
@@ -0,0 +1,74 @@
+//! Rendering of looping animation frames from the command line
+
+#![cfg(feature = "cpu")]
+
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Empty directory for the outputs of a test
+fn output_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("txkit-cli-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Run the command-line interface, returning its success and standard error
+fn txkit(args: &[&str]) -> (bool, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_txkit-cli"))
+        .args(args)
+        .output()
+        .expect("failed to run txkit-cli");
+
+    (
+        output.status.success(),
+        String::from_utf8_lossy(&output.stderr).into_owned(),
+    )
+}
+
+#[test]
+fn frames_are_rendered_without_preset() {
+    let dir = output_dir("frames");
+    let output = dir.join("noise.png");
+
+    let (success, stderr) = txkit(&[
+        "--cpu",
+        "-m",
+        "value_noise",
+        "-s",
+        "16",
+        "-n",
+        "3",
+        "--param",
+        "dimensions=4",
+        "--param",
+        "period=3",
+        "-o",
+        output.to_str().unwrap(),
+    ]);
+    assert!(success, "{}", stderr);
+
+    let frames: Vec<_> = (0..3)
+        .map(|frame| std::fs::read(dir.join(format!("noise_{:04}.png", frame))).unwrap())
+        .collect();
+    assert_ne!(frames[0], frames[1]);
+    assert_ne!(frames[0], frames[2]);
+    assert_ne!(frames[1], frames[2]);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn frames_require_looping_noise() {
+    let (success, stderr) = txkit(&["--cpu", "-m", "value_noise", "-s", "16", "-n", "3"]);
+    assert!(!success);
+    assert!(stderr.contains("--param dimensions=4"), "{}", stderr);
+}
+
+#[test]
+fn invalid_params_are_rejected() {
+    for param in ["scale", "scale=abc", "unknown=1", "stats_look_at=0.5"] {
+        let (success, _) = txkit(&["--cpu", "-m", "value_noise", "-s", "16", "--param", param]);
+        assert!(!success, "{}", param);
+    }
+}
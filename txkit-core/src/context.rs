@@ -1,3 +1,7 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::image::Image;
 use crate::Result;
 
 #[cfg(feature = "cpu")]
@@ -15,6 +19,16 @@ pub struct CpuContext;
 #[cfg(not(feature = "gpu-core"))]
 pub struct GpuContext;
 
+/// Kind of computing context
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContextKind {
+    /// CPU context, computing on a thread pool
+    Cpu,
+    /// GPU context, computing with OpenGL
+    Gpu,
+}
+
 /// txkit computing context
 pub enum Context {
     Cpu(CpuContext),
@@ -42,6 +56,67 @@ impl Context {
         Err(crate::Error::ContextNotSupported)
     }
 
+    /// Get the kind of this context
+    pub fn kind(&self) -> ContextKind {
+        match self {
+            Self::Cpu(_) => ContextKind::Cpu,
+            Self::Gpu(_) => ContextKind::Gpu,
+        }
+    }
+
+    /// Create a new context, preferring the GPU
+    ///
+    /// A CPU context is created if a GPU context can't be created, e.g. when no display is
+    /// available or when txkit is built without GPU support.
+    pub fn new_auto() -> Result<Self> {
+        Self::new_gpu().or_else(|error| {
+            log::info!("GPU context unavailable ({}), using a CPU context", error);
+            Self::new_cpu()
+        })
+    }
+
+    /// Set the texture coordinates at which the pixels of computed images are evaluated
+    ///
+    /// The domain is a 2D `Float32` image with the same width and height as the computed images,
+    /// and is used for all their layers. The red and green channels of a pixel of the domain are
+    /// texture coordinates, and its blue channel is their weight against the center of the pixel:
+    /// pixels with a zero weight are evaluated at their center, as without a domain.
+    ///
+    /// Methods defined on pixel indices (debug, white and blue noise) and methods transforming
+    /// whole images (distance transform, erosion) ignore the domain.
+    ///
+    /// # Parameters
+    ///
+    /// * `domain`: domain image, or `None` to evaluate pixels at their centers
+    ///
+    /// # Returns
+    ///
+    /// The previous domain of this context.
+    pub fn set_domain(&mut self, domain: Option<Rc<RefCell<Image>>>) -> Option<Rc<RefCell<Image>>> {
+        match self {
+            #[cfg(feature = "cpu")]
+            Self::Cpu(context) => std::mem::replace(&mut context.domain, domain),
+            #[cfg(feature = "gpu-core")]
+            Self::Gpu(context) => std::mem::replace(&mut context.domain, domain),
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+
+    /// Get the texture coordinates at which the pixels of computed images are evaluated
+    ///
+    /// See [Context::set_domain].
+    pub fn domain(&self) -> Option<&Rc<RefCell<Image>>> {
+        match self {
+            #[cfg(feature = "cpu")]
+            Self::Cpu(context) => context.domain.as_ref(),
+            #[cfg(feature = "gpu-core")]
+            Self::Gpu(context) => context.domain.as_ref(),
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+
     pub fn cpu(&self) -> Option<&CpuContext> {
         match self {
             Self::Cpu(context) => Some(context),
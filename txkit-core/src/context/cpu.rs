@@ -1,14 +1,22 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::image::Image;
 use crate::Result;
 
 /// txkit internal context for CPU computations
 pub struct CpuContext {
     pub thread_pool: rayon::ThreadPool,
+
+    /// Texture coordinates of the pixels, see [Context::set_domain](super::Context::set_domain)
+    pub domain: Option<Rc<RefCell<Image>>>,
 }
 
 impl CpuContext {
     pub fn new() -> Result<Self> {
         Ok(Self {
             thread_pool: rayon::ThreadPoolBuilder::new().build()?,
+            domain: None,
         })
     }
 }
@@ -1,3 +1,4 @@
+use std::cell::RefCell;
 use std::rc::Rc;
 
 use glutin::event_loop::EventLoop;
@@ -5,8 +6,11 @@ use glutin::{Context, ContextBuilder, PossiblyCurrent};
 
 use tinygl::wrappers::GlHandle;
 
-use crate::image::{gpu::GpuImageData, ImageDim};
-use crate::Result;
+use crate::image::{gpu::GpuImageData, Image, ImageDataType, ImageDim};
+use crate::{Error, Result};
+
+/// Texture unit of the domain, see `iDomain` in `shared.glsl`
+pub const DOMAIN_TEXTURE_UNIT: u32 = 15;
 
 /// txkit internal context for GPU computations
 #[allow(dead_code)]
@@ -22,6 +26,9 @@ pub struct GpuContext {
 
     /// Default render target
     rtt: TextureRenderTarget,
+
+    /// Texture coordinates of the pixels, see [Context::set_domain](super::Context::set_domain)
+    pub domain: Option<Rc<RefCell<Image>>>,
 }
 
 impl GpuContext {
@@ -71,6 +78,7 @@ impl GpuContext {
             gl,
             vao,
             rtt,
+            domain: None,
         })
     }
 
@@ -167,6 +175,38 @@ impl GpuContext {
     }
 }
 
+/// Bind the domain of a context to [DOMAIN_TEXTURE_UNIT]
+///
+/// Without a domain, the texture unit is unbound, so programs evaluate pixels at their centers.
+///
+/// # Parameters
+///
+/// * `gl`: OpenGL context
+/// * `domain`: domain of the context, see [GpuContext::domain]
+pub fn bind_domain(gl: &tinygl::Context, domain: Option<&Rc<RefCell<Image>>>) -> Result<()> {
+    let domain = match domain {
+        Some(domain) => domain.borrow(),
+        None => {
+            unsafe { gl.bind_texture_unit(DOMAIN_TEXTURE_UNIT, 0) };
+            return Ok(());
+        }
+    };
+
+    let gpu = domain
+        .as_gpu_image()
+        .filter(|gpu| gpu.target() == tinygl::gl::TEXTURE_2D)
+        .ok_or_else(|| Error::InvalidDomain("a 2D GPU image is required".to_owned()))?;
+
+    if domain.element_type() != ImageDataType::Float32 {
+        return Err(Error::InvalidDomain(
+            "a Float32 image is required".to_owned(),
+        ));
+    }
+
+    unsafe { gl.bind_texture_unit(DOMAIN_TEXTURE_UNIT, gpu.texture.name()) };
+    Ok(())
+}
+
 pub struct TextureRenderTarget {
     pub framebuffer: GlHandle<tinygl::wrappers::Framebuffer>,
     pub depthbuffer: GlHandle<tinygl::wrappers::Renderbuffer>,
@@ -1,5 +1,7 @@
 use thiserror::Error;
 
+use crate::method::ParamType;
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("the method doesn't support the given context")]
@@ -17,8 +19,28 @@ pub enum Error {
     MethodInitializationFailed(String),
     #[error("mapping image failed: {0}")]
     MappingFailed(#[from] crate::image::ImageDataError),
+    #[error("image creation failed: {0}")]
+    ImageCreationFailed(#[from] crate::image::ImageCreationError),
     #[error("the provided parameters do not apply to the given method")]
     InvalidParameters,
+    #[error("invalid parameter name")]
+    InvalidParamName,
+    #[error("the parameter `{0}` was not found")]
+    ParamNotFound(String),
+    #[error("invalid type for parameter `{name}`: expected {expected:?}, got {actual:?}")]
+    InvalidParamType {
+        name: String,
+        expected: ParamType,
+        actual: ParamType,
+    },
+    #[error("the parameter `{0}` cannot be accessed as a value")]
+    ParamNotAccessible(String),
+    #[error("the image binding `{0}` was not resolved")]
+    UnresolvedImageBinding(String),
+    #[error("invalid domain: {0}")]
+    InvalidDomain(String),
+    #[error("failed to load preset: {0}")]
+    PresetLoadFailed(String),
 
     #[cfg(feature = "gpu-core")]
     #[error("gpu context creation failed: {0}")]
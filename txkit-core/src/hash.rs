@@ -0,0 +1,142 @@
+//! CPU port of the hashing utilities in `shared.glsl`
+//!
+//! All functions in this module are bit-exact ports of their GLSL counterparts, so CPU methods
+//! sample the same random values as the GPU methods.
+
+/// Low-bias 32 bit hash function
+///
+/// See https://github.com/skeeto/hash-prospector#three-round-functions
+pub fn hash(x: u32) -> u32 {
+    let mut x = x.wrapping_add(1);
+    x = ((x >> 17) ^ x).wrapping_mul(0xed5ad4bb);
+    x = ((x >> 11) ^ x).wrapping_mul(0xac4c1b51);
+    x = ((x >> 15) ^ x).wrapping_mul(0x31848bab);
+    (x >> 14) ^ x
+}
+
+/// Insert a 0 bit after each of the 16 low bits of x
+///
+/// See https://fgiesen.wordpress.com/2009/12/13/decoding-morton-codes/
+pub fn morton_part_1_by_1(x: u32) -> u32 {
+    let mut x = x & 0x0000ffff;
+    x = (x ^ (x << 8)) & 0x00ff00ff;
+    x = (x ^ (x << 4)) & 0x0f0f0f0f;
+    x = (x ^ (x << 2)) & 0x33333333;
+    (x ^ (x << 1)) & 0x55555555
+}
+
+/// Insert two 0 bits after each of the 10 low bits of x
+///
+/// See https://fgiesen.wordpress.com/2009/12/13/decoding-morton-codes/
+pub fn morton_part_1_by_2(x: u32) -> u32 {
+    let mut x = x & 0x000003ff;
+    x = (x ^ (x << 16)) & 0xff0000ff;
+    x = (x ^ (x << 8)) & 0x0300f00f;
+    x = (x ^ (x << 4)) & 0x030c30c3;
+    (x ^ (x << 2)) & 0x09249249
+}
+
+/// Insert three 0 bits after each of the 8 low bits of x
+///
+/// See https://fgiesen.wordpress.com/2009/12/13/decoding-morton-codes/
+pub fn morton_part_1_by_3(x: u32) -> u32 {
+    let mut x = x & 0x000000ff;
+    x = (x ^ (x << 12)) & 0x000f000f;
+    x = (x ^ (x << 6)) & 0x03030303;
+    (x ^ (x << 3)) & 0x11111111
+}
+
+/// Encode two coordinates in Morton order
+pub fn morton(x: u32, y: u32) -> u32 {
+    (morton_part_1_by_1(y) << 1) | morton_part_1_by_1(x)
+}
+
+/// Encode three coordinates in Morton order
+pub fn morton3(x: u32, y: u32, z: u32) -> u32 {
+    (morton_part_1_by_2(z) << 2) | (morton_part_1_by_2(y) << 1) | morton_part_1_by_2(x)
+}
+
+/// Encode four coordinates in Morton order
+pub fn morton4(x: u32, y: u32, z: u32, w: u32) -> u32 {
+    (morton_part_1_by_3(w) << 3)
+        | (morton_part_1_by_3(z) << 2)
+        | (morton_part_1_by_3(y) << 1)
+        | morton_part_1_by_3(x)
+}
+
+/// Hash a (coordinates, seed) pair
+///
+/// Note that the coordinates are enumerated in Morton order, then hashed along with the seed.
+/// Thus, cell coordinates are limited to their low 8 bits. Same thing for the seed which is
+/// limited to its low 16 bits.
+pub fn hash_cell([x, y]: [u32; 2], seed: u32) -> u32 {
+    hash((seed << 16) | (0x0000ffff & morton(x, y)))
+}
+
+/// Hash a (coordinates, seed) pair, return two values
+///
+/// See [hash_cell] for limitations.
+pub fn hash2([x, y]: [u32; 2], seed: u32) -> [u32; 2] {
+    // Mix both coordinates into one seed value
+    let base = hash(seed.wrapping_add(morton(x, y)));
+    // Hash both coordinates
+    [
+        hash(base.wrapping_mul(2)),
+        hash(base.wrapping_mul(2).wrapping_add(1)),
+    ]
+}
+
+/// Hash a 3D (coordinates, seed) pair
+///
+/// Note that the coordinates are enumerated in Morton order, then mixed with the seed. Thus, cell
+/// coordinates are limited to their low 10 bits.
+pub fn hash_cell3([x, y, z]: [u32; 3], seed: u32) -> u32 {
+    hash(seed.wrapping_add(morton3(x, y, z)))
+}
+
+/// Hash a 3D (coordinates, seed) pair, return three values
+///
+/// See [hash_cell3] for limitations.
+pub fn hash3(p: [u32; 3], seed: u32) -> [u32; 3] {
+    // Mix all coordinates into one seed value
+    let base = hash_cell3(p, seed);
+    // Hash all coordinates
+    [
+        hash(base.wrapping_mul(3)),
+        hash(base.wrapping_mul(3).wrapping_add(1)),
+        hash(base.wrapping_mul(3).wrapping_add(2)),
+    ]
+}
+
+/// Hash a 4D (coordinates, seed) pair
+///
+/// Note that the coordinates are enumerated in Morton order, then mixed with the seed. Thus, cell
+/// coordinates are limited to their low 8 bits.
+pub fn hash_cell4([x, y, z, w]: [u32; 4], seed: u32) -> u32 {
+    hash(seed.wrapping_add(morton4(x, y, z, w)))
+}
+
+/// Hash a 4D (coordinates, seed) pair, return four values
+///
+/// See [hash_cell4] for limitations.
+pub fn hash4(p: [u32; 4], seed: u32) -> [u32; 4] {
+    // Mix all coordinates into one seed value
+    let base = hash_cell4(p, seed);
+    // Hash all coordinates
+    [
+        hash(base.wrapping_mul(4)),
+        hash(base.wrapping_mul(4).wrapping_add(1)),
+        hash(base.wrapping_mul(4).wrapping_add(2)),
+        hash(base.wrapping_mul(4).wrapping_add(3)),
+    ]
+}
+
+/// Convert an unsigned int to a float in [0, 1]
+pub fn tofloat(u: u32) -> f32 {
+    f32::from_bits(0x7f << 23 | u >> 9) - 1.
+}
+
+/// Convert an unsigned int to a float in [-1, 1]
+pub fn tofloat11(u: u32) -> f32 {
+    2. * tofloat(u) - 1.
+}
@@ -60,6 +60,58 @@ impl Image {
         }
     }
 
+    /// Create a 2D image where the given context computes: in memory for CPU contexts, as a
+    /// texture for GPU contexts
+    ///
+    /// # Parameters
+    ///
+    /// * `dim`: dimensions of the image
+    /// * `element_type`: type of the pixel channels
+    /// * `context`: context the image is used in
+    pub fn new_2d_for(
+        dim: ImageDim,
+        element_type: ImageDataType,
+        context: &crate::context::Context,
+    ) -> Result<Self, ImageCreationError> {
+        match context {
+            crate::context::Context::Cpu(_) => Ok(Self::new_cpu(dim, element_type)),
+            crate::context::Context::Gpu(_) => Self::new_gpu_2d(dim, element_type, context),
+        }
+    }
+
+    /// Copy a CPU image to a 2D image where the given context computes, see [Image::new_2d_for]
+    ///
+    /// CPU contexts use the image as-is, GPU contexts get a new texture with the same contents.
+    ///
+    /// # Parameters
+    ///
+    /// * `context`: context the image is used in
+    pub fn into_2d_for(self, context: &crate::context::Context) -> crate::Result<Self> {
+        if let crate::context::Context::Cpu(_) = context {
+            return Ok(self);
+        }
+
+        let mut image = Self::new_gpu_2d(self.dim(), self.element_type(), context)?;
+
+        {
+            let src = self.data()?;
+            let mut dst = image.data_mut()?;
+
+            if let (Some(src), Some(mut dst)) = (src.as_f32_nd_array(), dst.as_f32_nd_array_mut()) {
+                dst.assign(&src);
+            } else if let (Some(src), Some(mut dst)) =
+                (src.as_u8_nd_array(), dst.as_u8_nd_array_mut())
+            {
+                dst.assign(&src);
+            } else {
+                return Err(crate::Error::FormatNotSupported);
+            }
+        }
+
+        image.upload()?;
+        Ok(image)
+    }
+
     #[cfg(feature = "gpu-core")]
     pub fn new_gpu_1d(
         dim: ImageDim,
@@ -370,6 +370,13 @@ impl ImageDataBase for GpuImageData {
     fn element_type(&self) -> ImageDataType {
         self.element_type
     }
+    fn dimensionality(&self) -> ImageDimensionality {
+        match self.target {
+            tinygl::gl::TEXTURE_1D => ImageDimensionality::Image1D,
+            tinygl::gl::TEXTURE_2D => ImageDimensionality::Image2D,
+            _ => ImageDimensionality::Image3D,
+        }
+    }
     fn download(&mut self) -> Result<(), Error> {
         self.start_download()
     }
@@ -1,7 +1,7 @@
 use ndarray::{ArrayView4, ArrayViewMut4};
 use thiserror::Error;
 
-use super::{ImageDataType, ImageDim};
+use super::{ImageDataType, ImageDim, ImageDimensionality};
 
 pub trait ImageDataBase {
     /// Get the dimensions of the stored image
@@ -10,6 +10,11 @@ pub trait ImageDataBase {
     /// Get the native type of elements in this image
     fn element_type(&self) -> ImageDataType;
 
+    /// Get the dimensionality of this image
+    fn dimensionality(&self) -> ImageDimensionality {
+        self.dim().dimensionality()
+    }
+
     /// Download texture data to the mappable buffer
     /// Required for GPU backends. May be asynchronous.
     fn download(&mut self) -> crate::Result<()> {
@@ -59,6 +59,33 @@ impl<T> Into<(T, T, T, T)> for ImageDimensions<T> {
 
 pub type ImageDim = ImageDimensions<usize>;
 
+impl ImageDim {
+    /// Get the dimensionality of an image with these dimensions
+    ///
+    /// Unit dimensions are not counted, i.e. a `w x 1 x 1` image is 1D.
+    pub fn dimensionality(&self) -> ImageDimensionality {
+        if self.depth > 1 {
+            ImageDimensionality::Image3D
+        } else if self.height > 1 {
+            ImageDimensionality::Image2D
+        } else {
+            ImageDimensionality::Image1D
+        }
+    }
+}
+
+/// Number of dimensions spanned by an image
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ImageDimensionality {
+    /// 1D image (width)
+    Image1D,
+    /// 2D image (width and height)
+    Image2D,
+    /// 3D image (width, height and depth)
+    Image3D,
+}
+
 #[cfg(feature = "gpu-core")]
 mod gpu {
     use super::ImageDim;
@@ -11,6 +11,8 @@ pub enum ImageBinding {
     ImageRef(Rc<RefCell<Image>>),
     /// Reference to an image for FFI
     ImagePtr(*mut Image),
+    /// Reference to an image by name, to be replaced using [ImageIo::resolve] before computing
+    Named(String),
 }
 
 impl Default for ImageBinding {
@@ -31,6 +33,10 @@ impl PartialEq for ImageBinding {
                 Self::ImagePtr(other_ptr) => self_ptr == other_ptr,
                 _ => false,
             },
+            Self::Named(self_name) => match other {
+                Self::Named(other_name) => self_name == other_name,
+                _ => false,
+            },
         }
     }
 }
@@ -136,6 +142,215 @@ impl ImageIo {
 
         self.image_bindings[index] = binding;
     }
+
+    /// Replace named bindings with the images they refer to
+    ///
+    /// # Parameters
+    ///
+    /// * `resolver`: function returning the binding for an image name, or `None` if there is no
+    ///   image with this name
+    pub fn resolve(
+        &mut self,
+        mut resolver: impl FnMut(&str) -> Option<ImageBinding>,
+    ) -> crate::Result<()> {
+        for binding in self
+            .texture_bindings
+            .iter_mut()
+            .chain(self.image_bindings.iter_mut())
+        {
+            if let ImageBinding::Named(name) = binding {
+                *binding = resolver(name)
+                    .ok_or_else(|| crate::Error::UnresolvedImageBinding(name.clone()))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    //! Image bindings are serialized as the names of the images they refer to, keyed by unit
+    //! index. Only empty and named bindings can be serialized.
+
+    use std::collections::BTreeMap;
+
+    use serde::{de, ser, Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::*;
+
+    #[derive(Default, Serialize, Deserialize)]
+    #[serde(default)]
+    struct NamedBindings {
+        #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+        texture_bindings: BTreeMap<String, String>,
+        #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+        image_bindings: BTreeMap<String, String>,
+    }
+
+    fn to_names<E: ser::Error>(bindings: &[ImageBinding]) -> Result<BTreeMap<String, String>, E> {
+        let mut names = BTreeMap::new();
+
+        for (index, binding) in bindings.iter().enumerate() {
+            match binding {
+                ImageBinding::None => {}
+                ImageBinding::Named(name) => {
+                    names.insert(index.to_string(), name.clone());
+                }
+                _ => {
+                    return Err(E::custom(format!(
+                        "binding {} must be named to be serialized",
+                        index
+                    )))
+                }
+            }
+        }
+
+        Ok(names)
+    }
+
+    fn from_names<E: de::Error>(
+        names: BTreeMap<String, String>,
+        bindings: &mut [ImageBinding],
+    ) -> Result<(), E> {
+        let len = bindings.len();
+
+        for (index, name) in names {
+            let binding = index
+                .parse::<usize>()
+                .ok()
+                .and_then(|index| bindings.get_mut(index))
+                .ok_or_else(|| {
+                    E::custom(format!(
+                        "invalid binding index {:?}, expected an integer below {}",
+                        index, len
+                    ))
+                })?;
+
+            *binding = ImageBinding::Named(name);
+        }
+
+        Ok(())
+    }
+
+    impl Serialize for ImageIo {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            NamedBindings {
+                texture_bindings: to_names(&self.texture_bindings)?,
+                image_bindings: to_names(&self.image_bindings)?,
+            }
+            .serialize(serializer)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for ImageIo {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let names = NamedBindings::deserialize(deserializer)?;
+            let mut io = ImageIo::default();
+
+            from_names(names.texture_bindings, &mut io.texture_bindings)?;
+            from_names(names.image_bindings, &mut io.image_bindings)?;
+
+            Ok(io)
+        }
+    }
+}
+
+#[cfg(feature = "cpu")]
+pub mod cpu {
+    use ndarray::Array4;
+
+    use super::*;
+    use crate::image::IntoElementType;
+
+    /// CPU copy of an image bound to a texture unit
+    ///
+    /// Samples follow the state of textures allocated by txkit: bilinear filtering and repeat
+    /// wrapping. As in OpenGL, missing channels and unbound textures read as `(0, 0, 0, 1)`.
+    #[derive(Debug, Clone, Default)]
+    pub struct CpuTexture {
+        data: Option<Array4<f32>>,
+    }
+
+    impl CpuTexture {
+        /// Copy the contents of an image for sampling
+        ///
+        /// # Parameters
+        ///
+        /// * `image`: image to copy. GPU images must have been downloaded first.
+        pub fn from_image(image: &Image) -> crate::Result<Self> {
+            let data = image.data()?;
+
+            let data = if let Some(data) = data.as_f32_nd_array() {
+                data.to_owned()
+            } else if let Some(data) = data.as_u8_nd_array() {
+                data.mapv(|x| x.into_f32())
+            } else {
+                return Err(crate::Error::FormatNotSupported);
+            };
+
+            Ok(Self {
+                data: if data.is_empty() { None } else { Some(data) },
+            })
+        }
+
+        /// Sample the first layer of this texture, as GLSL's `texture(sampler2D, vec2)`
+        ///
+        /// # Parameters
+        ///
+        /// * `uv`: normalized texture coordinates
+        pub fn sample_2d(&self, [u, v]: [f32; 2]) -> [f32; 4] {
+            let mut res = [0., 0., 0., 1.];
+
+            if let Some(data) = &self.data {
+                let (_, height, width, channels) = data.dim();
+
+                // Texel centers are at half-integer coordinates
+                let x = u * width as f32 - 0.5;
+                let y = v * height as f32 - 0.5;
+                let (fx, fy) = (x - x.floor(), y - y.floor());
+
+                let wrap = |x: f32, n: usize| (x as i64).rem_euclid(n as i64) as usize;
+                let (i0, i1) = (wrap(x.floor(), width), wrap(x.floor() + 1., width));
+                let (j0, j1) = (wrap(y.floor(), height), wrap(y.floor() + 1., height));
+
+                for (l, r) in res.iter_mut().enumerate().take(channels) {
+                    let t = |j, i| data[[0, j, i, l]];
+
+                    *r = (t(j0, i0) * (1. - fx) + t(j0, i1) * fx) * (1. - fy)
+                        + (t(j1, i0) * (1. - fx) + t(j1, i1) * fx) * fy;
+                }
+            }
+
+            res
+        }
+    }
+
+    pub trait CpuImageIoExt {
+        /// Copy the image bound to a texture unit for sampling on the CPU
+        ///
+        /// Fails with [crate::Error::InvalidParameters] for a null image pointer.
+        ///
+        /// # Parameters
+        ///
+        /// * `index`: unit index for the binding
+        fn texture_binding_data(&self, index: usize) -> crate::Result<CpuTexture>;
+    }
+
+    impl CpuImageIoExt for ImageIo {
+        fn texture_binding_data(&self, index: usize) -> crate::Result<CpuTexture> {
+            match self.get_texture_binding(index) {
+                ImageBinding::None => Ok(CpuTexture::default()),
+                ImageBinding::ImageRef(img) => CpuTexture::from_image(&img.borrow()),
+                ImageBinding::ImagePtr(img) => CpuTexture::from_image(
+                    unsafe { img.as_ref() }.ok_or(crate::Error::InvalidParameters)?,
+                ),
+                ImageBinding::Named(name) => {
+                    Err(crate::Error::UnresolvedImageBinding(name.clone()))
+                }
+            }
+        }
+    }
 }
 
 #[cfg(feature = "gpu-core")]
@@ -149,8 +364,8 @@ pub mod gpu {
             index: usize,
             access: tinygl::gl::types::GLenum,
             format: tinygl::gl::types::GLenum,
-        );
-        fn apply_texture_binding(&self, gl: &tinygl::Context, index: usize);
+        ) -> crate::Result<()>;
+        fn apply_texture_binding(&self, gl: &tinygl::Context, index: usize) -> crate::Result<()>;
     }
 
     impl GpuImageIoExt for ImageIo {
@@ -160,7 +375,7 @@ pub mod gpu {
             index: usize,
             access: tinygl::gl::types::GLenum,
             format: tinygl::gl::types::GLenum,
-        ) {
+        ) -> crate::Result<()> {
             let binding = self.get_image_binding(index);
 
             unsafe {
@@ -201,11 +416,16 @@ pub mod gpu {
                             format,
                         );
                     }
+                    ImageBinding::Named(name) => {
+                        return Err(crate::Error::UnresolvedImageBinding(name.clone()))
+                    }
                 }
             }
+
+            Ok(())
         }
 
-        fn apply_texture_binding(&self, gl: &tinygl::Context, index: usize) {
+        fn apply_texture_binding(&self, gl: &tinygl::Context, index: usize) -> crate::Result<()> {
             let binding = self.get_texture_binding(index);
 
             unsafe {
@@ -230,8 +450,13 @@ pub mod gpu {
 
                         gl.bind_texture_unit(index as _, gpu.texture.name());
                     }
+                    ImageBinding::Named(name) => {
+                        return Err(crate::Error::UnresolvedImageBinding(name.clone()))
+                    }
                 }
             }
+
+            Ok(())
         }
     }
 }
@@ -0,0 +1,923 @@
+//! CPU port of the lattice noise utilities in `noise_stats.glsl`
+//!
+//! Lattice-based noise methods sample their lattice through [Lattice], which implements the
+//! statistics modes of the GPU methods: in process and look-at modes, each pixel is seeded
+//! differently, and lattice cells don't loop around the texture borders.
+
+use cgmath::{vec2, vec3, vec4, InnerSpace, Vector2, Vector3, Vector4};
+
+use crate::hash::{hash, hash2, hash3, morton, morton3, tofloat};
+use crate::image::ImageDim;
+
+/// Stats mode: regular noise evaluation
+pub const STATS_MODE_NORMAL: i32 = 0;
+/// Stats mode: each pixel is a sample of the noise process at a random position
+pub const STATS_MODE_PROCESS: i32 = 1;
+/// Stats mode: each pixel is a sample of the noise process at the look-at position
+pub const STATS_MODE_LOOKAT: i32 = 2;
+
+/// Lattice dimensions: noise over (x, y), repeated on every layer
+pub const LATTICE_DIMENSIONS_2D: i32 = 2;
+/// Lattice dimensions: noise over (x, y, layer)
+pub const LATTICE_DIMENSIONS_3D: i32 = 3;
+/// Lattice dimensions: noise over (x, y, layer, time)
+pub const LATTICE_DIMENSIONS_4D: i32 = 4;
+
+/// Simplex constant for (sqrt(3)-1)/2
+pub const SIMPLEX_K1: f32 = 0.366_025_42;
+/// Simplex constant for (3-sqrt(3))/6
+pub const SIMPLEX_K2: f32 = 0.211_324_87;
+/// 3D simplex skewing constant (1/3)
+pub const SIMPLEX3D_K1: f32 = 0.333_333_34;
+/// 3D simplex unskewing constant (1/6)
+pub const SIMPLEX3D_K2: f32 = 0.166_666_67;
+/// 4D simplex skewing constant ((sqrt(5)-1)/4)
+pub const SIMPLEX4D_K1: f32 = 0.309_017;
+/// 4D simplex unskewing constant ((5-sqrt(5))/20)
+pub const SIMPLEX4D_K2: f32 = 0.138_196_6;
+
+/// Largest scale and time period of looping 4D simplex lattices, in lattice cells
+///
+/// Reducing cells modulo larger periods overflows the 32-bit arithmetic of
+/// `simplexLatticeLoop4D` on the GPU, see [check_simplex_lattice_period_4d].
+pub const SIMPLEX_LATTICE_4D_MAX_PERIOD: f32 = 512.;
+
+/// Lattice type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LatticeMode {
+    /// Rectangular 2D lattice
+    Rect2D,
+    /// Simplex 2D lattice
+    Simplex2D,
+}
+
+/// 3D lattice type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LatticeMode3D {
+    /// Rectangular 3D lattice
+    Rect3D,
+    /// Simplex 3D lattice
+    Simplex3D,
+}
+
+/// 4D lattice type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LatticeMode4D {
+    /// Rectangular 4D lattice
+    Rect4D,
+    /// Simplex 4D lattice
+    Simplex4D,
+}
+
+/// Sample in a 2D lattice
+#[derive(Debug, Clone, Copy)]
+pub struct LatticeSample {
+    /// Position in the current cell (in [0, 1])
+    pub position: Vector2<f32>,
+    /// Cell number
+    pub cell: [i32; 2],
+    /// Seed for the current sample
+    pub seed: u32,
+}
+
+impl LatticeSample {
+    /// Cell coordinates of this sample as unsigned integers, as `uvec2(s.cell)` does
+    pub fn ucell(&self) -> [u32; 2] {
+        [self.cell[0] as u32, self.cell[1] as u32]
+    }
+}
+
+/// Sample in a 3D lattice
+#[derive(Debug, Clone, Copy)]
+pub struct LatticeSample3D {
+    /// Position in the current cell (in [0, 1])
+    pub position: Vector3<f32>,
+    /// Cell number
+    pub cell: [i32; 3],
+    /// Seed for the current sample
+    pub seed: u32,
+}
+
+impl LatticeSample3D {
+    /// Cell coordinates of this sample as unsigned integers, as `uvec3(s.cell)` does
+    pub fn ucell(&self) -> [u32; 3] {
+        [
+            self.cell[0] as u32,
+            self.cell[1] as u32,
+            self.cell[2] as u32,
+        ]
+    }
+}
+
+/// Sample in a 4D lattice
+#[derive(Debug, Clone, Copy)]
+pub struct LatticeSample4D {
+    /// Position in the current cell (in [0, 1])
+    pub position: Vector4<f32>,
+    /// Cell number
+    pub cell: [i32; 4],
+    /// Seed for the current sample
+    pub seed: u32,
+}
+
+/// 2D, 3D and 4D lattice sampler, the equivalent of the uniforms used by `noise_stats.glsl`
+#[derive(Debug, Clone, Copy)]
+pub struct Lattice {
+    /// How many lattice cells are visible in the current viewport
+    pub scale: f32,
+    /// Pseudo-random seed
+    pub global_seed: u32,
+    /// Stats mode, see `STATS_MODE_*`
+    pub stats_mode: i32,
+    /// Look-at parameter (if stats_mode == lookat)
+    pub stats_look_at: Vector2<f32>,
+    /// Size of the viewport in pixels, as `iResolution`
+    pub resolution: [u32; 3],
+    /// Period of 4D lattices along the time axis, in lattice cells, 0 for no looping
+    pub period: f32,
+}
+
+impl Lattice {
+    /// Create a new lattice sampler
+    ///
+    /// # Parameters
+    ///
+    /// * `scale`: lattice scale
+    /// * `global_seed`: pseudo-random seed
+    /// * `stats_mode`: stats mode, see `STATS_MODE_*`
+    /// * `stats_look_at`: look-at parameter
+    /// * `dim`: dimensions of the target image
+    pub fn new(
+        scale: f32,
+        global_seed: u32,
+        stats_mode: i32,
+        stats_look_at: Vector2<f32>,
+        dim: ImageDim,
+    ) -> Self {
+        Self {
+            scale,
+            global_seed,
+            stats_mode,
+            stats_look_at,
+            resolution: [dim.width as u32, dim.height as u32, dim.depth as u32],
+            period: 0.,
+        }
+    }
+
+    /// Set the period of 4D lattices along the time axis
+    ///
+    /// # Parameters
+    ///
+    /// * `period`: period in lattice cells, 0 for no looping
+    pub fn with_period(self, period: f32) -> Self {
+        Self { period, ..self }
+    }
+
+    /// Period of rectangular 4D lattices along the time axis, in whole lattice cells
+    ///
+    /// The period is rounded to the closest non-zero number of cells, and [Lattice::sample_4d]
+    /// stretches time accordingly so the noise loops exactly over `period`. Returns 0 if the
+    /// lattice does not loop in time.
+    pub fn time_period(&self) -> i32 {
+        if self.period > 0. {
+            ((self.period + 0.5).floor() as i32).max(1)
+        } else {
+            0
+        }
+    }
+
+    /// Sample the lattice
+    ///
+    /// # Parameters
+    ///
+    /// * `uv`: input position in the viewport
+    /// * `mode`: lattice type
+    pub fn sample(&self, uv: Vector2<f32>, mode: LatticeMode) -> LatticeSample {
+        // Regular sampling mode inside the lattice
+        let mut seed = self.global_seed;
+
+        let position = if self.stats_mode == STATS_MODE_NORMAL {
+            // Regular sampling mode is the default
+            uv * self.scale
+        } else {
+            // For process and look-at mode: each pixel being evaluated is seeded differently
+            let px = [
+                (uv.x * self.resolution[0] as f32) as u32,
+                (uv.y * self.resolution[1] as f32) as u32,
+            ];
+
+            // Generate one seed per pixel, mix it with the base seed, then hash it
+            seed = hash(morton(morton(px[0], px[1]), seed));
+
+            if self.stats_mode == STATS_MODE_PROCESS {
+                // In process mode, evaluate at random positions for each pixel
+                let [x, y] = hash2(px, seed.wrapping_mul(32165431));
+                vec2(tofloat(x), tofloat(y)) * self.scale
+            } else if self.stats_mode == STATS_MODE_LOOKAT {
+                // Look-at mode: all pixels describe the same position in a cell, but are
+                // seeded differently
+                self.stats_look_at
+            } else {
+                uv
+            }
+        };
+
+        match mode {
+            LatticeMode::Rect2D => LatticeSample {
+                position: vec2(
+                    position.x - position.x.floor(),
+                    position.y - position.y.floor(),
+                ),
+                cell: [position.x as i32, position.y as i32],
+                seed,
+            },
+            LatticeMode::Simplex2D => {
+                let position = if self.stats_mode == STATS_MODE_NORMAL {
+                    // Map the texture periods onto lattice vectors so the noise tiles. The
+                    // mapping is applied to the viewport position, so it holds for a zero scale.
+                    let a = simplex_lattice_vector(simplex_lattice_period(self.scale));
+                    a * uv.x + vec2(a.y, a.x) * uv.y
+                } else {
+                    position
+                };
+
+                let skew = (position.x + position.y) * SIMPLEX_K1;
+                let cell = [(position.x + skew) as i32, (position.y + skew) as i32];
+                let unskew = (cell[0] + cell[1]) as f32 * SIMPLEX_K2;
+
+                LatticeSample {
+                    position: vec2(
+                        position.x - cell[0] as f32 + unskew,
+                        position.y - cell[1] as f32 + unskew,
+                    ),
+                    cell,
+                    seed,
+                }
+            }
+        }
+    }
+
+    /// Sample the 3D lattice
+    ///
+    /// The lattice has the same scale along the three axes.
+    ///
+    /// # Parameters
+    ///
+    /// * `uvw`: input position in the viewport, the z coordinate spanning the layers of the
+    ///   target image
+    /// * `mode`: lattice type
+    pub fn sample_3d(&self, uvw: Vector3<f32>, mode: LatticeMode3D) -> LatticeSample3D {
+        // Regular sampling mode inside the lattice
+        let mut seed = self.global_seed;
+
+        let position = if self.stats_mode == STATS_MODE_NORMAL {
+            // Regular sampling mode is the default
+            uvw * self.scale
+        } else {
+            // For process and look-at mode: each voxel being evaluated is seeded differently
+            let px = [
+                (uvw.x * self.resolution[0] as f32) as u32,
+                (uvw.y * self.resolution[1] as f32) as u32,
+                (uvw.z * self.resolution[2] as f32) as u32,
+            ];
+
+            // Generate one seed per voxel, mix it with the base seed, then hash it
+            seed = hash(morton(morton3(px[0], px[1], px[2]), seed));
+
+            if self.stats_mode == STATS_MODE_PROCESS {
+                // In process mode, evaluate at random positions for each voxel
+                let [x, y, z] = hash3(px, seed.wrapping_mul(32165431));
+                vec3(tofloat(x), tofloat(y), tofloat(z)) * self.scale
+            } else if self.stats_mode == STATS_MODE_LOOKAT {
+                // Look-at mode: all voxels describe the same position in a cell, but are
+                // seeded differently
+                self.stats_look_at.extend(0.)
+            } else {
+                uvw
+            }
+        };
+
+        match mode {
+            LatticeMode3D::Rect3D => LatticeSample3D {
+                position: position.map(|x| x - x.floor()),
+                cell: [position.x as i32, position.y as i32, position.z as i32],
+                seed,
+            },
+            LatticeMode3D::Simplex3D => {
+                let position = if self.stats_mode == STATS_MODE_NORMAL {
+                    // Map the texture periods onto lattice vectors so the noise tiles
+                    let [m, n] = simplex_lattice_period_3d(self.scale);
+                    simplex_lattice_vector_3d([m, n, n]) * uvw.x
+                        + simplex_lattice_vector_3d([n, m, n]) * uvw.y
+                        + simplex_lattice_vector_3d([n, n, m]) * uvw.z
+                } else {
+                    position
+                };
+
+                let skew = (position.x + position.y + position.z) * SIMPLEX3D_K1;
+                let cell = [
+                    (position.x + skew) as i32,
+                    (position.y + skew) as i32,
+                    (position.z + skew) as i32,
+                ];
+                let unskew = (cell[0] + cell[1] + cell[2]) as f32 * SIMPLEX3D_K2;
+
+                LatticeSample3D {
+                    position: vec3(
+                        position.x - cell[0] as f32 + unskew,
+                        position.y - cell[1] as f32 + unskew,
+                        position.z - cell[2] as f32 + unskew,
+                    ),
+                    cell,
+                    seed,
+                }
+            }
+        }
+    }
+
+    /// Sample the 4D lattice
+    ///
+    /// The lattice has the same scale along the three spatial axes. Statistics modes only apply
+    /// to the spatial axes.
+    ///
+    /// # Parameters
+    ///
+    /// * `position`: input position in the viewport, the z coordinate spanning the layers of the
+    ///   target image, and the w coordinate being the time in lattice cells
+    /// * `mode`: lattice type
+    pub fn sample_4d(&self, position: Vector4<f32>, mode: LatticeMode4D) -> LatticeSample4D {
+        // Regular sampling mode inside the lattice
+        let mut seed = self.global_seed;
+        let uvw = position.truncate();
+
+        let spatial = if self.stats_mode == STATS_MODE_NORMAL {
+            // Regular sampling mode is the default
+            position.truncate() * self.scale
+        } else {
+            // For process and look-at mode: each voxel being evaluated is seeded differently
+            let px = [
+                (position.x * self.resolution[0] as f32) as u32,
+                (position.y * self.resolution[1] as f32) as u32,
+                (position.z * self.resolution[2] as f32) as u32,
+            ];
+
+            // Generate one seed per voxel, mix it with the base seed, then hash it
+            seed = hash(morton(morton3(px[0], px[1], px[2]), seed));
+
+            if self.stats_mode == STATS_MODE_PROCESS {
+                // In process mode, evaluate at random positions for each voxel
+                let [x, y, z] = hash3(px, seed.wrapping_mul(32165431));
+                vec3(tofloat(x), tofloat(y), tofloat(z)) * self.scale
+            } else if self.stats_mode == STATS_MODE_LOOKAT {
+                // Look-at mode: all voxels describe the same position in a cell, but are
+                // seeded differently
+                self.stats_look_at.extend(0.)
+            } else {
+                position.truncate()
+            }
+        };
+        let position = spatial.extend(position.w);
+
+        match mode {
+            LatticeMode4D::Rect4D => {
+                let mut position = position;
+
+                let period = self.time_period();
+                if period > 0 {
+                    // Stretch time so the period is a whole number of cells
+                    position.w *= period as f32 / self.period;
+                }
+
+                LatticeSample4D {
+                    // Time may be negative, round cells towards negative infinity
+                    position: position.map(|x| x - x.floor()),
+                    cell: [
+                        position.x.floor() as i32,
+                        position.y.floor() as i32,
+                        position.z.floor() as i32,
+                        position.w.floor() as i32,
+                    ],
+                    seed,
+                }
+            }
+            LatticeMode4D::Simplex4D => {
+                let position = if self.stats_mode == STATS_MODE_NORMAL {
+                    // Map the texture and time periods onto lattice vectors so the noise tiles
+                    // and loops
+                    let [m, n, mt, nt] = simplex_lattice_period_4d(self.scale, self.period);
+                    let mut p = simplex_lattice_vector_4d([m, n, n, n]) * uvw.x
+                        + simplex_lattice_vector_4d([n, m, n, n]) * uvw.y
+                        + simplex_lattice_vector_4d([n, n, m, n]) * uvw.z;
+
+                    if self.period > 0. {
+                        p += simplex_lattice_vector_4d([nt, nt, nt, mt]) * position.w / self.period;
+                    } else {
+                        p.w += position.w;
+                    }
+
+                    p
+                } else {
+                    position
+                };
+
+                let skew = (position.x + position.y + position.z + position.w) * SIMPLEX4D_K1;
+                let cell = [
+                    (position.x + skew).floor() as i32,
+                    (position.y + skew).floor() as i32,
+                    (position.z + skew).floor() as i32,
+                    (position.w + skew).floor() as i32,
+                ];
+                let unskew = (cell[0] + cell[1] + cell[2] + cell[3]) as f32 * SIMPLEX4D_K2;
+
+                LatticeSample4D {
+                    position: vec4(
+                        position.x - cell[0] as f32 + unskew,
+                        position.y - cell[1] as f32 + unskew,
+                        position.z - cell[2] as f32 + unskew,
+                        position.w - cell[3] as f32 + unskew,
+                    ),
+                    cell,
+                    seed,
+                }
+            }
+        }
+    }
+
+    /// Scale of the lattice coordinates of simplex gradients
+    ///
+    /// Lattice coordinates are the viewport position times the scale. A simplex lattice spans at
+    /// least one lattice vector per texture period, so for non-positive scales, gradients are
+    /// taken with respect to the viewport position instead.
+    fn simplex_lattice_scale(&self) -> f32 {
+        if self.scale > 0. {
+            self.scale
+        } else {
+            1.
+        }
+    }
+
+    /// Convert a gradient in a simplex lattice to lattice coordinates
+    ///
+    /// In normal mode, the simplex lattice is slightly distorted so it tiles, see
+    /// [simplex_lattice_period].
+    ///
+    /// # Parameters
+    ///
+    /// * `g`: gradient with respect to the simplex lattice positions returned by [Lattice::sample]
+    pub fn simplex_lattice_gradient(&self, g: Vector2<f32>) -> Vector2<f32> {
+        if self.stats_mode == STATS_MODE_NORMAL {
+            // The mapping is symmetric, so it is its own transpose
+            let a = simplex_lattice_vector(simplex_lattice_period(self.scale))
+                / self.simplex_lattice_scale();
+            return a * g.x + vec2(a.y, a.x) * g.y;
+        }
+
+        g
+    }
+
+    /// Convert a gradient in a 3D simplex lattice to lattice coordinates
+    ///
+    /// # Parameters
+    ///
+    /// * `g`: gradient with respect to the simplex lattice positions returned by
+    ///   [Lattice::sample_3d]
+    pub fn simplex_lattice_gradient_3d(&self, g: Vector3<f32>) -> Vector3<f32> {
+        if self.stats_mode == STATS_MODE_NORMAL {
+            let [m, n] = simplex_lattice_period_3d(self.scale);
+            return vec3(
+                simplex_lattice_vector_3d([m, n, n]).dot(g),
+                simplex_lattice_vector_3d([n, m, n]).dot(g),
+                simplex_lattice_vector_3d([n, n, m]).dot(g),
+            ) / self.simplex_lattice_scale();
+        }
+
+        g
+    }
+
+    /// Convert a gradient in a 4D simplex lattice to spatial lattice coordinates
+    ///
+    /// # Parameters
+    ///
+    /// * `g`: gradient with respect to the simplex lattice positions returned by
+    ///   [Lattice::sample_4d]
+    pub fn simplex_lattice_gradient_4d(&self, g: Vector4<f32>) -> Vector3<f32> {
+        if self.stats_mode == STATS_MODE_NORMAL {
+            let [m, n, _, _] = simplex_lattice_period_4d(self.scale, self.period);
+            return vec3(
+                simplex_lattice_vector_4d([m, n, n, n]).dot(g),
+                simplex_lattice_vector_4d([n, m, n, n]).dot(g),
+                simplex_lattice_vector_4d([n, n, m, n]).dot(g),
+            ) / self.simplex_lattice_scale();
+        }
+
+        g.truncate()
+    }
+
+    /// Ensure 2D lattice cells loop around the texture borders
+    ///
+    /// The scale is truncated to an integer cell count.
+    ///
+    /// # Parameters
+    ///
+    /// * `p`: cell coordinates to loop
+    pub fn lattice_loop(&self, [x, y]: [u32; 2]) -> [u32; 2] {
+        if self.stats_mode == STATS_MODE_NORMAL {
+            // Guard against a zero period, which is undefined behavior in the shader
+            let s = (self.scale as i32).max(1) as u32;
+            return [x % s, y % s];
+        }
+
+        [x, y]
+    }
+
+    /// Ensure 2D lattice cells loop around the texture borders, for signed cell coordinates
+    ///
+    /// The scale is truncated to an integer cell count.
+    ///
+    /// # Parameters
+    ///
+    /// * `p`: cell coordinates to loop
+    pub fn lattice_loop_signed(&self, [x, y]: [i32; 2]) -> [i32; 2] {
+        if self.stats_mode == STATS_MODE_NORMAL {
+            // Guard against a zero period, which is undefined behavior in the shader
+            let s = (self.scale as i32).max(1);
+            let wrap = |p: i32| if p >= 0 { p % s } else { s - (-p % s) };
+            return [wrap(x), wrap(y)];
+        }
+
+        [x, y]
+    }
+
+    /// Ensure 2D simplex lattice cells loop around the texture borders
+    ///
+    /// Cells are reduced modulo the lattice generated by the periods returned by
+    /// [simplex_lattice_period].
+    ///
+    /// # Parameters
+    ///
+    /// * `p`: skewed cell coordinates to loop
+    pub fn simplex_lattice_loop(&self, [x, y]: [i32; 2]) -> [i32; 2] {
+        if self.stats_mode == STATS_MODE_NORMAL {
+            let [m, n] = simplex_lattice_period(self.scale);
+            let det = m * m - n * n;
+
+            let alpha = (x * m - y * n).div_euclid(det);
+            let beta = (y * m - x * n).div_euclid(det);
+
+            return [x - alpha * m - beta * n, y - alpha * n - beta * m];
+        }
+
+        [x, y]
+    }
+
+    /// Ensure 3D lattice cells loop around the texture borders
+    ///
+    /// The scale is truncated to an integer cell count.
+    ///
+    /// # Parameters
+    ///
+    /// * `p`: cell coordinates to loop
+    pub fn lattice_loop_3d(&self, [x, y, z]: [u32; 3]) -> [u32; 3] {
+        if self.stats_mode == STATS_MODE_NORMAL {
+            // Guard against a zero period, which is undefined behavior in the shader
+            let s = (self.scale as i32).max(1) as u32;
+            return [x % s, y % s, z % s];
+        }
+
+        [x, y, z]
+    }
+
+    /// Ensure 3D simplex lattice cells loop around the texture borders
+    ///
+    /// Cells are reduced modulo the lattice generated by the periods returned by
+    /// [simplex_lattice_period_3d].
+    ///
+    /// # Parameters
+    ///
+    /// * `p`: skewed cell coordinates to loop
+    pub fn simplex_lattice_loop_3d(&self, p: [i32; 3]) -> [i32; 3] {
+        if self.stats_mode == STATS_MODE_NORMAL {
+            let [m, n] = simplex_lattice_period_3d(self.scale);
+            // The period matrix is (m - n) I + n J, with J the all-ones matrix
+            let k = m + 2 * n;
+            let det = (m - n) * k;
+            let sum = p[0] + p[1] + p[2];
+
+            let alpha = p.map(|x| (k * x - n * sum).div_euclid(det));
+            let offset = n * (alpha[0] + alpha[1] + alpha[2]);
+
+            return [
+                p[0] - (m - n) * alpha[0] - offset,
+                p[1] - (m - n) * alpha[1] - offset,
+                p[2] - (m - n) * alpha[2] - offset,
+            ];
+        }
+
+        p
+    }
+
+    /// Ensure 4D lattice cells loop around the texture borders and the time period
+    ///
+    /// The scale is truncated to an integer cell count, see [Lattice::time_period] for the
+    /// period.
+    ///
+    /// # Parameters
+    ///
+    /// * `p`: cell coordinates to loop
+    pub fn lattice_loop_4d(&self, [x, y, z, w]: [i32; 4]) -> [i32; 4] {
+        if self.stats_mode == STATS_MODE_NORMAL {
+            let s = (self.scale as i32).max(1);
+            let period = self.time_period();
+
+            return [
+                x.rem_euclid(s),
+                y.rem_euclid(s),
+                z.rem_euclid(s),
+                if period > 0 { w.rem_euclid(period) } else { w },
+            ];
+        }
+
+        [x, y, z, w]
+    }
+
+    /// Ensure 4D simplex lattice cells loop around the texture borders and the time period
+    ///
+    /// Cells are reduced modulo the lattice generated by the periods returned by
+    /// [simplex_lattice_period_4d]. The products of the periods and the coordinates are computed
+    /// in 64-bit arithmetic, so any period and time give the reduced cell.
+    ///
+    /// # Parameters
+    ///
+    /// * `p`: skewed cell coordinates to loop
+    pub fn simplex_lattice_loop_4d(&self, cell: [i32; 4]) -> [i32; 4] {
+        if self.stats_mode == STATS_MODE_NORMAL {
+            // The period matrix T has columns (m, n, n, n), (n, m, n, n), (n, n, m, n) and
+            // (n', n', n', m'). Solve T alpha = p by first solving for alpha.w and the sum of the
+            // spatial coefficients.
+            let [m, n, mt, nt] = simplex_lattice_period_4d(self.scale, self.period).map(i64::from);
+            let p = cell.map(i64::from);
+            let a = m - n;
+            let b = m + 2 * n;
+            let det = b * mt - 3 * n * nt;
+            let sum = p[0] + p[1] + p[2];
+
+            let sum_num = mt * sum - 3 * nt * p[3];
+            let w_num = b * p[3] - n * sum;
+
+            let alpha_w = w_num.div_euclid(det);
+            let alpha = [p[0], p[1], p[2]]
+                .map(|x| (det * x - n * sum_num - nt * w_num).div_euclid(a * det));
+
+            let offset = n * (alpha[0] + alpha[1] + alpha[2]);
+
+            // The reduced cell lies within one period of the origin
+            return [
+                p[0] - (a * alpha[0] + offset + nt * alpha_w),
+                p[1] - (a * alpha[1] + offset + nt * alpha_w),
+                p[2] - (a * alpha[2] + offset + nt * alpha_w),
+                p[3] - (offset + mt * alpha_w),
+            ]
+            .map(|x| x as i32);
+        }
+
+        cell
+    }
+}
+
+/// Offset unsigned cell coordinates, wrapping like GLSL `uvec2` arithmetic
+pub fn cell_offset([x, y]: [u32; 2], dx: u32, dy: u32) -> [u32; 2] {
+    [x.wrapping_add(dx), y.wrapping_add(dy)]
+}
+
+/// Offset unsigned 3D cell coordinates, wrapping like GLSL `uvec3` arithmetic
+pub fn cell_offset_3d([x, y, z]: [u32; 3], dx: u32, dy: u32, dz: u32) -> [u32; 3] {
+    [x.wrapping_add(dx), y.wrapping_add(dy), z.wrapping_add(dz)]
+}
+
+/// Compute the period of a looping simplex lattice
+///
+/// A simplex lattice cannot tile a square texture exactly. Instead, the texture periods
+/// `(scale, 0)` and `(0, scale)` are mapped onto the closest lattice vectors, which are `(m, n)`
+/// and `(n, m)` in skewed coordinates. This slightly distorts the lattice for small scales.
+///
+/// # Returns
+///
+/// Skewed coordinates `[m, n]` of the lattice vector closest to `(scale, 0)`.
+pub fn simplex_lattice_period(scale: f32) -> [i32; 2] {
+    [
+        ((scale * (1. + SIMPLEX_K1) + 0.5).floor() as i32).max(1),
+        (scale * SIMPLEX_K1 + 0.5).floor() as i32,
+    ]
+}
+
+/// Unskew a simplex lattice vector
+pub fn simplex_lattice_vector([x, y]: [i32; 2]) -> Vector2<f32> {
+    let unskew = (x + y) as f32 * SIMPLEX_K2;
+    vec2(x as f32 - unskew, y as f32 - unskew)
+}
+
+/// Compute the period of a looping 3D simplex lattice
+///
+/// See [simplex_lattice_period]. The other periods are `(n, m, n)` and `(n, n, m)`.
+///
+/// # Returns
+///
+/// Skewed coordinates `[m, n]` of the lattice vector `(m, n, n)` closest to `(scale, 0, 0)`.
+pub fn simplex_lattice_period_3d(scale: f32) -> [i32; 2] {
+    [
+        ((scale * (1. + SIMPLEX3D_K1) + 0.5).floor() as i32).max(1),
+        (scale * SIMPLEX3D_K1 + 0.5).floor() as i32,
+    ]
+}
+
+/// Unskew a 3D simplex lattice vector
+pub fn simplex_lattice_vector_3d([x, y, z]: [i32; 3]) -> Vector3<f32> {
+    let unskew = (x + y + z) as f32 * SIMPLEX3D_K2;
+    vec3(x as f32 - unskew, y as f32 - unskew, z as f32 - unskew)
+}
+
+/// Compute the periods of a looping 4D simplex lattice
+///
+/// See [simplex_lattice_period]. Without a time period, `(n', n', n', m')` is `(0, 0, 0, 1)`,
+/// which only serves to reduce cells modulo the spatial periods.
+///
+/// # Returns
+///
+/// Skewed coordinates `[m, n, m', n']` of the lattice vectors `(m, n, n, n)` closest to
+/// `(scale, 0, 0, 0)` and `(n', n', n', m')` closest to `(0, 0, 0, period)`.
+pub fn simplex_lattice_period_4d(scale: f32, period: f32) -> [i32; 4] {
+    let [m, n] = [
+        ((scale * (1. + SIMPLEX4D_K1) + 0.5).floor() as i32).max(1),
+        (scale * SIMPLEX4D_K1 + 0.5).floor() as i32,
+    ];
+
+    if period <= 0. {
+        return [m, n, 1, 0];
+    }
+
+    [
+        m,
+        n,
+        ((period * (1. + SIMPLEX4D_K1) + 0.5).floor() as i32).max(1),
+        (period * SIMPLEX4D_K1 + 0.5).floor() as i32,
+    ]
+}
+
+/// Check the scale and time period of a looping 4D simplex lattice
+///
+/// Fails with [crate::Error::InvalidParameters] if the lattice loops in time, and its scale or period
+/// exceed [SIMPLEX_LATTICE_4D_MAX_PERIOD] cells.
+///
+/// # Parameters
+///
+/// * `scale`: lattice scale, in cells per texture
+/// * `period`: time period in lattice cells, 0 for no looping
+pub fn check_simplex_lattice_period_4d(scale: f32, period: f32) -> crate::Result<()> {
+    let max = SIMPLEX_LATTICE_4D_MAX_PERIOD;
+    if period > 0. && (scale.is_nan() || scale > max || period > max) {
+        return Err(crate::Error::InvalidParameters);
+    }
+
+    Ok(())
+}
+
+/// Unskew a 4D simplex lattice vector
+pub fn simplex_lattice_vector_4d([x, y, z, w]: [i32; 4]) -> Vector4<f32> {
+    let unskew = (x + y + z + w) as f32 * SIMPLEX4D_K2;
+    vec4(
+        x as f32 - unskew,
+        y as f32 - unskew,
+        z as f32 - unskew,
+        w as f32 - unskew,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lattice(scale: f32) -> Lattice {
+        Lattice::new(
+            scale,
+            0,
+            STATS_MODE_NORMAL,
+            vec2(0., 0.),
+            ImageDim::new(16, 16, 1),
+        )
+    }
+
+    const SCALES: [f32; 5] = [0., 1., 3.7, 8., 32.];
+
+    #[test]
+    fn simplex_lattice_loop_is_periodic() {
+        for &scale in &SCALES {
+            let lattice = lattice(scale);
+            let [m, n] = simplex_lattice_period(scale);
+
+            for x in -40..40 {
+                for y in -40..40 {
+                    let p = lattice.simplex_lattice_loop([x, y]);
+                    assert_eq!(p, lattice.simplex_lattice_loop([x + m, y + n]));
+                    assert_eq!(p, lattice.simplex_lattice_loop([x - n, y - m]));
+                    assert_eq!(p, lattice.simplex_lattice_loop(p));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn simplex_lattice_loop_3d_is_periodic() {
+        for &scale in &SCALES {
+            let lattice = lattice(scale);
+            let [m, n] = simplex_lattice_period_3d(scale);
+            let periods = [[m, n, n], [n, m, n], [n, n, m]];
+
+            for x in -12..12 {
+                for y in -12..12 {
+                    for z in -12..12 {
+                        let p = lattice.simplex_lattice_loop_3d([x, y, z]);
+                        assert_eq!(p, lattice.simplex_lattice_loop_3d(p));
+
+                        for t in &periods {
+                            let q = [x + t[0], y + t[1], z + t[2]];
+                            assert_eq!(p, lattice.simplex_lattice_loop_3d(q));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn simplex_lattice_loop_4d_is_periodic() {
+        for &scale in &SCALES {
+            for &period in &[0., 2., 5.3] {
+                let lattice = lattice(scale).with_period(period);
+                let [m, n, mt, nt] = simplex_lattice_period_4d(scale, period);
+                let periods = [[m, n, n, n], [n, m, n, n], [n, n, m, n], [nt, nt, nt, mt]];
+
+                for x in -6..6 {
+                    for y in -6..6 {
+                        for z in -6..6 {
+                            for w in -6..6 {
+                                let p = lattice.simplex_lattice_loop_4d([x, y, z, w]);
+                                assert_eq!(p, lattice.simplex_lattice_loop_4d(p));
+
+                                for t in &periods {
+                                    let q = [x + t[0], y + t[1], z + t[2], w + t[3]];
+                                    assert_eq!(p, lattice.simplex_lattice_loop_4d(q));
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn simplex_lattice_loop_4d_is_periodic_for_large_periods() {
+        let max = SIMPLEX_LATTICE_4D_MAX_PERIOD;
+        for &(scale, period) in &[(1024., 700.), (max, max), (3.7, 1e6)] {
+            let lattice = lattice(scale).with_period(period);
+            let [m, n, mt, nt] = simplex_lattice_period_4d(scale, period);
+            let periods = [[m, n, n, n], [n, m, n, n], [n, n, m, n], [nt, nt, nt, mt]];
+
+            for &x in &[-2000, -1, 0, 999, 1_000_000] {
+                for &w in &[-5000, 0, 123_456, 2_000_000_000] {
+                    let p = lattice.simplex_lattice_loop_4d([x, x / 3, -x, w]);
+                    assert_eq!(p, lattice.simplex_lattice_loop_4d(p));
+
+                    // The reduced cell lies within one period of the origin
+                    assert!(p[..3].iter().all(|c| c.abs() <= m + 3 * n + nt));
+                    assert!(p[3].abs() <= 3 * n + mt);
+
+                    for t in &periods {
+                        let q = [p[0] + t[0], p[1] + t[1], p[2] + t[2], p[3] + t[3]];
+                        assert_eq!(p, lattice.simplex_lattice_loop_4d(q));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn simplex_lattice_period_4d_is_checked() {
+        let max = SIMPLEX_LATTICE_4D_MAX_PERIOD;
+        assert!(check_simplex_lattice_period_4d(max, max).is_ok());
+        assert!(check_simplex_lattice_period_4d(1e6, 0.).is_ok());
+        assert!(check_simplex_lattice_period_4d(1024., 700.).is_err());
+        assert!(check_simplex_lattice_period_4d(8., 1e6).is_err());
+        assert!(check_simplex_lattice_period_4d(f32::NAN, 4.).is_err());
+    }
+
+    #[test]
+    fn simplex_sample_with_zero_scale_is_finite() {
+        let lattice = lattice(0.);
+        let s = lattice.sample(vec2(0.3, 0.7), LatticeMode::Simplex2D);
+        assert!(s.position.x.is_finite() && s.position.y.is_finite());
+
+        let g = lattice.simplex_lattice_gradient(vec2(1., 1.));
+        assert!(g.x.is_finite() && g.y.is_finite());
+    }
+}
@@ -7,8 +7,10 @@
 #[macro_use]
 pub mod context;
 mod error;
+pub mod hash;
 pub mod image;
 pub mod io;
+pub mod lattice;
 pub mod method;
 
 pub use error::{Error, Result};
@@ -3,6 +3,13 @@ use std::any::Any;
 use crate::context::Context;
 use crate::error::*;
 use crate::image::Image;
+use crate::io::ImageBinding;
+
+mod metadata;
+pub use metadata::*;
+
+mod params;
+pub use params::*;
 
 mod registry;
 pub use registry::*;
@@ -43,6 +50,10 @@ pub fn downcast_params<'u, U: Default + 'static>(
     })
 }
 
+/// Callback receiving the number of completed steps and the total number of steps of a
+/// computation
+pub type Progress = Box<dyn FnMut(usize, usize)>;
+
 /// Generic interface to a procedural texturing method
 pub trait Method {
     fn compute(
@@ -51,4 +62,184 @@ pub trait Method {
         tgt: &mut Image,
         params: Option<&dyn Any>,
     ) -> Result<()>;
+
+    /// Get the runtime description of this method
+    fn metadata(&self) -> MethodMetadata;
+
+    /// Check if this method can compute into the given image in the given context
+    ///
+    /// GPU contexts require GPU images, while CPU contexts can compute into any image that can
+    /// be mapped.
+    ///
+    /// # Parameters
+    ///
+    /// * `ctx`: context to compute in
+    /// * `tgt`: target image
+    fn supports(&self, ctx: &Context, tgt: &Image) -> bool {
+        let metadata = self.metadata();
+        let support = match metadata.context(ctx.kind()) {
+            Some(support) => support,
+            None => return false,
+        };
+
+        #[cfg(feature = "gpu-core")]
+        if ctx.kind() == crate::context::ContextKind::Gpu && tgt.as_gpu_image().is_none() {
+            return false;
+        }
+
+        support.data_types.contains(&tgt.element_type())
+            && support.dimensionalities.contains(&tgt.dimensionality())
+    }
+
+    /// Get the runtime description of the parameters of this method
+    fn params_schema(&self) -> ParamsSchema;
+
+    /// Set a parameter of this method by name
+    ///
+    /// The parameters set on a method are used when [Method::compute] is called without
+    /// parameters. See [Method::set_param] for a wrapper accepting any value convertible to
+    /// [ParamValue].
+    ///
+    /// # Parameters
+    ///
+    /// * `name`: name of the parameter
+    /// * `value`: new value, which must have the same type as the parameter
+    fn set_param_value(&mut self, name: &str, value: ParamValue) -> Result<()>;
+
+    /// Set a parameter of this method by name, from any value convertible to [ParamValue]
+    ///
+    /// This allows writing `method.set_param("scale", 16.0)`, including on the boxed methods
+    /// built by a [MethodRegistry].
+    ///
+    /// # Parameters
+    ///
+    /// * `name`: name of the parameter
+    /// * `value`: new value, which must convert to the type of the parameter
+    fn set_param(&mut self, name: &str, value: impl Into<ParamValue>) -> Result<()>
+    where
+        Self: Sized,
+    {
+        self.set_param_value(name, value.into())
+    }
+
+    /// Get a parameter of this method by name
+    ///
+    /// # Parameters
+    ///
+    /// * `name`: name of the parameter
+    fn get_param(&self, name: &str) -> Result<ParamValue>;
+
+    /// Bind an image to a texture unit of this method by name
+    ///
+    /// The bindings set on a method are used when [Method::compute] is called without
+    /// parameters. The names of the texture units are listed in the parameters schema.
+    ///
+    /// # Parameters
+    ///
+    /// * `name`: name of the texture unit
+    /// * `binding`: binding object describing which image to bind
+    fn set_texture_binding(&mut self, name: &str, binding: ImageBinding) -> Result<()>;
+
+    /// Set an opaque parameter of this method by name
+    ///
+    /// Opaque parameters, such as the shapes of a signed distance field, cannot be accessed as
+    /// a [ParamValue]. The parameters set on a method are used when [Method::compute] is called
+    /// without parameters.
+    ///
+    /// # Parameters
+    ///
+    /// * `name`: name of the parameter
+    /// * `value`: new value, of the type of the parameter, or of `T` for a `Box<T>` parameter
+    fn set_opaque_param(&mut self, name: &str, value: &dyn Any) -> Result<()> {
+        let _ = value;
+        Err(Error::ParamNotFound(name.to_owned()))
+    }
+
+    /// Set all the parameters of this method
+    ///
+    /// The parameters set on a method are used when [Method::compute] is called without
+    /// parameters.
+    ///
+    /// # Parameters
+    ///
+    /// * `params`: parameters, of the same type as for [Method::compute]
+    fn set_params(&mut self, params: &dyn Any) -> Result<()>;
+
+    /// Set the method computing an input of this method
+    ///
+    /// Inner methods are computed with the parameters set on them, in the same context as this
+    /// method. The names of the inputs are listed in the metadata.
+    ///
+    /// # Parameters
+    ///
+    /// * `name`: name of the input
+    /// * `method`: inner method, or `None` to use the texture binding of the input instead
+    fn set_input(&mut self, name: &str, method: Option<Box<dyn Method>>) -> Result<()> {
+        let _ = method;
+        Err(Error::ParamNotFound(name.to_owned()))
+    }
+
+    /// Set the callback notified of the progress of long computations
+    ///
+    /// The callback is called on the thread computing the method. Methods which complete quickly
+    /// don't report progress, and ignore the callback.
+    ///
+    /// # Parameters
+    ///
+    /// * `progress`: callback receiving the number of completed steps and the total number of
+    ///   steps, or `None` to disable progress reports
+    fn set_progress(&mut self, progress: Option<Progress>) {
+        let _ = progress;
+    }
+}
+
+impl<M: Method + ?Sized> Method for Box<M> {
+    fn compute(
+        &mut self,
+        ctx: &mut Context,
+        tgt: &mut Image,
+        params: Option<&dyn Any>,
+    ) -> Result<()> {
+        (**self).compute(ctx, tgt, params)
+    }
+
+    fn metadata(&self) -> MethodMetadata {
+        (**self).metadata()
+    }
+
+    fn supports(&self, ctx: &Context, tgt: &Image) -> bool {
+        (**self).supports(ctx, tgt)
+    }
+
+    fn params_schema(&self) -> ParamsSchema {
+        (**self).params_schema()
+    }
+
+    fn set_param_value(&mut self, name: &str, value: ParamValue) -> Result<()> {
+        (**self).set_param_value(name, value)
+    }
+
+    fn get_param(&self, name: &str) -> Result<ParamValue> {
+        (**self).get_param(name)
+    }
+
+    fn set_texture_binding(&mut self, name: &str, binding: ImageBinding) -> Result<()> {
+        (**self).set_texture_binding(name, binding)
+    }
+
+    fn set_opaque_param(&mut self, name: &str, value: &dyn Any) -> Result<()> {
+        (**self).set_opaque_param(name, value)
+    }
+
+    fn set_params(&mut self, params: &dyn Any) -> Result<()> {
+        (**self).set_params(params)
+    }
+
+    fn set_input(&mut self, name: &str, method: Option<Box<dyn Method>>) -> Result<()> {
+        (**self).set_input(name, method)
+    }
+
+    fn set_progress(&mut self, progress: Option<Progress>) {
+        (**self).set_progress(progress)
+    }
 }
@@ -31,5 +31,8 @@ pub trait GpuMethod {
 /// * `gl`: OpenGL context
 /// * `P`: type of the program to set the values on
 pub trait GpuMethodParams<P> {
-    fn apply(&self, gl: &tinygl::Context, p: &P);
+    /// Set the uniforms and image bindings of a program
+    ///
+    /// Fails without drawing anything if an image binding is not resolved.
+    fn apply(&self, gl: &tinygl::Context, p: &P) -> Result<()>;
 }
@@ -0,0 +1,70 @@
+//! Runtime description of methods
+
+use crate::context::ContextKind;
+use crate::image::{ImageDataType, ImageDimensionality};
+
+use super::ParamsSchema;
+
+/// Images a method can compute in a given kind of context
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContextSupport {
+    /// Kind of context
+    pub context: ContextKind,
+    /// Supported element types of the target image
+    pub data_types: Vec<ImageDataType>,
+    /// Supported dimensionalities of the target image
+    pub dimensionalities: Vec<ImageDimensionality>,
+}
+
+impl ContextSupport {
+    /// Images supported by CPU methods: any element type and dimensionality
+    pub fn cpu() -> Self {
+        Self {
+            context: ContextKind::Cpu,
+            data_types: vec![ImageDataType::UInt8, ImageDataType::Float32],
+            dimensionalities: vec![
+                ImageDimensionality::Image1D,
+                ImageDimensionality::Image2D,
+                ImageDimensionality::Image3D,
+            ],
+        }
+    }
+
+    /// Images supported by GPU methods: 2D and 3D images, rendered layer by layer
+    pub fn gpu() -> Self {
+        Self {
+            context: ContextKind::Gpu,
+            data_types: vec![ImageDataType::UInt8, ImageDataType::Float32],
+            dimensionalities: vec![ImageDimensionality::Image2D, ImageDimensionality::Image3D],
+        }
+    }
+}
+
+/// Description of a method
+#[derive(Debug, Clone, PartialEq)]
+pub struct MethodMetadata {
+    /// Short description of the method
+    pub description: &'static str,
+    /// Kinds of contexts the method can compute in
+    pub contexts: Vec<ContextSupport>,
+    /// Runtime description of the method parameters
+    pub params: ParamsSchema,
+    /// Names of the inputs that can be computed by inner methods, see
+    /// [Method::set_input](super::Method::set_input)
+    pub inputs: Vec<&'static str>,
+}
+
+impl MethodMetadata {
+    /// Get the images supported by the method in the given kind of context
+    ///
+    /// # Parameters
+    ///
+    /// * `kind`: kind of context
+    ///
+    /// # Returns
+    ///
+    /// `None` if the method can't compute in this kind of context.
+    pub fn context(&self, kind: ContextKind) -> Option<&ContextSupport> {
+        self.contexts.iter().find(|c| c.context == kind)
+    }
+}
@@ -0,0 +1,225 @@
+//! Runtime description of method parameter structures
+
+use crate::io::ImageBinding;
+
+/// Type of a parameter field
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParamType {
+    /// Unsigned 32-bit integer
+    U32,
+    /// Signed 32-bit integer
+    I32,
+    /// 32-bit float
+    F32,
+    /// 2-component float vector
+    Vec2,
+    /// 3-component float vector
+    Vec3,
+    /// 4-component float vector
+    Vec4,
+    /// Image bindings
+    ImageIo,
+    /// Structured value, only accessible through the parameter structure
+    Opaque,
+}
+
+/// Value of a parameter field
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ParamValue {
+    U32(u32),
+    I32(i32),
+    F32(f32),
+    Vec2([f32; 2]),
+    Vec3([f32; 3]),
+    Vec4([f32; 4]),
+}
+
+impl ParamValue {
+    /// Get the type of this value
+    pub fn ty(&self) -> ParamType {
+        match self {
+            Self::U32(_) => ParamType::U32,
+            Self::I32(_) => ParamType::I32,
+            Self::F32(_) => ParamType::F32,
+            Self::Vec2(_) => ParamType::Vec2,
+            Self::Vec3(_) => ParamType::Vec3,
+            Self::Vec4(_) => ParamType::Vec4,
+        }
+    }
+}
+
+impl From<u32> for ParamValue {
+    fn from(value: u32) -> Self {
+        Self::U32(value)
+    }
+}
+
+impl From<i32> for ParamValue {
+    fn from(value: i32) -> Self {
+        Self::I32(value)
+    }
+}
+
+impl From<f32> for ParamValue {
+    fn from(value: f32) -> Self {
+        Self::F32(value)
+    }
+}
+
+impl From<f64> for ParamValue {
+    fn from(value: f64) -> Self {
+        Self::F32(value as f32)
+    }
+}
+
+impl From<[f32; 2]> for ParamValue {
+    fn from(value: [f32; 2]) -> Self {
+        Self::Vec2(value)
+    }
+}
+
+impl From<[f32; 3]> for ParamValue {
+    fn from(value: [f32; 3]) -> Self {
+        Self::Vec3(value)
+    }
+}
+
+impl From<[f32; 4]> for ParamValue {
+    fn from(value: [f32; 4]) -> Self {
+        Self::Vec4(value)
+    }
+}
+
+impl From<cgmath::Vector2<f32>> for ParamValue {
+    fn from(value: cgmath::Vector2<f32>) -> Self {
+        Self::Vec2(value.into())
+    }
+}
+
+impl From<cgmath::Vector3<f32>> for ParamValue {
+    fn from(value: cgmath::Vector3<f32>) -> Self {
+        Self::Vec3(value.into())
+    }
+}
+
+impl From<cgmath::Vector4<f32>> for ParamValue {
+    fn from(value: cgmath::Vector4<f32>) -> Self {
+        Self::Vec4(value.into())
+    }
+}
+
+macro_rules! impl_try_from_param_value {
+    ($($variant:ident => $t:ty),*) => {
+        $(
+            impl std::convert::TryFrom<ParamValue> for $t {
+                type Error = ParamValue;
+
+                fn try_from(value: ParamValue) -> Result<Self, Self::Error> {
+                    match value {
+                        ParamValue::$variant(v) => Ok(v.into()),
+                        other => Err(other),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_try_from_param_value!(
+    U32 => u32,
+    I32 => i32,
+    F32 => f32,
+    Vec2 => cgmath::Vector2<f32>,
+    Vec3 => cgmath::Vector3<f32>,
+    Vec4 => cgmath::Vector4<f32>
+);
+
+/// Named constant for an enum-like parameter field
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParamConstant {
+    /// Name of the constant, e.g. `PHASOR_PROFILE_SIN`
+    pub name: &'static str,
+    /// Value of the constant
+    pub value: ParamValue,
+}
+
+/// Description of a parameter field
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParamField {
+    /// Name of the field
+    pub name: &'static str,
+    /// Type of the field
+    pub ty: ParamType,
+    /// Offset of the field in the parameter structure, in bytes
+    pub offset: usize,
+    /// Documentation of the field
+    pub doc: &'static str,
+    /// Default value of the field, if it can be represented as a [ParamValue]
+    pub default: Option<ParamValue>,
+    /// Allowed values for enum-like fields, empty otherwise
+    pub constants: Vec<ParamConstant>,
+    /// Names of the texture units of image io fields, indexed by unit, empty otherwise. Units
+    /// bound by the method itself are not listed.
+    pub bindings: Vec<&'static str>,
+}
+
+/// Description of a parameter structure
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParamsSchema {
+    /// Name of the parameter structure
+    pub name: &'static str,
+    /// Documentation of the parameter structure
+    pub doc: &'static str,
+    /// Size of the parameter structure, in bytes
+    pub size: usize,
+    /// Fields of the parameter structure, in declaration order
+    pub fields: Vec<ParamField>,
+}
+
+impl ParamsSchema {
+    /// Find a field by name
+    ///
+    /// # Parameters
+    ///
+    /// * `name`: name of the field
+    pub fn field(&self, name: &str) -> Option<&ParamField> {
+        self.fields.iter().find(|f| f.name == name)
+    }
+}
+
+/// Parameter structure of a method, usually implemented by `#[derive(ParamsFor)]`
+pub trait MethodParams: Default {
+    /// Get the runtime description of this parameter structure
+    fn schema() -> ParamsSchema;
+
+    /// Set the value of a field by name
+    ///
+    /// # Parameters
+    ///
+    /// * `name`: name of the field
+    /// * `value`: new value, which must have the same type as the field
+    fn set_param(&mut self, name: &str, value: ParamValue) -> crate::Result<()>;
+
+    /// Get the value of a field by name
+    ///
+    /// # Parameters
+    ///
+    /// * `name`: name of the field
+    fn get_param(&self, name: &str) -> crate::Result<ParamValue>;
+
+    /// Bind an image to a texture unit by name
+    ///
+    /// # Parameters
+    ///
+    /// * `name`: name of the texture unit, as listed in [ParamField::bindings]
+    /// * `binding`: binding object describing which image to bind
+    fn set_texture_binding(&mut self, name: &str, binding: ImageBinding) -> crate::Result<()>;
+
+    /// Set the value of an opaque field by name
+    ///
+    /// # Parameters
+    ///
+    /// * `name`: name of the field, of type [ParamType::Opaque]
+    /// * `value`: new value, of the type of the field, or of `T` for a `Box<T>` field
+    fn set_opaque_param(&mut self, name: &str, value: &dyn std::any::Any) -> crate::Result<()>;
+}
@@ -1,16 +1,18 @@
 //! Definition of the method registry type
 
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
-use super::Method;
+use super::{Method, MethodMetadata, ParamsSchema};
 
 /// Type of a method constructor
 pub type MethodConstructor = Box<dyn Fn() -> Box<dyn Method>>;
 
 /// Registry to declare methods by name
+///
+/// Methods are enumerated in the lexicographic order of their names.
 #[derive(Default)]
 pub struct MethodRegistry {
-    method_constructors: HashMap<String, MethodConstructor>,
+    method_constructors: BTreeMap<String, MethodConstructor>,
 }
 
 impl MethodRegistry {
@@ -26,4 +28,44 @@ impl MethodRegistry {
     pub fn build(&self, name: &str) -> Option<Box<dyn Method>> {
         self.method_constructors.get(name).and_then(|v| Some(v()))
     }
+
+    /// Get the number of registered methods
+    pub fn len(&self) -> usize {
+        self.method_constructors.len()
+    }
+
+    /// Return `true` if no methods are registered
+    pub fn is_empty(&self) -> bool {
+        self.method_constructors.is_empty()
+    }
+
+    /// Get the names of the registered methods
+    pub fn names(&self) -> impl Iterator<Item = &str> + '_ {
+        self.method_constructors.keys().map(String::as_str)
+    }
+
+    /// Get the names and descriptions of the registered methods
+    pub fn iter(&self) -> impl Iterator<Item = (&str, MethodMetadata)> + '_ {
+        self.method_constructors
+            .iter()
+            .map(|(name, constructor)| (name.as_str(), constructor().metadata()))
+    }
+
+    /// Get the runtime description of a method
+    ///
+    /// # Parameters
+    ///
+    /// * `name`: name of the method
+    pub fn metadata(&self, name: &str) -> Option<MethodMetadata> {
+        self.build(name).map(|method| method.metadata())
+    }
+
+    /// Get the runtime description of the parameters of a method
+    ///
+    /// # Parameters
+    ///
+    /// * `name`: name of the method
+    pub fn schema(&self, name: &str) -> Option<ParamsSchema> {
+        self.build(name).map(|method| method.params_schema())
+    }
 }
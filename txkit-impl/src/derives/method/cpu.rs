@@ -5,6 +5,7 @@ use syn::DeriveInput;
 #[derive(Debug)]
 pub enum CpuDirectiveMethodKind {
     Iter { path: String },
+    Run { path: String },
 }
 
 impl CpuDirectiveMethodKind {
@@ -16,6 +17,12 @@ impl CpuDirectiveMethodKind {
                 }),
                 _ => Err(anyhow!("unexpected {:?} for cpu method", nv)),
             },
+            Some("run") => match &nv.lit {
+                syn::Lit::Str(s) => Ok(Self::Run {
+                    path: s.value().to_string(),
+                }),
+                _ => Err(anyhow!("unexpected {:?} for cpu method", nv)),
+            },
             _ => Err(anyhow!("unexpected {:?} for cpu method", nv)),
         }
     }
@@ -35,7 +42,11 @@ impl CpuDirectiveMethod {
         for item in &list.nested {
             match item {
                 syn::NestedMeta::Meta(syn::Meta::NameValue(nv))
-                    if nv.path.get_ident().map(|id| *id == "iter").unwrap_or(false) =>
+                    if nv
+                        .path
+                        .get_ident()
+                        .map(|id| *id == "iter" || *id == "run")
+                        .unwrap_or(false) =>
                 {
                     kind = Some(CpuDirectiveMethodKind::parse_from(nv)?);
                 }
@@ -51,8 +62,9 @@ impl CpuDirectiveMethod {
         }
 
         Ok(Self {
-            kind: kind
-                .ok_or_else(|| anyhow!("missing `iter = \"...\"` in cpu directive method"))?,
+            kind: kind.ok_or_else(|| {
+                anyhow!("missing `iter = \"...\"` or `run = \"...\"` in cpu directive method")
+            })?,
             params_struct_name: params
                 .ok_or_else(|| anyhow!("missing `params = \"...\"` in cpu directive method"))?,
         })
@@ -62,11 +74,17 @@ impl CpuDirectiveMethod {
 #[derive(Debug)]
 pub struct CpuDirective {
     pub method: Option<CpuDirectiveMethod>,
+    /// Supported element types, from `types(UInt8, Float32)`
+    pub data_types: Option<Vec<syn::Ident>>,
+    /// Supported dimensionalities, from `dims(Image1D, Image2D, Image3D)`
+    pub dimensionalities: Option<Vec<syn::Ident>>,
 }
 
 impl CpuDirective {
     pub fn parse_from(list: &syn::MetaList) -> Result<Self> {
         let mut method = None;
+        let mut data_types = None;
+        let mut dimensionalities = None;
 
         for item in &list.nested {
             match item {
@@ -80,13 +98,25 @@ impl CpuDirective {
                     method = Some(CpuDirectiveMethod::parse_from(list)?);
                     continue;
                 }
+                syn::NestedMeta::Meta(syn::Meta::List(list)) if list.path.is_ident("types") => {
+                    data_types = Some(crate::util::ident_list(list)?);
+                    continue;
+                }
+                syn::NestedMeta::Meta(syn::Meta::List(list)) if list.path.is_ident("dims") => {
+                    dimensionalities = Some(crate::util::ident_list(list)?);
+                    continue;
+                }
                 _ => {}
             }
 
             return Err(anyhow!("unexpected {:?} in cpu directive", item));
         }
 
-        Ok(Self { method })
+        Ok(Self {
+            method,
+            data_types,
+            dimensionalities,
+        })
     }
 }
 
@@ -104,49 +134,71 @@ pub fn process_txkit_cpu_directive(
     if let Some(method) = &cpu_directive.method {
         let struct_name = &input.ident;
         let params_struct: syn::Type = syn::parse_str(&method.params_struct_name)?;
-        // TODO: Support other types
-        let CpuDirectiveMethodKind::Iter { path } = &method.kind;
-        let path: syn::Path = syn::parse_str(path)?;
-
-        generated.push(quote! {
-            #[cfg(feature = "cpu")]
-            impl ::txkit_core::method::CpuMethod for #struct_name {
-                type Params = #params_struct;
-
-                fn compute_cpu(
-                    &mut self,
-                    ctx: &mut ::txkit_core::context::CpuContext,
-                    tgt: &mut ::txkit_core::image::Image,
-                    params: &Self::Params,
-                ) -> ::txkit_core::Result<()> {
-                    use ::txkit_core::image::IntoElementType;
-                    use ::ndarray::par_azip;
-
-                    let dim = tgt.dim();
-                    let mut data_mut = tgt.data_mut()?;
-
-                    if let Some(data) = data_mut.as_u8_nd_array_mut() {
-                        ctx.thread_pool.install(|| {
-                            par_azip!((index idx, o in data) {
-                                *o = #path(idx, dim, params).into_u8();
-                            });
-                        });
-
-                        Ok(())
-                    } else if let Some(data) = data_mut.as_f32_nd_array_mut() {
-                        ctx.thread_pool.install(|| {
-                            par_azip!((index idx, o in data) {
-                                *o = #path(idx, dim, params).into_f32();
-                            });
-                        });
-
-                        Ok(())
-                    } else {
-                        Err(::txkit_core::Error::FormatNotSupported)
+
+        match &method.kind {
+            CpuDirectiveMethodKind::Iter { path } => {
+                let path: syn::Path = syn::parse_str(path)?;
+
+                generated.push(quote! {
+                    #[cfg(feature = "cpu")]
+                    impl ::txkit_core::method::CpuMethod for #struct_name {
+                        type Params = #params_struct;
+
+                        fn compute_cpu(
+                            &mut self,
+                            ctx: &mut ::txkit_core::context::CpuContext,
+                            tgt: &mut ::txkit_core::image::Image,
+                            params: &Self::Params,
+                        ) -> ::txkit_core::Result<()> {
+                            use ::txkit_core::image::IntoElementType;
+                            use ::ndarray::par_azip;
+
+                            let dim = tgt.dim();
+                            let mut data_mut = tgt.data_mut()?;
+
+                            if let Some(data) = data_mut.as_u8_nd_array_mut() {
+                                ctx.thread_pool.install(|| {
+                                    par_azip!((index idx, o in data) {
+                                        *o = #path(idx, dim, params).into_u8();
+                                    });
+                                });
+
+                                Ok(())
+                            } else if let Some(data) = data_mut.as_f32_nd_array_mut() {
+                                ctx.thread_pool.install(|| {
+                                    par_azip!((index idx, o in data) {
+                                        *o = #path(idx, dim, params).into_f32();
+                                    });
+                                });
+
+                                Ok(())
+                            } else {
+                                Err(::txkit_core::Error::FormatNotSupported)
+                            }
+                        }
                     }
-                }
+                });
+            }
+            CpuDirectiveMethodKind::Run { path } => {
+                let path: syn::Path = syn::parse_str(path)?;
+
+                generated.push(quote! {
+                    #[cfg(feature = "cpu")]
+                    impl ::txkit_core::method::CpuMethod for #struct_name {
+                        type Params = #params_struct;
+
+                        fn compute_cpu(
+                            &mut self,
+                            ctx: &mut ::txkit_core::context::CpuContext,
+                            tgt: &mut ::txkit_core::image::Image,
+                            params: &Self::Params,
+                        ) -> ::txkit_core::Result<()> {
+                            #path(self, ctx, tgt, params)
+                        }
+                    }
+                });
             }
-        });
+        }
     }
 
     Ok((TokenStream::from(quote! { #(#generated)* }), cpu_directive))
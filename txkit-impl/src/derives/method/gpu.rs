@@ -125,6 +125,10 @@ pub struct GpuDirective {
     pub name: String,
     pub programs: Vec<GpuDirectiveProgram>,
     pub method: Option<GpuDirectiveMethod>,
+    /// Supported element types, from `types(UInt8, Float32)`
+    pub data_types: Option<Vec<syn::Ident>>,
+    /// Supported dimensionalities, from `dims(Image2D, Image3D)`
+    pub dimensionalities: Option<Vec<syn::Ident>>,
 }
 
 impl GpuDirective {
@@ -132,6 +136,8 @@ impl GpuDirective {
         let mut name = None;
         let mut programs = Vec::new();
         let mut method = None;
+        let mut data_types = None;
+        let mut dimensionalities = None;
 
         for item in &list.nested {
             match item {
@@ -155,6 +161,12 @@ impl GpuDirective {
                 {
                     method = Some(GpuDirectiveMethod::parse_from(&m)?);
                 }
+                syn::NestedMeta::Meta(syn::Meta::List(m)) if m.path.is_ident("types") => {
+                    data_types = Some(crate::util::ident_list(m)?);
+                }
+                syn::NestedMeta::Meta(syn::Meta::List(m)) if m.path.is_ident("dims") => {
+                    dimensionalities = Some(crate::util::ident_list(m)?);
+                }
                 syn::NestedMeta::Meta(syn::Meta::List(program)) => {
                     programs.push(GpuDirectiveProgram::parse_from(program)?);
                 }
@@ -172,6 +184,8 @@ impl GpuDirective {
             name,
             programs,
             method,
+            data_types,
+            dimensionalities,
         })
     }
 }
@@ -345,6 +359,7 @@ pub fn process_txkit_gpu_directive(
                     use ::txkit_core::{image::{ImageDataBase, ImageDimGpuExt}, method::GpuMethodParams};
 
                     let dim = tgt.dim().into_cgmath();
+                    let domain = ctx.domain.clone();
                     ctx.render_to_framebuffer(tgt, |gl, layer| {
                         unsafe {
                             self.#program_field_name.use_program(gl);
@@ -355,7 +370,10 @@ pub fn process_txkit_gpu_directive(
                         self.#program_field_name.set_i_layer(gl, layer);
 
                         // Method parameters
-                        params.apply(gl, &self.#program_field_name);
+                        params.apply(gl, &self.#program_field_name)?;
+
+                        // Texture coordinates of the pixels
+                        ::txkit_core::context::bind_domain(gl, domain.as_ref())?;
 
                         unsafe {
                             gl.draw_arrays(tinygl::gl::TRIANGLES, 0, 3);
@@ -3,42 +3,129 @@ use proc_macro2::TokenStream;
 use quote::{format_ident, quote};
 use syn::DeriveInput;
 
+/// Build the support of a context, restricting its defaults to the declared element types and
+/// dimensionalities
+fn context_support_for(
+    default: TokenStream,
+    data_types: &Option<Vec<syn::Ident>>,
+    dimensionalities: &Option<Vec<syn::Ident>>,
+) -> TokenStream {
+    let data_types = data_types.as_ref().map(|data_types| {
+        quote! {
+            data_types: vec![#(::txkit_core::image::ImageDataType::#data_types),*],
+        }
+    });
+
+    let dimensionalities = dimensionalities.as_ref().map(|dimensionalities| {
+        quote! {
+            dimensionalities: vec![#(::txkit_core::image::ImageDimensionality::#dimensionalities),*],
+        }
+    });
+
+    quote! {
+        ::txkit_core::method::ContextSupport {
+            #data_types
+            #dimensionalities
+            ..#default
+        }
+    }
+}
+
 pub fn process_txkit_method_directive(
     input: &DeriveInput,
     list: &syn::MetaList,
     gpu_directives: &[super::gpu::GpuDirective],
     cpu_directives: &[super::cpu::CpuDirective],
 ) -> Result<TokenStream> {
-    // Ensure the list is formatted correctly
-    if list.nested.len() != 0 {
+    // Hook called before computing, inputs computed by inner methods, and field storing the
+    // progress callback
+    let mut prepare = None;
+    let mut inputs = Vec::new();
+    let mut progress = None;
+
+    for item in &list.nested {
+        match item {
+            syn::NestedMeta::Meta(syn::Meta::NameValue(syn::MetaNameValue {
+                path,
+                lit: syn::Lit::Str(s),
+                ..
+            })) if path.is_ident("prepare") => {
+                prepare = Some(syn::parse_str::<syn::Path>(&s.value())?);
+            }
+            syn::NestedMeta::Meta(syn::Meta::NameValue(syn::MetaNameValue {
+                path,
+                lit: syn::Lit::Str(s),
+                ..
+            })) if path.is_ident("progress") => {
+                progress = Some(syn::parse_str::<syn::Ident>(&s.value())?);
+            }
+            syn::NestedMeta::Meta(syn::Meta::List(list)) if list.path.is_ident("inputs") => {
+                inputs = crate::util::ident_list(list)?;
+            }
+            other => {
+                return Err(anyhow!(
+                    "unexpected {:?} in top-level method txkit directive",
+                    other
+                ));
+            }
+        }
+    }
+
+    let struct_name = &input.ident;
+
+    // Parameters set by name are stored in the method struct
+    let has_params_field = match &input.data {
+        syn::Data::Struct(ds) => ds
+            .fields
+            .iter()
+            .any(|f| f.ident.as_ref().map(|id| *id == "params").unwrap_or(false)),
+        _ => false,
+    };
+
+    if !has_params_field {
         return Err(anyhow!(
-            "unexpected tokens in top-level method txkit directive"
+            "a `params` field is required to store the parameters of the method"
         ));
     }
 
-    let struct_name = &input.ident;
+    let cpu_directive = cpu_directives.iter().find(|cpud| cpud.method.is_some());
+    let gpu_directive = gpu_directives.iter().find(|gpud| gpud.method.is_some());
 
-    let cpu_struct_name = cpu_directives
-        .iter()
-        .filter_map(|cpud| {
-            if cpud.method.is_some() {
-                Some(())
-            } else {
-                None
-            }
-        })
-        .next();
-
-    let gpu_struct_name = gpu_directives
-        .iter()
-        .filter_map(|gpud| {
-            if gpud.method.is_some() {
-                Some(&gpud.name)
-            } else {
-                None
-            }
-        })
-        .next();
+    let gpu_struct_name = gpu_directive.map(|gpud| &gpud.name);
+
+    // Contexts the method supports, when the matching crate feature is enabled
+    let mut context_support = Vec::new();
+    if let Some(cpud) = cpu_directive {
+        let support = context_support_for(
+            quote! { ::txkit_core::method::ContextSupport::cpu() },
+            &cpud.data_types,
+            &cpud.dimensionalities,
+        );
+
+        context_support.push(quote! {
+            #[cfg(feature = "cpu")]
+            contexts.push(#support);
+        });
+    }
+    if let Some(gpud) = gpu_directive {
+        let support = context_support_for(
+            quote! { ::txkit_core::method::ContextSupport::gpu() },
+            &gpud.data_types,
+            &gpud.dimensionalities,
+        );
+
+        context_support.push(quote! {
+            #[cfg(feature = "gpu")]
+            contexts.push(#support);
+        });
+    }
+
+    // The description is the first paragraph of the doc comment
+    let description = crate::util::doc_string(&input.attrs)
+        .split("\n\n")
+        .next()
+        .unwrap_or("")
+        .replace('\n', " ");
 
     let gpu_code = if let Some(gpu_s_name) = gpu_struct_name {
         let gpu_s_name = format_ident!("{}", gpu_s_name);
@@ -69,7 +156,7 @@ pub fn process_txkit_method_directive(
         }
     };
 
-    let cpu_code = if let Some(_) = cpu_struct_name {
+    let cpu_code = if cpu_directive.is_some() {
         quote! {
             #[cfg(feature = "cpu")]
             Context::Cpu(cpu_context) => {
@@ -100,6 +187,47 @@ pub fn process_txkit_method_directive(
             })?,
     )?;
 
+    // The hook gets a copy of the parameters, to bind intermediate results
+    let prepare_code = prepare.map(|prepare| {
+        quote! {
+            let mut params = params.clone();
+            if let ::std::ops::ControlFlow::Break(()) = #prepare(self, ctx, tgt, &mut params)? {
+                return Ok(());
+            }
+
+            let params = &params;
+        }
+    });
+
+    let input_names: Vec<_> = inputs.iter().map(|input| input.to_string()).collect();
+    let set_input_code = if inputs.is_empty() {
+        None
+    } else {
+        Some(quote! {
+            fn set_input(
+                &mut self,
+                name: &str,
+                method: Option<Box<dyn ::txkit_core::method::Method>>,
+            ) -> ::txkit_core::Result<()> {
+                match name {
+                    #(#input_names => {
+                        self.#inputs = method;
+                        Ok(())
+                    })*
+                    _ => Err(::txkit_core::Error::ParamNotFound(name.to_string())),
+                }
+            }
+        })
+    };
+
+    let set_progress_code = progress.map(|progress| {
+        quote! {
+            fn set_progress(&mut self, progress: Option<::txkit_core::method::Progress>) {
+                self.#progress = progress;
+            }
+        }
+    });
+
     // Generate the impl
     Ok(TokenStream::from(quote! {
         impl ::txkit_core::method::Method for #struct_name {
@@ -111,13 +239,74 @@ pub fn process_txkit_method_directive(
             ) -> ::txkit_core::Result<()> {
                 use ::txkit_core::{context::Context, Error};
                 let mut default_params: Option<#params_type> = None;
-                let params = ::txkit_core::method::downcast_params(params, &mut default_params)?;
+                let params = match params {
+                    Some(_) => ::txkit_core::method::downcast_params(params, &mut default_params)?,
+                    None => default_params.insert(self.params.clone()),
+                };
+
+                #prepare_code
 
                 match ctx {
                     #gpu_code
                     #cpu_code
                 }
             }
+
+            fn metadata(&self) -> ::txkit_core::method::MethodMetadata {
+                #[allow(unused_mut)]
+                let mut contexts = Vec::new();
+                #(#context_support)*
+
+                ::txkit_core::method::MethodMetadata {
+                    description: #description,
+                    contexts,
+                    params: self.params_schema(),
+                    inputs: vec![#(#input_names),*],
+                }
+            }
+
+            fn params_schema(&self) -> ::txkit_core::method::ParamsSchema {
+                <#params_type as ::txkit_core::method::MethodParams>::schema()
+            }
+
+            fn set_param_value(
+                &mut self,
+                name: &str,
+                value: ::txkit_core::method::ParamValue,
+            ) -> ::txkit_core::Result<()> {
+                ::txkit_core::method::MethodParams::set_param(&mut self.params, name, value)
+            }
+
+            fn get_param(&self, name: &str) -> ::txkit_core::Result<::txkit_core::method::ParamValue> {
+                ::txkit_core::method::MethodParams::get_param(&self.params, name)
+            }
+
+            fn set_texture_binding(
+                &mut self,
+                name: &str,
+                binding: ::txkit_core::io::ImageBinding,
+            ) -> ::txkit_core::Result<()> {
+                ::txkit_core::method::MethodParams::set_texture_binding(&mut self.params, name, binding)
+            }
+
+            fn set_opaque_param(
+                &mut self,
+                name: &str,
+                value: &dyn ::std::any::Any,
+            ) -> ::txkit_core::Result<()> {
+                ::txkit_core::method::MethodParams::set_opaque_param(&mut self.params, name, value)
+            }
+
+            fn set_params(&mut self, params: &dyn std::any::Any) -> ::txkit_core::Result<()> {
+                let mut default_params: Option<#params_type> = None;
+                self.params =
+                    ::txkit_core::method::downcast_params(Some(params), &mut default_params)?.clone();
+                Ok(())
+            }
+
+            #set_input_code
+
+            #set_progress_code
         }
     }))
 }
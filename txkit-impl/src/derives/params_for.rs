@@ -82,9 +82,18 @@ fn process_txkit_directive(input: &DeriveInput, list: &syn::MetaList) -> Result<
                                                 let access_arg = &args[0];
 
                                                 field_setters.push(quote! {
-                                                    self.#field_name.apply_image_binding(gl, p.#get_binding_method() as _, #access_arg, p.#get_format_method());
+                                                    self.#field_name.apply_image_binding(gl, p.#get_binding_method() as _, #access_arg, p.#get_format_method())?;
                                                 });
-                                            } else if is_texture {
+                                            } else if is_texture && is_internal_texture(&list) {
+                                                let get_binding_method = format_ident!(
+                                                    "get_{}_binding",
+                                                    list.path.get_ident().unwrap()
+                                                );
+
+                                                field_setters.push(quote! {
+                                                    self.#field_name.apply_texture_binding(gl, p.#get_binding_method() as _)?;
+                                                });
+                                            } else if is_texture && !is_cpu_texture(&list) {
                                                 return Err(anyhow!("unexpected flags for texture binding for `{}` on field `{}`", list.path.get_ident().unwrap(), field_name));
                                             }
                                         }
@@ -100,7 +109,7 @@ fn process_txkit_directive(input: &DeriveInput, list: &syn::MetaList) -> Result<
                                                 return Err(anyhow!("image binding for `{}` on field `{}` requires access and format flags", p.get_ident().unwrap(), field_name));
                                             } else if is_texture {
                                                 field_setters.push(quote! {
-                                                    self.#field_name.apply_texture_binding(gl, p.#get_binding_method() as _);
+                                                    self.#field_name.apply_texture_binding(gl, p.#get_binding_method() as _)?;
                                                 });
                                             }
                                         }
@@ -122,7 +131,7 @@ fn process_txkit_directive(input: &DeriveInput, list: &syn::MetaList) -> Result<
                         }
                     }
 
-                    if !has_io_attrs {
+                    if !has_io_attrs && !is_opaque(field)? {
                         let setter_method = format_ident!("set_{}", field_name);
 
                         field_setters.push(quote! {
@@ -145,9 +154,10 @@ fn process_txkit_directive(input: &DeriveInput, list: &syn::MetaList) -> Result<
         generated.push(quote! {
             #[cfg(any(feature = "gpu", feature = "gpu45"))]
             impl ::txkit_core::method::GpuMethodParams<#ty> for #struct_name {
-                fn apply(&self, gl: &::tinygl::Context, p: &#ty) {
+                fn apply(&self, gl: &::tinygl::Context, p: &#ty) -> ::txkit_core::Result<()> {
                     use ::txkit_core::io::gpu::GpuImageIoExt;
                     #(#field_setters)*
+                    Ok(())
                 }
             }
         });
@@ -156,9 +166,392 @@ fn process_txkit_directive(input: &DeriveInput, list: &syn::MetaList) -> Result<
     Ok(TokenStream::from(quote! { #(#generated)* }))
 }
 
+/// Get the schema type of a parameter field
+fn param_type(field: &syn::Field) -> Result<TokenStream> {
+    let field_name = field.ident.as_ref().unwrap();
+
+    let is_io = field
+        .attrs
+        .iter()
+        .any(|attr| attr.path.is_ident("image_io") || attr.path.is_ident("texture_io"));
+
+    if is_io {
+        return Ok(quote! { ::txkit_core::method::ParamType::ImageIo });
+    }
+
+    if is_opaque(field)? {
+        return Ok(quote! { ::txkit_core::method::ParamType::Opaque });
+    }
+
+    let ty_name = match &field.ty {
+        syn::Type::Path(tp) => tp.path.segments.last().map(|s| s.ident.to_string()),
+        _ => None,
+    };
+
+    Ok(match ty_name.as_deref() {
+        Some("u32") => quote! { ::txkit_core::method::ParamType::U32 },
+        Some("i32") => quote! { ::txkit_core::method::ParamType::I32 },
+        Some("f32") => quote! { ::txkit_core::method::ParamType::F32 },
+        Some("Vector2") => quote! { ::txkit_core::method::ParamType::Vec2 },
+        Some("Vector3") => quote! { ::txkit_core::method::ParamType::Vec3 },
+        Some("Vector4") => quote! { ::txkit_core::method::ParamType::Vec4 },
+        _ => {
+            return Err(anyhow!(
+                "unsupported type for parameter field `{}`",
+                field_name
+            ))
+        }
+    })
+}
+
+/// Check if a texture binding is marked with the `cpu` flag, e.g. `#[texture_io(exemplar(cpu))]`
+///
+/// These textures are read by the method itself on the CPU, so they are not bound to programs.
+fn is_cpu_texture(list: &syn::MetaList) -> bool {
+    list.nested.len() == 1
+        && matches!(&list.nested[0], syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident("cpu"))
+}
+
+/// Check if a texture binding is marked with the `internal` flag, e.g.
+/// `#[texture_io(mask, columns(internal))]`
+///
+/// These textures are bound to programs by the method itself, e.g. to intermediate results, so
+/// they can't be set by name.
+fn is_internal_texture(list: &syn::MetaList) -> bool {
+    list.nested.len() == 1
+        && matches!(&list.nested[0], syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident("internal"))
+}
+
+/// Get the names of the texture units declared by `#[texture_io(...)]` on a parameter field
+///
+/// Units are numbered in declaration order, which must match the bindings of the programs.
+/// Internal units are not listed, so they must be declared after the other units.
+fn texture_bindings(field: &syn::Field) -> Result<Vec<String>> {
+    let mut bindings = Vec::new();
+    let mut internal = false;
+
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("texture_io"))
+    {
+        match attr.parse_meta()? {
+            syn::Meta::List(list) => {
+                for item in list.nested {
+                    let path = match item {
+                        syn::NestedMeta::Meta(syn::Meta::List(list)) => {
+                            if is_internal_texture(&list) {
+                                internal = true;
+                                continue;
+                            }
+
+                            list.path
+                        }
+                        syn::NestedMeta::Meta(syn::Meta::Path(path)) => path,
+                        _ => return Err(anyhow!("invalid texture binding {:?}", item)),
+                    };
+
+                    if internal {
+                        return Err(anyhow!(
+                            "texture binding {:?} must be declared before the internal ones",
+                            path
+                        ));
+                    }
+
+                    bindings.push(
+                        path.get_ident()
+                            .ok_or_else(|| anyhow!("invalid texture binding name {:?}", path))?
+                            .to_string(),
+                    );
+                }
+            }
+            _ => return Err(anyhow!("invalid texture_io attribute")),
+        }
+    }
+
+    Ok(bindings)
+}
+
+/// Check if a parameter field is marked with `#[txkit(opaque)]`
+///
+/// Opaque fields are not uniforms, and they can only be accessed through the parameter structure.
+fn is_opaque(field: &syn::Field) -> Result<bool> {
+    let mut opaque = false;
+
+    crate::util::process_directive(
+        &field.attrs,
+        |list| {
+            opaque |= list.nested.iter().any(|item| {
+                matches!(item, syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident("opaque"))
+            });
+
+            Ok(())
+        },
+        "txkit",
+        false,
+    )?;
+
+    Ok(opaque)
+}
+
+/// Get the enum-like constants declared by `#[txkit(values(...))]` on a parameter field
+fn param_constants(field: &syn::Field) -> Result<Vec<TokenStream>> {
+    let mut constants = Vec::new();
+
+    crate::util::process_directive(
+        &field.attrs,
+        |list| {
+            for item in &list.nested {
+                match item {
+                    syn::NestedMeta::Meta(syn::Meta::List(values))
+                        if values.path.is_ident("values") =>
+                    {
+                        for value in &values.nested {
+                            match value {
+                                syn::NestedMeta::Meta(syn::Meta::Path(path))
+                                    if !path.segments.is_empty() =>
+                                {
+                                    let name = path.segments.last().unwrap().ident.to_string();
+
+                                    constants.push(quote! {
+                                        ::txkit_core::method::ParamConstant {
+                                            name: #name,
+                                            value: ::txkit_core::method::ParamValue::from(#path),
+                                        }
+                                    });
+                                }
+                                _ => {
+                                    return Err(anyhow!(
+                                        "unexpected {:?} in values directive",
+                                        value
+                                    ))
+                                }
+                            }
+                        }
+                    }
+                    syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident("opaque") => {}
+                    _ => return Err(anyhow!("unexpected {:?} in field txkit directive", item)),
+                }
+            }
+
+            Ok(())
+        },
+        "txkit",
+        false,
+    )?;
+
+    Ok(constants)
+}
+
+/// Get `T` if the given type is `Box<T>`
+fn boxed_type(ty: &syn::Type) -> Option<&syn::Type> {
+    let segment = match ty {
+        syn::Type::Path(tp) => tp.path.segments.last()?,
+        _ => return None,
+    };
+
+    if segment.ident != "Box" {
+        return None;
+    }
+
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) if args.args.len() == 1 => {
+            match args.args.first()? {
+                syn::GenericArgument::Type(ty) => Some(ty),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+fn process_method_params(input: &DeriveInput) -> Result<TokenStream> {
+    let struct_name = &input.ident;
+    let struct_name_str = struct_name.to_string();
+    let struct_doc = crate::util::doc_string(&input.attrs);
+
+    let mut fields = Vec::new();
+    let mut setters = Vec::new();
+    let mut getters = Vec::new();
+    let mut binding_setters = Vec::new();
+    let mut opaque_setters = Vec::new();
+
+    match &input.data {
+        syn::Data::Struct(ds) => {
+            for field in &ds.fields {
+                let field_name = field.ident.as_ref().unwrap();
+                let field_name_str = field_name.to_string();
+                let field_doc = crate::util::doc_string(&field.attrs);
+                let ty = param_type(field)?;
+                let constants = param_constants(field)?;
+                let bindings = texture_bindings(field)?;
+
+                for (unit, binding) in bindings.iter().enumerate() {
+                    binding_setters.push(quote! {
+                        #binding => {
+                            self.#field_name.set_texture_binding(#unit, binding);
+                            Ok(())
+                        }
+                    });
+                }
+
+                let is_io = field
+                    .attrs
+                    .iter()
+                    .any(|attr| attr.path.is_ident("image_io") || attr.path.is_ident("texture_io"));
+
+                let is_opaque = is_opaque(field)?;
+
+                let default_value = if is_io || is_opaque {
+                    quote! { None }
+                } else {
+                    quote! { Some(::txkit_core::method::ParamValue::from(default.#field_name)) }
+                };
+
+                fields.push(quote! {
+                    ::txkit_core::method::ParamField {
+                        name: #field_name_str,
+                        ty: #ty,
+                        offset: &default.#field_name as *const _ as usize - base,
+                        doc: #field_doc,
+                        default: #default_value,
+                        constants: vec![#(#constants),*],
+                        bindings: vec![#(#bindings),*],
+                    }
+                });
+
+                if is_opaque {
+                    // Boxed values are set from the value they contain
+                    let assign = match boxed_type(&field.ty) {
+                        Some(inner) => quote! {
+                            self.#field_name = Box::new(
+                                value
+                                    .downcast_ref::<#inner>()
+                                    .ok_or(::txkit_core::Error::InvalidParameters)?
+                                    .clone(),
+                            );
+                        },
+                        None => {
+                            let field_ty = &field.ty;
+                            quote! {
+                                self.#field_name = value
+                                    .downcast_ref::<#field_ty>()
+                                    .ok_or(::txkit_core::Error::InvalidParameters)?
+                                    .clone();
+                            }
+                        }
+                    };
+
+                    opaque_setters.push(quote! {
+                        #field_name_str => {
+                            #assign
+                            Ok(())
+                        }
+                    });
+                } else {
+                    opaque_setters.push(quote! {
+                        #field_name_str => Err(::txkit_core::Error::ParamNotAccessible(name.to_string())),
+                    });
+                }
+
+                if is_io || is_opaque {
+                    setters.push(quote! {
+                        #field_name_str => Err(::txkit_core::Error::ParamNotAccessible(name.to_string())),
+                    });
+
+                    getters.push(quote! {
+                        #field_name_str => Err(::txkit_core::Error::ParamNotAccessible(name.to_string())),
+                    });
+                } else {
+                    setters.push(quote! {
+                        #field_name_str => {
+                            self.#field_name = ::std::convert::TryFrom::try_from(value).map_err(
+                                |value: ::txkit_core::method::ParamValue| {
+                                    ::txkit_core::Error::InvalidParamType {
+                                        name: name.to_string(),
+                                        expected: #ty,
+                                        actual: value.ty(),
+                                    }
+                                },
+                            )?;
+
+                            Ok(())
+                        }
+                    });
+
+                    getters.push(quote! {
+                        #field_name_str => Ok(::txkit_core::method::ParamValue::from(self.#field_name)),
+                    });
+                }
+            }
+        }
+        _ => {
+            return Err(anyhow!("unnamed structs are not supported by txkit"));
+        }
+    }
+
+    Ok(quote! {
+        impl ::txkit_core::method::MethodParams for #struct_name {
+            fn schema() -> ::txkit_core::method::ParamsSchema {
+                let default = <Self as Default>::default();
+                let base = &default as *const Self as usize;
+
+                ::txkit_core::method::ParamsSchema {
+                    name: #struct_name_str,
+                    doc: #struct_doc,
+                    size: ::std::mem::size_of::<Self>(),
+                    fields: vec![#(#fields),*],
+                }
+            }
+
+            fn set_param(
+                &mut self,
+                name: &str,
+                value: ::txkit_core::method::ParamValue,
+            ) -> ::txkit_core::Result<()> {
+                match name {
+                    #(#setters)*
+                    _ => Err(::txkit_core::Error::ParamNotFound(name.to_string())),
+                }
+            }
+
+            fn get_param(&self, name: &str) -> ::txkit_core::Result<::txkit_core::method::ParamValue> {
+                match name {
+                    #(#getters)*
+                    _ => Err(::txkit_core::Error::ParamNotFound(name.to_string())),
+                }
+            }
+
+            fn set_texture_binding(
+                &mut self,
+                name: &str,
+                #[allow(unused_variables)] binding: ::txkit_core::io::ImageBinding,
+            ) -> ::txkit_core::Result<()> {
+                match name {
+                    #(#binding_setters)*
+                    _ => Err(::txkit_core::Error::ParamNotFound(name.to_string())),
+                }
+            }
+
+            fn set_opaque_param(
+                &mut self,
+                name: &str,
+                #[allow(unused_variables)] value: &dyn ::std::any::Any,
+            ) -> ::txkit_core::Result<()> {
+                match name {
+                    #(#opaque_setters)*
+                    _ => Err(::txkit_core::Error::ParamNotFound(name.to_string())),
+                }
+            }
+        }
+    })
+}
+
 pub fn process_params_for(input: DeriveInput) -> Result<TokenStream> {
     let mut generated: Vec<TokenStream> = Vec::new();
 
+    generated.push(process_method_params(&input).context("failed to implement MethodParams")?);
+
     crate::util::process_directive(
         &input.attrs,
         |list| {
@@ -35,3 +35,33 @@ pub fn process_directive<T>(
 
     Ok(())
 }
+
+/// Concatenate the doc comments in a list of attributes
+pub fn doc_string(attrs: &[syn::Attribute]) -> String {
+    attrs
+        .iter()
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(syn::Meta::NameValue(syn::MetaNameValue {
+                path,
+                lit: syn::Lit::Str(s),
+                ..
+            })) if path.is_ident("doc") => Some(s.value().trim().to_string()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Read the identifiers of a list such as `dims(Image2D, Image3D)`
+pub fn ident_list(list: &MetaList) -> Result<Vec<syn::Ident>> {
+    list.nested
+        .iter()
+        .map(|item| match item {
+            syn::NestedMeta::Meta(syn::Meta::Path(path)) => path
+                .get_ident()
+                .cloned()
+                .ok_or_else(|| anyhow!("expected an identifier, not a path")),
+            other => Err(anyhow!("unexpected {:?} in identifier list", other)),
+        })
+        .collect()
+}
@@ -37,6 +37,8 @@ const Registry = Ptr{Cvoid}
 
 const ImageIo = Ptr{Cvoid}
 
+const Sdf2dShapes = Ptr{Cvoid}
+
 struct ImageDim
     width::UInt
     height::UInt
@@ -45,6 +47,7 @@ struct ImageDim
 end
 
 txkit_context_destroy(ctx::Context) = ccall((:txkit_context_destroy, libctxkit), Cvoid, (Context,), ctx)
+txkit_context_new_auto() = ccall((:txkit_context_new_auto, libctxkit), Context, ())
 txkit_context_new_cpu() = ccall((:txkit_context_new_cpu, libctxkit), Context, ())
 txkit_context_new_gpu() = ccall((:txkit_context_new_gpu, libctxkit), Context, ())
 
@@ -70,15 +73,35 @@ txkit_image_unmap_write(write_map::MappedImageDataRead) = ccall((:txkit_image_un
 
 txkit_method_compute(ctx::Context, method::TextureMethod, tgt::Image, params::Ptr{Cvoid}, params_size::UInt) = ccall((:txkit_method_compute, libctxkit), Int32, (Context, TextureMethod, Image, Ptr{Cvoid}, UInt), ctx, method, tgt, params, params_size)
 txkit_method_destroy(method::TextureMethod) = ccall((:txkit_method_destroy, libctxkit), Cvoid, (TextureMethod,), method)
+txkit_method_get_param_f32(method::TextureMethod, name::AbstractString, value::Ref{Float32}) = ccall((:txkit_method_get_param_f32, libctxkit), Int32, (TextureMethod, Cstring, Ref{Float32}), method, name, value)
+txkit_method_get_param_i32(method::TextureMethod, name::AbstractString, value::Ref{Int32}) = ccall((:txkit_method_get_param_i32, libctxkit), Int32, (TextureMethod, Cstring, Ref{Int32}), method, name, value)
+txkit_method_get_param_u32(method::TextureMethod, name::AbstractString, value::Ref{UInt32}) = ccall((:txkit_method_get_param_u32, libctxkit), Int32, (TextureMethod, Cstring, Ref{UInt32}), method, name, value)
+txkit_method_get_param_vec2(method::TextureMethod, name::AbstractString, x::Ref{Float32}, y::Ref{Float32}) = ccall((:txkit_method_get_param_vec2, libctxkit), Int32, (TextureMethod, Cstring, Ref{Float32}, Ref{Float32}), method, name, x, y)
 txkit_method_new(registry::Registry, method_name::AbstractString) = ccall((:txkit_method_new, libctxkit), TextureMethod, (Registry, Cstring), registry, method_name)
-
+txkit_method_set_input(method::TextureMethod, name::AbstractString, input::TextureMethod) = ccall((:txkit_method_set_input, libctxkit), Int32, (TextureMethod, Cstring, TextureMethod), method, name, input)
+txkit_method_set_param_f32(method::TextureMethod, name::AbstractString, value::Float32) = ccall((:txkit_method_set_param_f32, libctxkit), Int32, (TextureMethod, Cstring, Float32), method, name, value)
+txkit_method_set_param_i32(method::TextureMethod, name::AbstractString, value::Int32) = ccall((:txkit_method_set_param_i32, libctxkit), Int32, (TextureMethod, Cstring, Int32), method, name, value)
+txkit_method_set_param_u32(method::TextureMethod, name::AbstractString, value::UInt32) = ccall((:txkit_method_set_param_u32, libctxkit), Int32, (TextureMethod, Cstring, UInt32), method, name, value)
+txkit_method_set_param_vec2(method::TextureMethod, name::AbstractString, x::Float32, y::Float32) = ccall((:txkit_method_set_param_vec2, libctxkit), Int32, (TextureMethod, Cstring, Float32, Float32), method, name, x, y)
+txkit_method_set_progress(method::TextureMethod, callback::Ptr{Cvoid}, user_data::Ptr{Cvoid}) = ccall((:txkit_method_set_progress, libctxkit), Cvoid, (TextureMethod, Ptr{Cvoid}, Ptr{Cvoid}), method, callback, user_data)
+txkit_method_set_sdf_2d_shapes(method::TextureMethod, name::AbstractString, shapes::Sdf2dShapes) = ccall((:txkit_method_set_sdf_2d_shapes, libctxkit), Int32, (TextureMethod, Cstring, Sdf2dShapes), method, name, shapes)
+txkit_method_set_texture_binding(method::TextureMethod, name::AbstractString, image::Image) = ccall((:txkit_method_set_texture_binding, libctxkit), Int32, (TextureMethod, Cstring, Image), method, name, image)
+txkit_method_supports(ctx::Context, method::TextureMethod, tgt::Image) = ccall((:txkit_method_supports, libctxkit), Bool, (Context, TextureMethod, Image), ctx, method, tgt)
+
+txkit_registry_count(registry::Registry) = ccall((:txkit_registry_count, libctxkit), UInt, (Registry,), registry)
 txkit_registry_destroy(registry::Registry) = ccall((:txkit_registry_destroy, libctxkit), Cvoid, (Registry,), registry)
+txkit_registry_name_at(registry::Registry, index::UInt) = ccall((:txkit_registry_name_at, libctxkit), Cstring, (Registry, UInt), registry, index)
 
 txkit_image_io_destroy(io::ImageIo) = ccall((:txkit_image_io_destroy, libctxkit), Cvoid, (ImageIo,), io)
 txkit_image_io_new() = ccall((:txkit_image_io_new, libctxkit), ImageIo, ())
 txkit_image_io_set_image_binding(io::ImageIo, index::UInt, image::Image) = ccall((:txkit_image_io_set_image_binding, libctxkit), Int32, (ImageIo, UInt, Image), io, index, image)
 txkit_image_io_set_texture_binding(io::ImageIo, index::UInt, image::Image) = ccall((:txkit_image_io_set_texture_binding, libctxkit), Int32, (ImageIo, UInt, Image), io, index, image)
 
+txkit_sdf_2d_shapes_add(shapes::Sdf2dShapes, kind::Int32, op::Int32, smoothness::Float32, data::Vector{Float32}, len::UInt) = ccall((:txkit_sdf_2d_shapes_add, libctxkit), Int32, (Sdf2dShapes, Int32, Int32, Float32, Ptr{Float32}, UInt), shapes, kind, op, smoothness, data, len)
+txkit_sdf_2d_shapes_clear(shapes::Sdf2dShapes) = ccall((:txkit_sdf_2d_shapes_clear, libctxkit), Cvoid, (Sdf2dShapes,), shapes)
+txkit_sdf_2d_shapes_destroy(shapes::Sdf2dShapes) = ccall((:txkit_sdf_2d_shapes_destroy, libctxkit), Cvoid, (Sdf2dShapes,), shapes)
+txkit_sdf_2d_shapes_new() = ccall((:txkit_sdf_2d_shapes_new, libctxkit), Sdf2dShapes, ())
+
 const StatsMode = Int32
 
 const StatsMode_Normal = StatsMode(0)
@@ -90,14 +113,29 @@ struct Vector2_f32
     y::Float32
 end
 
+const NoiseOutput = Int32
+
+const NoiseOutput_Value = NoiseOutput(0)
+const NoiseOutput_Derivatives = NoiseOutput(1)
+
+const LatticeDimensions = Int32
+
+const LatticeDimensions_2D = LatticeDimensions(2)
+const LatticeDimensions_3D = LatticeDimensions(3)
+const LatticeDimensions_4D = LatticeDimensions(4)
+
 struct GradientNoiseParams
     global_seed::UInt32
     scale::Float32
     stats_mode::StatsMode
     stats_look_at::Vector2_f32
+    output_mode::NoiseOutput
+    dimensions::LatticeDimensions
+    time::Float32
+    period::Float32
 end
 
-GradientNoiseParams() = GradientNoiseParams(0, 32., StatsMode_Normal, Vector2_f32(0., 0.))
+GradientNoiseParams() = GradientNoiseParams(0, 32., StatsMode_Normal, Vector2_f32(0., 0.), NoiseOutput_Value, LatticeDimensions_2D, 0., 0.)
 
 const PhasorNoiseProfile = Int32
 
@@ -135,6 +173,8 @@ struct PhasorNoiseParams
 
     noise_frequency::Float32
     noise_angle::Float32
+    time::Float32
+    period::Float32
 
     jitter_amount::Float32
     jitter_max::Int32
@@ -142,31 +182,72 @@ struct PhasorNoiseParams
     io::ImageIo
 end
 
-PhasorNoiseParams() = PhasorNoiseParams(0, 32., StatsMode_Normal, Vector2_f32(0., 0.), 1, 8, PhasorNoiseProfile_Sin, PhasorNoiseWeights_None, PhasorNoisePointDistribution_StratPoisson, 4., 0., 1., 0, txkit_image_io_new())
+PhasorNoiseParams() = PhasorNoiseParams(0, 32., StatsMode_Normal, Vector2_f32(0., 0.), 1, 8, PhasorNoiseProfile_Sin, PhasorNoiseWeights_None, PhasorNoisePointDistribution_StratPoisson, 4., 0., 0., 0., 1., 0, txkit_image_io_new())
 
 struct SimplexNoiseParams
     global_seed::UInt32
     scale::Float32
     stats_mode::StatsMode
     stats_look_at::Vector2_f32
+    output_mode::NoiseOutput
+    dimensions::LatticeDimensions
+    time::Float32
+    period::Float32
 end
 
-SimplexNoiseParams() = SimplexNoiseParams(0, 32., StatsMode_Normal, Vector2_f32(0., 0.))
+SimplexNoiseParams() = SimplexNoiseParams(0, 32., StatsMode_Normal, Vector2_f32(0., 0.), NoiseOutput_Value, LatticeDimensions_2D, 0., 0.)
 
 struct ValueNoiseParams
     global_seed::UInt32
     scale::Float32
     stats_mode::StatsMode
     stats_look_at::Vector2_f32
+    dimensions::LatticeDimensions
+    time::Float32
+    period::Float32
 end
 
-ValueNoiseParams() = ValueNoiseParams(0, 32., StatsMode_Normal, Vector2_f32(0., 0.))
+ValueNoiseParams() = ValueNoiseParams(0, 32., StatsMode_Normal, Vector2_f32(0., 0.), LatticeDimensions_2D, 0., 0.)
+
+const WhiteNoiseDistribution = Int32
+
+const WhiteNoiseDistribution_Uniform = WhiteNoiseDistribution(0)
+const WhiteNoiseDistribution_Gaussian = WhiteNoiseDistribution(1)
+const WhiteNoiseDistribution_Exponential = WhiteNoiseDistribution(2)
+const WhiteNoiseDistribution_Bernoulli = WhiteNoiseDistribution(3)
+const WhiteNoiseDistribution_Discrete = WhiteNoiseDistribution(4)
+
+const WhiteNoiseChannels = Int32
+
+const WhiteNoiseChannels_Independent = WhiteNoiseChannels(0)
+const WhiteNoiseChannels_Shared = WhiteNoiseChannels(1)
 
 struct WhiteNoiseParams
     global_seed::UInt32
+    distribution::WhiteNoiseDistribution
+    channels::WhiteNoiseChannels
+    mean::Float32
+    std_dev::Float32
+    rate::Float32
+    probability::Float32
+    levels::UInt32
 end
 
-WhiteNoiseParams() = WhiteNoiseParams(0)
+WhiteNoiseParams() = WhiteNoiseParams(0, WhiteNoiseDistribution_Uniform, WhiteNoiseChannels_Independent, 0.5, 0.125, 4., 0.5, 2)
+
+const Sdf2dShape = Int32
+
+const Sdf2dShape_Circle = Sdf2dShape(0)
+const Sdf2dShape_Box = Sdf2dShape(1)
+const Sdf2dShape_RoundedBox = Sdf2dShape(2)
+const Sdf2dShape_Segment = Sdf2dShape(3)
+const Sdf2dShape_Polygon = Sdf2dShape(4)
+
+const Sdf2dOp = Int32
+
+const Sdf2dOp_Union = Sdf2dOp(0)
+const Sdf2dOp_Intersection = Sdf2dOp(1)
+const Sdf2dOp_Subtraction = Sdf2dOp(2)
 
 struct DebugParams
     alpha_value::Cfloat
@@ -179,7 +260,10 @@ txkit_registry_new_builtin() = ccall((:txkit_registry_new_builtin, libctxkit), R
 end # module
 
 import .Api.Vector2_f32, .Api.StatsMode, .Api.StatsMode_Normal,
-       .Api.StatsMode_Process, .Api.StatsMode_LookAt, .Api.GradientNoiseParams,
+       .Api.StatsMode_Process, .Api.StatsMode_LookAt, .Api.NoiseOutput,
+       .Api.NoiseOutput_Value, .Api.NoiseOutput_Derivatives, .Api.LatticeDimensions,
+       .Api.LatticeDimensions_2D, .Api.LatticeDimensions_3D, .Api.LatticeDimensions_4D,
+       .Api.GradientNoiseParams,
        .Api.PhasorNoiseProfile, .Api.PhasorNoiseProfile_Complex,
        .Api.PhasorNoiseProfile_Real, .Api.PhasorNoiseProfile_Imag,
        .Api.PhasorNoiseProfile_Sin, .Api.PhasorNoiseProfile_Saw,
@@ -188,22 +272,37 @@ import .Api.Vector2_f32, .Api.StatsMode, .Api.StatsMode_Normal,
        .Api.PhasorNoiseWeights_Uniform, .Api.PhasorNoisePointDistribution,
        .Api.PhasorNoisePointDistribution_StratPoisson,
        .Api.PhasorNoisePointDistribution_Poisson, .Api.PhasorNoiseParams,
-       .Api.SimplexNoiseParams, .Api.ValueNoiseParams, .Api.WhiteNoiseParams,
-       .Api.DebugParams
+       .Api.SimplexNoiseParams, .Api.ValueNoiseParams, .Api.WhiteNoiseDistribution,
+       .Api.WhiteNoiseDistribution_Uniform, .Api.WhiteNoiseDistribution_Gaussian,
+       .Api.WhiteNoiseDistribution_Exponential, .Api.WhiteNoiseDistribution_Bernoulli,
+       .Api.WhiteNoiseDistribution_Discrete, .Api.WhiteNoiseChannels,
+       .Api.WhiteNoiseChannels_Independent, .Api.WhiteNoiseChannels_Shared, .Api.WhiteNoiseParams,
+       .Api.Sdf2dShape, .Api.Sdf2dShape_Circle, .Api.Sdf2dShape_Box, .Api.Sdf2dShape_RoundedBox,
+       .Api.Sdf2dShape_Segment, .Api.Sdf2dShape_Polygon, .Api.Sdf2dOp, .Api.Sdf2dOp_Union,
+       .Api.Sdf2dOp_Intersection, .Api.Sdf2dOp_Subtraction, .Api.DebugParams
 
 export Vector2_f32, StatsMode_Normal, StatsMode_Process, StatsMode_LookAt, GradientNoiseParams, PhasorNoiseParams,
        PhasorNoiseProfile, PhasorNoiseProfile_Complex, PhasorNoiseProfile_Real, PhasorNoiseProfile_Imag,
        PhasorNoiseProfile_Sin, PhasorNoiseProfile_Saw, PhasorNoiseProfile_Impulses, PhasorNoiseWeights,
        PhasorNoiseWeights_None, PhasorNoiseWeights_Bernoulli, PhasorNoiseWeights_Uniform, PhasorNoisePointDistribution,
        PhasorNoisePointDistribution_StratPoisson, PhasorNoisePointDistribution_Poisson, SimplexNoiseParams,
-       ValueNoiseParams, WhiteNoiseParams, DebugParams, StatsMode
+       ValueNoiseParams, WhiteNoiseParams, DebugParams, StatsMode, NoiseOutput, NoiseOutput_Value,
+       NoiseOutput_Derivatives, LatticeDimensions, LatticeDimensions_2D, LatticeDimensions_3D,
+       LatticeDimensions_4D, WhiteNoiseDistribution, WhiteNoiseDistribution_Uniform,
+       WhiteNoiseDistribution_Gaussian, WhiteNoiseDistribution_Exponential, WhiteNoiseDistribution_Bernoulli,
+       WhiteNoiseDistribution_Discrete, WhiteNoiseChannels, WhiteNoiseChannels_Independent,
+       WhiteNoiseChannels_Shared, Sdf2dShape, Sdf2dShape_Circle, Sdf2dShape_Box, Sdf2dShape_RoundedBox,
+       Sdf2dShape_Segment, Sdf2dShape_Polygon, Sdf2dOp, Sdf2dOp_Union, Sdf2dOp_Intersection,
+       Sdf2dOp_Subtraction
 
 struct Context
     context::Api.Context
 end
 
 function new_context(type::Symbol)
-    ptr = if type == :cpu
+    ptr = if type == :auto
+        Api.txkit_context_new_auto()
+    elseif type == :cpu
         Api.txkit_context_new_cpu()
     elseif type == :gpu
         Api.txkit_context_new_gpu()
@@ -389,6 +488,11 @@ function destroy(registry::Registry)
     Api.txkit_registry_destroy(registry.registry)
 end
 
+function method_names(registry::Registry)
+    count = Api.txkit_registry_count(registry.registry)
+    [unsafe_string(Api.txkit_registry_name_at(registry.registry, UInt(i))) for i in 0:count-1]
+end
+
 struct TextureMethod
     method::Api.TextureMethod
 end
@@ -431,6 +535,79 @@ function compute(context::Context, method::TextureMethod, target::Image, params:
     nothing
 end
 
+function set_param(method::TextureMethod, name::AbstractString, value::Union{Float32, Int32, UInt32, Vector2_f32})
+    result = if value isa Float32
+        Api.txkit_method_set_param_f32(method.method, name, value)
+    elseif value isa Int32
+        Api.txkit_method_set_param_i32(method.method, name, value)
+    elseif value isa UInt32
+        Api.txkit_method_set_param_u32(method.method, name, value)
+    else
+        Api.txkit_method_set_param_vec2(method.method, name, value.x, value.y)
+    end
+
+    if result != 0
+        error("error setting parameter: " * unsafe_string(Api.txkit_get_last_error()))
+    end
+
+    nothing
+end
+
+function get_param(method::TextureMethod, name::AbstractString, ::Type{T}) where {T <: Union{Float32, Int32, UInt32, Vector2_f32}}
+    if T == Vector2_f32
+        x, y = Ref{Float32}(0), Ref{Float32}(0)
+        result = Api.txkit_method_get_param_vec2(method.method, name, x, y)
+        value = Vector2_f32(x[], y[])
+    else
+        ref = Ref{T}(0)
+        result = if T == Float32
+            Api.txkit_method_get_param_f32(method.method, name, ref)
+        elseif T == Int32
+            Api.txkit_method_get_param_i32(method.method, name, ref)
+        else
+            Api.txkit_method_get_param_u32(method.method, name, ref)
+        end
+        value = ref[]
+    end
+
+    if result != 0
+        error("error getting parameter: " * unsafe_string(Api.txkit_get_last_error()))
+    end
+
+    value
+end
+
+function set_texture_binding(method::TextureMethod, name::AbstractString, image::Union{Image, Nothing})
+    result = Api.txkit_method_set_texture_binding(method.method, name, image === nothing ? C_NULL : image.image)
+
+    if result != 0
+        error("error setting binding: " * unsafe_string(Api.txkit_get_last_error()))
+    end
+
+    nothing
+end
+
+# The input is owned by the method afterwards, and must not be destroyed
+function set_input(method::TextureMethod, name::AbstractString, input::Union{TextureMethod, Nothing})
+    result = Api.txkit_method_set_input(method.method, name, input === nothing ? C_NULL : input.method)
+
+    if result != 0
+        error("error setting input: " * unsafe_string(Api.txkit_get_last_error()))
+    end
+
+    nothing
+end
+
+# The callback is a C function pointer, e.g. from
+# @cfunction(f, Cvoid, (Csize_t, Csize_t, Ptr{Cvoid})), and user_data must outlive the method
+function set_progress(method::TextureMethod, callback::Union{Ptr{Cvoid}, Nothing}, user_data::Ptr{Cvoid} = C_NULL)
+    Api.txkit_method_set_progress(method.method, callback === nothing ? C_NULL : callback, user_data)
+end
+
+compute(context::Context, method::TextureMethod, target::Image) = compute(context, method, target, nothing)
+
+supports(context::Context, method::TextureMethod, target::Image) = Api.txkit_method_supports(context.context, method.method, target.image)
+
 struct ImageIo
     io::Api.ImageIo
 end
@@ -473,7 +650,40 @@ end
 
 set_texture_binding(io::ImageIo, index::UInt, image::Image) = set_texture_binding(io.io, index, image.image)
 
-export Api, Context, new_context, ImageDim, Image, new_image, destroy, download, upload, map_read, map_write, TextureMethod, new_method, compute, Registry, new_registry, set_image_binding, set_texture_binding
+struct Sdf2dShapes
+    shapes::Api.Sdf2dShapes
+end
+
+new_sdf_2d_shapes() = Sdf2dShapes(Api.txkit_sdf_2d_shapes_new())
+
+function destroy(shapes::Sdf2dShapes)
+    Api.txkit_sdf_2d_shapes_destroy(shapes.shapes)
+end
+
+function add_shape(shapes::Sdf2dShapes, kind::Sdf2dShape, op::Sdf2dOp, smoothness::Float32, data::Vector{Float32})
+    result = Api.txkit_sdf_2d_shapes_add(shapes.shapes, kind, op, smoothness, data, UInt(length(data)))
+
+    if result != 0
+        error("error adding shape: " * unsafe_string(Api.txkit_get_last_error()))
+    end
+
+    nothing
+end
+
+clear(shapes::Sdf2dShapes) = Api.txkit_sdf_2d_shapes_clear(shapes.shapes)
+
+# The shapes are copied, so they can be changed or destroyed afterwards
+function set_sdf_2d_shapes(method::TextureMethod, name::AbstractString, shapes::Sdf2dShapes)
+    result = Api.txkit_method_set_sdf_2d_shapes(method.method, name, shapes.shapes)
+
+    if result != 0
+        error("error setting shapes: " * unsafe_string(Api.txkit_get_last_error()))
+    end
+
+    nothing
+end
+
+export Api, Context, new_context, ImageDim, Image, new_image, destroy, download, upload, map_read, map_write, TextureMethod, new_method, compute, supports, set_param, get_param, Registry, new_registry, method_names, set_image_binding, set_texture_binding, set_input, set_progress, Sdf2dShapes, new_sdf_2d_shapes, add_shape, clear, set_sdf_2d_shapes
 
 end # module
 